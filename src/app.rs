@@ -1,11 +1,24 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use ratatui::layout::Rect;
 
-use crate::models::{Channel, ChannelMember, Chat, ChatMember, Message, SearchHit, Team, User};
+use crate::cards::CardAction;
+use crate::config::MarkReadMode;
+use crate::downloads::DownloadProgress;
+use crate::emoji::{self, EmojiPicker};
+use crate::files::{self, FileEntry};
+use crate::graphics::{self, GraphicsProtocol, ImagePreview};
+use crate::models::{
+    self, Channel, ChannelMember, Chat, ChatMember, ChatViewpoint, Message, PinnedMessage, SearchHit, SharedFile,
+    SharedFileSort, Team, User,
+};
+use crate::sections::{self, ChatSection};
+use crate::state::ChatPrefs;
 
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct UserSuggestion {
     pub display_name: String,
     pub email: String,
@@ -26,6 +39,159 @@ pub enum Panel {
     Input,
 }
 
+/// A line of the chat list: a section header or a chat (index into `App::chats`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatListRow {
+    Header {
+        name: String,
+        chats: usize,
+        /// Unread messages in the section's chats, muted chats excluded
+        unread: i32,
+        collapsed: bool,
+        first_chat: usize,
+    },
+    Chat(usize),
+}
+
+impl ChatListRow {
+    /// Terminal lines the row takes up in the chat list
+    pub fn height(&self) -> usize {
+        match self {
+            ChatListRow::Header { .. } => 1,
+            ChatListRow::Chat(_) => 3,
+        }
+    }
+
+    /// The chat index selected when the cursor rests on this row; expanded
+    /// section headers are skipped, collapsed ones stand for their first chat
+    fn stop(&self) -> Option<usize> {
+        match self {
+            ChatListRow::Chat(i) => Some(*i),
+            ChatListRow::Header { collapsed: true, first_chat, .. } => Some(*first_chat),
+            ChatListRow::Header { .. } => None,
+        }
+    }
+}
+
+/// How long `z` can restore a deleted message
+pub const UNDO_DELETE_WINDOW: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Conversation a message belongs to
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageLocation {
+    Chat(String),
+    Channel { team_id: String, channel_id: String },
+}
+
+/// A message deleted with `d`, which `z` restores within the undo window
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeletedMessage {
+    pub location: MessageLocation,
+    pub message_id: String,
+    pub deleted_at: std::time::Instant,
+}
+
+impl DeletedMessage {
+    pub fn new(location: MessageLocation, message_id: String) -> Self {
+        Self { location, message_id, deleted_at: std::time::Instant::now() }
+    }
+
+    /// Whole seconds left to undo, rounded up; 0 once the window has passed
+    pub fn seconds_left(&self) -> u64 {
+        let left = UNDO_DELETE_WINDOW.saturating_sub(self.deleted_at.elapsed());
+        left.as_secs() + u64::from(left.subsec_nanos() > 0)
+    }
+}
+
+/// How often cached channels are polled for new messages
+pub const CHANNEL_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Messages fetched per channel when polling for activity
+pub const CHANNEL_POLL_TOP: usize = 10;
+
+/// Most channels polled per round; recently opened ones beyond it are skipped
+pub const CHANNEL_POLL_MAX: usize = 20;
+
+/// Slots of each poll round given in turn to all other cached channels, so
+/// every team's unread count is eventually updated
+pub const CHANNEL_POLL_ROTATION: usize = 5;
+
+/// Times the poll interval is doubled after Graph throttles a poll (16 minutes)
+pub const CHANNEL_POLL_MAX_BACKOFF: u32 = 4;
+
+/// Downloaded image bytes kept for the viewer before the least recently used are dropped
+pub const IMAGE_SOURCE_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Chat types the filter bar can toggle, with their labels
+pub const CHAT_FILTER_TYPES: [(&str, &str); 3] = [("oneOnOne", "1:1"), ("group", "Group"), ("meeting", "Meeting")];
+
+/// Chat list filter bar: name query, unread-only and chat type toggles
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChatFilter {
+    pub query: String,
+    /// Typed characters go to `query`
+    pub editing: bool,
+    pub unread_only: bool,
+    /// Chat types to show; empty = all
+    pub chat_types: Vec<String>,
+}
+
+impl ChatFilter {
+    pub fn is_active(&self) -> bool {
+        !self.query.is_empty() || self.unread_only || !self.chat_types.is_empty()
+    }
+
+    /// Type and name rules; unread-only is applied by the caller
+    fn matches(&self, chat: &Chat, name: &str) -> bool {
+        (self.chat_types.is_empty() || self.chat_types.contains(&chat.chat_type))
+            && fuzzy_match(&self.query, name)
+    }
+}
+
+/// Add new messages and replace changed ones in place, keeping the list sorted
+/// by creation time (oldest first). Returns true if any were new.
+fn merge_messages(messages: &mut Vec<Message>, delta: Vec<Message>) -> bool {
+    let mut has_new = false;
+    for msg in delta {
+        if let Some(existing) = messages.iter_mut().find(|m| m.id == msg.id) {
+            *existing = msg;
+        } else {
+            has_new = true;
+            messages.push(msg);
+        }
+    }
+    messages.sort_by(|a, b| a.created_date_time.cmp(&b.created_date_time));
+    has_new
+}
+
+/// `createdDateTime` of the newest message, as sent by Graph
+fn latest_message_time(messages: &[Message]) -> Option<String> {
+    messages
+        .iter()
+        .filter_map(|m| m.created_at().map(|t| (t, m.created_date_time.clone())))
+        .max_by_key(|(t, _)| *t)
+        .and_then(|(_, raw)| raw)
+}
+
+/// Case-insensitive subsequence match: "jdoe" matches "Jane Doe"
+pub fn fuzzy_match(query: &str, text: &str) -> bool {
+    let mut chars = text.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+        .all(|q| chars.any(|c| c == q))
+}
+
+/// Where "next unread" (`N`) goes
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnreadTarget {
+    /// Index into `App::chats`
+    Chat(usize),
+    /// Team index and channel ID
+    Channel(usize, String),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ViewMode {
     Chats,
@@ -58,9 +224,22 @@ pub enum DialogMode {
     ChatManager,
     CommandPalette,
     FilePicker,
+    SharedFiles,
+    Forward,
+    ReactionDetails,
+    PinnedMessages,
+    ConfirmDelete,
+    ImageViewer,
     Error(ErrorInfo),
 }
 
+/// Step of the forward dialog: choosing where to send, then adding a comment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ForwardStage {
+    Target,
+    Comment,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum PaletteItemKind {
     Chat(String),           // chat_id
@@ -73,6 +252,15 @@ pub enum PaletteAction {
     NewChat,
     Search,
     SetStatus,
+    SharedFiles,
+    PasteImage,
+    PinChat,
+    MuteChat,
+    HideChat,
+    MarkRead,
+    MarkUnread,
+    MarkAllRead,
+    ShowHiddenChats,
     Settings,
     Quit,
 }
@@ -103,6 +291,8 @@ pub struct LayoutAreas {
     pub channel_messages: Rect,
     pub channel_input: Rect,
     pub channel_members: Rect,
+    // Image viewer dialog
+    pub image_viewer: Rect,
 }
 
 pub struct App {
@@ -111,8 +301,21 @@ pub struct App {
     pub view_mode: ViewMode,
 
     // Chats
+    /// Visible chats: pinned first, hidden ones left out unless shown
     pub chats: Vec<Chat>,
+    /// Every chat as returned by Graph, before pin/hide preferences are applied
+    pub all_chats: Vec<Chat>,
+    pub chat_prefs: ChatPrefs,
+    pub show_hidden_chats: bool,
+    /// Configured chat list sections; empty = one flat list
+    pub chat_sections: Vec<ChatSection>,
+    /// Section of each entry in `chats` (`chat_sections.len()` = "Other")
+    pub chat_section_of: Vec<usize>,
+    pub collapsed_sections: HashSet<String>,
+    pub chat_filter: ChatFilter,
     pub selected_chat: usize,
+    /// Chat whose messages were last loaded into `messages`
+    pub shown_chat_id: Option<String>,
     pub messages: Vec<Message>,
     pub input: String,
     pub input_cursor: usize,
@@ -127,11 +330,24 @@ pub struct App {
     // Refresh
     pub last_refresh: std::time::Instant,
     pub refresh_interval: std::time::Duration,
+    pub last_channel_poll: std::time::Instant,
+    /// How many times the channel poll interval is doubled after throttling
+    pub channel_poll_backoff: u32,
+    /// (team ID, channel ID) of recently opened channels, newest first
+    recent_channels: VecDeque<(String, String)>,
+    /// Where the next poll round continues through the other cached channels
+    channel_poll_rotation: usize,
 
     // New chat dialog
     pub new_chat_mode: bool,
     pub new_chat_input: String,
     pub new_chat_cursor: usize,
+    /// People added to the new chat, shown as removable chips
+    pub new_chat_people: Vec<UserSuggestion>,
+    /// Optional group chat topic
+    pub new_chat_topic: String,
+    /// Typing goes to the topic instead of the people field
+    pub new_chat_topic_focus: bool,
     pub suggestions: Vec<UserSuggestion>,
     pub selected_suggestion: usize,
     pub last_search_query: String,
@@ -142,7 +358,28 @@ pub struct App {
     // Message selection (for reactions)
     pub selected_message: Option<usize>,
     pub selected_channel_message: Option<usize>,
-    pub selected_reaction: usize,
+    pub emoji_picker: EmojiPicker,
+    pub recent_emojis: Vec<String>,
+    pub skin_tone: usize,
+
+    // Pinned messages of the selected chat
+    pub pinned_messages: Vec<PinnedMessage>,
+    pub pinned_chat_id: Option<String>,
+    /// Chat whose pinned messages the main loop should fetch in the background
+    pub pins_to_load: Option<String>,
+    pub pinned_selected: usize,
+    /// Scroll the chat so the selected message is visible on the next draw
+    pub scroll_to_selected: bool,
+    /// First unread message of the open chat, where the "new" divider goes
+    pub unread_marker: Option<String>,
+    pub mark_read_mode: MarkReadMode,
+    /// Chat to mark read once the delay of `MarkReadMode::After` has passed
+    pub pending_mark_read: Option<(String, std::time::Instant)>,
+
+    // Composer `:shortcode:` completion popup
+    pub shortcode_selected: usize,
+    pub shortcode_dismissed: bool,
+    pub selected_card_action: Option<usize>,
 
     // Presence
     pub my_presence: String,
@@ -163,6 +400,10 @@ pub struct App {
     // Caches for instant navigation
     pub channels_cache: HashMap<String, Vec<Channel>>,
     pub channel_message_cache: HashMap<String, Vec<Message>>,
+    /// Newest message time seen per channel; persisted per account
+    pub channel_last_read: HashMap<String, String>,
+    /// First unread message of the open channel, where the "new" divider goes
+    pub channel_unread_marker: Option<String>,
 
     // Channel members
     pub channel_members: Vec<ChannelMember>,
@@ -181,6 +422,12 @@ pub struct App {
 
     // Edit state
     pub editing_message_id: Option<String>,
+    /// Last deleted message, while it can still be restored
+    pub deleted_message: Option<DeletedMessage>,
+    /// Ask before deleting a message
+    pub confirm_delete: bool,
+    /// Whether sign-in asked for `ChannelMessage.ReadWrite`, so channel messages can be changed
+    pub channel_message_write: bool,
 
     // Pagination
     pub messages_next_link: Option<String>,
@@ -189,6 +436,9 @@ pub struct App {
 
     // Delta query tokens for incremental message sync
     pub chat_delta_links: HashMap<String, String>,
+    pub channel_delta_links: HashMap<String, String>,
+    /// Channels that do not support the delta query; refreshed by page instead
+    pub channel_delta_unsupported: HashSet<String>,
 
     // Settings dialog
     pub selected_setting: usize,
@@ -225,15 +475,64 @@ pub struct App {
     // File picker
     pub file_path_input: String,
     pub file_path_cursor: usize,
+    /// Directory shown in the picker (kept between openings)
+    pub file_picker_dir: PathBuf,
+    pub file_picker_entries: Vec<FileEntry>,
+    pub file_picker_selected: usize,
+    /// Files marked for a multi-file upload, in marking order
+    pub file_picker_marked: Vec<PathBuf>,
+    pub file_picker_show_hidden: bool,
+    /// Path input has focus (otherwise the directory listing does)
+    pub file_picker_typing: bool,
     pub file_uploading: bool,
     pub file_upload_error: Option<String>,
-    pub image_preview_cache: HashMap<String, Vec<String>>,
+    /// (bytes sent, total bytes) of a chunked upload
+    pub file_upload_progress: Option<(u64, u64)>,
+    /// Set to abort the running chunked upload
+    pub file_upload_cancel: Option<Arc<AtomicBool>>,
+    /// An inline image (file or clipboard) is being sent
+    pub inline_image_sending: bool,
+    pub image_preview_cache: HashMap<String, ImagePreview>,
     pub image_preview_pending: HashSet<String>,
+    /// Downloaded image bytes, kept for the full-screen viewer and re-rendering
+    pub image_sources: HashMap<String, Vec<u8>>,
+    /// URLs in `image_sources`, least recently used first
+    image_source_order: VecDeque<String>,
+    pub image_protocol: GraphicsProtocol,
+    pub image_protocol_setting: String,
+    pub image_viewer: Option<ImageViewer>,
+
+    // Attachment downloads
+    pub download: Option<DownloadProgress>,
+    pub download_dir: String,
+    pub open_after_download: bool,
+
+    // Forward dialog (target list reuses the palette items and filter)
+    pub forward_message: Option<Message>,
+    pub forward_stage: ForwardStage,
+    pub forward_target: Option<PaletteItem>,
+    pub forward_comment: String,
+    pub forward_comment_cursor: usize,
+    pub forward_sending: bool,
+
+    // Shared files panel
+    pub shared_files: Vec<SharedFile>,
+    pub shared_files_selected: usize,
+    pub shared_files_sort: SharedFileSort,
+    /// (team_id, channel_id) whose SharePoint folder is being listed
+    pub shared_files_loading: Option<(String, String)>,
+    pub shared_files_error: Option<String>,
 
     // Layout areas for mouse hit-testing (updated each frame)
     pub layout_areas: LayoutAreas,
 }
 
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl App {
     pub fn new() -> Self {
         Self {
@@ -243,7 +542,15 @@ impl App {
             active_panel: Panel::ChatList,
             view_mode: ViewMode::Chats,
             chats: Vec::new(),
+            all_chats: Vec::new(),
+            chat_prefs: ChatPrefs::default(),
+            show_hidden_chats: false,
+            chat_sections: Vec::new(),
+            chat_section_of: Vec::new(),
+            collapsed_sections: HashSet::new(),
+            chat_filter: ChatFilter::default(),
             selected_chat: 0,
+            shown_chat_id: None,
             messages: Vec::new(),
             input: String::new(),
             input_cursor: 0,
@@ -251,17 +558,37 @@ impl App {
             status_message: String::new(),
             scroll_offset: 0,
             last_refresh: std::time::Instant::now(),
+            last_channel_poll: std::time::Instant::now(),
+            channel_poll_backoff: 0,
+            recent_channels: VecDeque::new(),
+            channel_poll_rotation: 0,
             refresh_interval: std::time::Duration::from_secs(15),
             new_chat_mode: false,
             new_chat_input: String::new(),
             new_chat_cursor: 0,
+            new_chat_people: Vec::new(),
+            new_chat_topic: String::new(),
+            new_chat_topic_focus: false,
             suggestions: Vec::new(),
             selected_suggestion: 0,
             last_search_query: String::new(),
             dialog: DialogMode::None,
             selected_message: None,
+            selected_card_action: None,
             selected_channel_message: None,
-            selected_reaction: 0,
+            emoji_picker: EmojiPicker::default(),
+            recent_emojis: Vec::new(),
+            skin_tone: 0,
+            pinned_messages: Vec::new(),
+            pinned_chat_id: None,
+            pins_to_load: None,
+            pinned_selected: 0,
+            scroll_to_selected: false,
+            unread_marker: None,
+            mark_read_mode: MarkReadMode::OnView,
+            pending_mark_read: None,
+            shortcode_selected: 0,
+            shortcode_dismissed: false,
             my_presence: "PresenceUnknown".to_string(),
             presence_map: HashMap::new(),
             selected_presence: 0,
@@ -276,6 +603,8 @@ impl App {
             teams_panel: TeamsPanel::TeamList,
             channels_cache: HashMap::new(),
             channel_message_cache: HashMap::new(),
+            channel_last_read: HashMap::new(),
+            channel_unread_marker: None,
             channel_members: Vec::new(),
             show_members: false,
             channel_permission_denied: false,
@@ -284,10 +613,15 @@ impl App {
             reply_to_message_id: None,
             reply_to_preview: String::new(),
             editing_message_id: None,
+            deleted_message: None,
+            confirm_delete: true,
+            channel_message_write: false,
             messages_next_link: None,
             channel_messages_next_link: None,
             loading_more_messages: false,
             chat_delta_links: HashMap::new(),
+            channel_delta_links: HashMap::new(),
+            channel_delta_unsupported: HashSet::new(),
             selected_setting: 0,
             editing_setting: false,
             setting_input: String::new(),
@@ -314,10 +648,38 @@ impl App {
             palette_selected: 0,
             file_path_input: String::new(),
             file_path_cursor: 0,
+            file_picker_dir: PathBuf::new(),
+            file_picker_entries: Vec::new(),
+            file_picker_selected: 0,
+            file_picker_marked: Vec::new(),
+            file_picker_show_hidden: false,
+            file_picker_typing: false,
             file_uploading: false,
+            inline_image_sending: false,
             file_upload_error: None,
+            file_upload_progress: None,
+            file_upload_cancel: None,
             image_preview_cache: HashMap::new(),
             image_preview_pending: HashSet::new(),
+            image_sources: HashMap::new(),
+            image_source_order: VecDeque::new(),
+            image_protocol: GraphicsProtocol::Grayscale,
+            image_protocol_setting: "auto".to_string(),
+            image_viewer: None,
+            download: None,
+            download_dir: String::new(),
+            open_after_download: false,
+            forward_message: None,
+            forward_stage: ForwardStage::Target,
+            forward_target: None,
+            forward_comment: String::new(),
+            forward_comment_cursor: 0,
+            forward_sending: false,
+            shared_files: Vec::new(),
+            shared_files_selected: 0,
+            shared_files_sort: SharedFileSort::Date,
+            shared_files_loading: None,
+            shared_files_error: None,
             layout_areas: LayoutAreas::default(),
         }
     }
@@ -341,13 +703,287 @@ impl App {
     }
 
     pub fn select_next_chat(&mut self) {
-        if !self.chats.is_empty() {
-            self.selected_chat = (self.selected_chat + 1).min(self.chats.len() - 1);
+        let rows = self.chat_list_rows();
+        let current = self.selected_chat_row(&rows);
+        let next = rows
+            .iter()
+            .skip(current.map_or(0, |r| r + 1))
+            .find_map(ChatListRow::stop);
+        if let Some(idx) = next {
+            self.selected_chat = idx;
         }
     }
 
     pub fn select_prev_chat(&mut self) {
-        self.selected_chat = self.selected_chat.saturating_sub(1);
+        let rows = self.chat_list_rows();
+        let Some(current) = self.selected_chat_row(&rows) else {
+            return;
+        };
+        if let Some(idx) = rows[..current].iter().rev().find_map(ChatListRow::stop) {
+            self.selected_chat = idx;
+        }
+    }
+
+    /// Place the "new" divider before the first unread message of the open
+    /// chat, from the chat's read time or unread count before it was opened.
+    /// Returns whether there was one.
+    pub fn set_chat_unread_marker(&mut self, last_read: Option<&str>, unread_count: i32) -> bool {
+        self.unread_marker = models::first_unread_index(&self.messages, last_read, unread_count, self.current_user_id())
+            .map(|idx| self.messages[idx].id.clone());
+        self.unread_marker.is_some()
+    }
+
+    /// Record the open chat as read until the chat list is refreshed, so
+    /// "next unread" does not come back to it
+    pub fn mark_selected_chat_read(&mut self) {
+        let Some(chat_id) = self.selected_chat_id().map(String::from) else {
+            return;
+        };
+        let latest = latest_message_time(&self.messages);
+        for chat in self.all_chats.iter_mut().chain(self.chats.iter_mut()).filter(|c| c.id == chat_id) {
+            chat.unread_message_count = Some(0);
+            if latest.is_some() {
+                chat.viewpoint.get_or_insert_with(ChatViewpoint::default).last_message_read_date_time = latest.clone();
+            }
+        }
+        self.update_total_unread();
+    }
+
+    /// Decide when the chat that was just opened gets marked read; returns
+    /// whether to mark it right away
+    pub fn schedule_mark_read(&mut self, chat_id: &str) -> bool {
+        self.pending_mark_read = None;
+        match self.mark_read_mode {
+            MarkReadMode::OnView => true,
+            MarkReadMode::After(secs) => {
+                let due = std::time::Instant::now() + std::time::Duration::from_secs(secs);
+                self.pending_mark_read = Some((chat_id.to_string(), due));
+                false
+            }
+            MarkReadMode::Manual => false,
+        }
+    }
+
+    /// The chat whose delayed mark-read is due, if it is still the open chat
+    pub fn take_due_mark_read(&mut self) -> Option<String> {
+        let (_, due) = self.pending_mark_read.as_ref()?;
+        if std::time::Instant::now() < *due {
+            return None;
+        }
+        let (chat_id, _) = self.pending_mark_read.take()?;
+        (self.selected_chat_id() == Some(chat_id.as_str())).then_some(chat_id)
+    }
+
+    /// Read time to send with "mark unread": just before the selected
+    /// message, so it and everything after it become unread
+    pub fn mark_unread_from(&self) -> Option<String> {
+        let selected = self.selected_message.filter(|&i| i < self.messages.len())?;
+        self.messages[..selected]
+            .iter()
+            .rev()
+            .find(|m| m.is_user_message())
+            .and_then(|m| m.created_date_time.clone())
+    }
+
+    /// Record the open chat as unread after `markChatUnreadForUser`
+    pub fn mark_selected_chat_unread(&mut self, last_read: Option<String>) {
+        let Some(chat_id) = self.selected_chat_id().map(String::from) else {
+            return;
+        };
+        self.pending_mark_read = None;
+        let unread = self
+            .selected_message
+            .and_then(|selected| self.messages.get(selected..))
+            .map(|from| {
+                from.iter()
+                    .filter(|m| m.is_user_message() && m.sender_id() != Some(self.current_user_id()))
+                    .count()
+            })
+            .unwrap_or(0)
+            .max(1);
+        for chat in self.all_chats.iter_mut().chain(self.chats.iter_mut()).filter(|c| c.id == chat_id) {
+            chat.unread_message_count = Some(unread as i32);
+            chat.viewpoint.get_or_insert_with(ChatViewpoint::default).last_message_read_date_time = last_read.clone();
+        }
+        self.update_total_unread();
+    }
+
+    /// Chats with unread messages, for "mark all read"
+    pub fn unread_chat_ids(&self) -> Vec<String> {
+        self.all_chats
+            .iter()
+            .filter(|c| c.unread_count() > 0)
+            .map(|c| c.id.clone())
+            .collect()
+    }
+
+    /// Record chats as read after a bulk "mark all read"
+    pub fn set_chats_read(&mut self, chat_ids: &[String]) {
+        for chat in self.all_chats.iter_mut().chain(self.chats.iter_mut()) {
+            if chat_ids.contains(&chat.id) {
+                chat.unread_message_count = Some(0);
+            }
+        }
+        self.update_total_unread();
+    }
+
+    /// Next chat or channel with unread activity after the current one, in
+    /// chat list order and then team and channel order, wrapping around.
+    /// Muted chats are skipped.
+    pub fn next_unread_target(&self) -> Option<UnreadTarget> {
+        let mut slots: Vec<(UnreadTarget, bool)> = self
+            .chats
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (UnreadTarget::Chat(i), c.unread_count() > 0 && !self.is_chat_muted(&c.id)))
+            .collect();
+        for (team_idx, team) in self.teams.iter().enumerate() {
+            for channel in self.channels_cache.get(&team.id).into_iter().flatten() {
+                slots.push((
+                    UnreadTarget::Channel(team_idx, channel.id.clone()),
+                    self.channel_has_unread(&channel.id),
+                ));
+            }
+        }
+        let current = match self.view_mode {
+            ViewMode::Chats => Some(UnreadTarget::Chat(self.selected_chat)),
+            ViewMode::Teams => self
+                .selected_channel_id()
+                .map(|id| UnreadTarget::Channel(self.selected_team, id.to_string())),
+        };
+        let start = current
+            .as_ref()
+            .and_then(|cur| slots.iter().position(|(t, _)| t == cur))
+            .map_or(0, |pos| pos + 1);
+        let len = slots.len();
+        (0..len)
+            .map(|offset| &slots[(start + offset) % len])
+            .find(|(target, unread)| *unread && Some(target) != current.as_ref())
+            .map(|(target, _)| target.clone())
+    }
+
+    /// Compile the configured sections and their initial collapsed state.
+    /// Returns the names of sections skipped for an invalid topic pattern.
+    pub fn set_chat_sections(&mut self, configs: &[crate::config::ChatSectionConfig]) -> Vec<String> {
+        let (compiled, invalid) = sections::compile_sections(configs);
+        self.chat_sections = compiled;
+        self.collapsed_sections = configs
+            .iter()
+            .filter(|c| c.collapsed)
+            .map(|c| c.name.trim().to_string())
+            .collect();
+        self.apply_chat_prefs();
+        invalid
+    }
+
+    fn section_name(&self, section: usize) -> &str {
+        self.chat_sections
+            .get(section)
+            .map(|s| s.name.as_str())
+            .unwrap_or(sections::OTHER_SECTION)
+    }
+
+    /// The chat list as drawn: without sections one row per chat, otherwise
+    /// a header per non-empty section followed by its chats unless collapsed
+    pub fn chat_list_rows(&self) -> Vec<ChatListRow> {
+        if self.chat_sections.is_empty() || self.chat_section_of.len() != self.chats.len() {
+            return (0..self.chats.len()).map(ChatListRow::Chat).collect();
+        }
+        let mut rows = Vec::new();
+        let mut start = 0;
+        while start < self.chats.len() {
+            let section = self.chat_section_of[start];
+            let end = start
+                + self.chat_section_of[start..]
+                    .iter()
+                    .take_while(|&&s| s == section)
+                    .count();
+            let name = self.section_name(section).to_string();
+            let collapsed = self.collapsed_sections.contains(&name);
+            let unread = self.chats[start..end]
+                .iter()
+                .filter(|c| !self.is_chat_muted(&c.id))
+                .map(|c| c.unread_count())
+                .sum();
+            rows.push(ChatListRow::Header {
+                name,
+                chats: end - start,
+                unread,
+                collapsed,
+                first_chat: start,
+            });
+            if !collapsed {
+                rows.extend((start..end).map(ChatListRow::Chat));
+            }
+            start = end;
+        }
+        rows
+    }
+
+    /// Row showing the selected chat: its own row, or its section's header
+    /// when the section is collapsed
+    pub fn selected_chat_row(&self, rows: &[ChatListRow]) -> Option<usize> {
+        rows.iter().position(|row| match row {
+            ChatListRow::Chat(i) => *i == self.selected_chat,
+            ChatListRow::Header { collapsed: true, first_chat, chats, .. } => {
+                (*first_chat..first_chat + chats).contains(&self.selected_chat)
+            }
+            ChatListRow::Header { .. } => false,
+        })
+    }
+
+    /// First row to draw so the selected chat fits in `height` lines
+    pub fn chat_list_scroll(&self, rows: &[ChatListRow], height: usize) -> usize {
+        let Some(selected) = self.selected_chat_row(rows) else {
+            return 0;
+        };
+        let mut start = 0;
+        while start < selected && rows[start..=selected].iter().map(ChatListRow::height).sum::<usize>() > height {
+            start += 1;
+        }
+        start
+    }
+
+    /// Row under line `y` of a chat list `height` lines tall
+    pub fn chat_list_row_at(&self, y: usize, height: usize) -> Option<ChatListRow> {
+        let rows = self.chat_list_rows();
+        let start = self.chat_list_scroll(&rows, height);
+        let mut top = 0;
+        for row in rows.into_iter().skip(start) {
+            top += row.height();
+            if y < top {
+                return Some(row);
+            }
+        }
+        None
+    }
+
+    /// Whether the selected chat sits in a collapsed section (its header is selected)
+    pub fn selected_chat_collapsed(&self) -> bool {
+        !self.chat_sections.is_empty()
+            && self
+                .chat_section_of
+                .get(self.selected_chat)
+                .is_some_and(|&s| self.collapsed_sections.contains(self.section_name(s)))
+    }
+
+    /// Collapse or expand the selected chat's section. Collapsing moves the
+    /// selection to the section's first chat, which its header stands for.
+    /// Returns the section name and its new collapsed state.
+    pub fn toggle_selected_section(&mut self) -> Option<(String, bool)> {
+        if self.chat_sections.is_empty() {
+            return None;
+        }
+        let section = *self.chat_section_of.get(self.selected_chat)?;
+        let name = self.section_name(section).to_string();
+        let collapsed = !self.collapsed_sections.remove(&name);
+        if collapsed {
+            self.collapsed_sections.insert(name.clone());
+            if let Some(first) = self.chat_section_of.iter().position(|&s| s == section) {
+                self.selected_chat = first;
+            }
+        }
+        Some((name, collapsed))
     }
 
     pub fn next_panel(&mut self) {
@@ -369,6 +1005,7 @@ impl App {
     pub fn insert_char(&mut self, c: char) {
         self.input.insert(self.input_cursor, c);
         self.input_cursor += c.len_utf8();
+        self.reset_shortcode_popup();
     }
 
     pub fn delete_char(&mut self) {
@@ -381,6 +1018,7 @@ impl App {
             self.input_cursor -= prev_len;
             self.input.remove(self.input_cursor);
         }
+        self.reset_shortcode_popup();
     }
 
     pub fn take_input(&mut self) -> String {
@@ -428,6 +1066,25 @@ impl App {
         self.last_refresh = std::time::Instant::now();
     }
 
+    pub fn should_poll_channels(&self) -> bool {
+        self.last_channel_poll.elapsed() >= self.channel_poll_interval()
+    }
+
+    /// Poll interval, doubled for each throttled poll
+    pub fn channel_poll_interval(&self) -> std::time::Duration {
+        CHANNEL_POLL_INTERVAL * 2u32.pow(self.channel_poll_backoff)
+    }
+
+    /// Graph answered a poll with 429; wait longer before the next one
+    pub fn channel_poll_throttled(&mut self) {
+        self.channel_poll_backoff = (self.channel_poll_backoff + 1).min(CHANNEL_POLL_MAX_BACKOFF);
+    }
+
+    pub fn mark_channels_polled(&mut self) {
+        self.last_channel_poll = std::time::Instant::now();
+        self.channel_poll_rotation = self.channel_poll_rotation.wrapping_add(CHANNEL_POLL_ROTATION);
+    }
+
     // ---- New chat mode ----
 
     pub fn enter_new_chat_mode(&mut self) {
@@ -435,6 +1092,9 @@ impl App {
         self.dialog = DialogMode::NewChat;
         self.new_chat_input.clear();
         self.new_chat_cursor = 0;
+        self.new_chat_people.clear();
+        self.new_chat_topic.clear();
+        self.new_chat_topic_focus = false;
     }
 
     pub fn exit_new_chat_mode(&mut self) {
@@ -442,17 +1102,34 @@ impl App {
         self.dialog = DialogMode::None;
         self.new_chat_input.clear();
         self.new_chat_cursor = 0;
+        self.new_chat_people.clear();
+        self.new_chat_topic.clear();
+        self.new_chat_topic_focus = false;
         self.suggestions.clear();
         self.selected_suggestion = 0;
         self.last_search_query.clear();
     }
 
     pub fn new_chat_insert_char(&mut self, c: char) {
+        if self.new_chat_topic_focus {
+            self.new_chat_topic.push(c);
+            return;
+        }
         self.new_chat_input.insert(self.new_chat_cursor, c);
         self.new_chat_cursor += c.len_utf8();
     }
 
+    /// Backspace: edits the focused field; on an empty people field it
+    /// removes the last chip instead
     pub fn new_chat_delete_char(&mut self) {
+        if self.new_chat_topic_focus {
+            self.new_chat_topic.pop();
+            return;
+        }
+        if self.new_chat_input.is_empty() {
+            self.new_chat_people.pop();
+            return;
+        }
         if self.new_chat_cursor > 0 {
             let prev_len = self.new_chat_input[..self.new_chat_cursor]
                 .chars()
@@ -464,20 +1141,81 @@ impl App {
         }
     }
 
-    pub fn take_new_chat_input(&mut self) -> String {
-        let input = self.new_chat_input.clone();
+    /// Switch between the people and topic fields; the topic only applies to
+    /// group chats
+    pub fn toggle_new_chat_field(&mut self) {
+        self.new_chat_topic_focus = !self.new_chat_topic_focus;
+    }
+
+    fn add_new_chat_person(&mut self, person: UserSuggestion) {
+        let duplicate = self.new_chat_people.iter().any(|p| {
+            (!p.id.is_empty() && p.id == person.id) || p.email.eq_ignore_ascii_case(&person.email)
+        });
+        if !duplicate {
+            self.new_chat_people.push(person);
+        }
+        self.new_chat_input.clear();
+        self.new_chat_cursor = 0;
+        self.suggestions.clear();
+        self.selected_suggestion = 0;
+        self.last_search_query.clear();
+    }
+
+    /// Add the highlighted suggestion as a chip
+    pub fn add_selected_suggestion(&mut self) -> bool {
+        let Some(person) = self.suggestions.get(self.selected_suggestion).cloned() else {
+            return false;
+        };
+        self.add_new_chat_person(person);
+        true
+    }
+
+    /// Add the typed email address as a chip
+    pub fn add_typed_person(&mut self) -> bool {
+        let email = self.new_chat_input.trim().to_string();
+        if !email.contains('@') {
+            return false;
+        }
+        self.add_new_chat_person(UserSuggestion { display_name: email.clone(), email, id: String::new() });
+        true
+    }
+
+    /// People and topic for the chat to create; closes the dialog
+    pub fn take_new_chat(&mut self) -> Option<(Vec<UserSuggestion>, String)> {
+        if self.new_chat_people.is_empty() {
+            return None;
+        }
+        let people = std::mem::take(&mut self.new_chat_people);
+        let topic = std::mem::take(&mut self.new_chat_topic);
         self.exit_new_chat_mode();
-        input
+        Some((people, topic))
     }
 
-    pub fn select_suggestion(&mut self) -> Option<String> {
-        if let Some(s) = self.suggestions.get(self.selected_suggestion) {
-            let email = s.email.clone();
-            self.exit_new_chat_mode();
-            Some(email)
-        } else {
-            None
+    /// Existing 1:1 chat with `person`, matched by user ID or email
+    pub fn find_one_on_one_chat(&self, person: &UserSuggestion) -> Option<String> {
+        let me = self.current_user_id();
+        self.all_chats
+            .iter()
+            .filter(|c| c.chat_type == "oneOnOne")
+            .find(|c| {
+                c.members.iter().flatten().filter(|m| m.user_id.as_deref() != Some(me)).any(|m| {
+                    (!person.id.is_empty() && m.user_id.as_deref() == Some(person.id.as_str()))
+                        || m.email.as_deref().is_some_and(|e| e.eq_ignore_ascii_case(&person.email))
+                })
+            })
+            .map(|c| c.id.clone())
+    }
+
+    /// Make a chat visible in the chat list (clearing the filter and showing
+    /// hidden chats if needed) and select it
+    pub fn reveal_chat(&mut self, chat_id: &str) -> bool {
+        if !self.chats.iter().any(|c| c.id == chat_id) && self.chat_filter.is_active() {
+            self.clear_chat_filter();
         }
+        if !self.chats.iter().any(|c| c.id == chat_id) && !self.show_hidden_chats {
+            self.toggle_show_hidden_chats();
+        }
+        self.navigate_to_chat(chat_id)
     }
 
     pub fn suggestion_up(&mut self) {
@@ -499,6 +1237,7 @@ impl App {
     // ---- Message selection (for reactions) ----
 
     pub fn select_message_up(&mut self) {
+        self.selected_card_action = None;
         let user_msgs: Vec<usize> = self
             .messages
             .iter()
@@ -526,6 +1265,7 @@ impl App {
     }
 
     pub fn select_message_down(&mut self) {
+        self.selected_card_action = None;
         let user_msgs: Vec<usize> = self
             .messages
             .iter()
@@ -539,7 +1279,7 @@ impl App {
         }
 
         match self.selected_message {
-            None => return,
+            None => {}
             Some(cur) => {
                 if let Some(pos) = user_msgs.iter().position(|&i| i == cur) {
                     if pos + 1 < user_msgs.len() {
@@ -561,6 +1301,7 @@ impl App {
     // ---- Channel message selection (for reactions in teams view) ----
 
     pub fn select_channel_message_up(&mut self) {
+        self.selected_card_action = None;
         let msgs: Vec<usize> = self
             .channel_messages
             .iter()
@@ -584,6 +1325,7 @@ impl App {
     }
 
     pub fn select_channel_message_down(&mut self) {
+        self.selected_card_action = None;
         let msgs: Vec<usize> = self
             .channel_messages
             .iter()
@@ -595,7 +1337,7 @@ impl App {
             return;
         }
         match self.selected_channel_message {
-            None => return,
+            None => {}
             Some(cur) => {
                 if let Some(pos) = msgs.iter().position(|&i| i == cur) {
                     if pos + 1 < msgs.len() {
@@ -623,8 +1365,138 @@ impl App {
         };
         if has_selection {
             self.dialog = DialogMode::ReactionPicker;
-            self.selected_reaction = 0;
+            self.emoji_picker = EmojiPicker::new(self.skin_tone);
+        }
+    }
+
+    /// Emoji currently shown in the picker grid
+    pub fn emoji_picker_items(&self) -> Vec<String> {
+        self.emoji_picker.items(&self.recent_emojis)
+    }
+
+    pub fn selected_picker_emoji(&self) -> Option<String> {
+        self.emoji_picker.selected_emoji(&self.recent_emojis)
+    }
+
+    /// Move an emoji to the front of the recently used row
+    pub fn record_recent_emoji(&mut self, emoji: &str) {
+        emoji::push_recent(&mut self.recent_emojis, emoji, emoji::MAX_RECENT);
+    }
+
+    /// Whether the current user already reacted to the selected message with `emoji`
+    pub fn selected_message_has_my_reaction(&self, emoji: &str) -> bool {
+        self.selected_view_message()
+            .map(|m| m.has_reaction_from(self.current_user_id(), emoji))
+            .unwrap_or(false)
+    }
+
+    /// The reaction type the current user's `emoji` reaction on the selected
+    /// message is stored with, as `unsetReaction` needs it
+    pub fn selected_message_my_reaction_type(&self, emoji: &str) -> Option<String> {
+        self.selected_view_message()?
+            .reaction_type_from(self.current_user_id(), emoji)
+            .map(String::from)
+    }
+
+    /// Open the "who reacted" popup; false when the selected message has no reactions
+    pub fn open_reaction_details(&mut self) -> bool {
+        let has_reactions = self
+            .selected_view_message()
+            .map(|m| !m.reactions_summary().is_empty())
+            .unwrap_or(false);
+        if has_reactions {
+            self.dialog = DialogMode::ReactionDetails;
+        }
+        has_reactions
+    }
+
+    // ---- Pinned messages ----
+
+    pub fn set_pinned_messages(&mut self, chat_id: &str, pins: Vec<PinnedMessage>) {
+        self.pinned_chat_id = Some(chat_id.to_string());
+        self.pinned_messages = pins;
+        self.pinned_selected = self.pinned_selected.min(self.pinned_messages.len().saturating_sub(1));
+    }
+
+    /// Pins loaded for the selected chat (empty while another chat's pins are cached)
+    pub fn selected_chat_pins(&self) -> &[PinnedMessage] {
+        match (&self.pinned_chat_id, self.selected_chat_id()) {
+            (Some(pinned), Some(selected)) if pinned == selected => &self.pinned_messages,
+            _ => &[],
+        }
+    }
+
+    pub fn is_pinned(&self, message_id: &str) -> bool {
+        self.selected_chat_pins().iter().any(|p| p.message_id() == Some(message_id))
+    }
+
+    /// Pinned message ID of the selected chat message, if it is pinned
+    pub fn selected_message_pin_id(&self) -> Option<String> {
+        let message_id = self.selected_message_id()?;
+        self.selected_chat_pins()
+            .iter()
+            .find(|p| p.message_id() == Some(message_id))
+            .map(|p| p.id.clone())
+    }
+
+    /// Open the pinned messages popup; false when the chat has no pins
+    pub fn open_pinned_messages(&mut self) -> bool {
+        if self.selected_chat_pins().is_empty() {
+            return false;
+        }
+        self.pinned_selected = 0;
+        self.dialog = DialogMode::PinnedMessages;
+        true
+    }
+
+    pub fn pinned_move(&mut self, delta: isize) {
+        let len = self.selected_chat_pins().len();
+        if len > 0 {
+            self.pinned_selected = (self.pinned_selected as isize + delta).clamp(0, len as isize - 1) as usize;
+        }
+    }
+
+    pub fn selected_pin(&self) -> Option<&PinnedMessage> {
+        self.selected_chat_pins().get(self.pinned_selected)
+    }
+
+    /// Select a loaded chat message by ID and scroll it into view
+    pub fn select_message_by_id(&mut self, message_id: &str) -> bool {
+        let Some(idx) = self.messages.iter().position(|m| m.id == message_id) else {
+            return false;
+        };
+        self.selected_message = Some(idx);
+        self.selected_card_action = None;
+        self.active_panel = Panel::Messages;
+        self.scroll_to_selected = true;
+        true
+    }
+
+    /// Reactors per emoji on the selected message, with names resolved where possible
+    pub fn selected_reaction_details(&self) -> Vec<(String, Vec<String>)> {
+        self.selected_view_message()
+            .map(|m| m.reaction_details(|id| self.user_display_name(id)))
+            .unwrap_or_default()
+    }
+
+    /// Display name for a user ID from the signed-in user, chat members or loaded message senders
+    pub fn user_display_name(&self, user_id: &str) -> Option<String> {
+        if let Some(user) = self.current_user.as_ref().filter(|u| u.id == user_id) {
+            return Some(user.display_name.clone());
         }
+        let from_members = self
+            .chats
+            .iter()
+            .flat_map(|c| c.members.iter().flatten())
+            .find(|m| m.user_id.as_deref() == Some(user_id))
+            .and_then(|m| m.display_name.clone());
+        from_members.or_else(|| {
+            self.messages
+                .iter()
+                .chain(&self.channel_messages)
+                .find(|m| m.sender_id() == Some(user_id))
+                .map(|m| m.sender_name())
+        })
     }
 
     pub fn close_dialog(&mut self) {
@@ -730,6 +1602,56 @@ impl App {
             kind: PaletteItemKind::Action(PaletteAction::SetStatus),
             icon: "🟢",
         });
+        items.push(PaletteItem {
+            label: "Shared Files".to_string(),
+            kind: PaletteItemKind::Action(PaletteAction::SharedFiles),
+            icon: "📁",
+        });
+        items.push(PaletteItem {
+            label: "Paste Image from Clipboard".to_string(),
+            kind: PaletteItemKind::Action(PaletteAction::PasteImage),
+            icon: "🖼",
+        });
+        if self.selected_chat_id().is_some() {
+            let toggle = |on: bool, yes: &str, no: &str| if on { yes.to_string() } else { no.to_string() };
+            let pinned = self.selected_chat_id().map(|id| self.is_chat_pinned(id)).unwrap_or(false);
+            items.push(PaletteItem {
+                label: toggle(pinned, "Unpin Current Chat", "Pin Current Chat"),
+                kind: PaletteItemKind::Action(PaletteAction::PinChat),
+                icon: "📌",
+            });
+            items.push(PaletteItem {
+                label: toggle(self.selected_chat_muted(), "Unmute Current Chat", "Mute Current Chat"),
+                kind: PaletteItemKind::Action(PaletteAction::MuteChat),
+                icon: "🔕",
+            });
+            items.push(PaletteItem {
+                label: toggle(self.selected_chat_hidden(), "Unhide Current Chat", "Hide Current Chat"),
+                kind: PaletteItemKind::Action(PaletteAction::HideChat),
+                icon: "🙈",
+            });
+            items.push(PaletteItem {
+                label: "Mark Current Chat Read".to_string(),
+                kind: PaletteItemKind::Action(PaletteAction::MarkRead),
+                icon: "✓",
+            });
+            items.push(PaletteItem {
+                label: "Mark Current Chat Unread".to_string(),
+                kind: PaletteItemKind::Action(PaletteAction::MarkUnread),
+                icon: "●",
+            });
+        }
+        items.push(PaletteItem {
+            label: "Mark All Chats Read".to_string(),
+            kind: PaletteItemKind::Action(PaletteAction::MarkAllRead),
+            icon: "✓",
+        });
+        items.push(PaletteItem {
+            label: if self.show_hidden_chats { "Stop Showing Hidden Chats" } else { "Show Hidden Chats" }
+                .to_string(),
+            kind: PaletteItemKind::Action(PaletteAction::ShowHiddenChats),
+            icon: "👁",
+        });
         items.push(PaletteItem {
             label: "Settings".to_string(),
             kind: PaletteItemKind::Action(PaletteAction::Settings),
@@ -760,17 +1682,248 @@ impl App {
         self.palette_selected = 0;
     }
 
-    pub fn open_file_picker(&mut self) {
-        self.dialog = DialogMode::FilePicker;
-        self.file_path_input.clear();
-        self.file_path_cursor = 0;
-        self.file_uploading = false;
-        self.file_upload_error = None;
-    }
-
-    pub fn file_picker_insert_char(&mut self, c: char) {
-        self.file_path_input.insert(self.file_path_cursor, c);
-        self.file_path_cursor += c.len_utf8();
+    /// Open the forward dialog for the selected message. Targets are the chats
+    /// and channels of the command palette. Returns false without a selection.
+    pub fn open_forward(&mut self) -> bool {
+        let Some(message) = self.selected_view_message().cloned() else {
+            return false;
+        };
+        self.forward_message = Some(message);
+        self.forward_stage = ForwardStage::Target;
+        self.forward_target = None;
+        self.forward_comment.clear();
+        self.forward_comment_cursor = 0;
+        self.forward_sending = false;
+        self.build_palette_items();
+        self.palette_items
+            .retain(|item| !matches!(item.kind, PaletteItemKind::Action(_)));
+        self.palette_input.clear();
+        self.palette_cursor = 0;
+        self.palette_filter();
+        self.dialog = DialogMode::Forward;
+        true
+    }
+
+    /// Use the highlighted target and move on to the comment
+    pub fn forward_choose_target(&mut self) -> bool {
+        let Some(item) = self
+            .palette_filtered
+            .get(self.palette_selected)
+            .and_then(|&idx| self.palette_items.get(idx))
+            .cloned()
+        else {
+            return false;
+        };
+        self.forward_target = Some(item);
+        self.forward_stage = ForwardStage::Comment;
+        true
+    }
+
+    pub fn forward_back_to_targets(&mut self) {
+        self.forward_stage = ForwardStage::Target;
+        self.forward_target = None;
+    }
+
+    pub fn close_forward(&mut self) {
+        self.forward_message = None;
+        self.forward_target = None;
+        self.forward_sending = false;
+        self.close_dialog();
+    }
+
+    pub fn forward_insert_char(&mut self, c: char) {
+        self.forward_comment.insert(self.forward_comment_cursor, c);
+        self.forward_comment_cursor += c.len_utf8();
+    }
+
+    pub fn forward_delete_char(&mut self) {
+        if let Some(prev) = self.forward_comment[..self.forward_comment_cursor].chars().last() {
+            self.forward_comment_cursor -= prev.len_utf8();
+            self.forward_comment.remove(self.forward_comment_cursor);
+        }
+    }
+
+    pub fn forward_move_cursor_left(&mut self) {
+        if let Some(prev) = self.forward_comment[..self.forward_comment_cursor].chars().last() {
+            self.forward_comment_cursor -= prev.len_utf8();
+        }
+    }
+
+    pub fn forward_move_cursor_right(&mut self) {
+        if let Some(next) = self.forward_comment[self.forward_comment_cursor..].chars().next() {
+            self.forward_comment_cursor += next.len_utf8();
+        }
+    }
+
+    pub fn open_file_picker(&mut self) {
+        self.dialog = DialogMode::FilePicker;
+        self.file_path_input.clear();
+        self.file_path_cursor = 0;
+        self.file_uploading = false;
+        self.file_upload_error = None;
+        self.file_upload_progress = None;
+        self.file_upload_cancel = None;
+        self.file_picker_marked.clear();
+        self.file_picker_typing = false;
+        self.file_picker_selected = 0;
+        if !self.file_picker_dir.is_dir() {
+            self.file_picker_dir = std::env::current_dir()
+                .ok()
+                .or_else(dirs::home_dir)
+                .unwrap_or_else(|| PathBuf::from("/"));
+        }
+        self.file_picker_refresh();
+    }
+
+    /// Re-read the picker directory, keeping the selection in range
+    pub fn file_picker_refresh(&mut self) {
+        match files::list_dir(&self.file_picker_dir, self.file_picker_show_hidden) {
+            Ok(entries) => self.file_picker_entries = entries,
+            Err(e) => {
+                self.file_picker_entries.clear();
+                self.file_upload_error = Some(format!("Cannot read folder: {}", e));
+            }
+        }
+        self.file_picker_selected = self
+            .file_picker_selected
+            .min(self.file_picker_entries.len().saturating_sub(1));
+    }
+
+    pub fn file_picker_set_dir(&mut self, dir: PathBuf) {
+        self.file_picker_dir = dir;
+        self.file_picker_selected = 0;
+        self.file_upload_error = None;
+        self.file_picker_refresh();
+    }
+
+    /// Go to the parent folder, selecting the folder we came from
+    pub fn file_picker_parent(&mut self) {
+        let Some(parent) = self.file_picker_dir.parent().map(PathBuf::from) else {
+            return;
+        };
+        let previous = self.file_picker_dir.clone();
+        self.file_picker_set_dir(parent);
+        if let Some(idx) = self.file_picker_entries.iter().position(|e| e.path == previous) {
+            self.file_picker_selected = idx;
+        }
+    }
+
+    pub fn file_picker_move(&mut self, delta: isize) {
+        if self.file_picker_entries.is_empty() {
+            return;
+        }
+        let last = self.file_picker_entries.len() - 1;
+        self.file_picker_selected = self
+            .file_picker_selected
+            .saturating_add_signed(delta)
+            .min(last);
+    }
+
+    pub fn file_picker_selected_entry(&self) -> Option<&FileEntry> {
+        self.file_picker_entries.get(self.file_picker_selected)
+    }
+
+    /// Open the selected folder, or return the selected file
+    pub fn file_picker_open_selected(&mut self) -> Option<PathBuf> {
+        let entry = self.file_picker_selected_entry()?.clone();
+        if entry.is_dir {
+            self.file_picker_set_dir(entry.path);
+            None
+        } else {
+            Some(entry.path)
+        }
+    }
+
+    /// Mark/unmark the selected file for upload and move to the next entry
+    pub fn file_picker_toggle_mark(&mut self) {
+        let Some(entry) = self.file_picker_selected_entry() else {
+            return;
+        };
+        if entry.is_dir {
+            return;
+        }
+        let path = entry.path.clone();
+        if let Some(pos) = self.file_picker_marked.iter().position(|p| *p == path) {
+            self.file_picker_marked.remove(pos);
+        } else {
+            self.file_picker_marked.push(path);
+        }
+        self.file_picker_move(1);
+    }
+
+    pub fn file_picker_is_marked(&self, path: &Path) -> bool {
+        self.file_picker_marked.iter().any(|p| p == path)
+    }
+
+    pub fn file_picker_toggle_hidden(&mut self) {
+        self.file_picker_show_hidden = !self.file_picker_show_hidden;
+        let selected = self.file_picker_selected_entry().map(|e| e.path.clone());
+        self.file_picker_refresh();
+        if let Some(idx) = selected.and_then(|p| self.file_picker_entries.iter().position(|e| e.path == p)) {
+            self.file_picker_selected = idx;
+        }
+    }
+
+    /// Focus the path input, pre-filled with the current folder
+    pub fn file_picker_start_typing(&mut self) {
+        self.file_picker_typing = true;
+        let mut dir = self.file_picker_dir.display().to_string();
+        if !dir.ends_with(std::path::is_separator) {
+            dir.push(std::path::MAIN_SEPARATOR);
+        }
+        self.file_path_input = dir;
+        self.file_path_cursor = self.file_path_input.len();
+    }
+
+    /// Tab-complete the typed path; a completed folder is shown in the listing
+    pub fn file_picker_complete(&mut self) {
+        let Some(completed) = files::complete_path(&self.file_path_input, self.file_picker_show_hidden) else {
+            return;
+        };
+        self.file_path_input = completed;
+        self.file_path_cursor = self.file_path_input.len();
+        if self.file_path_input.ends_with(std::path::is_separator) {
+            let dir = files::expand_tilde(&self.file_path_input);
+            if dir.is_dir() {
+                self.file_picker_set_dir(dir);
+            }
+        }
+    }
+
+    /// Files to upload: the marked files plus `current` (the highlighted or
+    /// typed file) when it isn't a folder or already marked
+    pub fn file_picker_upload_targets(&self, current: Option<PathBuf>) -> Vec<PathBuf> {
+        let mut targets = self.file_picker_marked.clone();
+        if let Some(path) = current {
+            if !path.is_dir() && !targets.contains(&path) {
+                targets.push(path);
+            }
+        }
+        targets
+    }
+
+    /// Request cancellation of a running upload. Returns false when no upload
+    /// is running or it can't be cancelled.
+    pub fn cancel_file_upload(&mut self) -> bool {
+        match &self.file_upload_cancel {
+            Some(flag) if self.file_uploading => {
+                flag.store(true, Ordering::Relaxed);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_file_upload_cancelling(&self) -> bool {
+        self.file_upload_cancel
+            .as_ref()
+            .map(|flag| flag.load(Ordering::Relaxed))
+            .unwrap_or(false)
+    }
+
+    pub fn file_picker_insert_char(&mut self, c: char) {
+        self.file_path_input.insert(self.file_path_cursor, c);
+        self.file_path_cursor += c.len_utf8();
     }
 
     pub fn file_picker_delete_char(&mut self) {
@@ -833,6 +1986,157 @@ impl App {
         }
     }
 
+    // ---- Chat pin / mute / hide ----
+
+    /// Replace the chat list with a fresh one from Graph, keeping the selection
+    pub fn set_chats(&mut self, chats: Vec<Chat>) {
+        self.all_chats = chats;
+        self.apply_chat_prefs();
+    }
+
+    /// Rebuild the visible chat list from `all_chats`: hidden chats are left
+    /// out unless shown, chats are grouped by section with pinned chats first
+    /// in each, the filter bar is applied, and the selected chat stays
+    /// selected when it is still visible.
+    pub fn apply_chat_prefs(&mut self) {
+        let selected_id = self.selected_chat_id().map(String::from);
+        let user_id = self.current_user_id().to_string();
+        let mut chats: Vec<(usize, Chat)> = self
+            .all_chats
+            .iter()
+            .filter(|c| self.show_hidden_chats || !self.is_chat_hidden(c))
+            .map(|c| (sections::section_index(&self.chat_sections, c, &user_id), c.clone()))
+            .collect();
+        chats.sort_by_key(|(section, c)| (*section, !self.is_chat_pinned(&c.id)));
+        (self.chat_section_of, self.chats) = chats.into_iter().unzip();
+        self.update_total_unread();
+        if self.chat_filter.is_active() {
+            // The open chat stays listed under "unread only" once it has been read
+            let filter = &self.chat_filter;
+            (self.chat_section_of, self.chats) = std::mem::take(&mut self.chat_section_of)
+                .into_iter()
+                .zip(std::mem::take(&mut self.chats))
+                .filter(|(_, c)| {
+                    filter.matches(c, &c.display_name(&user_id))
+                        && (!filter.unread_only
+                            || c.unread_count() > 0
+                            || selected_id.as_deref() == Some(c.id.as_str()))
+                })
+                .unzip();
+        }
+        if let Some(idx) = selected_id.and_then(|id| self.chats.iter().position(|c| c.id == id)) {
+            self.selected_chat = idx;
+        }
+        self.selected_chat = self.selected_chat.min(self.chats.len().saturating_sub(1));
+    }
+
+    /// Start typing into the chat list filter bar
+    pub fn start_chat_filter(&mut self) {
+        self.chat_filter.editing = true;
+    }
+
+    /// Leave typing mode, keeping the filter
+    pub fn finish_chat_filter(&mut self) {
+        self.chat_filter.editing = false;
+    }
+
+    pub fn chat_filter_insert(&mut self, c: char) {
+        self.chat_filter.query.push(c);
+        self.apply_chat_prefs();
+    }
+
+    pub fn chat_filter_delete(&mut self) {
+        if self.chat_filter.query.pop().is_some() {
+            self.apply_chat_prefs();
+        }
+    }
+
+    /// Drop every filter and show the full chat list again
+    pub fn clear_chat_filter(&mut self) {
+        self.chat_filter = ChatFilter::default();
+        self.apply_chat_prefs();
+    }
+
+    pub fn toggle_chat_filter_unread(&mut self) {
+        self.chat_filter.unread_only = !self.chat_filter.unread_only;
+        self.apply_chat_prefs();
+    }
+
+    /// Show or stop showing only chats of `chat_type` (see `CHAT_FILTER_TYPES`)
+    pub fn toggle_chat_filter_type(&mut self, chat_type: &str) {
+        let types = &mut self.chat_filter.chat_types;
+        match types.iter().position(|t| t == chat_type) {
+            Some(pos) => {
+                types.remove(pos);
+            }
+            None => types.push(chat_type.to_string()),
+        }
+        self.apply_chat_prefs();
+    }
+
+    /// Whether the filter bar takes a line above the chat list
+    pub fn chat_filter_bar_visible(&self) -> bool {
+        self.chat_filter.editing || self.chat_filter.is_active()
+    }
+
+    pub fn is_chat_pinned(&self, chat_id: &str) -> bool {
+        self.chat_prefs.pinned.iter().any(|id| id == chat_id)
+    }
+
+    pub fn is_chat_muted(&self, chat_id: &str) -> bool {
+        self.chat_prefs.muted.iter().any(|id| id == chat_id)
+    }
+
+    /// Graph's viewpoint wins; the local list covers responses without one
+    pub fn is_chat_hidden(&self, chat: &Chat) -> bool {
+        chat.viewpoint
+            .as_ref()
+            .map(|v| v.is_hidden)
+            .unwrap_or_else(|| self.chat_prefs.hidden.contains(&chat.id))
+    }
+
+    pub fn selected_chat_muted(&self) -> bool {
+        self.selected_chat_id().map(|id| self.is_chat_muted(id)).unwrap_or(false)
+    }
+
+    pub fn selected_chat_hidden(&self) -> bool {
+        self.chats.get(self.selected_chat).map(|c| self.is_chat_hidden(c)).unwrap_or(false)
+    }
+
+    /// Pin or unpin the selected chat; returns the new state
+    pub fn toggle_selected_chat_pin(&mut self) -> Option<bool> {
+        let chat_id = self.selected_chat_id()?.to_string();
+        let pinned = ChatPrefs::toggle(&mut self.chat_prefs.pinned, &chat_id);
+        self.apply_chat_prefs();
+        Some(pinned)
+    }
+
+    /// Mute or unmute the selected chat; returns the new state
+    pub fn toggle_selected_chat_mute(&mut self) -> Option<bool> {
+        let chat_id = self.selected_chat_id()?.to_string();
+        let muted = ChatPrefs::toggle(&mut self.chat_prefs.muted, &chat_id);
+        self.apply_chat_prefs();
+        Some(muted)
+    }
+
+    /// Record a chat as hidden or visible after `hideForUser` / `unhideForUser`
+    pub fn set_chat_hidden(&mut self, chat_id: &str, hidden: bool) {
+        for chat in self.all_chats.iter_mut().filter(|c| c.id == chat_id) {
+            let viewpoint = chat.viewpoint.get_or_insert_with(ChatViewpoint::default);
+            viewpoint.is_hidden = hidden;
+        }
+        self.chat_prefs.hidden.retain(|id| id != chat_id);
+        if hidden {
+            self.chat_prefs.hidden.push(chat_id.to_string());
+        }
+        self.apply_chat_prefs();
+    }
+
+    pub fn toggle_show_hidden_chats(&mut self) {
+        self.show_hidden_chats = !self.show_hidden_chats;
+        self.apply_chat_prefs();
+    }
+
     // ---- Teams navigation ----
 
     pub fn switch_to_chats(&mut self) {
@@ -910,6 +2214,7 @@ impl App {
     pub fn channel_insert_char(&mut self, c: char) {
         self.channel_input.insert(self.channel_input_cursor, c);
         self.channel_input_cursor += c.len_utf8();
+        self.reset_shortcode_popup();
     }
 
     pub fn channel_delete_char(&mut self) {
@@ -922,6 +2227,7 @@ impl App {
             self.channel_input_cursor -= prev_len;
             self.channel_input.remove(self.channel_input_cursor);
         }
+        self.reset_shortcode_popup();
     }
 
     pub fn take_channel_input(&mut self) -> String {
@@ -963,8 +2269,16 @@ impl App {
 
     // ---- Unread tracking ----
 
+    /// Header badge: unread messages in every listed chat, whatever the
+    /// filter bar shows, leaving out muted chats
     pub fn update_total_unread(&mut self) {
-        self.total_unread = self.chats.iter().map(|c| c.unread_count()).sum();
+        self.total_unread = self
+            .all_chats
+            .iter()
+            .filter(|c| self.show_hidden_chats || !self.is_chat_hidden(c))
+            .filter(|c| !self.is_chat_muted(&c.id))
+            .map(|c| c.unread_count())
+            .sum();
     }
 
     /// Detect new messages and return true if there are new ones (for notification bell)
@@ -992,24 +2306,34 @@ impl App {
         if delta.is_empty() {
             return false;
         }
-        let mut has_new = false;
-        for msg in delta {
-            if let Some(existing) = self.messages.iter_mut().find(|m| m.id == msg.id) {
-                *existing = msg;
-            } else {
-                has_new = true;
-                self.messages.push(msg);
-            }
-        }
-        // Re-sort by creation time (oldest first)
-        self.messages.sort_by(|a, b| {
-            a.created_date_time.cmp(&b.created_date_time)
-        });
+        let has_new = merge_messages(&mut self.messages, delta);
         // Update known IDs
         self.known_message_ids = self.messages.iter().map(|m| m.id.clone()).collect();
         has_new
     }
 
+    /// Merge changed channel messages into the channel's cache and, when it is
+    /// open, into the message list, keeping older pages and the selected
+    /// message. Returns true if any new messages were added.
+    pub fn merge_channel_messages(&mut self, channel_id: &str, delta: Vec<Message>) -> bool {
+        if delta.is_empty() {
+            return false;
+        }
+        let cache = self.channel_message_cache.entry(channel_id.to_string()).or_default();
+        let has_new = merge_messages(cache, delta.clone());
+        if self.selected_channel_id() == Some(channel_id) && self.view_mode == ViewMode::Teams {
+            let selected_id = self
+                .selected_channel_message
+                .and_then(|i| self.channel_messages.get(i))
+                .map(|m| m.id.clone());
+            merge_messages(&mut self.channel_messages, delta);
+            if let Some(id) = selected_id {
+                self.selected_channel_message = self.channel_messages.iter().position(|m| m.id == id);
+            }
+        }
+        has_new
+    }
+
     // ---- Channel members ----
 
     pub fn toggle_members(&mut self) {
@@ -1083,6 +2407,25 @@ impl App {
         }
     }
 
+    /// The open chat or channel
+    pub fn current_message_location(&self) -> Option<MessageLocation> {
+        match self.view_mode {
+            ViewMode::Chats => self.selected_chat_id().map(|id| MessageLocation::Chat(id.to_string())),
+            ViewMode::Teams => Some(MessageLocation::Channel {
+                team_id: self.selected_team_id()?.to_string(),
+                channel_id: self.selected_channel_id()?.to_string(),
+            }),
+        }
+    }
+
+    /// The deleted message `z` can still restore in the open conversation
+    pub fn undoable_delete(&self) -> Option<&DeletedMessage> {
+        let location = self.current_message_location()?;
+        self.deleted_message
+            .as_ref()
+            .filter(|d| d.location == location && d.seconds_left() > 0)
+    }
+
     pub fn cancel_edit(&mut self) {
         self.editing_message_id = None;
     }
@@ -1092,10 +2435,12 @@ impl App {
     }
 
     /// Returns true if the currently selected message was sent by the current user
+    /// and has not been deleted
     pub fn is_own_selected_message(&self) -> bool {
         let uid = self.current_user_id();
         self.selected_message
             .and_then(|idx| self.messages.get(idx))
+            .filter(|m| !m.is_deleted())
             .and_then(|m| m.sender_id())
             .map(|id| id == uid)
             .unwrap_or(false)
@@ -1105,6 +2450,7 @@ impl App {
         let uid = self.current_user_id();
         self.selected_channel_message
             .and_then(|idx| self.channel_messages.get(idx))
+            .filter(|m| !m.is_deleted())
             .and_then(|m| m.sender_id())
             .map(|id| id == uid)
             .unwrap_or(false)
@@ -1132,6 +2478,238 @@ impl App {
             })
     }
 
+    /// First downloadable attachment (file or image reference) of the selected message, as (url, name)
+    pub fn selected_message_download(&self) -> Option<(String, String)> {
+        let message = self.selected_view_message()?;
+        message
+            .file_attachments()
+            .into_iter()
+            .chain(message.image_attachments())
+            .find_map(|a| {
+                let url = a.content_url.clone()?;
+                let name = a
+                    .name
+                    .clone()
+                    .filter(|n| !n.trim().is_empty())
+                    .unwrap_or_else(|| "attachment".to_string());
+                Some((url, name))
+            })
+    }
+
+    /// Take the composer text of the active view (e.g. as an image caption), clearing it
+    pub fn take_composer_text(&mut self) -> String {
+        match self.view_mode {
+            ViewMode::Chats => self.take_input(),
+            ViewMode::Teams => self.take_channel_input(),
+        }
+    }
+
+    /// Put text back into the composer of the active view unless something new was typed
+    pub fn restore_composer_text(&mut self, text: String) {
+        let (input, cursor) = match self.view_mode {
+            ViewMode::Chats => (&mut self.input, &mut self.input_cursor),
+            ViewMode::Teams => (&mut self.channel_input, &mut self.channel_input_cursor),
+        };
+        if input.is_empty() {
+            *cursor = text.len();
+            *input = text;
+        }
+    }
+
+    // ---- Shortcode completion ----
+
+    /// Text and cursor of the composer that has focus, if any
+    fn focused_composer(&self) -> Option<(&str, usize)> {
+        match self.view_mode {
+            ViewMode::Chats if self.active_panel == Panel::Input => {
+                Some((self.input.as_str(), self.input_cursor))
+            }
+            ViewMode::Teams if self.teams_panel == TeamsPanel::ChannelInput => {
+                Some((self.channel_input.as_str(), self.channel_input_cursor))
+            }
+            _ => None,
+        }
+    }
+
+    /// Completions for the `:shortcode` being typed in the focused composer
+    pub fn shortcode_suggestions(&self) -> Vec<&'static emoji::Emoji> {
+        if self.shortcode_dismissed {
+            return Vec::new();
+        }
+        self.focused_composer()
+            .and_then(|(text, cursor)| emoji::shortcode_at(text, cursor))
+            .map(|(_, query)| emoji::complete(query))
+            .unwrap_or_default()
+    }
+
+    pub fn shortcode_move(&mut self, delta: isize) {
+        let len = self.shortcode_suggestions().len();
+        if len > 0 {
+            self.shortcode_selected =
+                (self.shortcode_selected as isize + delta).rem_euclid(len as isize) as usize;
+        }
+    }
+
+    /// Replace the `:shortcode` before the cursor with the selected emoji
+    pub fn accept_shortcode(&mut self) -> bool {
+        let suggestions = self.shortcode_suggestions();
+        let Some(choice) = suggestions.get(self.shortcode_selected.min(suggestions.len().saturating_sub(1)))
+        else {
+            return false;
+        };
+        let emoji = choice.emoji;
+        let (input, cursor) = match self.view_mode {
+            ViewMode::Chats => (&mut self.input, &mut self.input_cursor),
+            ViewMode::Teams => (&mut self.channel_input, &mut self.channel_input_cursor),
+        };
+        let Some((start, _)) = emoji::shortcode_at(input, *cursor) else {
+            return false;
+        };
+        input.replace_range(start..*cursor, emoji);
+        *cursor = start + emoji.len();
+        self.reset_shortcode_popup();
+        true
+    }
+
+    /// Hide the popup until the composer text changes
+    pub fn dismiss_shortcodes(&mut self) {
+        self.shortcode_dismissed = true;
+    }
+
+    fn reset_shortcode_popup(&mut self) {
+        self.shortcode_selected = 0;
+        self.shortcode_dismissed = false;
+    }
+
+    /// Open the Files panel for the current chat or channel, listing attachments
+    /// from loaded messages. In the Teams view the channel's SharePoint folder
+    /// is marked as loading; its files arrive via `merge_channel_files`.
+    pub fn open_shared_files(&mut self) {
+        let messages = match self.view_mode {
+            ViewMode::Chats => &self.messages,
+            ViewMode::Teams => &self.channel_messages,
+        };
+        self.shared_files = models::shared_files_from_messages(messages);
+        models::sort_shared_files(&mut self.shared_files, self.shared_files_sort);
+        self.shared_files_selected = 0;
+        self.shared_files_error = None;
+        self.shared_files_loading = match (self.view_mode, self.selected_team_id(), self.selected_channel_id()) {
+            (ViewMode::Teams, Some(team_id), Some(channel_id)) => {
+                Some((team_id.to_string(), channel_id.to_string()))
+            }
+            _ => None,
+        };
+        self.dialog = DialogMode::SharedFiles;
+    }
+
+    /// Add the channel folder listing; ignored if the panel moved on
+    pub fn merge_channel_files(&mut self, team_id: &str, channel_id: &str, files: Vec<SharedFile>) {
+        if !self.is_loading_channel_files(team_id, channel_id) {
+            return;
+        }
+        self.shared_files_loading = None;
+        let selected = self.selected_shared_file().map(|f| f.web_url.clone());
+        models::merge_shared_files(&mut self.shared_files, files);
+        models::sort_shared_files(&mut self.shared_files, self.shared_files_sort);
+        self.reselect_shared_file(selected);
+    }
+
+    pub fn channel_files_failed(&mut self, team_id: &str, channel_id: &str, error: String) {
+        if self.is_loading_channel_files(team_id, channel_id) {
+            self.shared_files_loading = None;
+            self.shared_files_error = Some(error);
+        }
+    }
+
+    fn is_loading_channel_files(&self, team_id: &str, channel_id: &str) -> bool {
+        self.dialog == DialogMode::SharedFiles
+            && self
+                .shared_files_loading
+                .as_ref()
+                .map(|(t, c)| t == team_id && c == channel_id)
+                .unwrap_or(false)
+    }
+
+    pub fn selected_shared_file(&self) -> Option<&SharedFile> {
+        self.shared_files.get(self.shared_files_selected)
+    }
+
+    pub fn shared_files_move(&mut self, delta: isize) {
+        if self.shared_files.is_empty() {
+            return;
+        }
+        let max = self.shared_files.len() - 1;
+        self.shared_files_selected = self.shared_files_selected.saturating_add_signed(delta).min(max);
+    }
+
+    /// Switch to the next sort order (date → sender → type), keeping the selection
+    pub fn cycle_shared_files_sort(&mut self) {
+        let selected = self.selected_shared_file().map(|f| f.web_url.clone());
+        self.shared_files_sort = self.shared_files_sort.next();
+        models::sort_shared_files(&mut self.shared_files, self.shared_files_sort);
+        self.reselect_shared_file(selected);
+    }
+
+    fn reselect_shared_file(&mut self, web_url: Option<String>) {
+        self.shared_files_selected = web_url
+            .and_then(|url| self.shared_files.iter().position(|f| f.web_url == url))
+            .unwrap_or(0);
+    }
+
+    /// Ask before deleting the selected message
+    pub fn open_delete_confirm(&mut self) {
+        if self.selected_view_message().is_some() {
+            self.dialog = DialogMode::ConfirmDelete;
+        }
+    }
+
+    /// Start of the message the delete confirmation asks about
+    pub fn delete_confirm_preview(&self) -> String {
+        self.selected_view_message()
+            .map(|m| m.content_text().chars().take(60).collect())
+            .unwrap_or_default()
+    }
+
+    /// The selected message in the active view (chat or channel)
+    fn selected_view_message(&self) -> Option<&Message> {
+        match self.view_mode {
+            ViewMode::Chats => self.messages.get(self.selected_message?),
+            ViewMode::Teams => self.channel_messages.get(self.selected_channel_message?),
+        }
+    }
+
+    pub fn selected_message_code_blocks(&self) -> Vec<String> {
+        self.selected_view_message()
+            .map(|m| m.code_blocks())
+            .unwrap_or_default()
+    }
+
+    pub fn selected_message_card_actions(&self) -> Vec<CardAction> {
+        self.selected_view_message()
+            .map(|m| m.card_actions())
+            .unwrap_or_default()
+    }
+
+    /// Move the card action cursor to the next action of the selected message, wrapping around
+    pub fn cycle_card_action(&mut self) -> Option<CardAction> {
+        let actions = self.selected_message_card_actions();
+        if actions.is_empty() {
+            self.selected_card_action = None;
+            return None;
+        }
+        let next = match self.selected_card_action {
+            Some(cur) => (cur + 1) % actions.len(),
+            None => 0,
+        };
+        self.selected_card_action = Some(next);
+        actions.get(next).cloned()
+    }
+
+    pub fn selected_card_action(&self) -> Option<CardAction> {
+        let idx = self.selected_card_action?;
+        self.selected_message_card_actions().get(idx).cloned()
+    }
+
     pub fn mark_image_preview_pending(&mut self, url: &str) -> bool {
         if self.image_preview_cache.contains_key(url) || self.image_preview_pending.contains(url) {
             return false;
@@ -1140,13 +2718,88 @@ impl App {
         true
     }
 
-    pub fn set_image_preview(&mut self, url: String, lines: Vec<String>) {
+    pub fn set_image_preview(&mut self, url: String, preview: ImagePreview) {
         self.image_preview_pending.remove(&url);
-        self.image_preview_cache.insert(url, lines);
+        self.image_preview_cache.insert(url, preview);
+    }
+
+    /// Keep downloaded image bytes, dropping the least recently used images
+    /// (and their previews, so they are fetched again when shown) once the
+    /// cache grows past `IMAGE_SOURCE_CACHE_BYTES`
+    pub fn store_image_source(&mut self, url: String, bytes: Vec<u8>) {
+        self.image_source_order.retain(|u| u != &url);
+        self.image_source_order.push_back(url.clone());
+        self.image_sources.insert(url, bytes);
+        let mut total: usize = self.image_sources.values().map(Vec::len).sum();
+        while total > IMAGE_SOURCE_CACHE_BYTES && self.image_source_order.len() > 1 {
+            let Some(oldest) = self.image_source_order.pop_front() else {
+                break;
+            };
+            if let Some(bytes) = self.image_sources.remove(&oldest) {
+                total -= bytes.len();
+            }
+            self.image_preview_cache.remove(&oldest);
+        }
+    }
+
+    fn touch_image_source(&mut self, url: &str) {
+        if let Some(pos) = self.image_source_order.iter().position(|u| u == url) {
+            if let Some(url) = self.image_source_order.remove(pos) {
+                self.image_source_order.push_back(url);
+            }
+        }
+    }
+
+    pub fn image_preview(&self, url: &str) -> Option<&ImagePreview> {
+        self.image_preview_cache.get(url)
     }
 
-    pub fn image_preview_lines(&self, url: &str) -> Option<&[String]> {
-        self.image_preview_cache.get(url).map(|lines| lines.as_slice())
+    /// First image (attachment or inline hosted image) of the selected message, as (url, name)
+    pub fn selected_message_image(&self) -> Option<(String, String)> {
+        let message = self.selected_view_message()?;
+        message
+            .image_attachments()
+            .iter()
+            .find_map(|a| {
+                a.content_url
+                    .clone()
+                    .map(|url| (url, a.name.clone().unwrap_or_else(|| "image".to_string())))
+            })
+            .or_else(|| {
+                message
+                    .hosted_image_urls()
+                    .into_iter()
+                    .next()
+                    .map(|url| (url, "inline image".to_string()))
+            })
+    }
+
+    /// Open the full-screen viewer for a downloaded image. Returns false if the
+    /// image has not been downloaded yet or cannot be decoded.
+    pub fn open_image_viewer(&mut self, url: &str, name: &str) -> bool {
+        self.touch_image_source(url);
+        let Some(image) = self
+            .image_sources
+            .get(url)
+            .and_then(|bytes| graphics::decode_image(bytes).ok())
+        else {
+            return false;
+        };
+        self.image_viewer = Some(ImageViewer {
+            url: url.to_string(),
+            name: name.to_string(),
+            image,
+            zoom_index: 0,
+            center: (0.5, 0.5),
+            rendered: None,
+        });
+        self.dialog = DialogMode::ImageViewer;
+        true
+    }
+
+    pub fn close_image_viewer(&mut self) {
+        self.image_viewer = None;
+        self.close_dialog();
     }
 
     pub fn is_image_preview_pending(&self, url: &str) -> bool {
@@ -1190,6 +2843,146 @@ impl App {
         }
     }
 
+    /// Newest message time of a cached channel
+    pub fn channel_latest_time(&self, channel_id: &str) -> Option<String> {
+        latest_message_time(self.channel_message_cache.get(channel_id)?)
+    }
+
+    /// Treat what is cached for a channel as read when it has no read time yet,
+    /// so only activity after the first load counts as unread
+    pub fn seed_channel_last_read(&mut self, channel_id: &str) {
+        if !self.channel_last_read.contains_key(channel_id) {
+            if let Some(latest) = self.channel_latest_time(channel_id) {
+                self.channel_last_read.insert(channel_id.to_string(), latest);
+            }
+        }
+    }
+
+    /// The open channel is on screen, so everything cached for it is read
+    pub fn mark_open_channel_seen(&mut self, channel_id: &str) {
+        if self.view_mode == ViewMode::Teams && self.selected_channel_id() == Some(channel_id) {
+            self.channel_last_read.remove(channel_id);
+            self.seed_channel_last_read(channel_id);
+        }
+    }
+
+    /// Messages others posted in a cached channel since it was last read
+    pub fn channel_unread_count(&self, channel_id: &str) -> usize {
+        let Some(last_read) = self.channel_last_read.get(channel_id) else {
+            return 0;
+        };
+        let messages = self.channel_message_cache.get(channel_id).map(Vec::as_slice).unwrap_or_default();
+        models::count_unread_since(messages, last_read, self.current_user_id())
+    }
+
+    pub fn channel_has_unread(&self, channel_id: &str) -> bool {
+        self.channel_unread_count(channel_id) > 0
+    }
+
+    /// Unread messages across a team's cached channels
+    pub fn team_unread_count(&self, team_id: &str) -> usize {
+        self.channels_cache
+            .get(team_id)
+            .into_iter()
+            .flatten()
+            .map(|c| self.channel_unread_count(&c.id))
+            .sum()
+    }
+
+    /// Unread channel messages across all teams, for the header badge
+    pub fn total_channel_unread(&self) -> usize {
+        self.channels_cache.keys().map(|team_id| self.team_unread_count(team_id)).sum()
+    }
+
+    /// Remember an opened channel so it keeps being polled after switching away
+    pub fn note_channel_opened(&mut self, team_id: &str, channel_id: &str) {
+        self.recent_channels.retain(|(_, id)| id != channel_id);
+        self.recent_channels.push_front((team_id.to_string(), channel_id.to_string()));
+        self.recent_channels.truncate(CHANNEL_POLL_MAX);
+    }
+
+    /// (team ID, channel ID) of the channels to poll for activity, optionally
+    /// limited to one team: the selected team's cached channels and recently
+    /// opened ones, plus `CHANNEL_POLL_ROTATION` of all other cached channels
+    /// taken in turn, at most `CHANNEL_POLL_MAX`. The open channel is left out
+    /// since the auto-refresh already keeps it current.
+    pub fn channel_poll_targets(&self, team_id: Option<&str>) -> Vec<(String, String)> {
+        let open = match self.view_mode {
+            ViewMode::Teams => self.selected_channel_id(),
+            ViewMode::Chats => None,
+        };
+        let selected = self.selected_team_id().into_iter().flat_map(|t| {
+            self.channels_cache
+                .get(t)
+                .into_iter()
+                .flatten()
+                .map(move |c| (t.to_string(), c.id.clone()))
+        });
+        let recent = self
+            .recent_channels
+            .iter()
+            .filter(|(t, id)| self.channels_cache.get(t).is_some_and(|chs| chs.iter().any(|c| &c.id == id)))
+            .cloned();
+        let mut targets: Vec<(String, String)> = Vec::new();
+        for (t, id) in selected.chain(recent) {
+            if Some(id.as_str()) == open || targets.iter().any(|(_, seen)| seen == &id) {
+                continue;
+            }
+            targets.push((t, id));
+            if targets.len() == CHANNEL_POLL_MAX - CHANNEL_POLL_ROTATION {
+                break;
+            }
+        }
+        let others: Vec<(String, String)> = self
+            .teams
+            .iter()
+            .flat_map(|t| {
+                self.channels_cache
+                    .get(&t.id)
+                    .into_iter()
+                    .flatten()
+                    .map(|c| (t.id.clone(), c.id.clone()))
+            })
+            .filter(|(_, id)| Some(id.as_str()) != open && !targets.iter().any(|(_, seen)| seen == id))
+            .collect();
+        if !others.is_empty() {
+            let start = self.channel_poll_rotation % others.len();
+            let turn = CHANNEL_POLL_ROTATION.min(others.len());
+            targets.extend(others.iter().cycle().skip(start).take(turn).cloned());
+        }
+        targets.retain(|(t, _)| team_id.unwrap_or(t) == t);
+        targets
+    }
+
+    /// Merge the newest messages of a polled channel into its cache. Returns
+    /// whether any of them were new.
+    pub fn merge_channel_activity(&mut self, channel_id: &str, recent: Vec<Message>) -> bool {
+        let cache = self.channel_message_cache.entry(channel_id.to_string()).or_default();
+        let added = merge_messages(cache, recent);
+        self.seed_channel_last_read(channel_id);
+        added
+    }
+
+    /// Mark the open channel read, placing the "new" divider before its first
+    /// unread message. Returns whether there was one.
+    pub fn mark_selected_channel_read(&mut self) -> bool {
+        let Some(channel_id) = self.selected_channel_id().map(String::from) else {
+            return false;
+        };
+        let last_read = self.channel_last_read.get(&channel_id).map(String::as_str);
+        self.channel_unread_marker = models::first_unread_index(
+            &self.channel_messages,
+            last_read,
+            0,
+            self.current_user_id(),
+        )
+        .map(|idx| self.channel_messages[idx].id.clone());
+        if let Some(latest) = self.channel_latest_time(&channel_id) {
+            self.channel_last_read.insert(channel_id, latest);
+        }
+        self.channel_unread_marker.is_some()
+    }
+
     /// Show cached messages for the currently selected channel (instant)
     pub fn show_cached_messages_for_selected_channel(&mut self) {
         if let Some(ch_id) = self.selected_channel_id().map(String::from) {
@@ -1202,3 +2995,74 @@ impl App {
         }
     }
 }
+
+// ---- Image viewer ----
+
+/// Cache key for a viewer rendering: target area, zoom level and center (in 1/1000ths)
+pub type ViewerRenderKey = (Rect, usize, u32, u32);
+
+#[derive(Debug, Clone)]
+pub struct ImageViewer {
+    pub url: String,
+    pub name: String,
+    pub image: image::DynamicImage,
+    pub zoom_index: usize,
+    /// Normalized (0..1) center of the visible region
+    pub center: (f32, f32),
+    /// Last cell rendering, reused while the view does not change
+    pub rendered: Option<(ViewerRenderKey, ImagePreview)>,
+}
+
+impl ImageViewer {
+    pub fn zoom(&self) -> u32 {
+        graphics::ZOOM_LEVELS[self.zoom_index]
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.zoom_index = (self.zoom_index + 1).min(graphics::ZOOM_LEVELS.len() - 1);
+        self.clamp_center();
+    }
+
+    pub fn zoom_out(&mut self) {
+        self.zoom_index = self.zoom_index.saturating_sub(1);
+        self.clamp_center();
+    }
+
+    pub fn fit(&mut self) {
+        self.zoom_index = 0;
+        self.center = (0.5, 0.5);
+    }
+
+    /// Pan by a fraction of the visible region (e.g. 0.25 = a quarter screen)
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        let visible = 1.0 / self.zoom() as f32;
+        self.center.0 += dx * visible;
+        self.center.1 += dy * visible;
+        self.clamp_center();
+    }
+
+    fn clamp_center(&mut self) {
+        let half = 0.5 / self.zoom() as f32;
+        self.center.0 = self.center.0.clamp(half, 1.0 - half);
+        self.center.1 = self.center.1.clamp(half, 1.0 - half);
+    }
+
+    /// Source rectangle (x, y, width, height) currently visible
+    pub fn visible_region(&self) -> (u32, u32, u32, u32) {
+        graphics::crop_region(self.image.width(), self.image.height(), self.zoom(), self.center)
+    }
+
+    pub fn visible_image(&self) -> image::DynamicImage {
+        let (x, y, w, h) = self.visible_region();
+        self.image.crop_imm(x, y, w, h)
+    }
+
+    pub fn render_key(&self, area: Rect) -> ViewerRenderKey {
+        (
+            area,
+            self.zoom_index,
+            (self.center.0 * 1000.0) as u32,
+            (self.center.1 * 1000.0) as u32,
+        )
+    }
+}
@@ -9,7 +9,18 @@ use crate::config::Config;
 
 const KEYRING_SERVICE: &str = "ttyms-teams-client";
 const KEYRING_USER: &str = "default";
-const SCOPES: &str = "User.Read User.ReadBasic.All Chat.ReadWrite ChatMessage.Read ChatMessage.Send Presence.Read Presence.ReadWrite Team.ReadBasic.All Channel.ReadBasic.All ChannelMessage.Read.All ChannelMessage.Send Files.ReadWrite offline_access";
+const SCOPES: &str = "User.Read User.ReadBasic.All Chat.ReadWrite ChatMessage.Read ChatMessage.Send Presence.Read Presence.ReadWrite Team.ReadBasic.All Channel.ReadBasic.All ChannelMessage.Read.All ChannelMessage.Send Files.ReadWrite Files.Read.All offline_access";
+/// Only requested when `channel_message_write` is on, since most tenants need admin consent for it
+const CHANNEL_WRITE_SCOPE: &str = "ChannelMessage.ReadWrite";
+
+/// Scopes to request at sign-in for this configuration
+pub fn scopes(config: &Config) -> String {
+    if config.channel_message_write {
+        format!("{} {}", SCOPES, CHANNEL_WRITE_SCOPE)
+    } else {
+        SCOPES.to_string()
+    }
+}
 
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
@@ -29,6 +40,9 @@ pub struct TokenResponse {
     pub token_type: String,
     #[serde(default)]
     pub obtained_at: u64,
+    /// Space-separated scopes granted with the token, as returned by the token endpoint
+    #[serde(default)]
+    pub scope: Option<String>,
 }
 
 impl Drop for TokenResponse {
@@ -56,6 +70,17 @@ impl TokenResponse {
             .as_secs();
         self
     }
+
+    /// Whether the token was granted `scope`; granted scopes may carry a resource prefix
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scope.as_deref().unwrap_or("").split_whitespace()
+            .any(|s| s.rsplit('/').next().unwrap_or(s).eq_ignore_ascii_case(scope))
+    }
+
+    /// Whether the token covers the optional scopes this configuration asks for
+    pub fn grants_scopes_for(&self, config: &Config) -> bool {
+        !config.channel_message_write || self.has_scope(CHANNEL_WRITE_SCOPE)
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -97,7 +122,7 @@ fn store_token_keyring(token: &TokenResponse) -> bool {
     if rt_entry.set_password(token.refresh_token.as_deref().unwrap_or("")).is_err() {
         return false;
     }
-    let meta = format!("{},{}", token.expires_in, token.obtained_at);
+    let meta = format!("{},{},{}", token.expires_in, token.obtained_at, token.scope.as_deref().unwrap_or(""));
     meta_entry.set_password(&meta).is_ok()
 }
 
@@ -135,6 +160,7 @@ fn load_token_keyring() -> Option<TokenResponse> {
     let mut parts = meta.split(',');
     let expires_in = parts.next().and_then(|s| s.parse().ok()).unwrap_or(3600);
     let obtained_at = parts.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+    let scope = parts.next().filter(|s| !s.is_empty()).map(str::to_string);
 
     Some(TokenResponse {
         access_token,
@@ -142,6 +168,7 @@ fn load_token_keyring() -> Option<TokenResponse> {
         expires_in,
         token_type: "Bearer".to_string(),
         obtained_at,
+        scope,
     })
 }
 
@@ -187,7 +214,9 @@ pub async fn get_valid_token(
     config: &Config,
 ) -> Result<Option<TokenResponse>> {
     if let Some(token) = load_cached_token()? {
-        if !token.is_expired() {
+        // A token signed in before an optional scope was turned on is refreshed with the new
+        // scopes; if that needs consent the refresh fails and the caller signs in again
+        if !token.is_expired() && token.grants_scopes_for(config) {
             return Ok(Some(token));
         }
         if let Some(ref refresh_tok) = token.refresh_token {
@@ -212,7 +241,7 @@ pub async fn request_device_code(
         .post(&url)
         .form(&[
             ("client_id", config.client_id.as_str()),
-            ("scope", SCOPES),
+            ("scope", scopes(config).as_str()),
         ])
         .send()
         .await?
@@ -294,7 +323,7 @@ async fn refresh_access_token(
             ("grant_type", "refresh_token"),
             ("client_id", config.client_id.as_str()),
             ("refresh_token", refresh_tok),
-            ("scope", SCOPES),
+            ("scope", scopes(config).as_str()),
         ])
         .send()
         .await?;
@@ -330,7 +359,7 @@ pub async fn authenticate_browser(
         config.tenant_id,
         percent_encode(&config.client_id),
         percent_encode(&redirect_uri),
-        percent_encode(SCOPES),
+        percent_encode(&scopes(config)),
         &code_challenge,
     );
 
@@ -466,7 +495,7 @@ async fn exchange_code_for_token(
             ("code", code),
             ("redirect_uri", redirect_uri),
             ("code_verifier", code_verifier),
-            ("scope", SCOPES),
+            ("scope", scopes(config).as_str()),
         ])
         .send()
         .await?;
@@ -0,0 +1,345 @@
+//! Adaptive Card, hero/thumbnail card and Office 365 connector card parsing.
+//!
+//! Cards arrive as JSON strings in `attachments[].content`. They are flattened
+//! into a list of terminal lines plus a list of actions, which the UI renders
+//! below the message body.
+
+use serde_json::Value;
+
+use crate::models::{strip_html, ChatMessageAttachment};
+
+pub const ADAPTIVE_CARD: &str = "application/vnd.microsoft.card.adaptive";
+pub const HERO_CARD: &str = "application/vnd.microsoft.card.hero";
+pub const THUMBNAIL_CARD: &str = "application/vnd.microsoft.card.thumbnail";
+pub const CONNECTOR_CARD: &str = "application/vnd.microsoft.teams.card.o365connector";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEmphasis {
+    Heading,
+    Normal,
+    Subtle,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CardLine {
+    Text { text: String, emphasis: TextEmphasis },
+    Fact { title: String, value: String },
+    Image { alt: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CardAction {
+    OpenUrl { title: String, url: String },
+    /// Actions that need a bot round-trip (Action.Submit, Action.Execute, ...)
+    Unsupported { title: String, kind: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Card {
+    pub kind: &'static str,
+    pub lines: Vec<CardLine>,
+    pub actions: Vec<CardAction>,
+}
+
+pub fn is_card_content_type(content_type: &str) -> bool {
+    matches!(
+        content_type,
+        ADAPTIVE_CARD | HERO_CARD | THUMBNAIL_CARD | CONNECTOR_CARD
+    )
+}
+
+/// Parse a card attachment. Returns None for non-card attachments or invalid JSON.
+pub fn parse_card(attachment: &ChatMessageAttachment) -> Option<Card> {
+    let content_type = attachment.content_type.as_deref()?;
+    if !is_card_content_type(content_type) {
+        return None;
+    }
+    let value: Value = serde_json::from_str(attachment.content.as_deref()?).ok()?;
+    let mut card = Card {
+        kind: match content_type {
+            ADAPTIVE_CARD => "Adaptive Card",
+            CONNECTOR_CARD => "Connector Card",
+            _ => "Card",
+        },
+        lines: Vec::new(),
+        actions: Vec::new(),
+    };
+    match content_type {
+        ADAPTIVE_CARD => parse_adaptive(&value, &mut card),
+        CONNECTOR_CARD => parse_connector(&value, &mut card),
+        _ => parse_hero(&value, &mut card),
+    }
+    Some(card)
+}
+
+// ---- Adaptive Cards ----
+
+fn parse_adaptive(value: &Value, card: &mut Card) {
+    if let Some(body) = value.get("body").and_then(Value::as_array) {
+        push_elements(body, card);
+    }
+    if let Some(actions) = value.get("actions").and_then(Value::as_array) {
+        push_adaptive_actions(actions, card);
+    }
+}
+
+fn push_elements(elements: &[Value], card: &mut Card) {
+    for element in elements {
+        push_element(element, card);
+    }
+}
+
+fn push_element(element: &Value, card: &mut Card) {
+    if element.get("isVisible").and_then(Value::as_bool) == Some(false) {
+        return;
+    }
+    match str_field(element, "type").unwrap_or("") {
+        "TextBlock" => {
+            if let Some(text) = str_field(element, "text") {
+                push_text(card, &clean_markdown(text), adaptive_emphasis(element));
+            }
+        }
+        "RichTextBlock" => {
+            let text: String = array_field(element, "inlines")
+                .iter()
+                .filter_map(|inline| inline.as_str().or_else(|| str_field(inline, "text")))
+                .collect();
+            push_text(card, &text, adaptive_emphasis(element));
+        }
+        "FactSet" => {
+            for fact in array_field(element, "facts") {
+                card.lines.push(CardLine::Fact {
+                    title: clean_markdown(str_field(fact, "title").unwrap_or("")),
+                    value: clean_markdown(str_field(fact, "value").unwrap_or("")),
+                });
+            }
+        }
+        "Image" => push_image(card, element, "altText"),
+        "ImageSet" => {
+            for image in array_field(element, "images") {
+                push_image(card, image, "altText");
+            }
+        }
+        "ColumnSet" => push_column_set(element, card),
+        "Container" | "Column" => push_elements(array_field(element, "items"), card),
+        "ActionSet" => push_adaptive_actions(array_field(element, "actions"), card),
+        _ => {
+            if let Some(text) = str_field(element, "text") {
+                push_text(card, &clean_markdown(text), TextEmphasis::Normal);
+            }
+        }
+    }
+}
+
+/// Columns whose content is a single text line are joined side by side;
+/// anything richer is stacked vertically.
+fn push_column_set(element: &Value, card: &mut Card) {
+    let mut columns = Vec::new();
+    for column in array_field(element, "columns") {
+        let mut sub = Card { kind: card.kind, lines: Vec::new(), actions: Vec::new() };
+        push_element(column, &mut sub);
+        card.actions.append(&mut sub.actions);
+        if !sub.lines.is_empty() {
+            columns.push(sub.lines);
+        }
+    }
+
+    let single_texts: Option<Vec<(&str, TextEmphasis)>> = columns
+        .iter()
+        .map(|lines| match lines.as_slice() {
+            [CardLine::Text { text, emphasis }] => Some((text.as_str(), *emphasis)),
+            _ => None,
+        })
+        .collect();
+
+    match single_texts {
+        Some(texts) if texts.len() > 1 => {
+            let emphasis = texts[0].1;
+            let joined = texts.iter().map(|(t, _)| *t).collect::<Vec<_>>().join("  │  ");
+            card.lines.push(CardLine::Text { text: joined, emphasis });
+        }
+        _ => {
+            for mut lines in columns {
+                card.lines.append(&mut lines);
+            }
+        }
+    }
+}
+
+fn push_adaptive_actions(actions: &[Value], card: &mut Card) {
+    for action in actions {
+        let kind = str_field(action, "type").unwrap_or("Action");
+        let title = str_field(action, "title").unwrap_or("").to_string();
+        match (kind, str_field(action, "url")) {
+            ("Action.OpenUrl", Some(url)) => push_open_url(card, title, url),
+            _ => card.actions.push(CardAction::Unsupported {
+                title: if title.is_empty() { kind.to_string() } else { title },
+                kind: kind.to_string(),
+            }),
+        }
+    }
+}
+
+fn adaptive_emphasis(element: &Value) -> TextEmphasis {
+    let weight = str_field(element, "weight").unwrap_or("").to_ascii_lowercase();
+    let size = str_field(element, "size").unwrap_or("").to_ascii_lowercase();
+    if weight == "bolder" || matches!(size.as_str(), "medium" | "large" | "extralarge") {
+        TextEmphasis::Heading
+    } else if element.get("isSubtle").and_then(Value::as_bool) == Some(true) {
+        TextEmphasis::Subtle
+    } else {
+        TextEmphasis::Normal
+    }
+}
+
+// ---- Hero / thumbnail cards ----
+
+fn parse_hero(value: &Value, card: &mut Card) {
+    if let Some(title) = str_field(value, "title") {
+        push_text(card, &strip_html(title), TextEmphasis::Heading);
+    }
+    if let Some(subtitle) = str_field(value, "subtitle") {
+        push_text(card, &strip_html(subtitle), TextEmphasis::Subtle);
+    }
+    if let Some(text) = str_field(value, "text") {
+        push_text(card, &clean_markdown(&strip_html(text)), TextEmphasis::Normal);
+    }
+    for image in array_field(value, "images") {
+        push_image(card, image, "alt");
+    }
+    for button in array_field(value, "buttons") {
+        let kind = str_field(button, "type").unwrap_or("button");
+        let title = str_field(button, "title").unwrap_or("").to_string();
+        match (kind, str_field(button, "value")) {
+            ("openUrl", Some(url)) => push_open_url(card, title, url),
+            _ => card.actions.push(CardAction::Unsupported {
+                title: if title.is_empty() { kind.to_string() } else { title },
+                kind: kind.to_string(),
+            }),
+        }
+    }
+}
+
+// ---- Office 365 connector cards ----
+
+fn parse_connector(value: &Value, card: &mut Card) {
+    if let Some(title) = str_field(value, "title").or_else(|| str_field(value, "summary")) {
+        push_text(card, &strip_html(title), TextEmphasis::Heading);
+    }
+    if let Some(text) = str_field(value, "text") {
+        push_text(card, &clean_markdown(&strip_html(text)), TextEmphasis::Normal);
+    }
+    for section in array_field(value, "sections") {
+        if let Some(title) = str_field(section, "activityTitle") {
+            push_text(card, &clean_markdown(&strip_html(title)), TextEmphasis::Heading);
+        }
+        if let Some(subtitle) = str_field(section, "activitySubtitle") {
+            push_text(card, &clean_markdown(&strip_html(subtitle)), TextEmphasis::Subtle);
+        }
+        for key in ["activityText", "title", "text"] {
+            if let Some(text) = str_field(section, key) {
+                push_text(card, &clean_markdown(&strip_html(text)), TextEmphasis::Normal);
+            }
+        }
+        for fact in array_field(section, "facts") {
+            card.lines.push(CardLine::Fact {
+                title: clean_markdown(&strip_html(str_field(fact, "name").unwrap_or(""))),
+                value: clean_markdown(&strip_html(str_field(fact, "value").unwrap_or(""))),
+            });
+        }
+        for image in array_field(section, "images") {
+            push_image(card, image, "title");
+        }
+    }
+    for action in array_field(value, "potentialAction") {
+        let kind = str_field(action, "@type").unwrap_or("Action");
+        let title = str_field(action, "name").unwrap_or("").to_string();
+        let uri = array_field(action, "targets")
+            .iter()
+            .find_map(|target| str_field(target, "uri"));
+        match (kind, uri) {
+            ("OpenUri", Some(url)) => push_open_url(card, title, url),
+            _ => card.actions.push(CardAction::Unsupported {
+                title: if title.is_empty() { kind.to_string() } else { title },
+                kind: kind.to_string(),
+            }),
+        }
+    }
+}
+
+// ---- Helpers ----
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+fn array_field<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[])
+}
+
+fn push_text(card: &mut Card, text: &str, emphasis: TextEmphasis) {
+    for line in text.lines() {
+        let line = line.trim_end();
+        if !line.trim().is_empty() {
+            card.lines.push(CardLine::Text { text: line.to_string(), emphasis });
+        }
+    }
+}
+
+fn push_image(card: &mut Card, image: &Value, alt_key: &str) {
+    let alt = str_field(image, alt_key)
+        .filter(|a| !a.trim().is_empty())
+        .unwrap_or("image");
+    card.lines.push(CardLine::Image { alt: alt.to_string() });
+}
+
+/// Only http(s) links are opened from cards; cards come from bots and connectors,
+/// so other schemes (`file:`, `ms-*:`, custom protocol handlers) are refused.
+pub fn is_web_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    lower.starts_with("https://") || lower.starts_with("http://")
+}
+
+fn push_open_url(card: &mut Card, title: String, url: &str) {
+    if !is_web_url(url) {
+        card.actions.push(CardAction::Unsupported {
+            title: if title.is_empty() { url.to_string() } else { title },
+            kind: "Non-web link".to_string(),
+        });
+        return;
+    }
+    card.actions.push(CardAction::OpenUrl {
+        title: if title.is_empty() { url.to_string() } else { title },
+        url: url.to_string(),
+    });
+}
+
+/// Drop `**`/`__` emphasis markers and turn `[text](url)` links into `text`.
+fn clean_markdown(text: &str) -> String {
+    let text = text.replace("**", "").replace("__", "");
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while let Some(open) = rest.find('[') {
+        let after = &rest[open + 1..];
+        let link = after.find("](").and_then(|close| {
+            after[close + 2..].find(')').map(|end| (close, close + 2 + end))
+        });
+        match link {
+            Some((close, end)) => {
+                result.push_str(&rest[..open]);
+                result.push_str(&after[..close]);
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push_str(&rest[..=open]);
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
@@ -1,6 +1,8 @@
 use anyhow::{Context, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
 use zeroize::Zeroize;
 
+use crate::graphics::InlineImage;
 use crate::logging;
 use crate::models::*;
 
@@ -21,6 +23,173 @@ fn append_query_hint(url: &str, key: &str, value: &str) -> String {
     }
 }
 
+/// Encode a sharing URL as a `/shares/{id}` token ("u!" + unpadded base64url)
+pub fn encode_sharing_url(url: &str) -> String {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+    format!("u!{}", URL_SAFE_NO_PAD.encode(url))
+}
+
+/// Graph URL that streams the content of a shared item
+pub fn shared_item_content_url(sharing_url: &str) -> String {
+    format!(
+        "https://graph.microsoft.com/v1.0/shares/{}/driveItem/content",
+        encode_sharing_url(sharing_url)
+    )
+}
+
+/// Graph URL of a channel message, or of a reply when `parent_id` is the
+/// message it replies to
+pub fn channel_message_url(
+    team_id: &str,
+    channel_id: &str,
+    message_id: &str,
+    parent_id: Option<&str>,
+) -> String {
+    let base = format!("https://graph.microsoft.com/v1.0/teams/{}/channels/{}/messages", team_id, channel_id);
+    match parent_id {
+        Some(parent) => format!("{}/{}/replies/{}", base, parent, message_id),
+        None => format!("{}/{}", base, message_id),
+    }
+}
+
+/// Files up to this size are uploaded with a single PUT; larger ones use an upload session
+pub const SIMPLE_UPLOAD_MAX: u64 = 4 * 1024 * 1024;
+
+/// Upload session chunk size; Graph requires a multiple of 320 KiB
+pub const UPLOAD_CHUNK_SIZE: u64 = 320 * 1024 * 16;
+
+/// Attempts per chunk before the upload is abandoned
+const UPLOAD_CHUNK_ATTEMPTS: u32 = 4;
+
+/// Whether a failed delta query means the channel does not support delta
+/// (400, 403 or 501) rather than a failure worth retrying
+pub fn delta_unsupported(err: &anyhow::Error) -> bool {
+    let msg = err.to_string();
+    ["(400 ", "(403 ", "(501 "].iter().any(|status| msg.contains(status))
+}
+
+/// Offset of the next chunk after the server asked for `next`. A session that
+/// does not move past `offset` would have the same bytes sent forever.
+pub fn next_upload_offset(offset: u64, next: u64, total: u64) -> Result<u64> {
+    if next <= offset {
+        anyhow::bail!("Upload session did not advance past byte {}", offset);
+    }
+    Ok(next.min(total))
+}
+
+/// `Content-Range` header value for a chunk of `len` bytes at `offset`
+pub fn content_range(offset: u64, len: u64, total: u64) -> String {
+    format!("bytes {}-{}/{}", offset, offset + len - 1, total)
+}
+
+/// Outcome of uploading one chunk of an upload session
+#[derive(Debug)]
+pub enum ChunkOutcome {
+    /// More bytes are expected, starting at this offset
+    Continue(u64),
+    Complete(Box<DriveItem>),
+}
+
+/// Message body with one `reference` attachment per uploaded file
+pub fn attachment_message_body(items: &[DriveItem]) -> serde_json::Value {
+    let mut content = Vec::new();
+    let mut attachments = Vec::new();
+    for item in items {
+        let attachment_id = uuid::Uuid::new_v4().to_string();
+        content.push(format!(
+            "{} <attachment id=\"{}\"></attachment>",
+            escape_html(&item.name),
+            attachment_id
+        ));
+        attachments.push(serde_json::json!({
+            "id": attachment_id,
+            "contentType": "reference",
+            "contentUrl": item.web_url,
+            "name": item.name
+        }));
+    }
+    serde_json::json!({
+        "body": {
+            "contentType": "html",
+            "content": content.join("<br>")
+        },
+        "attachments": attachments
+    })
+}
+
+/// Message body with an image embedded as hosted content, optionally under a caption
+pub fn inline_image_message_body(caption: &str, image: &InlineImage) -> serde_json::Value {
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    let img = format!(
+        "<img src=\"../hostedContents/1/$value\" width=\"{w}\" height=\"{h}\" style=\"vertical-align:bottom; width:{w}px; height:{h}px\">",
+        w = image.width,
+        h = image.height
+    );
+    let caption = caption.trim();
+    let content = if caption.is_empty() {
+        format!("<p>{}</p>", img)
+    } else {
+        format!("<p>{}</p><p>{}</p>", escape_html(caption), img)
+    };
+    serde_json::json!({
+        "body": {
+            "contentType": "html",
+            "content": content
+        },
+        "hostedContents": [{
+            "@microsoft.graph.temporaryId": "1",
+            "contentBytes": STANDARD.encode(&image.bytes),
+            "contentType": image.content_type
+        }]
+    })
+}
+
+/// Body for forwarding `source`: an optional comment, the original text quoted
+/// with its sender and time, and the original file reference attachments
+pub fn forward_message_body(source: &Message, comment: &str) -> serde_json::Value {
+    let mut content = String::new();
+    let comment = comment.trim();
+    if !comment.is_empty() {
+        content.push_str(&format!("<p>{}</p>", escape_html(comment)));
+    }
+    let mut header = format!("<b>{}</b>", escape_html(&source.sender_name()));
+    let time = source.formatted_date_time();
+    if !time.is_empty() {
+        header.push_str(&format!(" · {}", time));
+    }
+    content.push_str(&format!(
+        "<blockquote><p>{}</p><p>{}</p></blockquote>",
+        header,
+        escape_html(&source.content_text()).replace('\n', "<br>")
+    ));
+
+    let mut attachments = Vec::new();
+    for attachment in source.file_attachments() {
+        let Some(url) = attachment.content_url.as_deref() else {
+            continue;
+        };
+        let id = attachment
+            .id
+            .clone()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let name = attachment.name.clone().unwrap_or_else(|| "attachment".to_string());
+        content.push_str(&format!("<attachment id=\"{}\"></attachment>", id));
+        attachments.push(serde_json::json!({
+            "id": id,
+            "contentType": "reference",
+            "contentUrl": url,
+            "name": name
+        }));
+    }
+    serde_json::json!({
+        "body": {
+            "contentType": "html",
+            "content": content
+        },
+        "attachments": attachments
+    })
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BinaryDownloadFailure {
     Transport,
@@ -32,6 +201,7 @@ pub enum BinaryDownloadFailure {
     HttpOther,
     ReadBody,
     NonImageBody,
+    WriteFile,
 }
 
 impl BinaryDownloadFailure {
@@ -57,6 +227,38 @@ impl BinaryDownloadFailure {
             BinaryDownloadFailure::HttpOther => "image_preview.download.http_other",
             BinaryDownloadFailure::ReadBody => "image_preview.download.read_body",
             BinaryDownloadFailure::NonImageBody => "image_preview.download.non_image",
+            BinaryDownloadFailure::WriteFile => "image_preview.download.write_file",
+        }
+    }
+
+    pub fn as_file_label(self) -> &'static str {
+        match self {
+            BinaryDownloadFailure::Transport => "file_download.transport",
+            BinaryDownloadFailure::Http401 => "file_download.http_401",
+            BinaryDownloadFailure::Http403 => "file_download.http_403",
+            BinaryDownloadFailure::Http404 => "file_download.http_404",
+            BinaryDownloadFailure::Http4xx => "file_download.http_4xx",
+            BinaryDownloadFailure::Http5xx => "file_download.http_5xx",
+            BinaryDownloadFailure::HttpOther => "file_download.http_other",
+            BinaryDownloadFailure::ReadBody => "file_download.read_body",
+            BinaryDownloadFailure::NonImageBody => "file_download.non_image",
+            BinaryDownloadFailure::WriteFile => "file_download.write_file",
+        }
+    }
+
+    /// User-facing explanation for error dialogs
+    pub fn describe(self) -> &'static str {
+        match self {
+            BinaryDownloadFailure::Transport => "Could not reach the server.",
+            BinaryDownloadFailure::Http401 => "Your session has expired (HTTP 401). Restart ttyms to sign in again.",
+            BinaryDownloadFailure::Http403 => "You don't have access to this file (HTTP 403).",
+            BinaryDownloadFailure::Http404 => "The file no longer exists or was moved (HTTP 404).",
+            BinaryDownloadFailure::Http4xx => "The request was rejected by the server.",
+            BinaryDownloadFailure::Http5xx => "The server had a problem; try again later.",
+            BinaryDownloadFailure::HttpOther => "The server returned an unexpected response.",
+            BinaryDownloadFailure::ReadBody => "The download was interrupted.",
+            BinaryDownloadFailure::NonImageBody => "The server did not return an image.",
+            BinaryDownloadFailure::WriteFile => "Could not write the file to the downloads folder.",
         }
     }
 }
@@ -123,6 +325,28 @@ impl GraphClient {
         }
     }
 
+    /// Authenticated GET for binary content, classifying transport and HTTP failures
+    async fn send_binary_request(
+        &self,
+        url: &str,
+        accept: &str,
+    ) -> std::result::Result<reqwest::Response, BinaryDownloadFailure> {
+        let resp = self
+            .client
+            .get(url)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .header("Accept", accept)
+            .send()
+            .await
+            .map_err(|_| BinaryDownloadFailure::Transport)?;
+        let status = resp.status();
+        if !status.is_success() {
+            let _ = resp.text().await;
+            return Err(BinaryDownloadFailure::from_http_status(status.as_u16()));
+        }
+        Ok(resp)
+    }
+
     pub async fn download_binary_with_reason(
         &self,
         url: &str,
@@ -139,26 +363,13 @@ impl GraphClient {
 
         let mut last_failure = BinaryDownloadFailure::Transport;
         for candidate in candidate_urls {
-            let resp = self
-                .client
-                .get(&candidate)
-                .header("Authorization", format!("Bearer {}", self.access_token))
-                .header("Accept", "image/*,*/*;q=0.8")
-                .send()
-                .await;
-            let resp = match resp {
+            let resp = match self.send_binary_request(&candidate, "image/*,*/*;q=0.8").await {
                 Ok(r) => r,
-                Err(_) => {
-                    last_failure = BinaryDownloadFailure::Transport;
+                Err(failure) => {
+                    last_failure = failure;
                     continue;
                 }
             };
-            let status = resp.status();
-            if !status.is_success() {
-                let _ = resp.text().await;
-                last_failure = BinaryDownloadFailure::from_http_status(status.as_u16());
-                continue;
-            }
             let bytes = match resp.bytes().await {
                 Ok(b) => b.to_vec(),
                 Err(_) => {
@@ -175,6 +386,62 @@ impl GraphClient {
         Err(last_failure)
     }
 
+    /// Resolve a SharePoint/OneDrive sharing URL (reference attachment) to its driveItem
+    pub async fn resolve_shared_item(
+        &self,
+        sharing_url: &str,
+    ) -> std::result::Result<DriveItem, BinaryDownloadFailure> {
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/shares/{}/driveItem",
+            encode_sharing_url(sharing_url)
+        );
+        let resp = self.send_binary_request(&url, "application/json").await?;
+        resp.json::<DriveItem>()
+            .await
+            .map_err(|_| BinaryDownloadFailure::ReadBody)
+    }
+
+    /// Stream a file to `path`, reporting (received, total) after each chunk.
+    /// The partially written file is removed on failure.
+    pub async fn download_to_file(
+        &self,
+        url: &str,
+        path: &std::path::Path,
+        mut on_progress: impl FnMut(u64, Option<u64>),
+    ) -> std::result::Result<u64, BinaryDownloadFailure> {
+        use std::io::Write;
+
+        let mut resp = self.send_binary_request(url, "*/*").await?;
+        let total = resp.content_length();
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map_err(|_| BinaryDownloadFailure::WriteFile)?;
+        let mut received = 0u64;
+        let result = loop {
+            match resp.chunk().await {
+                Ok(Some(chunk)) => {
+                    if file.write_all(&chunk).is_err() {
+                        break Err(BinaryDownloadFailure::WriteFile);
+                    }
+                    received += chunk.len() as u64;
+                    on_progress(received, total);
+                }
+                Ok(None) => break file.flush().map_err(|_| BinaryDownloadFailure::WriteFile),
+                Err(_) => break Err(BinaryDownloadFailure::ReadBody),
+            }
+        };
+        drop(file);
+        match result {
+            Ok(()) => Ok(received),
+            Err(failure) => {
+                let _ = std::fs::remove_file(path);
+                Err(failure)
+            }
+        }
+    }
+
     async fn post_json<T: serde::de::DeserializeOwned>(
         &self,
         url: &str,
@@ -530,22 +797,34 @@ impl GraphClient {
         self.post_no_response(&url).await
     }
 
-    pub async fn create_chat(&self, user_email: &str, my_id: &str) -> Result<Chat> {
-        let body = serde_json::json!({
-            "chatType": "oneOnOne",
-            "members": [
-                {
-                    "@odata.type": "#microsoft.graph.aadUserConversationMember",
-                    "roles": ["owner"],
-                    "user@odata.bind": format!("https://graph.microsoft.com/v1.0/users('{}')", my_id)
-                },
-                {
-                    "@odata.type": "#microsoft.graph.aadUserConversationMember",
-                    "roles": ["owner"],
-                    "user@odata.bind": format!("https://graph.microsoft.com/v1.0/users('{}')", user_email)
-                }
-            ]
+    pub async fn undo_soft_delete_message(&self, chat_id: &str, message_id: &str) -> Result<()> {
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/me/chats/{}/messages/{}/undoSoftDelete",
+            chat_id, message_id
+        );
+        self.post_no_response(&url).await
+    }
+
+    /// Create a chat with `members` (user IDs or emails): a 1:1 chat for one
+    /// person, otherwise a group chat with an optional topic
+    pub async fn create_chat(&self, members: &[String], my_id: &str, topic: Option<&str>) -> Result<Chat> {
+        let member = |user: &str| {
+            serde_json::json!({
+                "@odata.type": "#microsoft.graph.aadUserConversationMember",
+                "roles": ["owner"],
+                "user@odata.bind": format!("https://graph.microsoft.com/v1.0/users('{}')", user)
+            })
+        };
+        let mut body = serde_json::json!({
+            "chatType": if members.len() == 1 { "oneOnOne" } else { "group" },
+            "members": std::iter::once(my_id)
+                .chain(members.iter().map(String::as_str))
+                .map(member)
+                .collect::<Vec<_>>()
         });
+        if let Some(topic) = topic.filter(|t| members.len() > 1 && !t.trim().is_empty()) {
+            body["topic"] = serde_json::Value::String(topic.trim().to_string());
+        }
         self.post_json("https://graph.microsoft.com/v1.0/chats", &body)
             .await
     }
@@ -628,7 +907,39 @@ impl GraphClient {
         self.post_no_content(&url, &body).await
     }
 
-    #[allow(dead_code)]
+    /// Pinned messages of a chat, each with its message expanded
+    pub async fn get_pinned_messages(&self, chat_id: &str) -> Result<Vec<PinnedMessage>> {
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/chats/{}/pinnedMessages?$expand=message",
+            chat_id
+        );
+        let resp: PagedResponse<PinnedMessage> = self.get(&url).await?;
+        Ok(resp.value)
+    }
+
+    pub async fn pin_message(&self, chat_id: &str, message_id: &str) -> Result<()> {
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/chats/{}/pinnedMessages",
+            chat_id
+        );
+        let body = serde_json::json!({
+            "message@odata.bind": format!(
+                "https://graph.microsoft.com/v1.0/chats/{}/messages/{}",
+                chat_id, message_id
+            )
+        });
+        self.post_no_content(&url, &body).await
+    }
+
+    /// Unpin by the pinned message ID (not the chat message ID)
+    pub async fn unpin_message(&self, chat_id: &str, pinned_id: &str) -> Result<()> {
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/chats/{}/pinnedMessages/{}",
+            chat_id, pinned_id
+        );
+        self.delete(&url).await
+    }
+
     pub async fn unset_reaction(
         &self,
         chat_id: &str,
@@ -643,6 +954,21 @@ impl GraphClient {
         self.post_no_content(&url, &body).await
     }
 
+    pub async fn unset_channel_reaction(
+        &self,
+        team_id: &str,
+        channel_id: &str,
+        message_id: &str,
+        reaction_type: &str,
+    ) -> Result<()> {
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/teams/{}/channels/{}/messages/{}/unsetReaction",
+            team_id, channel_id, message_id
+        );
+        let body = serde_json::json!({ "reactionType": reaction_type });
+        self.post_no_content(&url, &body).await
+    }
+
     // ---- Presence ----
 
     pub async fn get_my_presence(&self) -> Result<Presence> {
@@ -707,6 +1033,31 @@ impl GraphClient {
         Ok(resp.value)
     }
 
+    /// Files in the channel's SharePoint folder (the channel "Files" tab).
+    /// Returns the drive ID alongside the items so they can be downloaded.
+    pub async fn get_channel_files(
+        &self,
+        team_id: &str,
+        channel_id: &str,
+    ) -> Result<(String, Vec<DriveItem>)> {
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/teams/{}/channels/{}/filesFolder",
+            team_id, channel_id
+        );
+        let folder: DriveItem = self.get(&url).await?;
+        let drive_id = folder
+            .parent_reference
+            .as_ref()
+            .and_then(|r| r.drive_id.clone())
+            .ok_or_else(|| anyhow::anyhow!("Channel files folder has no drive"))?;
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/drives/{}/items/{}/children?$top=200",
+            drive_id, folder.id
+        );
+        let resp: GraphResponse<DriveItem> = self.get(&url).await?;
+        Ok((drive_id, resp.value))
+    }
+
     pub async fn get_channel_messages(
         &self,
         team_id: &str,
@@ -722,6 +1073,57 @@ impl GraphClient {
         Ok((messages, resp.next_link))
     }
 
+    /// Channel messages changed since `delta_link`. Without a link a new delta
+    /// query is started, limited to messages modified after `since`.
+    pub async fn get_channel_messages_delta(
+        &self,
+        team_id: &str,
+        channel_id: &str,
+        delta_link: Option<&str>,
+        since: Option<&str>,
+    ) -> Result<(Vec<Message>, Option<String>)> {
+        let url = match (delta_link, since) {
+            (Some(link), _) => link.to_string(),
+            (None, Some(since)) => format!(
+                "https://graph.microsoft.com/v1.0/teams/{}/channels/{}/messages/delta?$filter=lastModifiedDateTime gt {}",
+                team_id, channel_id, since
+            ),
+            (None, None) => format!(
+                "https://graph.microsoft.com/v1.0/teams/{}/channels/{}/messages/delta",
+                team_id, channel_id
+            ),
+        };
+        let mut all_messages = Vec::new();
+        let mut current_url = url;
+        loop {
+            let resp: DeltaResponse<Message> = self.get(&current_url).await?;
+            all_messages.extend(resp.value);
+            if let Some(next) = resp.next_link {
+                current_url = next;
+            } else {
+                return Ok((all_messages, resp.delta_link));
+            }
+        }
+    }
+
+    /// The newest `top` top-level messages of a channel, oldest first; used to
+    /// poll channels for activity without loading a full page
+    pub async fn get_recent_channel_messages(
+        &self,
+        team_id: &str,
+        channel_id: &str,
+        top: usize,
+    ) -> Result<Vec<Message>> {
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/teams/{}/channels/{}/messages?$top={}",
+            team_id, channel_id, top
+        );
+        let resp: PagedResponse<Message> = self.get(&url).await?;
+        let mut messages = resp.value;
+        messages.reverse();
+        Ok(messages)
+    }
+
     pub async fn send_channel_message(
         &self,
         team_id: &str,
@@ -761,6 +1163,47 @@ impl GraphClient {
         self.post_json(&url, &body).await
     }
 
+    /// Edit a channel message or reply (needs `ChannelMessage.ReadWrite`)
+    pub async fn update_channel_message(
+        &self,
+        team_id: &str,
+        channel_id: &str,
+        message_id: &str,
+        parent_id: Option<&str>,
+        content: &str,
+    ) -> Result<()> {
+        let url = channel_message_url(team_id, channel_id, message_id, parent_id);
+        let body = serde_json::json!({
+            "body": {
+                "content": content,
+                "contentType": "text"
+            }
+        });
+        self.patch_no_content(&url, &body).await
+    }
+
+    pub async fn soft_delete_channel_message(
+        &self,
+        team_id: &str,
+        channel_id: &str,
+        message_id: &str,
+        parent_id: Option<&str>,
+    ) -> Result<()> {
+        let url = channel_message_url(team_id, channel_id, message_id, parent_id);
+        self.post_no_response(&format!("{}/softDelete", url)).await
+    }
+
+    pub async fn undo_soft_delete_channel_message(
+        &self,
+        team_id: &str,
+        channel_id: &str,
+        message_id: &str,
+        parent_id: Option<&str>,
+    ) -> Result<()> {
+        let url = channel_message_url(team_id, channel_id, message_id, parent_id);
+        self.post_no_response(&format!("{}/undoSoftDelete", url)).await
+    }
+
     // ---- File Upload ----
 
     /// Upload a file to OneDrive (Microsoft Teams Chat Files folder) and return the DriveItem
@@ -774,61 +1217,243 @@ impl GraphClient {
             .await
     }
 
-    /// Send a chat message with a file attachment reference
-    pub async fn send_message_with_attachment(
+    /// Send an image inline (as hosted content) to a chat
+    pub async fn send_inline_image(
         &self,
         chat_id: &str,
-        filename: &str,
-        drive_item: &DriveItem,
+        image: &InlineImage,
+        caption: &str,
     ) -> Result<Message> {
         let url = format!(
             "https://graph.microsoft.com/v1.0/me/chats/{}/messages",
             chat_id
         );
-        let attachment_id = uuid::Uuid::new_v4().to_string();
-        let body = serde_json::json!({
-            "body": {
-                "contentType": "html",
-                "content": format!("{} <attachment id=\"{}\"></attachment>", filename, attachment_id)
-            },
-            "attachments": [{
-                "id": attachment_id,
-                "contentType": "reference",
-                "contentUrl": drive_item.web_url,
-                "name": filename
-            }]
-        });
-        self.post_json(&url, &body).await
+        self.post_json(&url, &inline_image_message_body(caption, image)).await
     }
 
-    /// Send a channel message with a file attachment reference
-    pub async fn send_channel_message_with_attachment(
+    /// Send an image inline (as hosted content) to a channel
+    pub async fn send_channel_inline_image(
         &self,
         team_id: &str,
         channel_id: &str,
-        filename: &str,
-        drive_item: &DriveItem,
+        image: &InlineImage,
+        caption: &str,
     ) -> Result<Message> {
         let url = format!(
             "https://graph.microsoft.com/v1.0/teams/{}/channels/{}/messages",
             team_id, channel_id
         );
-        let attachment_id = uuid::Uuid::new_v4().to_string();
+        self.post_json(&url, &inline_image_message_body(caption, image)).await
+    }
+
+    /// Forward a message to a chat, quoting it under an optional comment
+    pub async fn forward_to_chat(&self, chat_id: &str, source: &Message, comment: &str) -> Result<Message> {
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/me/chats/{}/messages",
+            chat_id
+        );
+        self.post_json(&url, &forward_message_body(source, comment)).await
+    }
+
+    /// Forward a message to a channel, quoting it under an optional comment
+    pub async fn forward_to_channel(
+        &self,
+        team_id: &str,
+        channel_id: &str,
+        source: &Message,
+        comment: &str,
+    ) -> Result<Message> {
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/teams/{}/channels/{}/messages",
+            team_id, channel_id
+        );
+        self.post_json(&url, &forward_message_body(source, comment)).await
+    }
+
+    /// Send a chat message referencing one or more uploaded files
+    pub async fn send_message_with_attachments(
+        &self,
+        chat_id: &str,
+        items: &[DriveItem],
+    ) -> Result<Message> {
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/me/chats/{}/messages",
+            chat_id
+        );
+        self.post_json(&url, &attachment_message_body(items)).await
+    }
+
+    /// Send a channel message referencing one or more uploaded files
+    pub async fn send_channel_message_with_attachments(
+        &self,
+        team_id: &str,
+        channel_id: &str,
+        items: &[DriveItem],
+    ) -> Result<Message> {
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/teams/{}/channels/{}/messages",
+            team_id, channel_id
+        );
+        self.post_json(&url, &attachment_message_body(items)).await
+    }
+
+    /// Start a resumable upload into the Teams Chat Files folder
+    pub async fn create_upload_session(&self, filename: &str) -> Result<UploadSession> {
+        let encoded_name = filename.replace('\'', "''");
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/me/drive/root:/Microsoft Teams Chat Files/{}:/createUploadSession",
+            encoded_name
+        );
         let body = serde_json::json!({
-            "body": {
-                "contentType": "html",
-                "content": format!("{} <attachment id=\"{}\"></attachment>", filename, attachment_id)
-            },
-            "attachments": [{
-                "id": attachment_id,
-                "contentType": "reference",
-                "contentUrl": drive_item.web_url,
-                "name": filename
-            }]
+            "item": { "@microsoft.graph.conflictBehavior": "replace" }
         });
         self.post_json(&url, &body).await
     }
 
+    /// PUT one chunk to an upload session. The upload URL is pre-authenticated,
+    /// so no Authorization header is sent.
+    pub async fn upload_chunk(
+        &self,
+        upload_url: &str,
+        bytes: Vec<u8>,
+        offset: u64,
+        total: u64,
+    ) -> Result<ChunkOutcome> {
+        let len = bytes.len() as u64;
+        let resp = self
+            .client
+            .put(upload_url)
+            .header("Content-Length", len)
+            .header("Content-Range", content_range(offset, len, total))
+            .body(bytes)
+            .send()
+            .await
+            .inspect_err(|_| {
+                logging::try_log_failure("graph.upload_chunk.transport");
+            })?;
+        let status = resp.status();
+        if !status.is_success() {
+            logging::try_log_failure("graph.upload_chunk.http");
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Upload chunk failed ({}): {}", status, body);
+        }
+        if status.as_u16() == 202 {
+            let session: UploadSession = resp
+                .json()
+                .await
+                .context("Failed to parse upload session response")?;
+            return Ok(ChunkOutcome::Continue(session.next_offset().unwrap_or(offset + len)));
+        }
+        let item: DriveItem = resp
+            .json()
+            .await
+            .context("Failed to parse uploaded file response")?;
+        logging::try_log_event("graph.upload_chunk.complete");
+        Ok(ChunkOutcome::Complete(Box::new(item)))
+    }
+
+    /// Ask the session which bytes it still needs (used to resume after a failed chunk)
+    pub async fn upload_session_status(&self, upload_url: &str) -> Result<UploadSession> {
+        let resp = self.client.get(upload_url).send().await?;
+        let status = resp.status();
+        if !status.is_success() {
+            logging::try_log_failure("graph.upload_session_status.http");
+            anyhow::bail!("Upload session is no longer available ({})", status);
+        }
+        resp.json()
+            .await
+            .context("Failed to parse upload session status")
+    }
+
+    pub async fn cancel_upload_session(&self, upload_url: &str) -> Result<()> {
+        self.client.delete(upload_url).send().await?;
+        logging::try_log_event("graph.upload_session.cancelled");
+        Ok(())
+    }
+
+    /// Upload a large file in chunks through an upload session. Failed chunks are
+    /// retried with backoff, resuming from the offset the server reports.
+    /// Setting `cancel` aborts the upload and deletes the session.
+    pub async fn upload_large_file(
+        &self,
+        filename: &str,
+        path: &std::path::Path,
+        cancel: &AtomicBool,
+        mut on_progress: impl FnMut(u64, u64),
+    ) -> Result<DriveItem> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let mut file = tokio::fs::File::open(path).await.context("Cannot open file")?;
+        let total = file.metadata().await.context("Cannot read file")?.len();
+        let session = self.create_upload_session(filename).await?;
+        let upload_url = session
+            .upload_url
+            .context("Upload session did not return an upload URL")?;
+
+        let mut offset = 0u64;
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                let _ = self.cancel_upload_session(&upload_url).await;
+                anyhow::bail!("Upload cancelled");
+            }
+            if offset >= total {
+                // Every byte was accepted but no file came back; the session may
+                // already have completed, so it is left alone
+                logging::try_log_failure("graph.upload_large_file.incomplete");
+                anyhow::bail!("Upload ended without the server returning the file");
+            }
+            let len = UPLOAD_CHUNK_SIZE.min(total - offset);
+            let mut chunk = vec![0u8; len as usize];
+            file.seek(std::io::SeekFrom::Start(offset))
+                .await
+                .context("Cannot read file")?;
+            file.read_exact(&mut chunk).await.context("Cannot read file")?;
+
+            let mut attempt = 0;
+            let outcome = loop {
+                attempt += 1;
+                match self.upload_chunk(&upload_url, chunk.clone(), offset, total).await {
+                    Ok(outcome) => break outcome,
+                    Err(e) if attempt >= UPLOAD_CHUNK_ATTEMPTS || cancel.load(Ordering::Relaxed) => {
+                        let _ = self.cancel_upload_session(&upload_url).await;
+                        return Err(e);
+                    }
+                    Err(_) => {
+                        logging::try_log_failure("graph.upload_chunk.retry");
+                        tokio::time::sleep(std::time::Duration::from_secs(1 << attempt)).await;
+                        // The chunk may have partially landed; resume where the server says
+                        if let Some(next) = self
+                            .upload_session_status(&upload_url)
+                            .await
+                            .ok()
+                            .and_then(|s| s.next_offset())
+                        {
+                            if next > offset {
+                                break ChunkOutcome::Continue(next);
+                            }
+                        }
+                    }
+                }
+            };
+            match outcome {
+                ChunkOutcome::Continue(next) => match next_upload_offset(offset, next, total) {
+                    Ok(next) => {
+                        offset = next;
+                        on_progress(offset, total);
+                    }
+                    Err(e) => {
+                        let _ = self.cancel_upload_session(&upload_url).await;
+                        return Err(e);
+                    }
+                },
+                ChunkOutcome::Complete(item) => {
+                    on_progress(total, total);
+                    return Ok(*item);
+                }
+            }
+        }
+    }
+
     // ---- Search ----
 
     pub async fn search_messages(&self, query: &str) -> Result<Vec<SearchHit>> {
@@ -854,6 +1479,17 @@ impl GraphClient {
 
     // ---- Read receipts ----
 
+    /// Hide or unhide a chat for the signed-in user (`hideForUser` / `unhideForUser`)
+    pub async fn set_chat_hidden(&self, chat_id: &str, user_id: &str, hidden: bool) -> Result<()> {
+        let action = if hidden { "hideForUser" } else { "unhideForUser" };
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/chats/{}/{}",
+            chat_id, action
+        );
+        let body = serde_json::json!({ "user": { "id": user_id } });
+        self.post_no_content(&url, &body).await
+    }
+
     pub async fn mark_chat_read(&self, chat_id: &str, user_id: &str) -> Result<()> {
         let url = format!(
             "https://graph.microsoft.com/v1.0/me/chats/{}/markChatReadForUser",
@@ -873,9 +1509,8 @@ impl GraphClient {
             .json(&body)
             .send()
             .await
-            .map_err(|e| {
+            .inspect_err(|_| {
                 logging::try_log_failure("graph.mark_chat_read.transport");
-                e
             })?;
         let status = resp.status();
         if !status.is_success() && status.as_u16() != 204 {
@@ -886,6 +1521,20 @@ impl GraphClient {
         logging::try_log_event("graph.mark_chat_read.success");
         Ok(())
     }
+
+    /// Mark a chat unread for the user. With `last_read`, messages after that
+    /// time become unread; otherwise Graph marks the latest message unread.
+    pub async fn mark_chat_unread(&self, chat_id: &str, user_id: &str, last_read: Option<&str>) -> Result<()> {
+        let url = format!(
+            "https://graph.microsoft.com/v1.0/me/chats/{}/markChatUnreadForUser",
+            chat_id
+        );
+        let mut body = serde_json::json!({ "user": { "id": user_id } });
+        if let Some(last_read) = last_read {
+            body["lastMessageReadDateTime"] = serde_json::Value::String(last_read.to_string());
+        }
+        self.post_no_content(&url, &body).await
+    }
 }
 
 impl Drop for GraphClient {
@@ -896,9 +1545,27 @@ impl Drop for GraphClient {
 
 #[cfg(test)]
 mod tests {
-    use super::{append_query_hint, looks_like_image_bytes, BinaryDownloadFailure};
+    use super::{
+        append_query_hint, attachment_message_body, channel_message_url, content_range, delta_unsupported, encode_sharing_url,
+        forward_message_body, inline_image_message_body, looks_like_image_bytes, next_upload_offset, BinaryDownloadFailure,
+        UPLOAD_CHUNK_SIZE,
+    };
+    use crate::graphics::InlineImage;
+    use crate::models::{ChatMessageAttachment, DriveItem, Message, MessageBody, MessageFrom, MessageUser};
     use crate::logging::is_safe_event_label;
 
+    #[test]
+    fn channel_message_url_for_messages_and_replies() {
+        assert_eq!(
+            channel_message_url("t1", "19:c1", "m1", None),
+            "https://graph.microsoft.com/v1.0/teams/t1/channels/19:c1/messages/m1"
+        );
+        assert_eq!(
+            channel_message_url("t1", "19:c1", "r1", Some("m1")),
+            "https://graph.microsoft.com/v1.0/teams/t1/channels/19:c1/messages/m1/replies/r1"
+        );
+    }
+
     #[test]
     fn detects_png_signature() {
         let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 1];
@@ -935,7 +1602,141 @@ mod tests {
             BinaryDownloadFailure::HttpOther.as_label(),
             BinaryDownloadFailure::ReadBody.as_label(),
             BinaryDownloadFailure::NonImageBody.as_label(),
+            BinaryDownloadFailure::WriteFile.as_label(),
         ];
         assert!(labels.into_iter().all(is_safe_event_label));
     }
+
+    #[test]
+    fn file_download_labels_are_safe() {
+        let labels = [
+            BinaryDownloadFailure::Transport.as_file_label(),
+            BinaryDownloadFailure::Http401.as_file_label(),
+            BinaryDownloadFailure::Http403.as_file_label(),
+            BinaryDownloadFailure::Http404.as_file_label(),
+            BinaryDownloadFailure::Http4xx.as_file_label(),
+            BinaryDownloadFailure::Http5xx.as_file_label(),
+            BinaryDownloadFailure::HttpOther.as_file_label(),
+            BinaryDownloadFailure::ReadBody.as_file_label(),
+            BinaryDownloadFailure::NonImageBody.as_file_label(),
+            BinaryDownloadFailure::WriteFile.as_file_label(),
+        ];
+        assert!(labels.into_iter().all(is_safe_event_label));
+    }
+
+    #[test]
+    fn only_permanent_delta_errors_are_unsupported() {
+        let err = |msg: &str| anyhow::anyhow!(msg.to_string());
+        assert!(delta_unsupported(&err("Graph API error (400 Bad Request): {}")));
+        assert!(delta_unsupported(&err("Graph API error (403 Forbidden): {}")));
+        assert!(delta_unsupported(&err("Graph API error (501 Not Implemented): {}")));
+        assert!(!delta_unsupported(&err("Graph API error (429 Too Many Requests): {}")));
+        assert!(!delta_unsupported(&err("error sending request")));
+    }
+
+    #[test]
+    fn upload_offset_must_move_forward() {
+        assert_eq!(next_upload_offset(0, 10, 100).unwrap(), 10);
+        assert_eq!(next_upload_offset(90, 120, 100).unwrap(), 100);
+        assert!(next_upload_offset(10, 10, 100).is_err());
+        assert!(next_upload_offset(10, 5, 100).is_err());
+    }
+
+    #[test]
+    fn content_range_is_inclusive() {
+        assert_eq!(content_range(0, 10, 100), "bytes 0-9/100");
+        assert_eq!(content_range(90, 10, 100), "bytes 90-99/100");
+    }
+
+    #[test]
+    fn chunk_size_is_multiple_of_320_kib() {
+        assert_eq!(UPLOAD_CHUNK_SIZE % (320 * 1024), 0);
+    }
+
+    #[test]
+    fn inline_image_body_references_hosted_content() {
+        let image = InlineImage { content_type: "image/png", bytes: b"abc".to_vec(), width: 40, height: 20 };
+        let body = inline_image_message_body("  a <b>caption</b> ", &image);
+        let content = body["body"]["content"].as_str().unwrap();
+        assert!(content.starts_with("<p>a &lt;b&gt;caption&lt;/b&gt;</p>"));
+        assert!(content.contains("src=\"../hostedContents/1/$value\" width=\"40\" height=\"20\""));
+        let hosted = &body["hostedContents"][0];
+        assert_eq!(hosted["@microsoft.graph.temporaryId"], "1");
+        assert_eq!(hosted["contentBytes"], "YWJj");
+        assert_eq!(hosted["contentType"], "image/png");
+
+        let body = inline_image_message_body("", &image);
+        assert!(body["body"]["content"].as_str().unwrap().starts_with("<p><img "));
+    }
+
+    #[test]
+    fn attachment_body_escapes_file_names() {
+        let item: DriveItem = serde_json::from_value(serde_json::json!({
+            "id": "i1",
+            "name": "<img src=x onerror=alert(1)>.txt",
+            "webUrl": "https://contoso.sharepoint.com/f.txt"
+        }))
+        .unwrap();
+        let body = attachment_message_body(&[item]);
+        let content = body["body"]["content"].as_str().unwrap();
+        assert!(content.starts_with("&lt;img src=x onerror=alert(1)&gt;.txt <attachment id=\""));
+        assert_eq!(body["attachments"][0]["name"], "<img src=x onerror=alert(1)>.txt");
+    }
+
+    #[test]
+    fn forward_body_quotes_sender_and_keeps_file_references() {
+        let source = Message {
+            id: "m1".to_string(),
+            message_type: Some("message".to_string()),
+            body: Some(MessageBody {
+                content: Some("<p>Q3 numbers &amp; plan</p><attachment id=\"a1\"></attachment>".to_string()),
+                content_type: Some("html".to_string()),
+            }),
+            from: Some(MessageFrom {
+                user: Some(MessageUser { display_name: Some("Alice".to_string()), id: Some("u1".to_string()) }),
+                application: None,
+            }),
+            created_date_time: None,
+            reactions: None,
+            attachments: vec![
+                ChatMessageAttachment {
+                    id: Some("a1".to_string()),
+                    content_type: Some("reference".to_string()),
+                    content_url: Some("https://contoso.sharepoint.com/q3.xlsx".to_string()),
+                    name: Some("q3.xlsx".to_string()),
+                    content: None,
+                },
+                ChatMessageAttachment {
+                    id: Some("card".to_string()),
+                    content_type: Some("application/vnd.microsoft.card.adaptive".to_string()),
+                    content_url: None,
+                    name: None,
+                    content: Some("{}".to_string()),
+                },
+            ],
+            event_detail: None,
+            deleted_date_time: None,
+        };
+        let body = forward_message_body(&source, "FYI <team>");
+        let content = body["body"]["content"].as_str().unwrap();
+        assert!(content.starts_with("<p>FYI &lt;team&gt;</p><blockquote><p><b>Alice</b></p>"));
+        assert!(content.contains("<p>Q3 numbers &amp; plan</p></blockquote>"));
+        assert!(content.ends_with("<attachment id=\"a1\"></attachment>"));
+        let attachments = body["attachments"].as_array().unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0]["contentUrl"], "https://contoso.sharepoint.com/q3.xlsx");
+        assert_eq!(attachments[0]["name"], "q3.xlsx");
+
+        let body = forward_message_body(&source, "  ");
+        assert!(body["body"]["content"].as_str().unwrap().starts_with("<blockquote>"));
+    }
+
+    #[test]
+    fn encodes_sharing_url_as_unpadded_base64url() {
+        // Example from the Graph "Accessing shared DriveItems" documentation
+        assert_eq!(
+            encode_sharing_url("https://onedrive.live.com/redir?resid=1231244193912!12&authKey=1201919!12921!1"),
+            "u!aHR0cHM6Ly9vbmVkcml2ZS5saXZlLmNvbS9yZWRpcj9yZXNpZD0xMjMxMjQ0MTkzOTEyITEyJmF1dGhLZXk9MTIwMTkxOSExMjkyMSEx"
+        );
+    }
 }
@@ -0,0 +1,74 @@
+//! Reads image data from the system clipboard through the platform's
+//! command-line tools (wl-paste, xclip, pngpaste, PowerShell).
+
+use std::process::Command;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardTool {
+    pub program: &'static str,
+    pub args: &'static [&'static str],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardError {
+    /// None of the clipboard tools for this platform is installed
+    NoTool,
+    /// A tool ran but the clipboard holds no image
+    NoImage,
+}
+
+impl ClipboardError {
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            ClipboardError::NoTool => "clipboard.image.no_tool",
+            ClipboardError::NoImage => "clipboard.image.empty",
+        }
+    }
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            ClipboardError::NoTool => {
+                "No clipboard tool found. Install wl-clipboard (Wayland), xclip (X11) or pngpaste (macOS)."
+            }
+            ClipboardError::NoImage => "The clipboard does not contain an image.",
+        }
+    }
+}
+
+const WINDOWS_CLIPBOARD_SCRIPT: &str = "Add-Type -AssemblyName System.Windows.Forms; \
+    $img = [System.Windows.Forms.Clipboard]::GetImage(); \
+    if ($img) { $ms = New-Object System.IO.MemoryStream; \
+    $img.Save($ms, [System.Drawing.Imaging.ImageFormat]::Png); \
+    $out = [Console]::OpenStandardOutput(); $out.Write($ms.ToArray(), 0, $ms.Length) }";
+
+/// Clipboard tools to try, in order, for the given OS (`std::env::consts::OS`)
+pub fn image_tools(os: &str, wayland: bool) -> Vec<ClipboardTool> {
+    let wl_paste = ClipboardTool { program: "wl-paste", args: &["--no-newline", "--type", "image/png"] };
+    let xclip = ClipboardTool { program: "xclip", args: &["-selection", "clipboard", "-t", "image/png", "-o"] };
+    match os {
+        "macos" => vec![ClipboardTool { program: "pngpaste", args: &["-"] }],
+        "windows" => vec![ClipboardTool {
+            program: "powershell",
+            args: &["-NoProfile", "-NonInteractive", "-Command", WINDOWS_CLIPBOARD_SCRIPT],
+        }],
+        _ if wayland => vec![wl_paste, xclip],
+        _ => vec![xclip, wl_paste],
+    }
+}
+
+/// Read an image from the clipboard. Blocking: run it off the UI task.
+pub fn read_image() -> Result<Vec<u8>, ClipboardError> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let mut result = Err(ClipboardError::NoTool);
+    for tool in image_tools(std::env::consts::OS, wayland) {
+        match Command::new(tool.program).args(tool.args).output() {
+            Ok(output) if output.status.success() && !output.stdout.is_empty() => {
+                return Ok(output.stdout);
+            }
+            Ok(_) => result = Err(ClipboardError::NoImage),
+            // Not installed: try the next tool
+            Err(_) => {}
+        }
+    }
+    result
+}
@@ -1,7 +1,10 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::state::ChatPrefs;
+
 /// Default Azure AD client ID for ttyms
 pub const DEFAULT_CLIENT_ID: &str = "ac138a64-055b-4915-b670-31200c6235e6";
 
@@ -11,12 +14,123 @@ pub struct Config {
     pub tenant_id: String,
     #[serde(default = "default_refresh_interval")]
     pub refresh_interval_secs: u64,
+    /// Image output: auto, kitty, iterm2, sixel, truecolor or grayscale
+    #[serde(default = "default_image_protocol")]
+    pub image_protocol: String,
+    /// Where attachments are saved; empty = the system Downloads folder
+    #[serde(default)]
+    pub download_dir: String,
+    /// Open downloaded files with the system's default application
+    #[serde(default)]
+    pub open_after_download: bool,
+    /// When an opened chat is marked read: "view", "manual" or a delay in seconds
+    #[serde(default = "default_mark_read")]
+    pub mark_read: String,
+    /// Ask before deleting a message
+    #[serde(default = "default_confirm_delete")]
+    pub confirm_delete: bool,
+    /// Request `ChannelMessage.ReadWrite` at sign-in so your channel messages can be edited and deleted
+    #[serde(default)]
+    pub channel_message_write: bool,
+    /// Sections of the chat list, in display order; empty = one flat list
+    #[serde(default)]
+    pub chat_sections: Vec<ChatSectionConfig>,
+
+    // Runtime state older versions kept here; read once to migrate it to the
+    // state file and dropped the next time the config is saved
+    #[serde(default, skip_serializing)]
+    pub skin_tone: usize,
+    #[serde(default, skip_serializing)]
+    pub recent_emojis: Vec<String>,
+    #[serde(default, skip_serializing)]
+    pub chat_prefs: HashMap<String, ChatPrefs>,
+    #[serde(default, skip_serializing)]
+    pub channel_last_read: HashMap<String, HashMap<String, String>>,
+}
+
+/// A `[[chat_sections]]` entry. A chat joins the first section that lists its
+/// ID or whose rules all match; rules that are left out are not checked.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChatSectionConfig {
+    pub name: String,
+    /// Explicit chat IDs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chats: Vec<String>,
+    /// `oneOnOne`, `group` or `meeting`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chat_types: Vec<String>,
+    /// Member display names (case-insensitive substring) or user IDs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<String>,
+    /// Regular expression matched against the chat's topic or display name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+    /// Only chats with (true) or without (false) unread messages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unread: Option<bool>,
+    /// Start collapsed
+    #[serde(default)]
+    pub collapsed: bool,
 }
 
 fn default_refresh_interval() -> u64 {
     15
 }
 
+fn default_image_protocol() -> String {
+    "auto".to_string()
+}
+
+fn default_mark_read() -> String {
+    "view".to_string()
+}
+
+fn default_confirm_delete() -> bool {
+    true
+}
+
+/// When an opened chat is marked read (`mark_read` setting)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarkReadMode {
+    #[default]
+    OnView,
+    /// After the chat has stayed open this many seconds
+    After(u64),
+    Manual,
+}
+
+impl MarkReadMode {
+    /// "view", "manual" or seconds such as "5" / "5s"; 0 seconds means on view
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        match value.as_str() {
+            "" | "view" | "on view" => Some(Self::OnView),
+            "manual" | "never" => Some(Self::Manual),
+            _ => match value.trim_end_matches('s').trim().parse::<u64>().ok()? {
+                0 => Some(Self::OnView),
+                secs => Some(Self::After(secs)),
+            },
+        }
+    }
+
+    /// Value as written to `config.toml`
+    pub fn as_setting(&self) -> String {
+        match self {
+            Self::OnView => "view".to_string(),
+            Self::After(secs) => secs.to_string(),
+            Self::Manual => "manual".to_string(),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::OnView => "on view".to_string(),
+            Self::After(secs) => format!("after {}s", secs),
+            Self::Manual => "manual".to_string(),
+        }
+    }
+}
+
 pub fn config_dir() -> Result<PathBuf> {
     let dir = dirs::config_dir()
         .context("Could not determine config directory")?
@@ -46,6 +160,42 @@ tenant_id = "common"
 
 # Auto-refresh interval in seconds (minimum: 5)
 refresh_interval_secs = 15
+
+# Image rendering: "auto" (detect), "kitty", "iterm2", "sixel", "truecolor" or "grayscale"
+image_protocol = "auto"
+
+# Folder for downloaded attachments (empty = system Downloads folder)
+download_dir = ""
+
+# Open downloaded files with the default application
+open_after_download = false
+
+# When an opened chat is marked read: "view", "manual" or seconds, e.g. "5"
+mark_read = "view"
+
+# Ask before deleting a message (deletes can be undone with z for a few seconds)
+confirm_delete = true
+
+# Edit and delete your channel messages. Adds the ChannelMessage.ReadWrite
+# permission at sign-in, which most organizations must approve first
+channel_message_write = false
+
+# Chat list sections, in display order. A chat joins the first section that
+# lists its ID or whose rules all match; the rest are shown under "Other".
+# [[chat_sections]]
+# name = "Favorites"
+# chats = ["19:abc123@thread.v2"]
+#
+# [[chat_sections]]
+# name = "Meetings"
+# chat_types = ["meeting"]      # oneOnOne, group, meeting
+#
+# [[chat_sections]]
+# name = "Bots"
+# topic = "(?i)bot|alerts"      # regex on the chat name
+# members = ["Build Agent"]     # member name or user ID
+# unread = true                 # only chats with unread messages
+# collapsed = true              # start collapsed
 "#,
             DEFAULT_CLIENT_ID
         );
@@ -82,6 +232,9 @@ pub fn print_setup_guide() {
     eprintln!("       ChatMessage.Read, ChatMessage.Send,");
     eprintln!("       Presence.Read, Presence.ReadWrite,");
     eprintln!("       Team.ReadBasic.All, Channel.ReadBasic.All,");
-    eprintln!("       ChannelMessage.Read.All, ChannelMessage.Send");
+    eprintln!("       ChannelMessage.Read.All, ChannelMessage.Send,");
+    eprintln!("       Files.ReadWrite, Files.Read.All");
+    eprintln!("       Optional: ChannelMessage.ReadWrite (admin consent) to edit/delete channel messages,");
+    eprintln!("       then set channel_message_write = true");
     eprintln!("  8. Copy the 'Application (client) ID' to your config file");
 }
@@ -0,0 +1,108 @@
+//! Attachment downloads: target directory, safe file names and progress display.
+
+use std::path::{Path, PathBuf};
+
+/// Resolve the configured downloads directory. An empty setting means the
+/// platform Downloads folder (falling back to `~/Downloads`); `~/` is expanded.
+pub fn download_dir(setting: &str) -> Option<PathBuf> {
+    let setting = setting.trim();
+    if setting.is_empty() {
+        return dirs::download_dir().or_else(|| dirs::home_dir().map(|h| h.join("Downloads")));
+    }
+    if setting == "~" {
+        return dirs::home_dir();
+    }
+    if let Some(rest) = setting.strip_prefix("~/") {
+        return dirs::home_dir().map(|h| h.join(rest));
+    }
+    Some(PathBuf::from(setting))
+}
+
+/// Strip path separators and control characters so a remote file name can't
+/// escape the downloads directory.
+pub fn sanitize_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned.trim().trim_start_matches('.').trim();
+    if cleaned.is_empty() {
+        "download".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// First path in `dir` for `name` that doesn't exist yet: `report.pdf`,
+/// `report (1).pdf`, `report (2).pdf`, ...
+pub fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    unique_path_with(dir, name, |p| p.exists())
+}
+
+pub fn unique_path_with(dir: &Path, name: &str, exists: impl Fn(&Path) -> bool) -> PathBuf {
+    let candidate = dir.join(name);
+    if !exists(&candidate) {
+        return candidate;
+    }
+    let (stem, ext) = match name.rfind('.') {
+        Some(dot) if dot > 0 => (&name[..dot], &name[dot..]),
+        _ => (name, ""),
+    };
+    (1..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, ext)))
+        .find(|p| !exists(p))
+        .unwrap_or(candidate)
+}
+
+/// Human-readable byte count (B, KB, MB, GB)
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadProgress {
+    pub name: String,
+    pub received: u64,
+    pub total: Option<u64>,
+}
+
+impl DownloadProgress {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), received: 0, total: None }
+    }
+
+    pub fn percent(&self) -> Option<u64> {
+        self.total
+            .filter(|t| *t > 0)
+            .map(|t| (self.received.min(t) * 100) / t)
+    }
+
+    /// Status-bar label, e.g. `⬇ report.pdf 45% (1.2 MB / 2.6 MB)`
+    pub fn label(&self) -> String {
+        match (self.percent(), self.total) {
+            (Some(pct), Some(total)) => format!(
+                "⬇ {} {}% ({} / {})",
+                self.name,
+                pct,
+                format_bytes(self.received),
+                format_bytes(total)
+            ),
+            _ => format!("⬇ {} {}", self.name, format_bytes(self.received)),
+        }
+    }
+}
@@ -0,0 +1,827 @@
+//! Bundled emoji table shared by the reaction picker and the composer's
+//! `:shortcode:` completion: shortcodes, search keywords, categories and skin tones.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Smileys,
+    People,
+    Animals,
+    Food,
+    Activities,
+    Travel,
+    Objects,
+    Symbols,
+    Flags,
+}
+
+impl Category {
+    pub const ALL: [Category; 9] = [
+        Category::Smileys,
+        Category::People,
+        Category::Animals,
+        Category::Food,
+        Category::Activities,
+        Category::Travel,
+        Category::Objects,
+        Category::Symbols,
+        Category::Flags,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Category::Smileys => "Smileys",
+            Category::People => "People",
+            Category::Animals => "Animals & Nature",
+            Category::Food => "Food & Drink",
+            Category::Activities => "Activities",
+            Category::Travel => "Travel & Places",
+            Category::Objects => "Objects",
+            Category::Symbols => "Symbols",
+            Category::Flags => "Flags",
+        }
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            Category::Smileys => "😀",
+            Category::People => "👋",
+            Category::Animals => "🐶",
+            Category::Food => "🍕",
+            Category::Activities => "⚽",
+            Category::Travel => "✈️",
+            Category::Objects => "💡",
+            Category::Symbols => "❤️",
+            Category::Flags => "🏁",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Emoji {
+    pub emoji: &'static str,
+    /// Slack/GitHub-style shortcode without colons, e.g. `thumbsup`
+    pub shortcode: &'static str,
+    /// Extra space-separated search words
+    pub keywords: &'static str,
+    pub category: Category,
+    /// Accepts a skin tone modifier
+    pub tones: bool,
+}
+
+const fn e(emoji: &'static str, shortcode: &'static str, keywords: &'static str, category: Category, tones: bool) -> Emoji {
+    Emoji { emoji, shortcode, keywords, category, tones }
+}
+
+use Category::*;
+
+pub static EMOJIS: &[Emoji] = &[
+    // Smileys
+    e("😀", "grinning", "smile happy", Smileys, false),
+    e("😃", "smiley", "happy joy", Smileys, false),
+    e("😄", "smile", "happy joy laugh", Smileys, false),
+    e("😁", "grin", "happy teeth", Smileys, false),
+    e("😆", "laughing", "happy haha", Smileys, false),
+    e("😅", "sweat_smile", "relief nervous", Smileys, false),
+    e("🤣", "rofl", "laugh floor lol", Smileys, false),
+    e("😂", "joy", "laugh tears lol", Smileys, false),
+    e("🙂", "slightly_smiling_face", "smile", Smileys, false),
+    e("🙃", "upside_down_face", "silly sarcasm", Smileys, false),
+    e("😉", "wink", "flirt", Smileys, false),
+    e("😊", "blush", "smile happy", Smileys, false),
+    e("😇", "innocent", "angel halo", Smileys, false),
+    e("🥰", "smiling_face_with_three_hearts", "love crush", Smileys, false),
+    e("😍", "heart_eyes", "love crush", Smileys, false),
+    e("🤩", "star_struck", "wow excited", Smileys, false),
+    e("😘", "kissing_heart", "love kiss", Smileys, false),
+    e("😋", "yum", "tasty delicious", Smileys, false),
+    e("😛", "stuck_out_tongue", "silly", Smileys, false),
+    e("😜", "stuck_out_tongue_winking_eye", "silly joke", Smileys, false),
+    e("🤪", "zany_face", "crazy goofy", Smileys, false),
+    e("🤑", "money_mouth_face", "rich dollar", Smileys, false),
+    e("🤗", "hugs", "hug thanks", Smileys, false),
+    e("🤭", "hand_over_mouth", "oops giggle", Smileys, false),
+    e("🤫", "shushing_face", "quiet secret", Smileys, false),
+    e("🤔", "thinking", "hmm think consider", Smileys, false),
+    e("🤐", "zipper_mouth_face", "secret quiet", Smileys, false),
+    e("🤨", "raised_eyebrow", "skeptic doubt", Smileys, false),
+    e("😐", "neutral_face", "meh", Smileys, false),
+    e("😑", "expressionless", "blank", Smileys, false),
+    e("😶", "no_mouth", "silent", Smileys, false),
+    e("😏", "smirk", "smug", Smileys, false),
+    e("😒", "unamused", "meh annoyed", Smileys, false),
+    e("🙄", "roll_eyes", "eyeroll whatever", Smileys, false),
+    e("😬", "grimacing", "awkward yikes", Smileys, false),
+    e("😌", "relieved", "calm", Smileys, false),
+    e("😔", "pensive", "sad thoughtful", Smileys, false),
+    e("😪", "sleepy", "tired", Smileys, false),
+    e("😴", "sleeping", "zzz tired", Smileys, false),
+    e("😷", "mask", "sick ill", Smileys, false),
+    e("🤒", "face_with_thermometer", "sick fever", Smileys, false),
+    e("🤢", "nauseated_face", "sick gross", Smileys, false),
+    e("🤧", "sneezing_face", "sick cold", Smileys, false),
+    e("🥵", "hot_face", "heat sweating", Smileys, false),
+    e("🥶", "cold_face", "freezing", Smileys, false),
+    e("😵", "dizzy_face", "confused", Smileys, false),
+    e("🤯", "exploding_head", "mind blown shocked", Smileys, false),
+    e("🥳", "partying_face", "party celebrate", Smileys, false),
+    e("😎", "sunglasses", "cool", Smileys, false),
+    e("🤓", "nerd_face", "geek", Smileys, false),
+    e("🧐", "monocle_face", "inspect curious", Smileys, false),
+    e("😕", "confused", "unsure", Smileys, false),
+    e("😟", "worried", "concern", Smileys, false),
+    e("🙁", "slightly_frowning_face", "sad", Smileys, false),
+    e("😮", "open_mouth", "surprised wow", Smileys, false),
+    e("😯", "hushed", "surprised", Smileys, false),
+    e("😲", "astonished", "shocked wow", Smileys, false),
+    e("😳", "flushed", "embarrassed", Smileys, false),
+    e("🥺", "pleading_face", "please puppy", Smileys, false),
+    e("😦", "frowning", "sad", Smileys, false),
+    e("😨", "fearful", "scared", Smileys, false),
+    e("😰", "cold_sweat", "nervous", Smileys, false),
+    e("😥", "disappointed_relieved", "phew", Smileys, false),
+    e("😢", "cry", "sad tear", Smileys, false),
+    e("😭", "sob", "cry sad", Smileys, false),
+    e("😱", "scream", "shocked horror", Smileys, false),
+    e("😖", "confounded", "frustrated", Smileys, false),
+    e("😣", "persevere", "struggle", Smileys, false),
+    e("😞", "disappointed", "sad", Smileys, false),
+    e("😓", "sweat", "hard work", Smileys, false),
+    e("😩", "weary", "tired", Smileys, false),
+    e("😫", "tired_face", "exhausted", Smileys, false),
+    e("🥱", "yawning_face", "bored tired", Smileys, false),
+    e("😤", "triumph", "proud huff", Smileys, false),
+    e("😡", "rage", "angry mad", Smileys, false),
+    e("😠", "angry", "mad annoyed", Smileys, false),
+    e("🤬", "cursing_face", "swear angry", Smileys, false),
+    e("😈", "smiling_imp", "devil evil", Smileys, false),
+    e("💀", "skull", "dead dying lol", Smileys, false),
+    e("💩", "poop", "hankey", Smileys, false),
+    e("🤡", "clown_face", "clown", Smileys, false),
+    e("👻", "ghost", "boo halloween", Smileys, false),
+    e("👽", "alien", "ufo", Smileys, false),
+    e("🤖", "robot", "bot", Smileys, false),
+    e("😺", "smiley_cat", "cat happy", Smileys, false),
+    e("🙈", "see_no_evil", "monkey shy", Smileys, false),
+    e("🙉", "hear_no_evil", "monkey", Smileys, false),
+    e("🙊", "speak_no_evil", "monkey oops", Smileys, false),
+    // People
+    e("👋", "wave", "hello hi bye", People, true),
+    e("🤚", "raised_back_of_hand", "hand", People, true),
+    e("✋", "raised_hand", "hand stop high five", People, true),
+    e("🖖", "vulcan_salute", "spock", People, true),
+    e("👌", "ok_hand", "ok perfect", People, true),
+    e("🤌", "pinched_fingers", "italian", People, true),
+    e("🤏", "pinching_hand", "small little", People, true),
+    e("✌️", "v", "peace victory", People, true),
+    e("🤞", "crossed_fingers", "luck hope", People, true),
+    e("🤟", "love_you_gesture", "ily", People, true),
+    e("🤘", "metal", "rock horns", People, true),
+    e("🤙", "call_me_hand", "shaka", People, true),
+    e("👈", "point_left", "left", People, true),
+    e("👉", "point_right", "right", People, true),
+    e("👆", "point_up_2", "up", People, true),
+    e("👇", "point_down", "down", People, true),
+    e("☝️", "point_up", "one", People, true),
+    e("👍", "thumbsup", "+1 like yes approve", People, true),
+    e("👎", "thumbsdown", "-1 no dislike", People, true),
+    e("✊", "fist", "power", People, true),
+    e("👊", "punch", "fist bump", People, true),
+    e("👏", "clap", "applause bravo", People, true),
+    e("🙌", "raised_hands", "hooray celebrate", People, true),
+    e("👐", "open_hands", "hug", People, true),
+    e("🤲", "palms_up_together", "prayer", People, true),
+    e("🤝", "handshake", "deal agreement", People, false),
+    e("🙏", "pray", "please thanks hope", People, true),
+    e("✍️", "writing_hand", "write", People, true),
+    e("💪", "muscle", "strong flex", People, true),
+    e("🧠", "brain", "smart", People, false),
+    e("👀", "eyes", "look see watching", People, false),
+    e("👁️", "eye", "look", People, false),
+    e("👶", "baby", "child", People, true),
+    e("🧑", "adult", "person", People, true),
+    e("👩", "woman", "lady", People, true),
+    e("👨", "man", "guy", People, true),
+    e("🧓", "older_adult", "elder", People, true),
+    e("🙋", "raising_hand", "question me", People, true),
+    e("🤷", "shrug", "dunno whatever", People, true),
+    e("🤦", "facepalm", "ugh", People, true),
+    e("🙇", "bow", "sorry respect", People, true),
+    e("💁", "tipping_hand_person", "info sassy", People, true),
+    e("🙅", "no_good", "no stop", People, true),
+    e("🙆", "ok_person", "ok yes", People, true),
+    e("🧑‍💻", "technologist", "developer coder programmer", People, true),
+    e("🧑‍🏫", "teacher", "school", People, true),
+    e("🕺", "man_dancing", "dance party", People, true),
+    e("💃", "dancer", "dance party", People, true),
+    e("🏃", "runner", "run hurry", People, true),
+    e("🚶", "walking", "walk", People, true),
+    e("👯", "dancers", "party bunny", People, false),
+    e("🗣️", "speaking_head", "talk speak", People, false),
+    // Animals & Nature
+    e("🐶", "dog", "puppy pet", Animals, false),
+    e("🐱", "cat", "kitten pet", Animals, false),
+    e("🐭", "mouse", "animal", Animals, false),
+    e("🐰", "rabbit", "bunny", Animals, false),
+    e("🦊", "fox_face", "fox", Animals, false),
+    e("🐻", "bear", "animal", Animals, false),
+    e("🐼", "panda_face", "panda", Animals, false),
+    e("🐨", "koala", "animal", Animals, false),
+    e("🐯", "tiger", "animal", Animals, false),
+    e("🦁", "lion", "animal", Animals, false),
+    e("🐮", "cow", "animal", Animals, false),
+    e("🐷", "pig", "animal", Animals, false),
+    e("🐸", "frog", "animal", Animals, false),
+    e("🐵", "monkey_face", "monkey", Animals, false),
+    e("🐔", "chicken", "bird", Animals, false),
+    e("🐧", "penguin", "bird", Animals, false),
+    e("🐦", "bird", "tweet", Animals, false),
+    e("🦆", "duck", "bird", Animals, false),
+    e("🦉", "owl", "bird wise", Animals, false),
+    e("🐝", "bee", "honeybee", Animals, false),
+    e("🐛", "bug", "insect", Animals, false),
+    e("🦋", "butterfly", "insect", Animals, false),
+    e("🐌", "snail", "slow", Animals, false),
+    e("🐢", "turtle", "slow", Animals, false),
+    e("🐍", "snake", "python", Animals, false),
+    e("🦀", "crab", "rust ferris", Animals, false),
+    e("🐙", "octopus", "animal", Animals, false),
+    e("🐳", "whale", "docker", Animals, false),
+    e("🐬", "dolphin", "animal", Animals, false),
+    e("🐟", "fish", "animal", Animals, false),
+    e("🦄", "unicorn", "magic", Animals, false),
+    e("🌵", "cactus", "plant", Animals, false),
+    e("🌲", "evergreen_tree", "tree", Animals, false),
+    e("🌳", "deciduous_tree", "tree", Animals, false),
+    e("🌱", "seedling", "plant grow", Animals, false),
+    e("🍀", "four_leaf_clover", "luck", Animals, false),
+    e("🍁", "maple_leaf", "autumn fall", Animals, false),
+    e("🌸", "cherry_blossom", "flower spring", Animals, false),
+    e("🌹", "rose", "flower", Animals, false),
+    e("🌻", "sunflower", "flower", Animals, false),
+    e("🌈", "rainbow", "weather", Animals, false),
+    e("☀️", "sunny", "sun weather", Animals, false),
+    e("⛅", "partly_sunny", "weather cloud", Animals, false),
+    e("🌧️", "cloud_with_rain", "rain weather", Animals, false),
+    e("⛈️", "cloud_with_lightning_and_rain", "storm", Animals, false),
+    e("❄️", "snowflake", "cold winter", Animals, false),
+    e("⛄", "snowman", "winter", Animals, false),
+    e("🌊", "ocean", "wave sea", Animals, false),
+    e("🌙", "crescent_moon", "night", Animals, false),
+    e("⭐", "star", "favorite", Animals, false),
+    e("🌟", "star2", "glow", Animals, false),
+    e("🔥", "fire", "lit hot flame", Animals, false),
+    e("⚡", "zap", "lightning fast", Animals, false),
+    e("💧", "droplet", "water", Animals, false),
+    // Food & Drink
+    e("🍏", "green_apple", "fruit", Food, false),
+    e("🍎", "apple", "fruit", Food, false),
+    e("🍌", "banana", "fruit", Food, false),
+    e("🍉", "watermelon", "fruit", Food, false),
+    e("🍇", "grapes", "fruit", Food, false),
+    e("🍓", "strawberry", "fruit", Food, false),
+    e("🍒", "cherries", "fruit", Food, false),
+    e("🍑", "peach", "fruit", Food, false),
+    e("🍍", "pineapple", "fruit", Food, false),
+    e("🥑", "avocado", "fruit", Food, false),
+    e("🌶️", "hot_pepper", "spicy", Food, false),
+    e("🥕", "carrot", "vegetable", Food, false),
+    e("🌽", "corn", "vegetable", Food, false),
+    e("🥐", "croissant", "bread breakfast", Food, false),
+    e("🍞", "bread", "toast", Food, false),
+    e("🧀", "cheese", "food", Food, false),
+    e("🥓", "bacon", "breakfast", Food, false),
+    e("🍳", "fried_egg", "breakfast cooking", Food, false),
+    e("🍔", "hamburger", "burger", Food, false),
+    e("🍟", "fries", "chips", Food, false),
+    e("🍕", "pizza", "food", Food, false),
+    e("🌭", "hotdog", "food", Food, false),
+    e("🌮", "taco", "food", Food, false),
+    e("🌯", "burrito", "food", Food, false),
+    e("🥗", "green_salad", "salad healthy", Food, false),
+    e("🍝", "spaghetti", "pasta", Food, false),
+    e("🍜", "ramen", "noodles", Food, false),
+    e("🍣", "sushi", "fish", Food, false),
+    e("🍩", "doughnut", "donut", Food, false),
+    e("🍪", "cookie", "biscuit", Food, false),
+    e("🎂", "birthday", "cake", Food, false),
+    e("🍰", "cake", "dessert", Food, false),
+    e("🧁", "cupcake", "dessert", Food, false),
+    e("🍫", "chocolate_bar", "chocolate", Food, false),
+    e("🍿", "popcorn", "movie", Food, false),
+    e("🍦", "icecream", "ice cream", Food, false),
+    e("☕", "coffee", "tea cafe", Food, false),
+    e("🍵", "tea", "green", Food, false),
+    e("🥤", "cup_with_straw", "soda drink", Food, false),
+    e("🍺", "beer", "drink cheers", Food, false),
+    e("🍻", "beers", "cheers drinks", Food, false),
+    e("🥂", "clinking_glasses", "cheers toast", Food, false),
+    e("🍷", "wine_glass", "wine", Food, false),
+    e("🍸", "cocktail", "drink", Food, false),
+    e("🍾", "champagne", "celebrate bottle", Food, false),
+    // Activities
+    e("⚽", "soccer", "football ball", Activities, false),
+    e("🏀", "basketball", "ball", Activities, false),
+    e("🏈", "football", "american ball", Activities, false),
+    e("⚾", "baseball", "ball", Activities, false),
+    e("🎾", "tennis", "ball", Activities, false),
+    e("🏐", "volleyball", "ball", Activities, false),
+    e("🏓", "ping_pong", "table tennis", Activities, false),
+    e("⛳", "golf", "flag", Activities, false),
+    e("🎯", "dart", "target bullseye goal", Activities, false),
+    e("🎮", "video_game", "gaming controller", Activities, false),
+    e("🎲", "game_die", "dice random", Activities, false),
+    e("🧩", "jigsaw", "puzzle", Activities, false),
+    e("♟️", "chess_pawn", "chess", Activities, false),
+    e("🎨", "art", "paint palette", Activities, false),
+    e("🎭", "performing_arts", "theater", Activities, false),
+    e("🎬", "clapper", "movie film", Activities, false),
+    e("🎤", "microphone", "sing karaoke", Activities, false),
+    e("🎧", "headphones", "music", Activities, false),
+    e("🎸", "guitar", "music rock", Activities, false),
+    e("🎹", "musical_keyboard", "piano", Activities, false),
+    e("🥁", "drum", "music", Activities, false),
+    e("🏆", "trophy", "win award", Activities, false),
+    e("🥇", "1st_place_medal", "gold first win", Activities, false),
+    e("🥈", "2nd_place_medal", "silver second", Activities, false),
+    e("🥉", "3rd_place_medal", "bronze third", Activities, false),
+    e("🏅", "medal_sports", "award", Activities, false),
+    e("🎉", "tada", "party celebrate hooray", Activities, false),
+    e("🎊", "confetti_ball", "party celebrate", Activities, false),
+    e("🎈", "balloon", "party birthday", Activities, false),
+    e("🎁", "gift", "present birthday", Activities, false),
+    e("🎄", "christmas_tree", "holiday", Activities, false),
+    e("🎃", "jack_o_lantern", "halloween pumpkin", Activities, false),
+    e("🏋️", "weight_lifting", "gym", Activities, true),
+    e("🚴", "bike", "cycling bicycle", Activities, true),
+    e("🧘", "lotus_position", "yoga meditate", Activities, true),
+    // Travel & Places
+    e("🚗", "car", "automobile drive", Travel, false),
+    e("🚕", "taxi", "cab", Travel, false),
+    e("🚌", "bus", "transport", Travel, false),
+    e("🚑", "ambulance", "emergency", Travel, false),
+    e("🚒", "fire_engine", "emergency", Travel, false),
+    e("🚓", "police_car", "cops", Travel, false),
+    e("🚲", "bicycle", "bike", Travel, false),
+    e("🛴", "kick_scooter", "scooter", Travel, false),
+    e("🚂", "steam_locomotive", "train", Travel, false),
+    e("🚆", "train2", "train rail", Travel, false),
+    e("✈️", "airplane", "flight travel", Travel, false),
+    e("🛫", "flight_departure", "takeoff", Travel, false),
+    e("🛬", "flight_arrival", "landing", Travel, false),
+    e("🚀", "rocket", "launch ship deploy", Travel, false),
+    e("🛸", "flying_saucer", "ufo", Travel, false),
+    e("🚢", "ship", "boat cruise", Travel, false),
+    e("⛵", "boat", "sailboat", Travel, false),
+    e("🚧", "construction", "wip work in progress", Travel, false),
+    e("🚦", "vertical_traffic_light", "traffic", Travel, false),
+    e("🗺️", "world_map", "map", Travel, false),
+    e("🏠", "house", "home", Travel, false),
+    e("🏢", "office", "building work", Travel, false),
+    e("🏥", "hospital", "health", Travel, false),
+    e("🏫", "school", "education", Travel, false),
+    e("🏰", "european_castle", "castle", Travel, false),
+    e("⛪", "church", "building", Travel, false),
+    e("🗽", "statue_of_liberty", "new york", Travel, false),
+    e("🗼", "tokyo_tower", "tower", Travel, false),
+    e("🏖️", "beach_umbrella", "beach vacation", Travel, false),
+    e("🏝️", "desert_island", "island vacation", Travel, false),
+    e("⛰️", "mountain", "hiking", Travel, false),
+    e("🏕️", "camping", "tent", Travel, false),
+    e("🌍", "earth_africa", "world globe", Travel, false),
+    e("🌎", "earth_americas", "world globe", Travel, false),
+    e("🌏", "earth_asia", "world globe", Travel, false),
+    e("🌃", "night_with_stars", "city night", Travel, false),
+    e("🌅", "sunrise", "morning", Travel, false),
+    // Objects
+    e("⌚", "watch", "time", Objects, false),
+    e("📱", "iphone", "phone mobile", Objects, false),
+    e("💻", "computer", "laptop", Objects, false),
+    e("⌨️", "keyboard", "typing", Objects, false),
+    e("🖥️", "desktop_computer", "monitor", Objects, false),
+    e("🖨️", "printer", "print", Objects, false),
+    e("🖱️", "computer_mouse", "mouse click", Objects, false),
+    e("💾", "floppy_disk", "save", Objects, false),
+    e("💿", "cd", "disc", Objects, false),
+    e("📷", "camera", "photo", Objects, false),
+    e("🎥", "movie_camera", "video film", Objects, false),
+    e("📞", "telephone_receiver", "phone call", Objects, false),
+    e("📺", "tv", "television", Objects, false),
+    e("🔋", "battery", "power", Objects, false),
+    e("🔌", "electric_plug", "power", Objects, false),
+    e("💡", "bulb", "idea light", Objects, false),
+    e("🔦", "flashlight", "torch", Objects, false),
+    e("📚", "books", "library read", Objects, false),
+    e("📖", "book", "read open_book", Objects, false),
+    e("📝", "memo", "note write pencil", Objects, false),
+    e("✏️", "pencil2", "write edit", Objects, false),
+    e("🖊️", "pen", "write", Objects, false),
+    e("📌", "pushpin", "pin", Objects, false),
+    e("📎", "paperclip", "attachment", Objects, false),
+    e("✂️", "scissors", "cut", Objects, false),
+    e("📁", "file_folder", "folder directory", Objects, false),
+    e("📂", "open_file_folder", "folder", Objects, false),
+    e("📅", "date", "calendar", Objects, false),
+    e("📆", "calendar", "schedule", Objects, false),
+    e("📈", "chart_with_upwards_trend", "graph growth up", Objects, false),
+    e("📉", "chart_with_downwards_trend", "graph down", Objects, false),
+    e("📊", "bar_chart", "stats graph", Objects, false),
+    e("📋", "clipboard", "list", Objects, false),
+    e("📦", "package", "box shipping", Objects, false),
+    e("✉️", "email", "envelope letter mail", Objects, false),
+    e("📧", "e-mail", "email mail", Objects, false),
+    e("📣", "mega", "announcement megaphone", Objects, false),
+    e("📢", "loudspeaker", "announcement", Objects, false),
+    e("🔔", "bell", "notification", Objects, false),
+    e("🔕", "no_bell", "mute silent", Objects, false),
+    e("🔒", "lock", "secure private", Objects, false),
+    e("🔓", "unlock", "open", Objects, false),
+    e("🔑", "key", "password", Objects, false),
+    e("🔨", "hammer", "tool build", Objects, false),
+    e("🔧", "wrench", "tool fix", Objects, false),
+    e("⚙️", "gear", "settings", Objects, false),
+    e("🧪", "test_tube", "experiment science", Objects, false),
+    e("🔬", "microscope", "science", Objects, false),
+    e("💊", "pill", "medicine", Objects, false),
+    e("💰", "moneybag", "money rich", Objects, false),
+    e("💸", "money_with_wings", "spend", Objects, false),
+    e("💳", "credit_card", "payment", Objects, false),
+    e("💎", "gem", "diamond", Objects, false),
+    e("⏰", "alarm_clock", "time", Objects, false),
+    e("⏳", "hourglass_flowing_sand", "wait time", Objects, false),
+    e("🛒", "shopping_cart", "buy", Objects, false),
+    e("🧹", "broom", "clean", Objects, false),
+    e("🗑️", "wastebasket", "trash delete", Objects, false),
+    // Symbols
+    e("❤️", "heart", "love like", Symbols, false),
+    e("🧡", "orange_heart", "love", Symbols, false),
+    e("💛", "yellow_heart", "love", Symbols, false),
+    e("💚", "green_heart", "love", Symbols, false),
+    e("💙", "blue_heart", "love", Symbols, false),
+    e("💜", "purple_heart", "love", Symbols, false),
+    e("🖤", "black_heart", "love", Symbols, false),
+    e("🤍", "white_heart", "love", Symbols, false),
+    e("💔", "broken_heart", "sad", Symbols, false),
+    e("💕", "two_hearts", "love", Symbols, false),
+    e("💖", "sparkling_heart", "love", Symbols, false),
+    e("💯", "100", "hundred perfect score", Symbols, false),
+    e("💢", "anger", "angry", Symbols, false),
+    e("💥", "boom", "collision explosion", Symbols, false),
+    e("💫", "dizzy", "star", Symbols, false),
+    e("💬", "speech_balloon", "chat comment", Symbols, false),
+    e("💭", "thought_balloon", "think", Symbols, false),
+    e("💤", "zzz", "sleep", Symbols, false),
+    e("✨", "sparkles", "shiny new", Symbols, false),
+    e("✅", "white_check_mark", "done check yes", Symbols, false),
+    e("☑️", "ballot_box_with_check", "check done", Symbols, false),
+    e("✔️", "heavy_check_mark", "check ok", Symbols, false),
+    e("❌", "x", "no cross wrong", Symbols, false),
+    e("❎", "negative_squared_cross_mark", "no", Symbols, false),
+    e("➕", "heavy_plus_sign", "plus add", Symbols, false),
+    e("➖", "heavy_minus_sign", "minus", Symbols, false),
+    e("❓", "question", "what", Symbols, false),
+    e("❗", "exclamation", "important", Symbols, false),
+    e("‼️", "bangbang", "exclamation", Symbols, false),
+    e("⚠️", "warning", "caution alert", Symbols, false),
+    e("🚫", "no_entry_sign", "forbidden", Symbols, false),
+    e("⛔", "no_entry", "stop", Symbols, false),
+    e("🆗", "ok", "okay", Symbols, false),
+    e("🆕", "new", "fresh", Symbols, false),
+    e("🆒", "cool", "nice", Symbols, false),
+    e("🆘", "sos", "help", Symbols, false),
+    e("🔴", "red_circle", "red", Symbols, false),
+    e("🟠", "orange_circle", "orange", Symbols, false),
+    e("🟡", "yellow_circle", "yellow", Symbols, false),
+    e("🟢", "green_circle", "green", Symbols, false),
+    e("🔵", "large_blue_circle", "blue", Symbols, false),
+    e("⚫", "black_circle", "black", Symbols, false),
+    e("⚪", "white_circle", "white", Symbols, false),
+    e("⬆️", "arrow_up", "up", Symbols, false),
+    e("⬇️", "arrow_down", "down", Symbols, false),
+    e("➡️", "arrow_right", "right next", Symbols, false),
+    e("⬅️", "arrow_left", "left back", Symbols, false),
+    e("🔄", "arrows_counterclockwise", "refresh sync", Symbols, false),
+    e("🔁", "repeat", "loop", Symbols, false),
+    e("♻️", "recycle", "reuse", Symbols, false),
+    e("🔗", "link", "url chain", Symbols, false),
+    e("🏷️", "label", "tag", Symbols, false),
+    e("©️", "copyright", "c", Symbols, false),
+    e("®️", "registered", "r", Symbols, false),
+    e("™️", "tm", "trademark", Symbols, false),
+    e("#️⃣", "hash", "number", Symbols, false),
+    e("🔢", "1234", "numbers", Symbols, false),
+    e("🎵", "musical_note", "music", Symbols, false),
+    e("🎶", "notes", "music", Symbols, false),
+    e("♾️", "infinity", "forever", Symbols, false),
+    // Flags
+    e("🏁", "checkered_flag", "finish race", Flags, false),
+    e("🚩", "triangular_flag_on_post", "red flag", Flags, false),
+    e("🏳️", "white_flag", "surrender", Flags, false),
+    e("🏴", "black_flag", "flag", Flags, false),
+    e("🏳️‍🌈", "rainbow_flag", "pride", Flags, false),
+    e("🇺🇸", "us", "united states america usa", Flags, false),
+    e("🇬🇧", "gb", "united kingdom uk britain", Flags, false),
+    e("🇨🇦", "canada", "ca", Flags, false),
+    e("🇩🇪", "de", "germany", Flags, false),
+    e("🇫🇷", "fr", "france", Flags, false),
+    e("🇪🇸", "es", "spain", Flags, false),
+    e("🇮🇹", "it", "italy", Flags, false),
+    e("🇳🇱", "netherlands", "nl holland", Flags, false),
+    e("🇸🇪", "sweden", "se", Flags, false),
+    e("🇳🇴", "norway", "no", Flags, false),
+    e("🇩🇰", "denmark", "dk", Flags, false),
+    e("🇫🇮", "finland", "fi", Flags, false),
+    e("🇵🇱", "poland", "pl", Flags, false),
+    e("🇨🇿", "czech_republic", "cz czechia", Flags, false),
+    e("🇸🇰", "slovakia", "sk", Flags, false),
+    e("🇺🇦", "ukraine", "ua", Flags, false),
+    e("🇮🇪", "ireland", "ie", Flags, false),
+    e("🇵🇹", "portugal", "pt", Flags, false),
+    e("🇨🇭", "switzerland", "ch", Flags, false),
+    e("🇦🇹", "austria", "at", Flags, false),
+    e("🇧🇷", "brazil", "br", Flags, false),
+    e("🇲🇽", "mx", "mexico", Flags, false),
+    e("🇮🇳", "india", "in", Flags, false),
+    e("🇨🇳", "cn", "china", Flags, false),
+    e("🇯🇵", "jp", "japan", Flags, false),
+    e("🇰🇷", "kr", "korea", Flags, false),
+    e("🇦🇺", "australia", "au", Flags, false),
+    e("🇪🇺", "eu", "european union", Flags, false),
+];
+
+/// Skin tone modifiers; index 0 is the default (yellow) tone
+pub const SKIN_TONES: [&str; 6] = ["", "🏻", "🏼", "🏽", "🏾", "🏿"];
+
+/// Swatch shown for each skin tone option
+pub const SKIN_TONE_SWATCHES: [&str; 6] = ["✋", "✋🏻", "✋🏼", "✋🏽", "✋🏾", "✋🏿"];
+
+/// Apply a skin tone (index into `SKIN_TONES`) to an emoji. The modifier goes
+/// after the first code point; a following variation selector is dropped.
+pub fn apply_skin_tone(emoji: &str, tone: usize) -> String {
+    let modifier = SKIN_TONES.get(tone).copied().unwrap_or("");
+    let mut chars = emoji.chars();
+    let Some(first) = chars.next() else {
+        return String::new();
+    };
+    if modifier.is_empty() {
+        return emoji.to_string();
+    }
+    let rest = chars.as_str().trim_start_matches('\u{FE0F}');
+    format!("{}{}{}", first, modifier, rest)
+}
+
+/// Strip skin tone modifiers and variation selectors for comparisons
+pub fn base_emoji(emoji: &str) -> String {
+    emoji
+        .chars()
+        .filter(|c| *c != '\u{FE0F}' && !('\u{1F3FB}'..='\u{1F3FF}').contains(c))
+        .collect()
+}
+
+/// Table entry for an emoji, ignoring skin tone and variation selector
+pub fn lookup(emoji: &str) -> Option<&'static Emoji> {
+    let base = base_emoji(emoji);
+    EMOJIS.iter().find(|e| base_emoji(e.emoji) == base)
+}
+
+pub fn by_shortcode(shortcode: &str) -> Option<&'static Emoji> {
+    EMOJIS.iter().find(|e| e.shortcode == shortcode)
+}
+
+pub fn in_category(category: Category) -> impl Iterator<Item = &'static Emoji> {
+    EMOJIS.iter().filter(move |e| e.category == category)
+}
+
+/// Emoji whose shortcode or keywords match `query`: shortcode prefix matches
+/// first, then other shortcode matches, then keyword matches.
+pub fn search(query: &str) -> Vec<&'static Emoji> {
+    let query = query.trim().to_lowercase().replace(' ', "_");
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let words = query.replace('_', " ");
+    let mut ranked: Vec<(u8, &'static Emoji)> = EMOJIS
+        .iter()
+        .filter_map(|e| {
+            let rank = if e.shortcode.starts_with(&query) {
+                0
+            } else if e.shortcode.contains(&query) {
+                1
+            } else if e.keywords.split(' ').any(|k| k.starts_with(words.as_str())) {
+                2
+            } else {
+                return None;
+            };
+            Some((rank, e))
+        })
+        .collect();
+    ranked.sort_by_key(|(rank, _)| *rank);
+    ranked.into_iter().map(|(_, e)| e).collect()
+}
+
+/// Whether a string is made of emoji/pictographic characters rather than a type name
+pub fn looks_like_emoji(text: &str) -> bool {
+    !text.is_empty() && !text.chars().any(|c| c.is_ascii_alphanumeric() || c.is_whitespace())
+}
+
+/// Most-recently-used list update: move `emoji` to the front, keeping at most `max`
+pub fn push_recent(recent: &mut Vec<String>, emoji: &str, max: usize) {
+    recent.retain(|r| r != emoji);
+    recent.insert(0, emoji.to_string());
+    recent.truncate(max);
+}
+
+/// Emoji shown per row in the picker grid
+pub const PICKER_COLUMNS: usize = 10;
+
+/// Recently used emoji kept in the config file
+pub const MAX_RECENT: usize = 20;
+
+/// Quick reactions shown on the recent tab until something has been used
+pub const DEFAULT_REACTIONS: [&str; 6] = ["👍", "❤️", "😂", "😮", "😢", "😡"];
+
+/// State of the emoji reaction picker: a search query, the active tab
+/// (0 = recent, then one tab per category), the grid selection and skin tone.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmojiPicker {
+    pub query: String,
+    pub cursor: usize,
+    pub tab: usize,
+    pub selected: usize,
+    pub skin_tone: usize,
+}
+
+impl EmojiPicker {
+    pub fn new(skin_tone: usize) -> Self {
+        Self { skin_tone: skin_tone.min(SKIN_TONES.len() - 1), ..Self::default() }
+    }
+
+    pub fn tab_count() -> usize {
+        Category::ALL.len() + 1
+    }
+
+    /// Category of the active tab (None for the recent tab)
+    pub fn category(&self) -> Option<Category> {
+        self.tab.checked_sub(1).and_then(|i| Category::ALL.get(i).copied())
+    }
+
+    pub fn tab_title(&self) -> &'static str {
+        if !self.query.is_empty() {
+            return "Search results";
+        }
+        self.category().map(Category::label).unwrap_or("Recently used")
+    }
+
+    /// Emoji shown in the grid, with the skin tone applied where supported
+    pub fn items(&self, recent: &[String]) -> Vec<String> {
+        let tone = |e: &Emoji| {
+            if e.tones {
+                apply_skin_tone(e.emoji, self.skin_tone)
+            } else {
+                e.emoji.to_string()
+            }
+        };
+        if !self.query.is_empty() {
+            return search(&self.query).into_iter().map(tone).collect();
+        }
+        match self.category() {
+            Some(category) => in_category(category).map(tone).collect(),
+            None if recent.is_empty() => DEFAULT_REACTIONS.iter().map(|e| e.to_string()).collect(),
+            None => recent.to_vec(),
+        }
+    }
+
+    pub fn selected_emoji(&self, recent: &[String]) -> Option<String> {
+        self.items(recent).into_iter().nth(self.selected)
+    }
+
+    pub fn next_tab(&mut self) {
+        self.tab = (self.tab + 1) % Self::tab_count();
+        self.selected = 0;
+    }
+
+    pub fn prev_tab(&mut self) {
+        self.tab = (self.tab + Self::tab_count() - 1) % Self::tab_count();
+        self.selected = 0;
+    }
+
+    /// Move the grid selection by `delta` (±1 across, ±PICKER_COLUMNS down/up)
+    pub fn move_selection(&mut self, delta: isize, len: usize) {
+        if len == 0 {
+            self.selected = 0;
+            return;
+        }
+        let target = self.selected as isize + delta;
+        if (0..len as isize).contains(&target) {
+            self.selected = target as usize;
+        }
+    }
+
+    pub fn cycle_skin_tone(&mut self) {
+        self.skin_tone = (self.skin_tone + 1) % SKIN_TONES.len();
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.query.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+        self.selected = 0;
+    }
+
+    pub fn delete_char(&mut self) {
+        if let Some(prev) = self.query[..self.cursor].chars().last() {
+            self.cursor -= prev.len_utf8();
+            self.query.remove(self.cursor);
+            self.selected = 0;
+        }
+    }
+}
+
+/// Suggestions shown in the composer's shortcode popup
+pub const MAX_COMPLETIONS: usize = 8;
+
+/// Characters allowed inside a `:shortcode:`
+fn is_shortcode_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '+' || c == '-'
+}
+
+/// The shortcode being typed just before `cursor`: the byte offset of its
+/// opening `:` and the text after it. The colon must start the text or follow
+/// whitespace, and at least two characters must follow it.
+pub fn shortcode_at(text: &str, cursor: usize) -> Option<(usize, &str)> {
+    let before = text.get(..cursor)?;
+    let start = before.rfind(|c: char| !is_shortcode_char(c))?;
+    if !before[start..].starts_with(':') {
+        return None;
+    }
+    if before[..start].chars().next_back().is_some_and(|c| !c.is_whitespace()) {
+        return None;
+    }
+    let query = &before[start + 1..];
+    (query.chars().count() >= 2).then_some((start, query))
+}
+
+/// Emoji whose shortcode starts with `prefix`, then other search matches
+pub fn complete(prefix: &str) -> Vec<&'static Emoji> {
+    let prefix = prefix.to_lowercase();
+    let mut matches: Vec<&'static Emoji> =
+        EMOJIS.iter().filter(|e| e.shortcode.starts_with(&prefix)).collect();
+    for e in search(&prefix) {
+        if !matches.iter().any(|m| m.shortcode == e.shortcode) {
+            matches.push(e);
+        }
+    }
+    matches.truncate(MAX_COMPLETIONS);
+    matches
+}
+
+/// Replace known `:shortcode:`s with their emoji; unknown ones and anything
+/// inside backtick code spans are left as typed.
+pub fn replace_shortcodes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (i, segment) in text.split('`').enumerate() {
+        if i > 0 {
+            out.push('`');
+        }
+        if i % 2 == 1 {
+            out.push_str(segment);
+        } else {
+            out.push_str(&replace_in_plain_text(segment));
+        }
+    }
+    out
+}
+
+fn replace_in_plain_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    // Last character of the original text before `rest`
+    let mut prev: Option<char> = None;
+    while let Some(open) = rest.find(':') {
+        out.push_str(&rest[..open]);
+        prev = rest[..open].chars().last().or(prev);
+        let after = &rest[open + 1..];
+        let end = after.find(|c: char| !is_shortcode_char(c)).unwrap_or(after.len());
+        let code = &after[..end];
+        let closed = after[end..].starts_with(':');
+        let bounded = is_shortcode_boundary(prev)
+            && is_shortcode_boundary(after.get(end + 1..).and_then(|tail| tail.chars().next()));
+        match by_shortcode(&code.to_lowercase()).filter(|_| closed && bounded && !code.is_empty()) {
+            Some(entry) => {
+                out.push_str(entry.emoji);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push(':');
+                rest = after;
+            }
+        }
+        prev = Some(':');
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Shortcodes only stand alone: next to whitespace, punctuation or the ends of
+/// the text, so `a:b:c` or `host:smile:8080` are left as typed
+fn is_shortcode_boundary(c: Option<char>) -> bool {
+    c.map(|c| c.is_whitespace() || c.is_ascii_punctuation()).unwrap_or(true)
+}
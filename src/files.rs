@@ -0,0 +1,134 @@
+//! Local filesystem helpers for the file picker: directory listing, `~`
+//! expansion, path completion and file type descriptions.
+
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<chrono::DateTime<chrono::Local>>,
+}
+
+/// List a directory: folders first, then files, each sorted case-insensitively.
+/// Dotfiles are skipped unless `show_hidden` is set.
+pub fn list_dir(dir: &Path, show_hidden: bool) -> std::io::Result<Vec<FileEntry>> {
+    let mut entries: Vec<FileEntry> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !show_hidden && is_hidden(&name) {
+                return None;
+            }
+            // Follow symlinks so linked folders can be opened
+            let meta = std::fs::metadata(entry.path()).ok()?;
+            Some(FileEntry {
+                name,
+                path: entry.path(),
+                is_dir: meta.is_dir(),
+                size: if meta.is_dir() { 0 } else { meta.len() },
+                modified: meta.modified().ok().map(chrono::DateTime::from),
+            })
+        })
+        .collect();
+    sort_entries(&mut entries);
+    Ok(entries)
+}
+
+pub fn sort_entries(entries: &mut [FileEntry]) {
+    entries.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+}
+
+pub fn is_hidden(name: &str) -> bool {
+    name.starts_with('.')
+}
+
+/// Expand a leading `~` to the home directory
+pub fn expand_tilde(input: &str) -> PathBuf {
+    expand_tilde_with(input, dirs::home_dir())
+}
+
+pub fn expand_tilde_with(input: &str, home: Option<PathBuf>) -> PathBuf {
+    match (input, home) {
+        ("~", Some(home)) => home,
+        (_, Some(home)) if input.starts_with("~/") => home.join(&input[2..]),
+        _ => PathBuf::from(input),
+    }
+}
+
+/// Complete a typed path to the longest common prefix of matching entries.
+/// A unique directory match gets a trailing separator. Returns None when nothing matches.
+pub fn complete_path(input: &str, show_hidden: bool) -> Option<String> {
+    let (dir_part, prefix) = match input.rfind(std::path::is_separator) {
+        Some(slash) => (&input[..=slash], &input[slash + 1..]),
+        None => ("", input),
+    };
+    let dir = if dir_part.is_empty() {
+        PathBuf::from(".")
+    } else {
+        expand_tilde(dir_part)
+    };
+    let show_hidden = show_hidden || prefix.starts_with('.');
+    let candidates: Vec<FileEntry> = list_dir(&dir, show_hidden)
+        .ok()?
+        .into_iter()
+        .filter(|e| e.name.starts_with(prefix))
+        .collect();
+    let completed = complete_from(prefix, &candidates)?;
+    Some(format!("{}{}", dir_part, completed))
+}
+
+/// Longest common prefix of the candidate names (all must start with `prefix`)
+pub fn complete_from(prefix: &str, candidates: &[FileEntry]) -> Option<String> {
+    let first = candidates.first()?;
+    if candidates.len() == 1 {
+        let suffix = if first.is_dir { std::path::MAIN_SEPARATOR_STR } else { "" };
+        return Some(format!("{}{}", first.name, suffix));
+    }
+    let mut common: &str = &first.name;
+    for entry in &candidates[1..] {
+        let shared = common
+            .char_indices()
+            .zip(entry.name.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map(|((i, c), _)| i + c.len_utf8())
+            .unwrap_or(0);
+        common = &common[..shared];
+    }
+    if common.len() < prefix.len() {
+        return Some(prefix.to_string());
+    }
+    Some(common.to_string())
+}
+
+/// Short human-readable type for a file, based on its extension
+pub fn describe_file_type(name: &str) -> &'static str {
+    let ext = name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" | "svg" | "heic" => "Image",
+        "mp4" | "mov" | "mkv" | "avi" | "webm" => "Video",
+        "mp3" | "wav" | "flac" | "ogg" | "m4a" => "Audio",
+        "pdf" => "PDF document",
+        "doc" | "docx" | "odt" | "rtf" => "Word document",
+        "xls" | "xlsx" | "ods" | "csv" => "Spreadsheet",
+        "ppt" | "pptx" | "odp" => "Presentation",
+        "zip" | "tar" | "gz" | "tgz" | "bz2" | "xz" | "7z" | "rar" | "zst" => "Archive",
+        "txt" | "md" | "log" => "Text",
+        "json" | "yaml" | "yml" | "toml" | "xml" | "ini" => "Config / data",
+        "rs" | "py" | "js" | "ts" | "go" | "java" | "c" | "h" | "cpp" | "cs" | "sh" | "rb" => {
+            "Source code"
+        }
+        "dmp" | "core" => "Dump",
+        _ => "File",
+    }
+}
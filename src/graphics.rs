@@ -0,0 +1,439 @@
+//! Terminal image rendering.
+//!
+//! Detects which image output the terminal supports (kitty graphics protocol,
+//! iTerm2 inline images, sixel, truecolor or plain shade characters), renders
+//! cell-based previews, and encodes pixel-protocol escape sequences for the
+//! full-screen image viewer.
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use image::{imageops::FilterType, DynamicImage, RgbImage};
+
+/// Inline previews are downsampled to this many columns × pixel rows (2 pixel rows per cell)
+pub const PREVIEW_COLS: u32 = 28;
+pub const PREVIEW_PIXEL_ROWS: u32 = 16;
+
+/// Zoom levels of the image viewer; 1 = fit the whole image
+pub const ZOOM_LEVELS: [u32; 6] = [1, 2, 3, 4, 6, 8];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Iterm2,
+    Sixel,
+    Truecolor,
+    Grayscale,
+}
+
+impl GraphicsProtocol {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "kitty" => Some(Self::Kitty),
+            "iterm2" | "iterm" => Some(Self::Iterm2),
+            "sixel" => Some(Self::Sixel),
+            "truecolor" | "color" => Some(Self::Truecolor),
+            "grayscale" | "ascii" => Some(Self::Grayscale),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Kitty => "kitty",
+            Self::Iterm2 => "iterm2",
+            Self::Sixel => "sixel",
+            Self::Truecolor => "truecolor",
+            Self::Grayscale => "grayscale",
+        }
+    }
+
+    /// Whether the protocol draws real pixels (as opposed to character cells)
+    pub fn is_pixel_protocol(&self) -> bool {
+        matches!(self, Self::Kitty | Self::Iterm2 | Self::Sixel)
+    }
+
+    pub fn supports_color(&self) -> bool {
+        *self != Self::Grayscale
+    }
+}
+
+/// Resolve the `image_protocol` setting; anything other than a known protocol name
+/// (normally `auto`) means detection from the environment.
+pub fn resolve_protocol(setting: &str) -> GraphicsProtocol {
+    GraphicsProtocol::parse(setting).unwrap_or_else(|| detect_protocol(|key| std::env::var(key).ok()))
+}
+
+/// Guess terminal image capabilities from environment variables
+pub fn detect_protocol(env: impl Fn(&str) -> Option<String>) -> GraphicsProtocol {
+    let term = env("TERM").unwrap_or_default().to_ascii_lowercase();
+    let program = env("TERM_PROGRAM").unwrap_or_default().to_ascii_lowercase();
+    // tmux/screen swallow graphics escape sequences unless passthrough is configured
+    let multiplexed = env("TMUX").is_some() || term.starts_with("screen") || term.starts_with("tmux");
+
+    if !multiplexed {
+        if env("KITTY_WINDOW_ID").is_some()
+            || term == "xterm-kitty"
+            || term == "xterm-ghostty"
+            || program == "ghostty"
+        {
+            return GraphicsProtocol::Kitty;
+        }
+        if program == "iterm.app"
+            || program == "wezterm"
+            || env("LC_TERMINAL").is_some_and(|t| t == "iTerm2")
+        {
+            return GraphicsProtocol::Iterm2;
+        }
+        if term.contains("sixel") || term == "foot" || term.starts_with("foot-") || term.starts_with("mlterm") {
+            return GraphicsProtocol::Sixel;
+        }
+    }
+
+    let colorterm = env("COLORTERM").unwrap_or_default().to_ascii_lowercase();
+    if colorterm == "truecolor" || colorterm == "24bit" || env("WT_SESSION").is_some() {
+        GraphicsProtocol::Truecolor
+    } else {
+        GraphicsProtocol::Grayscale
+    }
+}
+
+// ---- Cell-based rendering ----
+
+pub type Rgb = [u8; 3];
+
+/// One terminal cell drawn as `▀` with the top pixel as foreground and the bottom as background
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HalfBlock {
+    pub top: Rgb,
+    pub bottom: Rgb,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImagePreview {
+    /// Shade-character rows for terminals without truecolor
+    Grayscale(Vec<String>),
+    /// Rows of colored half-block cells
+    Truecolor(Vec<Vec<HalfBlock>>),
+    Unavailable,
+}
+
+impl ImagePreview {
+    pub fn height(&self) -> usize {
+        match self {
+            ImagePreview::Grayscale(lines) => lines.len(),
+            ImagePreview::Truecolor(rows) => rows.len(),
+            ImagePreview::Unavailable => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewDecodeFailure {
+    InvalidBytes,
+    EmptyDimensions,
+}
+
+impl PreviewDecodeFailure {
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            PreviewDecodeFailure::InvalidBytes => "image_preview.decode.invalid_bytes",
+            PreviewDecodeFailure::EmptyDimensions => "image_preview.decode.empty_dimensions",
+        }
+    }
+}
+
+pub fn decode_image(bytes: &[u8]) -> Result<DynamicImage, PreviewDecodeFailure> {
+    let img = image::load_from_memory(bytes).map_err(|_| PreviewDecodeFailure::InvalidBytes)?;
+    if img.width() == 0 || img.height() == 0 {
+        return Err(PreviewDecodeFailure::EmptyDimensions);
+    }
+    Ok(img)
+}
+
+/// Small inline preview for the message flow
+pub fn render_preview(bytes: &[u8], protocol: GraphicsProtocol) -> Result<ImagePreview, PreviewDecodeFailure> {
+    let thumb = decode_image(bytes)?.thumbnail(PREVIEW_COLS, PREVIEW_PIXEL_ROWS);
+    if thumb.width() == 0 || thumb.height() == 0 {
+        return Err(PreviewDecodeFailure::EmptyDimensions);
+    }
+    if protocol.supports_color() {
+        return Ok(ImagePreview::Truecolor(half_blocks(&thumb.to_rgb8())));
+    }
+    let width = thumb.width() as usize;
+    let mut lines = Vec::new();
+    lines.push(format!("┌{}┐", "─".repeat(width)));
+    for row in shade_rows(&thumb) {
+        lines.push(format!("│{}│", row));
+    }
+    lines.push(format!("└{}┘", "─".repeat(width)));
+    Ok(ImagePreview::Grayscale(lines))
+}
+
+/// Render an image scaled to fit `cols` × `rows` cells (aspect preserved)
+pub fn render_cells(img: &DynamicImage, cols: u16, rows: u16, color: bool) -> ImagePreview {
+    let (fit_cols, fit_rows) = fit_cells(img.width(), img.height(), cols, rows, 1, 2);
+    let scaled = img.resize_exact(fit_cols as u32, fit_rows as u32 * 2, FilterType::Triangle);
+    if color {
+        ImagePreview::Truecolor(half_blocks(&scaled.to_rgb8()))
+    } else {
+        ImagePreview::Grayscale(shade_rows(&scaled))
+    }
+}
+
+pub fn half_blocks(rgb: &RgbImage) -> Vec<Vec<HalfBlock>> {
+    let (width, height) = rgb.dimensions();
+    (0..height)
+        .step_by(2)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let top = rgb.get_pixel(x, y).0;
+                    let bottom = if y + 1 < height { rgb.get_pixel(x, y + 1).0 } else { top };
+                    HalfBlock { top, bottom }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn shade_rows(img: &DynamicImage) -> Vec<String> {
+    let luma = img.to_luma8();
+    let (width, height) = luma.dimensions();
+    (0..height)
+        .step_by(2)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let top = luma.get_pixel(x, y)[0];
+                    let bottom = if y + 1 < height { luma.get_pixel(x, y + 1)[0] } else { top };
+                    luma_pair_to_block(top, bottom)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn luma_pair_to_block(top: u8, bottom: u8) -> char {
+    if top.saturating_sub(bottom) > 70 {
+        return '▀';
+    }
+    if bottom.saturating_sub(top) > 70 {
+        return '▄';
+    }
+    let avg = ((top as u16 + bottom as u16) / 2) as u8;
+    match avg {
+        0..=40 => ' ',
+        41..=90 => '░',
+        91..=150 => '▒',
+        151..=210 => '▓',
+        _ => '█',
+    }
+}
+
+// ---- Viewer geometry ----
+
+/// Largest cell box (cols, rows) with the image's aspect ratio that fits in `max_cols` × `max_rows`,
+/// given the pixel size of one cell.
+pub fn fit_cells(
+    img_width: u32,
+    img_height: u32,
+    max_cols: u16,
+    max_rows: u16,
+    cell_width: u16,
+    cell_height: u16,
+) -> (u16, u16) {
+    if img_width == 0 || img_height == 0 || max_cols == 0 || max_rows == 0 {
+        return (0, 0);
+    }
+    let box_w = max_cols as f64 * cell_width.max(1) as f64;
+    let box_h = max_rows as f64 * cell_height.max(1) as f64;
+    let scale = (box_w / img_width as f64).min(box_h / img_height as f64);
+    let cols = ((img_width as f64 * scale) / cell_width.max(1) as f64).round() as u16;
+    let rows = ((img_height as f64 * scale) / cell_height.max(1) as f64).round() as u16;
+    (cols.clamp(1, max_cols), rows.clamp(1, max_rows))
+}
+
+/// Visible source rectangle (x, y, width, height) for a zoom level and a normalized center point
+pub fn crop_region(img_width: u32, img_height: u32, zoom: u32, center: (f32, f32)) -> (u32, u32, u32, u32) {
+    let zoom = zoom.max(1);
+    let width = (img_width / zoom).max(1);
+    let height = (img_height / zoom).max(1);
+    let cx = (center.0.clamp(0.0, 1.0) * img_width as f32) as i64;
+    let cy = (center.1.clamp(0.0, 1.0) * img_height as f32) as i64;
+    let x = (cx - width as i64 / 2).clamp(0, (img_width - width) as i64) as u32;
+    let y = (cy - height as i64 / 2).clamp(0, (img_height - height) as i64) as u32;
+    (x, y, width, height)
+}
+
+// ---- Pixel protocols ----
+
+pub fn encode_png(img: &DynamicImage) -> Option<Vec<u8>> {
+    let mut buf = std::io::Cursor::new(Vec::new());
+    img.write_to(&mut buf, image::ImageFormat::Png).ok()?;
+    Some(buf.into_inner())
+}
+
+/// Kitty graphics protocol: transmit PNG data and display it scaled to `cols` × `rows` cells
+pub fn kitty_sequence(png: &[u8], cols: u16, rows: u16) -> String {
+    let encoded = STANDARD.encode(png);
+    let chunks: Vec<&str> = encoded
+        .as_bytes()
+        .chunks(4096)
+        .map(|c| std::str::from_utf8(c).unwrap_or(""))
+        .collect();
+    let mut out = String::with_capacity(encoded.len() + chunks.len() * 16);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        if i == 0 {
+            out.push_str(&format!("\x1b_Ga=T,f=100,q=2,c={},r={},m={};{}\x1b\\", cols, rows, more, chunk));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    out
+}
+
+/// Delete all kitty images placed by this client
+pub const KITTY_DELETE_ALL: &str = "\x1b_Ga=d,d=A,q=2\x1b\\";
+
+/// iTerm2 inline image (also understood by WezTerm)
+pub fn iterm2_sequence(png: &[u8], cols: u16, rows: u16) -> String {
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+        png.len(),
+        cols,
+        rows,
+        STANDARD.encode(png)
+    )
+}
+
+/// Sixel image using a 6×6×6 color cube palette
+pub fn sixel_sequence(rgb: &RgbImage) -> String {
+    let (width, height) = rgb.dimensions();
+    let level = |v: u8| ((v as u16 * 5 + 127) / 255) as usize;
+    let indices: Vec<u8> = rgb
+        .pixels()
+        .map(|p| (level(p[0]) * 36 + level(p[1]) * 6 + level(p[2])) as u8)
+        .collect();
+
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", width, height);
+    let mut used = [false; 216];
+    for &i in &indices {
+        used[i as usize] = true;
+    }
+    for (i, _) in used.iter().enumerate().filter(|(_, u)| **u) {
+        let (r, g, b) = (i / 36, (i / 6) % 6, i % 6);
+        out.push_str(&format!("#{};2;{};{};{}", i, r * 20, g * 20, b * 20));
+    }
+
+    let width = width as usize;
+    for band_top in (0..height as usize).step_by(6) {
+        let band_height = 6.min(height as usize - band_top);
+        let mut masks: Vec<Option<Vec<u8>>> = vec![None; 216];
+        for dy in 0..band_height {
+            let row = (band_top + dy) * width;
+            for x in 0..width {
+                let color = indices[row + x] as usize;
+                masks[color].get_or_insert_with(|| vec![0; width])[x] |= 1 << dy;
+            }
+        }
+        let mut first = true;
+        for (color, mask) in masks.iter().enumerate() {
+            let Some(mask) = mask else { continue };
+            if !first {
+                out.push('$');
+            }
+            first = false;
+            out.push_str(&format!("#{}", color));
+            push_sixel_runs(&mut out, mask);
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+fn push_sixel_runs(out: &mut String, mask: &[u8]) {
+    let mut i = 0;
+    while i < mask.len() {
+        let value = mask[i];
+        let mut run = 1;
+        while i + run < mask.len() && mask[i + run] == value {
+            run += 1;
+        }
+        let ch = (63 + value) as char;
+        if run > 3 {
+            out.push_str(&format!("!{}{}", run, ch));
+        } else {
+            for _ in 0..run {
+                out.push(ch);
+            }
+        }
+        i += run;
+    }
+}
+
+// ---- Outgoing inline images ----
+
+/// Largest image sent inline as hosted content; bigger images should be shared as files
+pub const INLINE_IMAGE_MAX: usize = 4 * 1024 * 1024;
+
+/// Inline images wider than this are displayed scaled down in Teams
+const INLINE_DISPLAY_MAX_WIDTH: u32 = 480;
+
+/// Image ready to be sent inline (`hostedContents`) with its display size
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineImage {
+    pub content_type: &'static str,
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlineImageError {
+    NotAnImage,
+    TooLarge,
+}
+
+impl InlineImageError {
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            InlineImageError::NotAnImage => "inline_image.prepare.not_image",
+            InlineImageError::TooLarge => "inline_image.prepare.too_large",
+        }
+    }
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            InlineImageError::NotAnImage => "The file is not a supported image.",
+            InlineImageError::TooLarge => "The image is larger than 4 MB; share it as a file with f instead.",
+        }
+    }
+}
+
+/// Validate image bytes for inline sending. PNG, JPEG and GIF are sent as-is;
+/// other formats (BMP, WebP, …) are converted to PNG.
+pub fn prepare_inline_image(bytes: Vec<u8>) -> Result<InlineImage, InlineImageError> {
+    let format = image::guess_format(&bytes).map_err(|_| InlineImageError::NotAnImage)?;
+    let img = decode_image(&bytes).map_err(|_| InlineImageError::NotAnImage)?;
+    let (content_type, bytes) = match format {
+        image::ImageFormat::Png => ("image/png", bytes),
+        image::ImageFormat::Jpeg => ("image/jpeg", bytes),
+        image::ImageFormat::Gif => ("image/gif", bytes),
+        _ => ("image/png", encode_png(&img).ok_or(InlineImageError::NotAnImage)?),
+    };
+    if bytes.len() > INLINE_IMAGE_MAX {
+        return Err(InlineImageError::TooLarge);
+    }
+    let (width, height) = inline_display_size(img.width(), img.height());
+    Ok(InlineImage { content_type, bytes, width, height })
+}
+
+/// Display size for an inline image, scaled down to fit the message column
+pub fn inline_display_size(width: u32, height: u32) -> (u32, u32) {
+    if width <= INLINE_DISPLAY_MAX_WIDTH {
+        return (width, height);
+    }
+    let scaled = (height as u64 * INLINE_DISPLAY_MAX_WIDTH as u64 / width.max(1) as u64).max(1);
+    (INLINE_DISPLAY_MAX_WIDTH, scaled as u32)
+}
@@ -0,0 +1,337 @@
+/// Token classes produced by the lightweight syntax highlighter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Plain,
+    Keyword,
+    String,
+    Comment,
+    Number,
+}
+
+struct LanguageSpec {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    keywords: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    string_delims: &'static [char],
+    case_insensitive: bool,
+}
+
+const C_FAMILY_KEYWORDS: &[&str] = &[
+    "auto", "break", "case", "char", "class", "const", "continue", "default", "delete", "do",
+    "double", "else", "enum", "extern", "false", "float", "for", "goto", "if", "include",
+    "inline", "int", "long", "namespace", "new", "nullptr", "private", "protected", "public",
+    "return", "short", "signed", "sizeof", "static", "struct", "switch", "template", "this",
+    "throw", "true", "try", "catch", "typedef", "typename", "union", "unsigned", "using",
+    "virtual", "void", "volatile", "while",
+];
+
+const LANGUAGES: &[LanguageSpec] = &[
+    LanguageSpec {
+        name: "rust",
+        aliases: &["rs"],
+        keywords: &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+            "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+            "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super",
+            "trait", "true", "type", "unsafe", "use", "where", "while", "Some", "None", "Ok",
+            "Err",
+        ],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        string_delims: &['"'],
+        case_insensitive: false,
+    },
+    LanguageSpec {
+        name: "python",
+        aliases: &["py", "python3"],
+        keywords: &[
+            "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+            "elif", "else", "except", "False", "finally", "for", "from", "global", "if",
+            "import", "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass", "raise",
+            "return", "True", "try", "while", "with", "yield",
+        ],
+        line_comments: &["#"],
+        block_comment: None,
+        string_delims: &['"', '\''],
+        case_insensitive: false,
+    },
+    LanguageSpec {
+        name: "javascript",
+        aliases: &["js", "jsx", "typescript", "ts", "tsx", "node"],
+        keywords: &[
+            "async", "await", "break", "case", "catch", "class", "const", "continue", "default",
+            "delete", "do", "else", "export", "extends", "false", "finally", "for", "from",
+            "function", "if", "import", "in", "instanceof", "interface", "let", "new", "null",
+            "of", "return", "static", "super", "switch", "this", "throw", "true", "try", "type",
+            "typeof", "undefined", "var", "void", "while", "yield",
+        ],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        string_delims: &['"', '\'', '`'],
+        case_insensitive: false,
+    },
+    LanguageSpec {
+        name: "go",
+        aliases: &["golang"],
+        keywords: &[
+            "break", "case", "chan", "const", "continue", "default", "defer", "else",
+            "fallthrough", "false", "for", "func", "go", "goto", "if", "import", "interface",
+            "map", "nil", "package", "range", "return", "select", "struct", "switch", "true",
+            "type", "var",
+        ],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        string_delims: &['"', '`'],
+        case_insensitive: false,
+    },
+    LanguageSpec {
+        name: "c",
+        aliases: &["cpp", "c++", "h", "hpp", "cc", "objective-c"],
+        keywords: C_FAMILY_KEYWORDS,
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        string_delims: &['"', '\''],
+        case_insensitive: false,
+    },
+    LanguageSpec {
+        name: "csharp",
+        aliases: &["cs", "c#", "dotnet"],
+        keywords: &[
+            "abstract", "as", "async", "await", "base", "bool", "break", "case", "catch",
+            "class", "const", "continue", "default", "do", "else", "enum", "false", "finally",
+            "for", "foreach", "if", "in", "int", "interface", "internal", "is", "namespace",
+            "new", "null", "object", "out", "override", "private", "protected", "public",
+            "readonly", "return", "sealed", "static", "string", "struct", "switch", "this",
+            "throw", "true", "try", "using", "var", "virtual", "void", "while",
+        ],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        string_delims: &['"', '\''],
+        case_insensitive: false,
+    },
+    LanguageSpec {
+        name: "java",
+        aliases: &["kotlin", "kt", "scala"],
+        keywords: &[
+            "abstract", "boolean", "break", "case", "catch", "class", "continue", "default",
+            "do", "else", "enum", "extends", "false", "final", "finally", "for", "fun", "if",
+            "implements", "import", "instanceof", "int", "interface", "new", "null", "package",
+            "private", "protected", "public", "return", "static", "super", "switch", "this",
+            "throw", "throws", "true", "try", "val", "var", "void", "while",
+        ],
+        line_comments: &["//"],
+        block_comment: Some(("/*", "*/")),
+        string_delims: &['"', '\''],
+        case_insensitive: false,
+    },
+    LanguageSpec {
+        name: "bash",
+        aliases: &["sh", "shell", "zsh", "console", "shellscript"],
+        keywords: &[
+            "case", "do", "done", "echo", "elif", "else", "esac", "exit", "export", "fi", "for",
+            "function", "if", "in", "local", "return", "set", "then", "until", "while",
+        ],
+        line_comments: &["#"],
+        block_comment: None,
+        string_delims: &['"', '\''],
+        case_insensitive: false,
+    },
+    LanguageSpec {
+        name: "powershell",
+        aliases: &["ps1", "pwsh", "ps"],
+        keywords: &[
+            "begin", "break", "catch", "continue", "do", "else", "elseif", "end", "exit",
+            "filter", "finally", "foreach", "function", "if", "in", "param", "process",
+            "return", "switch", "throw", "trap", "try", "until", "while",
+        ],
+        line_comments: &["#"],
+        block_comment: Some(("<#", "#>")),
+        string_delims: &['"', '\''],
+        case_insensitive: true,
+    },
+    LanguageSpec {
+        name: "sql",
+        aliases: &["tsql", "mysql", "postgresql", "postgres", "kql"],
+        keywords: &[
+            "and", "as", "asc", "by", "create", "delete", "desc", "distinct", "drop", "from",
+            "group", "having", "in", "insert", "into", "is", "join", "left", "limit", "not",
+            "null", "on", "or", "order", "right", "select", "set", "table", "top", "union",
+            "update", "values", "where", "with",
+        ],
+        line_comments: &["--"],
+        block_comment: Some(("/*", "*/")),
+        string_delims: &['\''],
+        case_insensitive: true,
+    },
+    LanguageSpec {
+        name: "json",
+        aliases: &["jsonc"],
+        keywords: &["true", "false", "null"],
+        line_comments: &[],
+        block_comment: None,
+        string_delims: &['"'],
+        case_insensitive: false,
+    },
+    LanguageSpec {
+        name: "yaml",
+        aliases: &["yml", "toml", "ini"],
+        keywords: &["true", "false", "null", "yes", "no"],
+        line_comments: &["#"],
+        block_comment: None,
+        string_delims: &['"', '\''],
+        case_insensitive: false,
+    },
+];
+
+/// Resolve a language hint (e.g. `language-rust`, `Python`, `ts`) to a canonical name
+pub fn normalize_language(hint: &str) -> Option<&'static str> {
+    let lower = hint.trim().to_lowercase();
+    let stripped = lower
+        .strip_prefix("language-")
+        .or_else(|| lower.strip_prefix("lang-"))
+        .unwrap_or(&lower);
+    find_spec(stripped).map(|spec| spec.name)
+}
+
+fn find_spec(name: &str) -> Option<&'static LanguageSpec> {
+    LANGUAGES
+        .iter()
+        .find(|spec| spec.name == name || spec.aliases.contains(&name))
+}
+
+/// Highlight a code block line by line. Block comments are tracked across lines.
+/// Unknown or missing languages produce a single plain token per line.
+pub fn highlight_code(language: Option<&str>, code: &str) -> Vec<Vec<(TokenKind, String)>> {
+    let spec = language.and_then(normalize_language).and_then(find_spec);
+    let mut in_block_comment = false;
+    code.lines()
+        .map(|line| {
+            let line = line.replace('\t', "    ");
+            match spec {
+                Some(spec) => highlight_line(spec, &line, &mut in_block_comment),
+                None => vec![(TokenKind::Plain, line)],
+            }
+        })
+        .collect()
+}
+
+fn highlight_line(
+    spec: &LanguageSpec,
+    line: &str,
+    in_block_comment: &mut bool,
+) -> Vec<(TokenKind, String)> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut tokens: Vec<(TokenKind, String)> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if *in_block_comment {
+            let (_, end) = spec.block_comment.unwrap_or(("", ""));
+            let stop = find_from(&chars, i, end)
+                .map(|pos| pos + end.chars().count())
+                .unwrap_or(chars.len());
+            *in_block_comment = stop == chars.len() && !ends_with_at(&chars, stop, end);
+            push_token(&mut tokens, TokenKind::Comment, &chars[i..stop]);
+            i = stop;
+            continue;
+        }
+
+        if spec.line_comments.iter().any(|c| starts_with_at(&chars, i, c)) {
+            push_token(&mut tokens, TokenKind::Comment, &chars[i..]);
+            break;
+        }
+
+        if let Some((start, _)) = spec.block_comment {
+            if starts_with_at(&chars, i, start) {
+                *in_block_comment = true;
+                let skip = start.chars().count();
+                push_token(&mut tokens, TokenKind::Comment, &chars[i..i + skip]);
+                i += skip;
+                continue;
+            }
+        }
+
+        let ch = chars[i];
+        if spec.string_delims.contains(&ch) {
+            let mut j = i + 1;
+            while j < chars.len() && chars[j] != ch {
+                if chars[j] == '\\' {
+                    j += 1;
+                }
+                j += 1;
+            }
+            let stop = (j + 1).min(chars.len());
+            push_token(&mut tokens, TokenKind::String, &chars[i..stop]);
+            i = stop;
+            continue;
+        }
+
+        let prev_is_word = i > 0 && is_word_char(chars[i - 1]);
+        if ch.is_ascii_digit() && !prev_is_word {
+            let mut j = i;
+            while j < chars.len() && (chars[j].is_ascii_alphanumeric() || chars[j] == '.' || chars[j] == '_') {
+                j += 1;
+            }
+            push_token(&mut tokens, TokenKind::Number, &chars[i..j]);
+            i = j;
+            continue;
+        }
+
+        if is_word_char(ch) && !ch.is_ascii_digit() {
+            let mut j = i;
+            while j < chars.len() && is_word_char(chars[j]) {
+                j += 1;
+            }
+            let word: String = chars[i..j].iter().collect();
+            let is_keyword = if spec.case_insensitive {
+                let lower = word.to_lowercase();
+                spec.keywords.iter().any(|k| *k == lower)
+            } else {
+                spec.keywords.contains(&word.as_str())
+            };
+            let kind = if is_keyword { TokenKind::Keyword } else { TokenKind::Plain };
+            push_token(&mut tokens, kind, &chars[i..j]);
+            i = j;
+            continue;
+        }
+
+        push_token(&mut tokens, TokenKind::Plain, &chars[i..i + 1]);
+        i += 1;
+    }
+
+    tokens
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn starts_with_at(chars: &[char], index: usize, pattern: &str) -> bool {
+    let pat: Vec<char> = pattern.chars().collect();
+    !pat.is_empty() && chars.len() >= index + pat.len() && chars[index..index + pat.len()] == pat[..]
+}
+
+fn ends_with_at(chars: &[char], stop: usize, pattern: &str) -> bool {
+    let len = pattern.chars().count();
+    stop >= len && starts_with_at(chars, stop - len, pattern)
+}
+
+fn find_from(chars: &[char], start: usize, pattern: &str) -> Option<usize> {
+    (start..chars.len()).find(|&i| starts_with_at(chars, i, pattern))
+}
+
+/// Append text to the token list, merging with the previous token of the same kind
+fn push_token(tokens: &mut Vec<(TokenKind, String)>, kind: TokenKind, text: &[char]) {
+    if text.is_empty() {
+        return;
+    }
+    if let Some(last) = tokens.last_mut() {
+        if last.0 == kind {
+            last.1.extend(text.iter());
+            return;
+        }
+    }
+    tokens.push((kind, text.iter().collect()));
+}
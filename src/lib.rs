@@ -1,7 +1,16 @@
 pub mod app;
 pub mod auth;
+pub mod cards;
 pub mod client;
+pub mod clipboard;
 pub mod config;
+pub mod downloads;
+pub mod emoji;
+pub mod files;
+pub mod graphics;
+pub mod highlight;
 pub mod logging;
 pub mod models;
+pub mod sections;
+pub mod state;
 pub mod ui;
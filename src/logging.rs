@@ -92,8 +92,8 @@ fn standard_log_dir() -> Result<PathBuf> {
             return Ok(state_dir.join("ttyms").join("logs"));
         }
 
-        return dirs::home_dir()
+        dirs::home_dir()
             .context("Could not determine home directory")
-            .map(|h| h.join(".local").join("state").join("ttyms").join("logs"));
+            .map(|h| h.join(".local").join("state").join("ttyms").join("logs"))
     }
 }
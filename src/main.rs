@@ -1,9 +1,18 @@
 mod app;
 mod auth;
+mod cards;
 mod client;
+mod clipboard;
 mod config;
+mod downloads;
+mod emoji;
+mod files;
+mod graphics;
+mod highlight;
 mod logging;
 mod models;
+mod sections;
+mod state;
 mod ui;
 
 use anyhow::Result;
@@ -13,9 +22,13 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::{collections::HashSet, io};
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+};
 
 use app::{AppScreen, DialogMode, Panel, TeamsPanel, ViewMode};
+use cards::CardAction;
 
 fn log_event(event: &str) {
     logging::try_log_event(event);
@@ -87,17 +100,27 @@ impl<'a> std::io::Write for Base64Writer<'a> {
 
 // Background task results delivered via channel
 enum BgResult {
+    Teams(Vec<models::Team>),
     Channels(String, Vec<models::Channel>),
     ChannelMessages(String, Vec<models::Message>),
+    /// Newest messages of a channel polled for unread activity
+    ChannelActivity(String, Vec<models::Message>),
+    ChannelPollThrottled,
     PresenceMap(std::collections::HashMap<String, String>),
     MyPresence(String),
     // Auto-refresh results
     RefreshedChats(Vec<models::Chat>),
     RefreshedChatMessages(Vec<models::Message>, Option<String>),
-    RefreshedChannelMessages(String, Vec<models::Message>, Option<String>),
+    RefreshedChannelMessages(String, Vec<models::Message>),
+    /// Chats marked read by "mark all read", and how many failed
+    ChatsMarkedRead(Vec<String>, usize),
     TokenRefreshed(String),
     // Delta query results (incremental sync)
     DeltaChatMessages(String, Vec<models::Message>, Option<String>),
+    DeltaChannelMessages(String, Vec<models::Message>, Option<String>),
+    /// The channel delta query failed; refresh this channel by page instead,
+    /// and stop trying delta for it when the failure says it is unsupported
+    ChannelDeltaFailed(String, bool),
     // Search results
     SearchResults(Vec<models::SearchHit>),
     SearchError(String),
@@ -108,8 +131,24 @@ enum BgResult {
     // File upload results
     FileUploaded(String),
     FileUploadError(String),
+    FileUploadProgress(u64, u64),
     // Image preview results
-    ImagePreview(String, Vec<String>),
+    ImagePreview(String, graphics::ImagePreview, Option<Vec<u8>>),
+    // Attachment download results
+    DownloadProgress(u64, Option<u64>),
+    DownloadFinished(std::path::PathBuf),
+    DownloadError(String),
+    // Shared files panel: channel SharePoint folder listing
+    ChannelFiles(String, String, Vec<models::SharedFile>),
+    ChannelFilesError(String, String, String),
+    // Forward results (target label on success)
+    Forwarded(String),
+    ForwardError(String),
+    // Inline image send results (error carries the caption to restore)
+    InlineImageSent,
+    InlineImageError(String, String),
+    /// Pinned messages of a chat; empty when they could not be loaded
+    PinnedMessages(String, Vec<models::PinnedMessage>),
 }
 
 #[tokio::main]
@@ -242,6 +281,34 @@ async fn run_app(
     log_event("app.run.start");
     let mut app = app::App::new();
     app.refresh_interval = std::time::Duration::from_secs(config.refresh_interval_secs.max(5));
+    app.image_protocol_setting = config.image_protocol.clone();
+    app.image_protocol = graphics::resolve_protocol(&config.image_protocol);
+    app.download_dir = config.download_dir.clone();
+    app.open_after_download = config.open_after_download;
+    let mut state = match state::load_state(&config) {
+        Ok(state) => state,
+        Err(e) => {
+            log_failure("state.load");
+            app.show_error(
+                "State Not Loaded",
+                "Recent emoji, skin tone, chat preferences and channel read times start empty.",
+                &format!("{:#}", e),
+            );
+            state::State::default()
+        }
+    };
+    app.skin_tone = state.skin_tone.min(emoji::SKIN_TONES.len() - 1);
+    app.recent_emojis = state.recent_emojis.clone();
+    app.mark_read_mode = config::MarkReadMode::parse(&config.mark_read).unwrap_or_default();
+    app.confirm_delete = config.confirm_delete;
+    app.channel_message_write = config.channel_message_write;
+    log_event(match app.image_protocol.as_str() {
+        "kitty" => "graphics.protocol.kitty",
+        "iterm2" => "graphics.protocol.iterm2",
+        "sixel" => "graphics.protocol.sixel",
+        "truecolor" => "graphics.protocol.truecolor",
+        _ => "graphics.protocol.grayscale",
+    });
     let mut graph = client::GraphClient::new(token.access_token.clone());
 
     // Background task channel for non-blocking data loading
@@ -257,7 +324,14 @@ async fn run_app(
     log_event("startup.profile.fetch");
     match graph.get_me().await {
         Ok(user) => {
+            app.chat_prefs = state.chat_prefs.get(&user.id).cloned().unwrap_or_default();
+            app.channel_last_read = state.channel_last_read.get(&user.id).cloned().unwrap_or_default();
             app.current_user = Some(user);
+            let invalid = app.set_chat_sections(&config.chat_sections);
+            if !invalid.is_empty() {
+                log_failure("config.chat_sections.topic");
+                app.status_message = format!("Invalid topic pattern in chat section: {}", invalid.join(", "));
+            }
             log_event("startup.profile.success");
         }
         Err(e) => {
@@ -275,8 +349,7 @@ async fn run_app(
     log_event("startup.chats.fetch");
     match graph.list_chats().await {
         Ok(chats) => {
-            app.chats = chats;
-            app.update_total_unread();
+            app.set_chats(chats);
             app.screen = AppScreen::Main;
             log_event("startup.chats.success");
         }
@@ -306,18 +379,42 @@ async fn run_app(
     // Fetch presence in background (non-blocking)
     spawn_presence_load(&graph, &app, &bg_tx);
 
+    // Load teams in background so channel activity shows up in the header
+    spawn_teams_load(&graph, &bg_tx);
+
     app.mark_refreshed();
 
+    let mut last_viewer_key: Option<app::ViewerRenderKey> = None;
+    let mut last_state_save = std::time::Instant::now();
+
     // Main event loop
     loop {
         // Process any completed background tasks (non-blocking)
         while let Ok(result) = bg_rx.try_recv() {
             match result {
+                BgResult::PinnedMessages(chat_id, pins) => {
+                    if app.selected_chat_id() == Some(chat_id.as_str()) {
+                        app.set_pinned_messages(&chat_id, pins);
+                    }
+                }
+                BgResult::Teams(teams) => {
+                    log_event("bg.teams.loaded");
+                    if app.teams.is_empty() {
+                        app.teams = teams;
+                        spawn_channels_preload(&graph, &app, &bg_tx);
+                    }
+                }
                 BgResult::Channels(team_id, channels) => {
                     log_event("bg.channels.loaded");
                     // Cache and update display if this team is still selected
-                    app.channels_cache.insert(team_id.clone(), channels.clone());
-                    if app.selected_team_id() == Some(team_id.as_str()) {
+                    let first_load = app.channels_cache.insert(team_id.clone(), channels.clone()).is_none();
+                    if first_load {
+                        let targets = app.channel_poll_targets(Some(&team_id));
+                        spawn_channel_activity_poll(&graph, targets, &bg_tx);
+                    }
+                    if app.selected_team_id() == Some(team_id.as_str())
+                        && app.view_mode == ViewMode::Teams
+                    {
                         app.channels = channels;
                         if app.selected_channel == 0 {
                             app.show_cached_messages_for_selected_channel();
@@ -327,12 +424,22 @@ async fn run_app(
                 BgResult::ChannelMessages(channel_id, messages) => {
                     log_event("bg.channel_messages.loaded");
                     app.channel_message_cache.insert(channel_id.clone(), messages.clone());
+                    app.seed_channel_last_read(&channel_id);
                     if app.selected_channel_id() == Some(channel_id.as_str())
                         && app.view_mode == ViewMode::Teams
                     {
                         app.channel_messages = messages;
                     }
                 }
+                BgResult::ChannelActivity(channel_id, messages) => {
+                    log_event("bg.channel_activity.loaded");
+                    app.channel_poll_backoff = 0;
+                    app.merge_channel_activity(&channel_id, messages);
+                }
+                BgResult::ChannelPollThrottled => {
+                    log_failure("bg.channel_activity.throttled");
+                    app.channel_poll_throttled();
+                }
                 BgResult::PresenceMap(map) => {
                     log_event("bg.presence_map.loaded");
                     app.presence_map.extend(map);
@@ -347,22 +454,37 @@ async fn run_app(
                 }
                 BgResult::RefreshedChats(chats) => {
                     log_event("bg.refresh.chats");
-                    app.chats = chats;
-                    app.update_total_unread();
+                    app.set_chats(chats);
                 }
                 BgResult::RefreshedChatMessages(messages, next_link) => {
                     log_event("bg.refresh.chat_messages");
                     app.messages = messages;
                     app.messages_next_link = next_link;
-                    if app.detect_new_messages() {
+                    if app.detect_new_messages() && !app.selected_chat_muted() {
                         print!("\x07");
                     }
                 }
-                BgResult::RefreshedChannelMessages(channel_id, msgs, next_link) => {
+                BgResult::RefreshedChannelMessages(channel_id, msgs) => {
                     log_event("bg.refresh.channel_messages");
-                    app.channel_messages = msgs.clone();
-                    app.channel_messages_next_link = next_link;
-                    app.channel_message_cache.insert(channel_id, msgs);
+                    app.merge_channel_messages(&channel_id, msgs);
+                    app.mark_open_channel_seen(&channel_id);
+                }
+                BgResult::DeltaChannelMessages(channel_id, msgs, delta_link) => {
+                    log_event("bg.delta.channel_messages");
+                    if let Some(link) = delta_link {
+                        app.channel_delta_links.insert(channel_id.clone(), link);
+                    }
+                    if app.merge_channel_messages(&channel_id, msgs) {
+                        app.mark_open_channel_seen(&channel_id);
+                    }
+                }
+                BgResult::ChannelDeltaFailed(channel_id, unsupported) => {
+                    log_failure("bg.delta.channel_messages");
+                    // Without a link the next refresh starts a fresh delta query
+                    app.channel_delta_links.remove(&channel_id);
+                    if unsupported {
+                        app.channel_delta_unsupported.insert(channel_id);
+                    }
                 }
                 BgResult::DeltaChatMessages(chat_id, delta_msgs, delta_link) => {
                     log_event("bg.delta.chat_messages");
@@ -370,7 +492,7 @@ async fn run_app(
                         if let Some(link) = delta_link {
                             app.chat_delta_links.insert(chat_id, link);
                         }
-                        if app.merge_delta_messages(delta_msgs) {
+                        if app.merge_delta_messages(delta_msgs) && !app.selected_chat_muted() {
                             print!("\x07");
                         }
                     }
@@ -412,21 +534,99 @@ async fn run_app(
                 BgResult::FileUploaded(msg) => {
                     log_event("file_share.success");
                     app.file_uploading = false;
+                    app.file_upload_progress = None;
+                    app.file_upload_cancel = None;
                     app.close_dialog();
                     app.status_message = msg;
                 }
                 BgResult::FileUploadError(err) => {
                     log_failure("file_share.background");
                     app.file_uploading = false;
+                    app.file_upload_progress = None;
+                    app.file_upload_cancel = None;
                     app.file_upload_error = Some(err);
                 }
-                BgResult::ImagePreview(url, lines) => {
+                BgResult::FileUploadProgress(sent, total) => {
+                    if app.file_uploading {
+                        app.file_upload_progress = Some((sent, total));
+                    }
+                }
+                BgResult::ImagePreview(url, preview, bytes) => {
                     log_event("image_preview.result.ready");
-                    app.set_image_preview(url, lines);
+                    if let Some(bytes) = bytes {
+                        app.store_image_source(url.clone(), bytes);
+                    }
+                    app.set_image_preview(url, preview);
+                }
+                BgResult::DownloadProgress(received, total) => {
+                    if let Some(download) = app.download.as_mut() {
+                        download.received = received;
+                        download.total = total;
+                    }
+                }
+                BgResult::DownloadFinished(path) => {
+                    log_event("file_download.success");
+                    app.download = None;
+                    app.status_message = format!("Saved to {}", path.display());
+                    if app.open_after_download && open::that(&path).is_err() {
+                        log_failure("file_download.open");
+                        app.status_message = format!("Saved to {} (could not open it)", path.display());
+                    }
+                }
+                BgResult::DownloadError(err) => {
+                    app.download = None;
+                    app.show_error(
+                        "Download Failed",
+                        "Could not download the attachment.",
+                        &err,
+                    );
+                }
+                BgResult::Forwarded(target) => {
+                    log_event("forward.success");
+                    app.close_forward();
+                    app.status_message = format!("↪ Forwarded to {}", target);
+                }
+                BgResult::ForwardError(err) => {
+                    app.close_forward();
+                    app.show_error("Forward Failed", "Could not forward the message.", &err);
+                }
+                BgResult::InlineImageSent => {
+                    log_event("inline_image.send.success");
+                    app.inline_image_sending = false;
+                    app.status_message = "🖼 Image sent".to_string();
+                }
+                BgResult::InlineImageError(err, caption) => {
+                    app.inline_image_sending = false;
+                    app.restore_composer_text(caption);
+                    app.show_error("Image Not Sent", "Could not send the image.", &err);
+                }
+                BgResult::ChannelFiles(team_id, channel_id, files) => {
+                    log_event("channel_files.success");
+                    app.merge_channel_files(&team_id, &channel_id, files);
+                }
+                BgResult::ChannelFilesError(team_id, channel_id, err) => {
+                    app.channel_files_failed(&team_id, &channel_id, err);
+                }
+                BgResult::ChatsMarkedRead(chat_ids, failed) => {
+                    app.set_chats_read(&chat_ids);
+                    app.status_message = if failed == 0 {
+                        format!("✓ Marked {} chats read", chat_ids.len())
+                    } else {
+                        format!("Marked {} chats read, {} failed", chat_ids.len(), failed)
+                    };
                 }
             }
         }
 
+        if let Some(chat_id) = app.take_due_mark_read() {
+            log_event("chat_mark_read.delayed");
+            mark_chat_read(&graph, &mut app, &chat_id).await;
+        }
+
+        if let Some(chat_id) = app.pins_to_load.take() {
+            spawn_pinned_messages_load(&graph, chat_id, &bg_tx);
+        }
+
         let chat_preview_urls = collect_image_preview_urls(&app.messages);
         queue_image_preview_fetches(&graph, &mut app, &bg_tx, chat_preview_urls);
         let channel_preview_urls = collect_image_preview_urls(&app.channel_messages);
@@ -434,6 +634,23 @@ async fn run_app(
 
         terminal.draw(|f| ui::draw(f, &mut app))?;
 
+        // Pixel protocols bypass ratatui: emit the image whenever the viewer's view changes
+        let viewer_key = app
+            .image_viewer
+            .as_ref()
+            .map(|viewer| viewer.render_key(app.layout_areas.image_viewer));
+        if viewer_key != last_viewer_key && app.image_protocol.is_pixel_protocol() {
+            if app.image_protocol == graphics::GraphicsProtocol::Kitty {
+                print!("{}", graphics::KITTY_DELETE_ALL);
+            }
+            terminal.clear()?;
+            terminal.draw(|f| ui::draw(f, &mut app))?;
+            if let Some(viewer) = app.image_viewer.as_ref() {
+                emit_viewer_image(viewer, app.layout_areas.image_viewer, app.image_protocol)?;
+            }
+        }
+        last_viewer_key = viewer_key;
+
         if event::poll(std::time::Duration::from_millis(100))? {
             match event::read()? {
                 Event::Key(key) => {
@@ -457,14 +674,31 @@ async fn run_app(
                     continue;
                 }
 
+                // Ctrl+V sends a clipboard image to the current chat or channel
+                if key.code == KeyCode::Char('v')
+                    && key.modifiers.contains(KeyModifiers::CONTROL)
+                    && app.dialog == app::DialogMode::None
+                {
+                    start_inline_image_send(&mut app, &graph, &bg_tx, None);
+                    continue;
+                }
+
                 // Dialog mode intercepts all keys
                 match &app.dialog {
                     DialogMode::NewChat => {
                         handle_new_chat_keys(&mut app, &graph, key.code).await;
                         continue;
                     }
+                    DialogMode::PinnedMessages => {
+                        handle_pinned_messages_keys(&mut app, &graph, key.code).await;
+                        continue;
+                    }
+                    DialogMode::ConfirmDelete => {
+                        handle_delete_confirm_keys(&mut app, &graph, key.code).await;
+                        continue;
+                    }
                     DialogMode::ReactionPicker => {
-                        handle_reaction_picker_keys(&mut app, &graph, key.code).await;
+                        handle_reaction_picker_keys(&mut app, &graph, key).await;
                         continue;
                     }
                     DialogMode::PresencePicker => {
@@ -491,6 +725,22 @@ async fn run_app(
                         handle_file_picker_keys(&mut app, &graph, &bg_tx, key.code).await;
                         continue;
                     }
+                    DialogMode::ReactionDetails => {
+                        handle_reaction_details_keys(&mut app, key.code);
+                        continue;
+                    }
+                    DialogMode::Forward => {
+                        handle_forward_keys(&mut app, &graph, &bg_tx, key.code);
+                        continue;
+                    }
+                    DialogMode::SharedFiles => {
+                        handle_shared_files_keys(&mut app, &graph, &bg_tx, key.code);
+                        continue;
+                    }
+                    DialogMode::ImageViewer => {
+                        handle_image_viewer_keys(&mut app, key.code);
+                        continue;
+                    }
                     DialogMode::Error(info) => {
                         match key.code {
                             KeyCode::Char('c') | KeyCode::Char('C') => {
@@ -514,6 +764,11 @@ async fn run_app(
                     DialogMode::None => {}
                 }
 
+                if app.view_mode == ViewMode::Chats && app.chat_filter.editing {
+                    handle_chat_filter_keys(&mut app, &graph, key.code).await;
+                    continue;
+                }
+
                 // Global keys (work in both views)
                 match key.code {
                     KeyCode::Char('1') if app.active_panel != Panel::Input
@@ -527,6 +782,9 @@ async fn run_app(
                     {
                         if app.teams.is_empty() {
                             load_teams_with_preload(&graph, &mut app, &bg_tx).await;
+                        } else if app.channels.is_empty() {
+                            // Teams were loaded in background at startup
+                            load_channels_with_preload(&graph, &mut app, &bg_tx).await;
                         }
                         app.switch_to_teams();
                         continue;
@@ -549,6 +807,12 @@ async fn run_app(
                         app.open_search();
                         continue;
                     }
+                    KeyCode::Char('N') if app.active_panel != Panel::Input
+                        && app.teams_panel != TeamsPanel::ChannelInput =>
+                    {
+                        jump_to_next_unread(&graph, &mut app).await;
+                        continue;
+                    }
                     _ => {}
                 }
 
@@ -582,8 +846,17 @@ async fn run_app(
             spawn_presence_load(&graph, &app, &bg_tx);
             app.mark_refreshed();
         }
+
+        // Light poll of other channels for unread activity
+        if app.should_poll_channels() {
+            let targets = app.channel_poll_targets(None);
+            spawn_channel_activity_poll(&graph, targets, &bg_tx);
+            app.mark_channels_polled();
+        }
+        save_state(&mut app, &mut state, &mut last_state_save, false);
     }
 
+    save_state(&mut app, &mut state, &mut last_state_save, true);
     Ok(())
 }
 
@@ -595,6 +868,7 @@ fn collect_image_preview_urls(messages: &[models::Message]) -> Vec<String> {
                 urls.insert(url.clone());
             }
         }
+        urls.extend(msg.hosted_image_urls());
     }
     urls.into_iter().collect()
 }
@@ -612,99 +886,473 @@ fn queue_image_preview_fetches(
         log_event("image_preview.fetch.queued");
         let bg_graph = graph.clone_for_background();
         let tx = bg_tx.clone();
+        let protocol = app.image_protocol;
         tokio::spawn(async move {
             log_event("image_preview.fetch.start");
-            let lines = match bg_graph.download_binary_with_reason(&url).await {
+            let (preview, source) = match bg_graph.download_binary_with_reason(&url).await {
                 Ok(bytes) => {
                     log_event("image_preview.download.success");
-                    match decode_image_preview_lines(&bytes) {
-                        Ok(lines) => {
+                    match graphics::render_preview(&bytes, protocol) {
+                        Ok(preview) => {
                             log_event("image_preview.decode.success");
-                            lines
+                            (preview, Some(bytes))
                         }
                         Err(kind) => {
                             log_failure(kind.as_label());
-                            vec!["(preview unavailable)".to_string()]
+                            (graphics::ImagePreview::Unavailable, None)
                         }
                     }
                 }
                 Err(kind) => {
                     log_failure(kind.as_label());
-                    vec!["(preview unavailable)".to_string()]
+                    (graphics::ImagePreview::Unavailable, None)
                 }
             };
-            if tx.send(BgResult::ImagePreview(url, lines)).is_err() {
+            if tx.send(BgResult::ImagePreview(url, preview, source)).is_err() {
                 log_failure("image_preview.result.dispatch");
             }
         });
     }
 }
 
-enum PreviewDecodeFailure {
-    InvalidBytes,
-    EmptyDimensions,
+// ---- Chat view key handling ----
+
+/// Copy the code blocks of the selected message to the clipboard via OSC 52
+fn copy_selected_code_blocks(app: &mut app::App) {
+    let blocks = app.selected_message_code_blocks();
+    if blocks.is_empty() {
+        app.status_message = "No code block in selected message".to_string();
+        return;
+    }
+    let b64 = base64_encode(&blocks.join("\n\n"));
+    print!("\x1b]52;c;{}\x07", b64);
+    app.status_message = if blocks.len() == 1 {
+        "Code block copied to clipboard".to_string()
+    } else {
+        format!("{} code blocks copied to clipboard", blocks.len())
+    };
 }
 
-impl PreviewDecodeFailure {
-    fn as_label(&self) -> &'static str {
-        match self {
-            PreviewDecodeFailure::InvalidBytes => "image_preview.decode.invalid_bytes",
-            PreviewDecodeFailure::EmptyDimensions => "image_preview.decode.empty_dimensions",
-        }
+/// Open the popup listing who reacted to the selected message
+fn show_reaction_details(app: &mut app::App) {
+    if !app.open_reaction_details() {
+        app.status_message = "No reactions on selected message".to_string();
     }
 }
 
-fn decode_image_preview_lines(bytes: &[u8]) -> std::result::Result<Vec<String>, PreviewDecodeFailure> {
-    let decoded = match image::load_from_memory(bytes) {
-        Ok(img) => img.thumbnail(28, 16).to_luma8(),
-        Err(_) => return Err(PreviewDecodeFailure::InvalidBytes),
+/// Move the card action cursor of the selected message and describe the target in the status bar
+fn select_next_card_action(app: &mut app::App) {
+    app.status_message = match app.cycle_card_action() {
+        Some(CardAction::OpenUrl { title, .. }) => format!("Card action: {} (Enter to open)", title),
+        Some(CardAction::Unsupported { title, kind }) => {
+            format!("Card action: {} ({} is not supported)", title, kind)
+        }
+        None => "No card actions in selected message".to_string(),
     };
+}
 
-    let width = decoded.width() as usize;
-    let height = decoded.height() as usize;
-    if width == 0 || height == 0 {
-        return Err(PreviewDecodeFailure::EmptyDimensions);
-    }
-
-    let mut lines = Vec::new();
-    lines.push(format!("┌{}┐", "─".repeat(width)));
-    let mut y = 0usize;
-    while y < height {
-        let mut row = String::with_capacity(width);
-        for x in 0..width {
-            let top = decoded.get_pixel(x as u32, y as u32)[0];
-            let bottom = if y + 1 < height {
-                decoded.get_pixel(x as u32, (y + 1) as u32)[0]
+/// Run the selected card action. Returns false when no card action is selected.
+fn run_selected_card_action(app: &mut app::App) -> bool {
+    match app.selected_card_action() {
+        Some(CardAction::OpenUrl { url, .. }) => {
+            if !cards::is_web_url(&url) {
+                logging::try_log_failure("card.action.open_url.scheme");
+                app.status_message = "Refusing to open a non-web card link".to_string();
+            } else if open::that(&url).is_err() {
+                logging::try_log_failure("card.action.open_url");
+                app.status_message = "Failed to open card link".to_string();
             } else {
-                top
+                logging::try_log_event("card.action.open_url");
+            }
+            true
+        }
+        Some(CardAction::Unsupported { kind, .. }) => {
+            app.status_message = format!("{} is not supported in ttyms — open the message in Teams", kind);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Open the selected message's image in the viewer. Returns false when the message
+/// has no image, so the caller can fall back to opening the attachment.
+fn open_selected_image(app: &mut app::App) -> bool {
+    let Some((url, name)) = app.selected_message_image() else {
+        return false;
+    };
+    if app.open_image_viewer(&url, &name) {
+        log_event("image_viewer.open");
+        return true;
+    }
+    if models::is_hosted_content_url(&url) {
+        // Inline images can't be opened in a browser without a token
+        app.status_message = if app.is_image_preview_pending(&url) {
+            "Image is still loading…".to_string()
+        } else {
+            "Image could not be loaded".to_string()
+        };
+        return true;
+    }
+    false
+}
+
+/// Run the selected message's card action, view its image or open its
+/// attachment. Returns false when it has none of them.
+fn open_selected_message_item(app: &mut app::App) -> bool {
+    if run_selected_card_action(app) || open_selected_image(app) {
+        return true;
+    }
+    match app.selected_message_attachment_url() {
+        Some(url) => {
+            let _ = open::that(&url);
+            true
+        }
+        None => false,
+    }
+}
+
+fn handle_image_viewer_keys(app: &mut app::App, code: KeyCode) {
+    let Some(viewer) = app.image_viewer.as_mut() else {
+        app.close_dialog();
+        return;
+    };
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.close_image_viewer(),
+        KeyCode::Char('+') | KeyCode::Char('=') => viewer.zoom_in(),
+        KeyCode::Char('-') => viewer.zoom_out(),
+        KeyCode::Char('0') | KeyCode::Char('f') => viewer.fit(),
+        KeyCode::Left | KeyCode::Char('h') => viewer.pan(-0.25, 0.0),
+        KeyCode::Right | KeyCode::Char('l') => viewer.pan(0.25, 0.0),
+        KeyCode::Up | KeyCode::Char('k') => viewer.pan(0.0, -0.25),
+        KeyCode::Down | KeyCode::Char('j') => viewer.pan(0.0, 0.25),
+        KeyCode::Char('O') => {
+            let url = viewer.url.clone();
+            if models::is_hosted_content_url(&url) {
+                app.status_message = "Inline images can only be viewed in ttyms".to_string();
+            } else if open::that(&url).is_err() {
+                log_failure("image_viewer.open_external");
+                app.status_message = "Failed to open image".to_string();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Draw the viewer's visible region with a pixel protocol, centered in `area`
+fn emit_viewer_image(
+    viewer: &app::ImageViewer,
+    area: ratatui::layout::Rect,
+    protocol: graphics::GraphicsProtocol,
+) -> io::Result<()> {
+    if area.width == 0 || area.height == 0 {
+        return Ok(());
+    }
+    let (cell_w, cell_h) = crossterm::terminal::window_size()
+        .ok()
+        .filter(|size| size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0)
+        .map(|size| (size.width / size.columns, size.height / size.rows))
+        .unwrap_or((8, 16));
+    let visible = viewer.visible_image();
+    let (cols, rows) =
+        graphics::fit_cells(visible.width(), visible.height(), area.width, area.height, cell_w, cell_h);
+    if cols == 0 || rows == 0 {
+        return Ok(());
+    }
+    let sequence = match protocol {
+        graphics::GraphicsProtocol::Kitty | graphics::GraphicsProtocol::Iterm2 => {
+            let Some(png) = graphics::encode_png(&visible) else {
+                log_failure("image_viewer.encode");
+                return Ok(());
             };
-            row.push(luma_pair_to_block(top, bottom));
+            if protocol == graphics::GraphicsProtocol::Kitty {
+                graphics::kitty_sequence(&png, cols, rows)
+            } else {
+                graphics::iterm2_sequence(&png, cols, rows)
+            }
+        }
+        graphics::GraphicsProtocol::Sixel => {
+            let scaled = visible.resize_exact(
+                cols as u32 * cell_w.max(1) as u32,
+                rows as u32 * cell_h.max(1) as u32,
+                image::imageops::FilterType::Triangle,
+            );
+            graphics::sixel_sequence(&scaled.to_rgb8())
+        }
+        _ => return Ok(()),
+    };
+    let x = area.x + (area.width - cols) / 2;
+    let y = area.y + (area.height - rows) / 2;
+    let mut stdout = io::stdout();
+    execute!(stdout, crossterm::cursor::MoveTo(x, y))?;
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()
+}
+
+/// Save the selected message's attachment to the downloads folder in the background.
+/// Reference attachments (SharePoint/OneDrive links) are resolved via `/shares` first.
+fn start_attachment_download(
+    app: &mut app::App,
+    graph: &client::GraphClient,
+    bg_tx: &tokio::sync::mpsc::UnboundedSender<BgResult>,
+) {
+    let Some((url, name)) = app.selected_message_download() else {
+        app.status_message = "No attachment to download".to_string();
+        return;
+    };
+    start_download(app, graph, bg_tx, url, name);
+}
+
+/// Download `url` into the downloads folder. Graph URLs are fetched directly;
+/// anything else is treated as a SharePoint/OneDrive sharing link.
+fn start_download(
+    app: &mut app::App,
+    graph: &client::GraphClient,
+    bg_tx: &tokio::sync::mpsc::UnboundedSender<BgResult>,
+    url: String,
+    name: String,
+) {
+    if app.download.is_some() {
+        app.status_message = "A download is already in progress".to_string();
+        return;
+    }
+    let Some(dir) = downloads::download_dir(&app.download_dir) else {
+        app.status_message = "Could not determine the downloads folder".to_string();
+        return;
+    };
+    app.download = Some(downloads::DownloadProgress::new(&name));
+    log_event("file_download.start");
+
+    let bg_graph = graph.clone_for_background();
+    let tx = bg_tx.clone();
+    tokio::spawn(async move {
+        let (source, name) = if url.starts_with("https://graph.microsoft.com/") {
+            (url, name)
+        } else {
+            match bg_graph.resolve_shared_item(&url).await {
+                Ok(item) => (client::shared_item_content_url(&url), item.name),
+                Err(failure) => {
+                    logging::try_log_failure(failure.as_file_label());
+                    let _ = tx.send(BgResult::DownloadError(failure.describe().to_string()));
+                    return;
+                }
+            }
+        };
+        if std::fs::create_dir_all(&dir).is_err() {
+            logging::try_log_failure("file_download.create_dir");
+            let _ = tx.send(BgResult::DownloadError(format!(
+                "Could not create {}",
+                dir.display()
+            )));
+            return;
+        }
+        let path = downloads::unique_path(&dir, &downloads::sanitize_file_name(&name));
+        // Throttle progress updates to roughly every 256 KB
+        let mut last_reported = 0u64;
+        let progress_tx = tx.clone();
+        let result = bg_graph
+            .download_to_file(&source, &path, |received, total| {
+                if received - last_reported >= 256 * 1024 || Some(received) == total {
+                    last_reported = received;
+                    let _ = progress_tx.send(BgResult::DownloadProgress(received, total));
+                }
+            })
+            .await;
+        match result {
+            Ok(_) => {
+                let _ = tx.send(BgResult::DownloadFinished(path));
+            }
+            Err(failure) => {
+                logging::try_log_failure(failure.as_file_label());
+                let _ = tx.send(BgResult::DownloadError(failure.describe().to_string()));
+            }
         }
-        lines.push(format!("│{}│", row));
-        y += 2;
+    });
+}
+
+fn handle_forward_keys(
+    app: &mut app::App,
+    graph: &client::GraphClient,
+    bg_tx: &tokio::sync::mpsc::UnboundedSender<BgResult>,
+    code: KeyCode,
+) {
+    if app.forward_sending {
+        return;
+    }
+    match app.forward_stage {
+        app::ForwardStage::Target => match code {
+            KeyCode::Esc => app.close_forward(),
+            KeyCode::Enter => {
+                app.forward_choose_target();
+            }
+            KeyCode::Up => {
+                app.palette_selected = app.palette_selected.saturating_sub(1);
+            }
+            KeyCode::Down if !app.palette_filtered.is_empty() => {
+                app.palette_selected = (app.palette_selected + 1)
+                    .min(app.palette_filtered.len().saturating_sub(1));
+            }
+            KeyCode::Char(c) => {
+                app.palette_input.insert(app.palette_cursor, c);
+                app.palette_cursor += c.len_utf8();
+                app.palette_filter();
+            }
+            KeyCode::Backspace => {
+                if let Some(prev) = app.palette_input[..app.palette_cursor].chars().last() {
+                    app.palette_cursor -= prev.len_utf8();
+                    app.palette_input.remove(app.palette_cursor);
+                    app.palette_filter();
+                }
+            }
+            _ => {}
+        },
+        app::ForwardStage::Comment => match code {
+            KeyCode::Esc => app.forward_back_to_targets(),
+            KeyCode::Enter => start_forward(app, graph, bg_tx),
+            KeyCode::Char(c) => app.forward_insert_char(c),
+            KeyCode::Backspace => app.forward_delete_char(),
+            KeyCode::Left => app.forward_move_cursor_left(),
+            KeyCode::Right => app.forward_move_cursor_right(),
+            _ => {}
+        },
     }
-    lines.push(format!("└{}┘", "─".repeat(width)));
-    Ok(lines)
 }
 
-fn luma_pair_to_block(top: u8, bottom: u8) -> char {
-    if top.saturating_sub(bottom) > 70 {
-        return '▀';
+/// Post the forwarded message to the chosen chat or channel in the background
+fn start_forward(
+    app: &mut app::App,
+    graph: &client::GraphClient,
+    bg_tx: &tokio::sync::mpsc::UnboundedSender<BgResult>,
+) {
+    let (Some(source), Some(target)) = (app.forward_message.clone(), app.forward_target.clone()) else {
+        return;
+    };
+    app.forward_sending = true;
+    let comment = emoji::replace_shortcodes(&app.forward_comment);
+    log_event("forward.start");
+    let g = graph.clone_for_background();
+    let tx = bg_tx.clone();
+    tokio::spawn(async move {
+        let result = match &target.kind {
+            app::PaletteItemKind::Chat(chat_id) => g.forward_to_chat(chat_id, &source, &comment).await,
+            app::PaletteItemKind::Channel(team_id, channel_id) => {
+                g.forward_to_channel(team_id, channel_id, &source, &comment).await
+            }
+            app::PaletteItemKind::Action(_) => Err(anyhow::anyhow!("Not a chat or channel")),
+        };
+        match result {
+            Ok(_) => {
+                let _ = tx.send(BgResult::Forwarded(target.label));
+            }
+            Err(e) => {
+                logging::try_log_failure("forward.failed");
+                let _ = tx.send(BgResult::ForwardError(e.to_string()));
+            }
+        }
+    });
+}
+
+/// Open the Files panel; in a channel, also list its SharePoint folder in the background
+fn open_shared_files(
+    app: &mut app::App,
+    graph: &client::GraphClient,
+    bg_tx: &tokio::sync::mpsc::UnboundedSender<BgResult>,
+) {
+    app.open_shared_files();
+    let Some((team_id, channel_id)) = app.shared_files_loading.clone() else {
+        return;
+    };
+    log_event("channel_files.start");
+    let bg_graph = graph.clone_for_background();
+    let tx = bg_tx.clone();
+    tokio::spawn(async move {
+        match bg_graph.get_channel_files(&team_id, &channel_id).await {
+            Ok((drive_id, items)) => {
+                let files = models::shared_files_from_drive_items(&drive_id, &items);
+                let _ = tx.send(BgResult::ChannelFiles(team_id, channel_id, files));
+            }
+            Err(e) => {
+                logging::try_log_failure("channel_files.failure");
+                let _ = tx.send(BgResult::ChannelFilesError(team_id, channel_id, e.to_string()));
+            }
+        }
+    });
+}
+
+fn handle_shared_files_keys(
+    app: &mut app::App,
+    graph: &client::GraphClient,
+    bg_tx: &tokio::sync::mpsc::UnboundedSender<BgResult>,
+    code: KeyCode,
+) {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => app.close_dialog(),
+        KeyCode::Up | KeyCode::Char('k') => app.shared_files_move(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.shared_files_move(1),
+        KeyCode::PageUp => app.shared_files_move(-10),
+        KeyCode::PageDown => app.shared_files_move(10),
+        KeyCode::Char('s') => {
+            app.cycle_shared_files_sort();
+            app.status_message = format!("Files sorted by {}", app.shared_files_sort.label());
+        }
+        KeyCode::Enter | KeyCode::Char('o') => {
+            if let Some(file) = app.selected_shared_file() {
+                if open::that(&file.web_url).is_err() {
+                    app.status_message = "Could not open the file".to_string();
+                }
+            }
+        }
+        KeyCode::Char('S') | KeyCode::Char('d') => {
+            if let Some(file) = app.selected_shared_file().cloned() {
+                start_download(app, graph, bg_tx, file.download_url, file.name);
+            }
+        }
+        KeyCode::Char('c') | KeyCode::Char('y') => {
+            if let Some(file) = app.selected_shared_file() {
+                let b64 = base64_encode(&file.web_url);
+                print!("\x1b]52;c;{}\x07", b64);
+                app.status_message = "File link copied to clipboard".to_string();
+            }
+        }
+        _ => {}
     }
-    if bottom.saturating_sub(top) > 70 {
-        return '▄';
+}
+
+/// Change the chat list filter and open the selected chat if it is not the one shown
+async fn update_chat_filter(app: &mut app::App, graph: &client::GraphClient, update: impl FnOnce(&mut app::App)) {
+    update(app);
+    load_chat_if_selection_moved(app, graph).await;
+}
+
+/// Typing into the chat list filter bar (`i`): Enter keeps the filter, Esc clears it.
+/// Editing the query only moves the selection; the chat is loaded on Enter, Esc or Up/Down.
+async fn handle_chat_filter_keys(app: &mut app::App, graph: &client::GraphClient, code: KeyCode) {
+    match code {
+        KeyCode::Enter => update_chat_filter(app, graph, app::App::finish_chat_filter).await,
+        KeyCode::Esc => update_chat_filter(app, graph, app::App::clear_chat_filter).await,
+        KeyCode::Backspace => app.chat_filter_delete(),
+        KeyCode::Up => update_chat_filter(app, graph, app::App::select_prev_chat).await,
+        KeyCode::Down => update_chat_filter(app, graph, app::App::select_next_chat).await,
+        KeyCode::Char(c) => app.chat_filter_insert(c),
+        _ => {}
     }
-    let avg = ((top as u16 + bottom as u16) / 2) as u8;
-    match avg {
-        0..=40 => ' ',
-        41..=90 => '░',
-        91..=150 => '▒',
-        151..=210 => '▓',
-        _ => '█',
+}
+
+/// Collapse or expand the selected chat's section in the chat list
+/// Collapsing a section moves the selection to its first chat, which is then loaded
+async fn toggle_section(app: &mut app::App, graph: &client::GraphClient) {
+    if let Some((name, collapsed)) = app.toggle_selected_section() {
+        app.status_message = format!("{} {}", if collapsed { "Collapsed" } else { "Expanded" }, name);
+        load_chat_if_selection_moved(app, graph).await;
     }
 }
 
-// ---- Chat view key handling ----
+/// Load the selected chat when the selection moved off the chat being shown,
+/// so the message pane and the composer target the same chat
+async fn load_chat_if_selection_moved(app: &mut app::App, graph: &client::GraphClient) {
+    if app.selected_chat_id().is_some() && app.selected_chat_id() != app.shown_chat_id.as_deref() {
+        load_messages(graph, app).await;
+    }
+}
 
 async fn handle_chats_keys(
     app: &mut app::App,
@@ -712,6 +1360,9 @@ async fn handle_chats_keys(
     bg_tx: &tokio::sync::mpsc::UnboundedSender<BgResult>,
     code: KeyCode,
 ) {
+    if app.active_panel == Panel::Input && handle_shortcode_keys(app, code) {
+        return;
+    }
     match app.active_panel {
         Panel::ChatList => match code {
             KeyCode::Char('q') => std::process::exit(0),
@@ -732,18 +1383,34 @@ async fn handle_chats_keys(
                     load_messages(graph, app).await;
                 }
             }
+            KeyCode::Enter if app.selected_chat_collapsed() => toggle_section(app, graph).await,
             KeyCode::Enter => app.active_panel = Panel::Input,
+            KeyCode::Char(' ') => toggle_section(app, graph).await,
+            KeyCode::Char('i') => app.start_chat_filter(),
+            KeyCode::Char('R') => mark_selected_read(graph, app).await,
+            KeyCode::Char('U') => mark_selected_unread(graph, app).await,
+            KeyCode::Char('u') => update_chat_filter(app, graph, app::App::toggle_chat_filter_unread).await,
+            KeyCode::Char('O') => update_chat_filter(app, graph, |a| a.toggle_chat_filter_type("oneOnOne")).await,
+            KeyCode::Char('G') => update_chat_filter(app, graph, |a| a.toggle_chat_filter_type("group")).await,
+            KeyCode::Char('M') => update_chat_filter(app, graph, |a| a.toggle_chat_filter_type("meeting")).await,
+            KeyCode::Esc if app.chat_filter.is_active() => {
+                update_chat_filter(app, graph, app::App::clear_chat_filter).await
+            }
             KeyCode::Char('r') => refresh_all(graph, app).await,
             KeyCode::Char('g') => {
-                open_chat_manager(app, graph, &bg_tx).await;
+                open_chat_manager(app, graph, bg_tx).await;
             }
             KeyCode::Char('f') => app.open_file_picker(),
+            KeyCode::Char('F') => open_shared_files(app, graph, bg_tx),
             _ => {}
         },
         Panel::Messages => match code {
             KeyCode::Char('q') => std::process::exit(0),
             KeyCode::Char('n') => app.enter_new_chat_mode(),
+            KeyCode::Char('R') => mark_selected_read(graph, app).await,
+            KeyCode::Char('U') => mark_selected_unread(graph, app).await,
             KeyCode::Char('f') => app.open_file_picker(),
+            KeyCode::Char('F') => open_shared_files(app, graph, bg_tx),
             KeyCode::Tab => app.next_panel(),
             KeyCode::BackTab => app.prev_panel(),
             KeyCode::Up | KeyCode::Char('k') => {
@@ -775,6 +1442,13 @@ async fn handle_chats_keys(
             KeyCode::Char('e') => {
                 app.open_reaction_picker();
             }
+            KeyCode::Char('E') => show_reaction_details(app),
+            KeyCode::Char('y') => {
+                copy_selected_code_blocks(app);
+            }
+            KeyCode::Char('a') => {
+                select_next_card_action(app);
+            }
             KeyCode::Char('r') => {
                 if app.selected_message.is_some() {
                     app.start_reply();
@@ -782,20 +1456,27 @@ async fn handle_chats_keys(
                     load_messages(graph, app).await;
                 }
             }
-            KeyCode::Char('d') => {
-                if app.selected_message.is_some() && app.is_own_selected_message() {
-                    delete_message(graph, app).await;
-                }
+            KeyCode::Char('d')
+                if app.selected_message.is_some() && app.is_own_selected_message() =>
+            {
+                request_delete(graph, app).await;
             }
-            KeyCode::Char('w') => {
-                if app.selected_message.is_some() && app.is_own_selected_message() {
-                    app.start_edit();
-                }
+            KeyCode::Char('w')
+                if app.selected_message.is_some() && app.is_own_selected_message() =>
+            {
+                app.start_edit();
             }
-            KeyCode::Enter => {
-                if let Some(url) = app.selected_message_attachment_url() {
-                    let _ = open::that(&url);
-                }
+            KeyCode::Char('z') => undo_delete(graph, app).await,
+            KeyCode::Char('>') if app.selected_message.is_some() => {
+                app.open_forward();
+            }
+            KeyCode::Char('t') if app.selected_message.is_some() => {
+                toggle_pin(graph, app).await;
+            }
+            KeyCode::Char('P') => show_pinned_messages(app),
+            KeyCode::Char('S') => start_attachment_download(app, graph, bg_tx),
+            KeyCode::Enter => {
+                open_selected_message_item(app);
             }
             KeyCode::Esc => {
                 if app.selected_message.is_some() {
@@ -815,7 +1496,7 @@ async fn handle_chats_keys(
             KeyCode::Tab => app.next_panel(),
             KeyCode::BackTab => app.prev_panel(),
             KeyCode::Enter => {
-                let msg = app.take_input();
+                let msg = emoji::replace_shortcodes(&app.take_input());
                 if !msg.is_empty() {
                     if let Some(edit_id) = app.editing_message_id.clone() {
                         edit_message(graph, app, &edit_id, &msg).await;
@@ -835,6 +1516,21 @@ async fn handle_chats_keys(
     }
 }
 
+/// Keys for the composer's `:shortcode` popup; true when the key was consumed
+fn handle_shortcode_keys(app: &mut app::App, code: KeyCode) -> bool {
+    if app.shortcode_suggestions().is_empty() {
+        return false;
+    }
+    match code {
+        KeyCode::Up => app.shortcode_move(-1),
+        KeyCode::Down => app.shortcode_move(1),
+        KeyCode::Tab | KeyCode::Enter => return app.accept_shortcode(),
+        KeyCode::Esc => app.dismiss_shortcodes(),
+        _ => return false,
+    }
+    true
+}
+
 // ---- Teams view key handling ----
 
 async fn handle_teams_keys(
@@ -843,6 +1539,9 @@ async fn handle_teams_keys(
     bg_tx: &tokio::sync::mpsc::UnboundedSender<BgResult>,
     code: KeyCode,
 ) {
+    if app.teams_panel == TeamsPanel::ChannelInput && handle_shortcode_keys(app, code) {
+        return;
+    }
     match app.teams_panel {
         TeamsPanel::TeamList => match code {
             KeyCode::Char('q') => std::process::exit(0),
@@ -921,6 +1620,13 @@ async fn handle_teams_keys(
             KeyCode::Char('e') => {
                 app.open_reaction_picker();
             }
+            KeyCode::Char('E') => show_reaction_details(app),
+            KeyCode::Char('y') => {
+                copy_selected_code_blocks(app);
+            }
+            KeyCode::Char('a') => {
+                select_next_card_action(app);
+            }
             KeyCode::Char('r') => {
                 if app.selected_channel_message.is_some() {
                     app.start_channel_reply();
@@ -928,22 +1634,28 @@ async fn handle_teams_keys(
                     load_channel_messages_cached(graph, app).await;
                 }
             }
-            KeyCode::Char('d') => {
-                if app.selected_channel_message.is_some() && app.is_own_selected_channel_message() {
-                    delete_channel_message(graph, app).await;
-                }
+            KeyCode::Char('d')
+                if app.selected_channel_message.is_some() && app.is_own_selected_channel_message() =>
+            {
+                request_delete(graph, app).await;
             }
-            KeyCode::Char('w') => {
-                if app.selected_channel_message.is_some() && app.is_own_selected_channel_message() {
-                    app.start_channel_edit();
-                }
+            KeyCode::Char('w')
+                if app.selected_channel_message.is_some() && app.is_own_selected_channel_message() =>
+            {
+                start_channel_edit(app);
+            }
+            KeyCode::Char('z') => undo_delete(graph, app).await,
+            KeyCode::Char('>') if app.selected_channel_message.is_some() => {
+                app.open_forward();
             }
             KeyCode::Char('m') => load_and_toggle_members(graph, app).await,
             KeyCode::Char('f') => app.open_file_picker(),
+            KeyCode::Char('F') => open_shared_files(app, graph, bg_tx),
+            KeyCode::Char('S') => start_attachment_download(app, graph, bg_tx),
             KeyCode::Enter => {
-                if let Some(url) = app.selected_message_attachment_url() {
-                    let _ = open::that(&url);
-                } else {
+                let opened = open_selected_message_item(app);
+                if !opened {
+                    // Nothing to open: Enter moves to the channel composer
                     app.teams_panel = TeamsPanel::ChannelInput;
                 }
             }
@@ -965,12 +1677,10 @@ async fn handle_teams_keys(
             KeyCode::Tab => app.next_teams_panel(),
             KeyCode::BackTab => app.prev_teams_panel(),
             KeyCode::Enter => {
-                let msg = app.take_channel_input();
+                let msg = emoji::replace_shortcodes(&app.take_channel_input());
                 if !msg.is_empty() {
-                    if app.editing_message_id.is_some() {
-                        // Channel message editing not supported by Graph API v1.0
-                        app.status_message = "Channel message editing not supported".to_string();
-                        app.cancel_edit();
+                    if let Some(edit_id) = app.editing_message_id.clone() {
+                        edit_channel_message(graph, app, &edit_id, &msg).await;
                     } else if let Some(reply_id) = app.reply_to_message_id.clone() {
                         send_channel_reply(graph, app, &reply_id, &msg).await;
                     } else {
@@ -1009,22 +1719,20 @@ async fn handle_mouse_event(
                 ViewMode::Chats => {
                     if rect_contains(areas.chat_list, col, row) {
                         app.active_panel = Panel::ChatList;
-                        // Each chat item is 3 lines; account for border (1px top)
-                        let inner_y = row.saturating_sub(areas.chat_list.y + 1);
-                        let item_height = 3u16;
-                        let clicked_offset = (inner_y / item_height) as usize;
-                        // Compute scroll_start same as draw_chat_list
-                        let inner_height = areas.chat_list.height.saturating_sub(2) as usize;
-                        let max_visible = if item_height > 0 { inner_height / item_height as usize } else { 0 };
-                        let scroll_start = if max_visible > 0 && app.selected_chat >= max_visible {
-                            app.selected_chat - max_visible + 1
-                        } else {
-                            0
-                        };
-                        let new_idx = scroll_start + clicked_offset;
-                        if new_idx < app.chats.len() && new_idx != app.selected_chat {
-                            app.selected_chat = new_idx;
-                            load_messages(graph, app).await;
+                        // Account for the border (1px top) and filter bar when mapping to a row
+                        let bar = u16::from(app.chat_filter_bar_visible());
+                        let inner_y = row.checked_sub(areas.chat_list.y + 1 + bar);
+                        let inner_height = areas.chat_list.height.saturating_sub(2 + bar) as usize;
+                        match inner_y.and_then(|y| app.chat_list_row_at(y as usize, inner_height)) {
+                            Some(app::ChatListRow::Chat(idx)) if idx != app.selected_chat => {
+                                app.selected_chat = idx;
+                                load_messages(graph, app).await;
+                            }
+                            Some(app::ChatListRow::Header { first_chat, .. }) => {
+                                app.selected_chat = first_chat;
+                                toggle_section(app, graph).await;
+                            }
+                            _ => {}
                         }
                     } else if rect_contains(areas.messages, col, row) {
                         app.active_panel = Panel::Messages;
@@ -1123,24 +1831,19 @@ async fn handle_new_chat_keys(
     match code {
         KeyCode::Esc => app.exit_new_chat_mode(),
         KeyCode::Enter => {
-            let email = if !app.suggestions.is_empty() {
-                app.select_suggestion().unwrap_or_default()
-            } else {
-                app.take_new_chat_input()
-            };
-            if !email.is_empty() {
-                create_new_chat(graph, app, &email).await;
+            // Enter adds the highlighted person (or typed email) as a chip;
+            // on an empty field, or from the topic, it creates the chat
+            let added = !app.new_chat_topic_focus
+                && (app.add_selected_suggestion() || app.add_typed_person());
+            if !added && (app.new_chat_topic_focus || app.new_chat_input.trim().is_empty()) {
+                if let Some((people, topic)) = app.take_new_chat() {
+                    create_new_chat(graph, app, people, topic).await;
+                }
             }
         }
         KeyCode::Up => app.suggestion_up(),
         KeyCode::Down => app.suggestion_down(),
-        KeyCode::Tab => {
-            if let Some(s) = app.suggestions.get(app.selected_suggestion) {
-                app.new_chat_input = s.email.clone();
-                app.new_chat_cursor = app.new_chat_input.len();
-                app.suggestions.clear();
-            }
-        }
+        KeyCode::Tab => app.toggle_new_chat_field(),
         KeyCode::Char(c) => {
             app.new_chat_insert_char(c);
             app.selected_suggestion = 0;
@@ -1180,19 +1883,41 @@ async fn handle_new_chat_keys(
 async fn handle_reaction_picker_keys(
     app: &mut app::App,
     graph: &client::GraphClient,
-    code: KeyCode,
+    key: event::KeyEvent,
 ) {
-    match code {
+    let columns = emoji::PICKER_COLUMNS as isize;
+    let len = app.emoji_picker_items().len();
+    match key.code {
         KeyCode::Esc => app.close_dialog(),
-        KeyCode::Left => {
-            app.selected_reaction = app.selected_reaction.saturating_sub(1);
+        KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.emoji_picker.cycle_skin_tone();
+            app.skin_tone = app.emoji_picker.skin_tone;
         }
-        KeyCode::Right => {
-            let max = models::REACTION_TYPES.len().saturating_sub(1);
-            app.selected_reaction = (app.selected_reaction + 1).min(max);
+        KeyCode::Tab => app.emoji_picker.next_tab(),
+        KeyCode::BackTab => app.emoji_picker.prev_tab(),
+        KeyCode::Left => app.emoji_picker.move_selection(-1, len),
+        KeyCode::Right => app.emoji_picker.move_selection(1, len),
+        KeyCode::Up => app.emoji_picker.move_selection(-columns, len),
+        KeyCode::Down => app.emoji_picker.move_selection(columns, len),
+        KeyCode::Backspace => app.emoji_picker.delete_char(),
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.emoji_picker.insert_char(c)
         }
         KeyCode::Enter => {
-            let (reaction_type, label) = models::REACTION_TYPES[app.selected_reaction];
+            let Some(picked) = app.selected_picker_emoji() else {
+                return;
+            };
+            let reaction_type = picked.as_str();
+            let label = &match emoji::lookup(reaction_type) {
+                Some(entry) => format!("{} :{}:", reaction_type, entry.shortcode),
+                None => reaction_type.to_string(),
+            };
+            // Selecting a reaction the user already applied removes it, by the
+            // type it is stored with (e.g. a legacy "like")
+            let existing = app.selected_message_my_reaction_type(reaction_type);
+            let remove = existing.is_some();
+            let unset_type = existing.as_deref().unwrap_or(reaction_type);
+            let verb = if remove { "remove" } else { "add" };
 
             match app.view_mode {
                 ViewMode::Chats => {
@@ -1200,16 +1925,22 @@ async fn handle_reaction_picker_keys(
                         app.selected_chat_id().map(String::from),
                         app.selected_message_id().map(String::from),
                     ) {
-                        match graph.set_reaction(&chat_id, &msg_id, reaction_type).await {
+                        let result = if remove {
+                            graph.unset_reaction(&chat_id, &msg_id, unset_type).await
+                        } else {
+                            graph.set_reaction(&chat_id, &msg_id, reaction_type).await
+                        };
+                        match result {
                             Ok(_) => {
-                                app.status_message = format!("Reacted with {}", label);
+                                app.status_message = reaction_status(remove, label);
+                                app.record_recent_emoji(reaction_type);
                                 app.close_dialog();
                                 load_messages(graph, app).await;
                             }
                             Err(e) => {
                                 app.show_error(
                                     "Reaction Failed",
-                                    &format!("Could not add {} reaction.", label),
+                                    &format!("Could not {} {} reaction.", verb, label),
                                     &format!(
                                         "Chat: {}\nMessage: {}\nReaction: {}\nError: {}",
                                         chat_id, msg_id, label, e
@@ -1227,19 +1958,26 @@ async fn handle_reaction_picker_keys(
                         app.selected_channel_id().map(String::from),
                         app.selected_channel_message_id().map(String::from),
                     ) {
-                        match graph
-                            .set_channel_reaction(&team_id, &channel_id, &msg_id, reaction_type)
-                            .await
-                        {
+                        let result = if remove {
+                            graph
+                                .unset_channel_reaction(&team_id, &channel_id, &msg_id, unset_type)
+                                .await
+                        } else {
+                            graph
+                                .set_channel_reaction(&team_id, &channel_id, &msg_id, reaction_type)
+                                .await
+                        };
+                        match result {
                             Ok(_) => {
-                                app.status_message = format!("Reacted with {}", label);
+                                app.status_message = reaction_status(remove, label);
+                                app.record_recent_emoji(reaction_type);
                                 app.close_dialog();
                                 load_channel_messages_cached(graph, app).await;
                             }
                             Err(e) => {
                                 app.show_error(
                                     "Reaction Failed",
-                                    &format!("Could not add {} reaction.", label),
+                                    &format!("Could not {} {} reaction.", verb, label),
                                     &format!(
                                         "Team: {}\nChannel: {}\nMessage: {}\nReaction: {}\nError: {}",
                                         team_id, channel_id, msg_id, label, e
@@ -1257,6 +1995,108 @@ async fn handle_reaction_picker_keys(
     }
 }
 
+fn reaction_status(removed: bool, label: &str) -> String {
+    if removed {
+        format!("Removed {} reaction", label)
+    } else {
+        format!("Reacted with {}", label)
+    }
+}
+
+/// Open the pinned messages popup of the current chat
+fn show_pinned_messages(app: &mut app::App) {
+    if !app.open_pinned_messages() {
+        app.status_message = "No pinned messages in this chat".to_string();
+    }
+}
+
+/// Pin the selected chat message, or unpin it if it is already pinned
+async fn toggle_pin(graph: &client::GraphClient, app: &mut app::App) {
+    let (Some(chat_id), Some(msg_id)) = (
+        app.selected_chat_id().map(String::from),
+        app.selected_message_id().map(String::from),
+    ) else {
+        return;
+    };
+    let pin_id = app.selected_message_pin_id();
+    let result = match &pin_id {
+        Some(pin_id) => graph.unpin_message(&chat_id, pin_id).await,
+        None => graph.pin_message(&chat_id, &msg_id).await,
+    };
+    let verb = if pin_id.is_some() { "unpin" } else { "pin" };
+    match result {
+        Ok(()) => {
+            log_event("chat_pin.success");
+            app.status_message = if pin_id.is_some() {
+                "Unpinned message".to_string()
+            } else {
+                "📌 Pinned message".to_string()
+            };
+            load_pinned_messages(graph, app, &chat_id).await;
+        }
+        Err(e) => {
+            log_failure("chat_pin.failed");
+            app.show_error(
+                "Pin Failed",
+                &format!("Could not {} the message.", verb),
+                &format!("Chat: {}\nMessage: {}\nError: {}", chat_id, msg_id, e),
+            );
+        }
+    }
+}
+
+async fn handle_pinned_messages_keys(
+    app: &mut app::App,
+    graph: &client::GraphClient,
+    code: KeyCode,
+) {
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('P') => app.close_dialog(),
+        KeyCode::Up | KeyCode::Char('k') => app.pinned_move(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.pinned_move(1),
+        KeyCode::Enter => {
+            let Some(message_id) = app.selected_pin().and_then(|p| p.message_id()).map(String::from) else {
+                return;
+            };
+            app.close_dialog();
+            jump_to_message(graph, app, &message_id).await;
+        }
+        KeyCode::Char('u') | KeyCode::Char('t') => {
+            let (Some(chat_id), Some(pin_id)) = (
+                app.selected_chat_id().map(String::from),
+                app.selected_pin().map(|p| p.id.clone()),
+            ) else {
+                return;
+            };
+            match graph.unpin_message(&chat_id, &pin_id).await {
+                Ok(()) => {
+                    log_event("chat_pin.remove.success");
+                    app.status_message = "Unpinned message".to_string();
+                    load_pinned_messages(graph, app, &chat_id).await;
+                    if app.selected_chat_pins().is_empty() {
+                        app.close_dialog();
+                    }
+                }
+                Err(e) => {
+                    log_failure("chat_pin.remove.failed");
+                    app.show_error(
+                        "Unpin Failed",
+                        "Could not unpin the message.",
+                        &format!("Chat: {}\nPin: {}\nError: {}", chat_id, pin_id, e),
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn handle_reaction_details_keys(app: &mut app::App, code: KeyCode) {
+    if matches!(code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') | KeyCode::Char('E')) {
+        app.close_dialog();
+    }
+}
+
 async fn handle_presence_picker_keys(
     app: &mut app::App,
     graph: &client::GraphClient,
@@ -1305,11 +2145,19 @@ async fn handle_presence_picker_keys(
     }
 }
 
-const SETTINGS_COUNT: usize = 1;
+const SETTINGS_COUNT: usize = 6;
+
+/// How often changed channel read times are written to the state file
+const STATE_SAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
 
 fn settings_value(index: usize, config: &config::Config) -> String {
     match index {
         0 => config.refresh_interval_secs.to_string(),
+        1 => config.image_protocol.clone(),
+        2 => config.download_dir.clone(),
+        3 => if config.open_after_download { "on" } else { "off" }.to_string(),
+        4 => config.mark_read.clone(),
+        5 => if config.confirm_delete { "on" } else { "off" }.to_string(),
         _ => String::new(),
     }
 }
@@ -1336,11 +2184,9 @@ fn handle_settings_keys(
                 app.setting_input.insert(app.setting_input_cursor, c);
                 app.setting_input_cursor += 1;
             }
-            KeyCode::Backspace => {
-                if app.setting_input_cursor > 0 {
-                    app.setting_input_cursor -= 1;
-                    app.setting_input.remove(app.setting_input_cursor);
-                }
+            KeyCode::Backspace if app.setting_input_cursor > 0 => {
+                app.setting_input_cursor -= 1;
+                app.setting_input.remove(app.setting_input_cursor);
             }
             KeyCode::Left => {
                 app.setting_input_cursor = app.setting_input_cursor.saturating_sub(1);
@@ -1361,8 +2207,8 @@ fn handle_settings_keys(
         KeyCode::Up | KeyCode::Char('k') => {
             app.selected_setting = app.selected_setting.saturating_sub(1);
         }
-        KeyCode::Down | KeyCode::Char('j') => {
-            app.selected_setting = (app.selected_setting + 1).min(SETTINGS_COUNT - 1);
+        KeyCode::Down | KeyCode::Char('j') if app.selected_setting + 1 < SETTINGS_COUNT => {
+            app.selected_setting += 1;
         }
         KeyCode::Enter => {
             app.editing_setting = true;
@@ -1373,6 +2219,14 @@ fn handle_settings_keys(
     }
 }
 
+fn parse_on_off(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "on" | "true" | "yes" | "1" => Some(true),
+        "off" | "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
 fn apply_setting(app: &mut app::App, config: &mut config::Config, index: usize, value: &str) {
     match index {
         0 => {
@@ -1386,6 +2240,62 @@ fn apply_setting(app: &mut app::App, config: &mut config::Config, index: usize,
                 return;
             }
         }
+        1 => {
+            let setting = value.trim().to_ascii_lowercase();
+            if setting != "auto" && graphics::GraphicsProtocol::parse(&setting).is_none() {
+                app.status_message =
+                    "Use auto, kitty, iterm2, sixel, truecolor or grayscale".to_string();
+                return;
+            }
+            app.image_protocol = graphics::resolve_protocol(&setting);
+            app.image_protocol_setting = setting.clone();
+            config.image_protocol = setting;
+            rerender_image_previews(app);
+            app.status_message = format!("Image rendering set to {}", app.image_protocol.as_str());
+        }
+        2 => {
+            let dir = value.trim().to_string();
+            app.status_message = match downloads::download_dir(&dir) {
+                Some(path) => format!("Downloads will be saved to {}", path.display()),
+                None => "Could not determine the downloads folder".to_string(),
+            };
+            app.download_dir = dir.clone();
+            config.download_dir = dir;
+        }
+        3 => {
+            let Some(enabled) = parse_on_off(value) else {
+                app.status_message = "Use on or off".to_string();
+                return;
+            };
+            app.open_after_download = enabled;
+            config.open_after_download = enabled;
+            app.status_message = format!(
+                "Open after download {}",
+                if enabled { "enabled" } else { "disabled" }
+            );
+        }
+        4 => {
+            let Some(mode) = config::MarkReadMode::parse(value) else {
+                app.status_message = "Use view, manual or a number of seconds".to_string();
+                return;
+            };
+            app.mark_read_mode = mode;
+            app.pending_mark_read = None;
+            config.mark_read = mode.as_setting();
+            app.status_message = format!("Chats are marked read {}", mode.label());
+        }
+        5 => {
+            let Some(enabled) = parse_on_off(value) else {
+                app.status_message = "Use on or off".to_string();
+                return;
+            };
+            app.confirm_delete = enabled;
+            config.confirm_delete = enabled;
+            app.status_message = format!(
+                "Delete confirmation {}",
+                if enabled { "enabled" } else { "disabled" }
+            );
+        }
         _ => return,
     }
     if let Err(e) = config::save_config(config) {
@@ -1397,6 +2307,23 @@ fn apply_setting(app: &mut app::App, config: &mut config::Config, index: usize,
     }
 }
 
+/// Re-render cached previews after the image protocol changes
+fn rerender_image_previews(app: &mut app::App) {
+    let protocol = app.image_protocol;
+    let rendered: Vec<(String, graphics::ImagePreview)> = app
+        .image_sources
+        .iter()
+        .map(|(url, bytes)| {
+            let preview = graphics::render_preview(bytes, protocol)
+                .unwrap_or(graphics::ImagePreview::Unavailable);
+            (url.clone(), preview)
+        })
+        .collect();
+    for (url, preview) in rendered {
+        app.set_image_preview(url, preview);
+    }
+}
+
 async fn handle_search_keys(
     app: &mut app::App,
     graph: &client::GraphClient,
@@ -1446,17 +2373,13 @@ async fn handle_search_keys(
                 });
             }
         }
-        KeyCode::Up | KeyCode::BackTab => {
-            if !app.search_results.is_empty() {
-                app.selected_search_result =
-                    app.selected_search_result.saturating_sub(1);
-            }
+        KeyCode::Up | KeyCode::BackTab if !app.search_results.is_empty() => {
+            app.selected_search_result =
+                app.selected_search_result.saturating_sub(1);
         }
-        KeyCode::Down | KeyCode::Tab => {
-            if !app.search_results.is_empty() {
-                app.selected_search_result = (app.selected_search_result + 1)
-                    .min(app.search_results.len().saturating_sub(1));
-            }
+        KeyCode::Down | KeyCode::Tab if !app.search_results.is_empty() => {
+            app.selected_search_result = (app.selected_search_result + 1)
+                .min(app.search_results.len().saturating_sub(1));
         }
         KeyCode::Char(c) => {
             app.search_input.insert(app.search_cursor, c);
@@ -1465,26 +2388,93 @@ async fn handle_search_keys(
             app.search_results.clear();
             app.selected_search_result = 0;
         }
-        KeyCode::Backspace => {
-            if app.search_cursor > 0 {
-                app.search_cursor -= 1;
-                app.search_input.remove(app.search_cursor);
-                app.search_results.clear();
-                app.selected_search_result = 0;
-            }
+        KeyCode::Backspace if app.search_cursor > 0 => {
+            app.search_cursor -= 1;
+            app.search_input.remove(app.search_cursor);
+            app.search_results.clear();
+            app.selected_search_result = 0;
         }
         KeyCode::Left => {
             app.search_cursor = app.search_cursor.saturating_sub(1);
         }
-        KeyCode::Right => {
-            if app.search_cursor < app.search_input.len() {
-                app.search_cursor += 1;
-            }
+        KeyCode::Right if app.search_cursor < app.search_input.len() => {
+            app.search_cursor += 1;
         }
         _ => {}
     }
 }
 
+/// Copy the remembered state from the app and write the state file when it
+/// changed. User actions (emoji, skin tone, chat preferences) are saved right
+/// away; channel read times, which change while reading, at most every
+/// `STATE_SAVE_INTERVAL` unless `flush` is set.
+fn save_state(
+    app: &mut app::App,
+    state: &mut state::State,
+    last_save: &mut std::time::Instant,
+    flush: bool,
+) {
+    let mut changed = false;
+    if state.skin_tone != app.skin_tone {
+        state.skin_tone = app.skin_tone;
+        changed = true;
+    }
+    if state.recent_emojis != app.recent_emojis {
+        state.recent_emojis = app.recent_emojis.clone();
+        changed = true;
+    }
+    let user_id = app.current_user_id().to_string();
+    if !user_id.is_empty() {
+        changed |= state.set_chat_prefs(&user_id, &app.chat_prefs);
+        state::prune_read_times(&mut app.channel_last_read, state::MAX_CHANNEL_READ_TIMES);
+        let reads_changed = !app.channel_last_read.is_empty()
+            && state.channel_last_read.get(&user_id) != Some(&app.channel_last_read);
+        if reads_changed && (changed || flush || last_save.elapsed() >= STATE_SAVE_INTERVAL) {
+            state.channel_last_read.insert(user_id, app.channel_last_read.clone());
+            changed = true;
+        }
+    }
+    if !changed {
+        return;
+    }
+    *last_save = std::time::Instant::now();
+    if state::save_state(state).is_err() {
+        log_failure("state.save");
+    }
+}
+
+/// Hide the selected chat with `hideForUser`, or unhide it if it is hidden
+async fn toggle_chat_hidden(graph: &client::GraphClient, app: &mut app::App) {
+    let Some(chat_id) = app.selected_chat_id().map(String::from) else {
+        return;
+    };
+    let hide = !app.selected_chat_hidden();
+    let user_id = app.current_user_id().to_string();
+    match graph.set_chat_hidden(&chat_id, &user_id, hide).await {
+        Ok(()) => {
+            log_event("chat_hide.success");
+            app.set_chat_hidden(&chat_id, hide);
+            app.status_message = if hide {
+                "🙈 Chat hidden (palette: Show Hidden Chats)"
+            } else {
+                "Chat unhidden"
+            }
+            .to_string();
+            if app.selected_chat_id() != Some(chat_id.as_str()) {
+                load_messages(graph, app).await;
+            }
+        }
+        Err(e) => {
+            log_failure("chat_hide.failed");
+            app.show_error(
+                if hide { "Hide Chat Failed" } else { "Unhide Chat Failed" },
+                "Could not change the chat's visibility.",
+                &format!("Chat: {}\nError: {}", chat_id, e),
+            );
+        }
+    }
+}
+
 async fn handle_command_palette_keys(
     app: &mut app::App,
     graph: &client::GraphClient,
@@ -1513,6 +2503,33 @@ async fn handle_command_palette_keys(
                             app::PaletteAction::NewChat => app.enter_new_chat_mode(),
                             app::PaletteAction::Search => app.open_search(),
                             app::PaletteAction::SetStatus => app.open_presence_picker(),
+                            app::PaletteAction::SharedFiles => open_shared_files(app, graph, bg_tx),
+                            app::PaletteAction::PasteImage => start_inline_image_send(app, graph, bg_tx, None),
+                            app::PaletteAction::PinChat => {
+                                if let Some(pinned) = app.toggle_selected_chat_pin() {
+                                    app.status_message =
+                                        if pinned { "📌 Chat pinned" } else { "Chat unpinned" }.to_string();
+                                }
+                            }
+                            app::PaletteAction::MuteChat => {
+                                if let Some(muted) = app.toggle_selected_chat_mute() {
+                                    app.status_message =
+                                        if muted { "🔕 Chat muted" } else { "Chat unmuted" }.to_string();
+                                }
+                            }
+                            app::PaletteAction::HideChat => toggle_chat_hidden(graph, app).await,
+                            app::PaletteAction::MarkRead => mark_selected_read(graph, app).await,
+                            app::PaletteAction::MarkUnread => mark_selected_unread(graph, app).await,
+                            app::PaletteAction::MarkAllRead => mark_all_chats_read(app, graph, bg_tx),
+                            app::PaletteAction::ShowHiddenChats => {
+                                app.toggle_show_hidden_chats();
+                                app.status_message = if app.show_hidden_chats {
+                                    "Showing hidden chats"
+                                } else {
+                                    "Hidden chats are hidden again"
+                                }
+                                .to_string();
+                            }
                             app::PaletteAction::Settings => app.open_settings(),
                             app::PaletteAction::Quit => std::process::exit(0),
                         },
@@ -1523,36 +2540,30 @@ async fn handle_command_palette_keys(
         KeyCode::Up => {
             app.palette_selected = app.palette_selected.saturating_sub(1);
         }
-        KeyCode::Down => {
-            if !app.palette_filtered.is_empty() {
-                app.palette_selected = (app.palette_selected + 1)
-                    .min(app.palette_filtered.len().saturating_sub(1));
-            }
+        KeyCode::Down if !app.palette_filtered.is_empty() => {
+            app.palette_selected = (app.palette_selected + 1)
+                .min(app.palette_filtered.len().saturating_sub(1));
         }
         KeyCode::Char(c) => {
             app.palette_input.insert(app.palette_cursor, c);
             app.palette_cursor += c.len_utf8();
             app.palette_filter();
         }
-        KeyCode::Backspace => {
-            if app.palette_cursor > 0 {
-                let prev_len = app.palette_input[..app.palette_cursor]
-                    .chars()
-                    .last()
-                    .map(|c| c.len_utf8())
-                    .unwrap_or(0);
-                app.palette_cursor -= prev_len;
-                app.palette_input.remove(app.palette_cursor);
-                app.palette_filter();
-            }
+        KeyCode::Backspace if app.palette_cursor > 0 => {
+            let prev_len = app.palette_input[..app.palette_cursor]
+                .chars()
+                .last()
+                .map(|c| c.len_utf8())
+                .unwrap_or(0);
+            app.palette_cursor -= prev_len;
+            app.palette_input.remove(app.palette_cursor);
+            app.palette_filter();
         }
         KeyCode::Left => {
             app.palette_cursor = app.palette_cursor.saturating_sub(1);
         }
-        KeyCode::Right => {
-            if app.palette_cursor < app.palette_input.len() {
-                app.palette_cursor += 1;
-            }
+        KeyCode::Right if app.palette_cursor < app.palette_input.len() => {
+            app.palette_cursor += 1;
         }
         _ => {}
     }
@@ -1597,8 +2608,6 @@ async fn navigate_to_channel(
     }
 }
 
-const MAX_FILE_SIZE: u64 = 4 * 1024 * 1024; // 4 MB
-
 async fn handle_file_picker_keys(
     app: &mut app::App,
     graph: &client::GraphClient,
@@ -1606,132 +2615,311 @@ async fn handle_file_picker_keys(
     code: KeyCode,
 ) {
     if app.file_uploading {
-        return; // Ignore input while uploading
+        // Only cancellation is accepted while uploading
+        if code == KeyCode::Esc && app.cancel_file_upload() {
+            log_event("file_share.cancel");
+        }
+        return;
+    }
+
+    if app.file_picker_typing {
+        match code {
+            KeyCode::Esc => {
+                app.file_picker_typing = false;
+                app.file_path_input.clear();
+                app.file_path_cursor = 0;
+            }
+            KeyCode::Tab => app.file_picker_complete(),
+            KeyCode::Enter => {
+                let path_str = app.file_path_input.trim().to_string();
+                if path_str.is_empty() {
+                    return;
+                }
+                let path = files::expand_tilde(&path_str);
+                if path.is_dir() {
+                    app.file_picker_set_dir(path);
+                    app.file_picker_typing = false;
+                    app.file_path_input.clear();
+                    app.file_path_cursor = 0;
+                } else {
+                    let targets = app.file_picker_upload_targets(Some(path));
+                    start_file_upload(app, graph, bg_tx, targets);
+                }
+            }
+            KeyCode::Char(c) => {
+                app.file_picker_insert_char(c);
+                app.file_upload_error = None;
+            }
+            KeyCode::Backspace => {
+                app.file_picker_delete_char();
+                app.file_upload_error = None;
+            }
+            KeyCode::Left => app.file_picker_cursor_left(),
+            KeyCode::Right => app.file_picker_cursor_right(),
+            _ => {}
+        }
+        return;
     }
+
     match code {
         KeyCode::Esc => {
             app.close_dialog();
         }
+        KeyCode::Up | KeyCode::Char('k') => app.file_picker_move(-1),
+        KeyCode::Down | KeyCode::Char('j') => app.file_picker_move(1),
+        KeyCode::PageUp => app.file_picker_move(-10),
+        KeyCode::PageDown => app.file_picker_move(10),
+        KeyCode::Left | KeyCode::Backspace | KeyCode::Char('h') => app.file_picker_parent(),
+        KeyCode::Right | KeyCode::Char('l')
+            if app.file_picker_selected_entry().map(|e| e.is_dir).unwrap_or(false) =>
+        {
+            app.file_picker_open_selected();
+        }
         KeyCode::Enter => {
-            let path_str = app.file_path_input.trim().to_string();
-            if path_str.is_empty() {
-                return;
+            if let Some(file) = app.file_picker_open_selected() {
+                let targets = app.file_picker_upload_targets(Some(file));
+                start_file_upload(app, graph, bg_tx, targets);
             }
-            let path = std::path::Path::new(&path_str);
-            if !path.exists() {
-                log_failure("file_share.validate.not_found");
-                app.file_upload_error = Some("File not found".to_string());
-                return;
+        }
+        KeyCode::Char('u') if !app.file_picker_marked.is_empty() => {
+            let targets = app.file_picker_upload_targets(None);
+            start_file_upload(app, graph, bg_tx, targets);
+        }
+        KeyCode::Char('I') => {
+            if let Some(entry) = app.file_picker_selected_entry().filter(|e| !e.is_dir) {
+                let path = entry.path.clone();
+                app.close_dialog();
+                start_inline_image_send(app, graph, bg_tx, Some(path));
             }
-            if !path.is_file() {
-                log_failure("file_share.validate.not_file");
-                app.file_upload_error = Some("Not a file".to_string());
-                return;
+        }
+        KeyCode::Char(' ') => app.file_picker_toggle_mark(),
+        KeyCode::Char('.') => app.file_picker_toggle_hidden(),
+        KeyCode::Char('~') => {
+            if let Some(home) = dirs::home_dir() {
+                app.file_picker_set_dir(home);
             }
-            match std::fs::metadata(path) {
-                Ok(meta) if meta.len() > MAX_FILE_SIZE => {
-                    app.file_upload_error =
-                        Some(format!("File too large (max {}MB)", MAX_FILE_SIZE / 1024 / 1024));
-                    return;
-                }
-                Err(e) => {
-                    log_failure("file_share.validate.metadata");
-                    app.file_upload_error = Some(format!("Cannot read file: {}", e));
-                    return;
+        }
+        KeyCode::Char('/') | KeyCode::Char('i') => app.file_picker_start_typing(),
+        _ => {}
+    }
+}
+
+/// Send an image inline (hosted content) to the selected chat or channel, read
+/// from `path` or, when None, from the system clipboard. Text in the composer
+/// becomes the caption.
+fn start_inline_image_send(
+    app: &mut app::App,
+    graph: &client::GraphClient,
+    bg_tx: &tokio::sync::mpsc::UnboundedSender<BgResult>,
+    path: Option<std::path::PathBuf>,
+) {
+    if app.inline_image_sending {
+        app.status_message = "An image is already being sent".to_string();
+        return;
+    }
+    let chat_id = match app.view_mode {
+        ViewMode::Chats => app.selected_chat_id().map(String::from),
+        ViewMode::Teams => None,
+    };
+    let team_channel = match (app.view_mode, app.selected_team_id(), app.selected_channel_id()) {
+        (ViewMode::Teams, Some(t), Some(c)) => Some((t.to_string(), c.to_string())),
+        _ => None,
+    };
+    if chat_id.is_none() && team_channel.is_none() {
+        app.status_message = "Open a chat or channel to send an image".to_string();
+        return;
+    }
+    let caption = emoji::replace_shortcodes(&app.take_composer_text());
+    app.inline_image_sending = true;
+    app.status_message = "🖼 Sending image…".to_string();
+    log_event("inline_image.start");
+
+    let g = graph.clone_for_background();
+    let tx = bg_tx.clone();
+    tokio::spawn(async move {
+        let bytes = match path {
+            Some(path) => std::fs::read(&path).map_err(|e| {
+                logging::try_log_failure("inline_image.read_file");
+                format!("Cannot read {}: {}", path.display(), e)
+            }),
+            None => match tokio::task::spawn_blocking(clipboard::read_image).await {
+                Ok(Ok(bytes)) => Ok(bytes),
+                Ok(Err(failure)) => {
+                    logging::try_log_failure(failure.as_label());
+                    Err(failure.describe().to_string())
                 }
-                _ => {}
+                Err(_) => Err("Clipboard read was interrupted".to_string()),
+            },
+        };
+        let image = bytes.and_then(|bytes| {
+            graphics::prepare_inline_image(bytes).map_err(|failure| {
+                logging::try_log_failure(failure.as_label());
+                failure.describe().to_string()
+            })
+        });
+        let image = match image {
+            Ok(image) => image,
+            Err(err) => {
+                let _ = tx.send(BgResult::InlineImageError(err, caption));
+                return;
             }
-            let filename = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("file")
-                .to_string();
-            let bytes = match std::fs::read(path) {
-                Ok(b) => b,
-                Err(e) => {
-                    log_failure("file_share.read_file");
-                    app.file_upload_error = Some(format!("Read error: {}", e));
-                    return;
-                }
-            };
+        };
+        let result = match (chat_id, team_channel) {
+            (Some(cid), _) => g.send_inline_image(&cid, &image, &caption).await,
+            (None, Some((tid, chid))) => g.send_channel_inline_image(&tid, &chid, &image, &caption).await,
+            (None, None) => Err(anyhow::anyhow!("No chat or channel selected")),
+        };
+        match result {
+            Ok(_) => {
+                let _ = tx.send(BgResult::InlineImageSent);
+            }
+            Err(e) => {
+                logging::try_log_failure("inline_image.send.failed");
+                let _ = tx.send(BgResult::InlineImageError(e.to_string(), caption));
+            }
+        }
+    });
+}
 
-            app.file_uploading = true;
-            app.file_upload_error = None;
+/// Upload the given files in the background and send them as one message to the
+/// selected chat or channel. Small files use a single PUT; larger ones are
+/// streamed through a resumable upload session.
+fn start_file_upload(
+    app: &mut app::App,
+    graph: &client::GraphClient,
+    bg_tx: &tokio::sync::mpsc::UnboundedSender<BgResult>,
+    paths: Vec<std::path::PathBuf>,
+) {
+    let mut uploads = Vec::new();
+    for path in paths {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("file")
+            .to_string();
+        if !path.exists() {
+            log_failure("file_share.validate.not_found");
+            app.file_upload_error = Some(format!("{}: file not found", name));
+            return;
+        }
+        if !path.is_file() {
+            log_failure("file_share.validate.not_file");
+            app.file_upload_error = Some(format!("{}: not a file", name));
+            return;
+        }
+        match std::fs::metadata(&path) {
+            Ok(meta) => uploads.push((path, name, meta.len())),
+            Err(e) => {
+                log_failure("file_share.validate.metadata");
+                app.file_upload_error = Some(format!("Cannot read {}: {}", name, e));
+                return;
+            }
+        }
+    }
+    if uploads.is_empty() {
+        return;
+    }
 
-            // Determine target (chat or channel)
-            let is_teams = app.view_mode == ViewMode::Teams;
-            let chat_id = if !is_teams {
-                app.selected_chat_id().map(String::from)
-            } else {
-                None
-            };
-            let team_channel = if is_teams {
-                match (
-                    app.selected_team_id().map(String::from),
-                    app.selected_channel_id().map(String::from),
-                ) {
-                    (Some(t), Some(c)) => Some((t, c)),
-                    _ => None,
-                }
-            } else {
-                None
-            };
+    let grand_total: u64 = uploads.iter().map(|(_, _, size)| size).sum();
+    let cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    app.file_uploading = true;
+    app.file_upload_error = None;
+    app.file_upload_progress = Some((0, grand_total));
+    app.file_upload_cancel = Some(cancel.clone());
 
-            let g = graph.clone_for_background();
-            let tx = bg_tx.clone();
-            let fname = filename.clone();
-            log_event("file_share.spawned");
-            tokio::spawn(async move {
-                match g.upload_file(&fname, bytes).await {
-                    Ok(drive_item) => {
-                        log_event("file_share.upload.success");
-                        // Now send the message with attachment
-                        let send_result = if let Some(cid) = chat_id {
-                            g.send_message_with_attachment(&cid, &fname, &drive_item)
-                                .await
-                        } else if let Some((tid, chid)) = team_channel {
-                            g.send_channel_message_with_attachment(
-                                &tid, &chid, &fname, &drive_item,
-                            )
-                            .await
-                        } else {
-                            log_failure("file_share.target.missing");
-                            Err(anyhow::anyhow!("No chat or channel selected"))
-                        };
-                        match send_result {
-                            Ok(_) => {
-                                log_event("file_share.send.success");
-                                let _ = tx.send(BgResult::FileUploaded(
-                                    format!("📎 {} shared", fname),
-                                ));
-                            }
-                            Err(e) => {
-                                log_failure("file_share.send.failed");
-                                let _ = tx.send(BgResult::FileUploadError(
-                                    format!("Upload succeeded but send failed: {}", e),
-                                ));
-                            }
-                        }
-                    }
+    // Determine target (chat or channel)
+    let is_teams = app.view_mode == ViewMode::Teams;
+    let chat_id = if !is_teams {
+        app.selected_chat_id().map(String::from)
+    } else {
+        None
+    };
+    let team_channel = if is_teams {
+        match (
+            app.selected_team_id().map(String::from),
+            app.selected_channel_id().map(String::from),
+        ) {
+            (Some(t), Some(c)) => Some((t, c)),
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    let g = graph.clone_for_background();
+    let tx = bg_tx.clone();
+    log_event("file_share.spawned");
+    tokio::spawn(async move {
+        let file_count = uploads.len();
+        let mut items = Vec::with_capacity(file_count);
+        let mut uploaded = 0u64;
+        for (path, name, size) in uploads {
+            if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                let _ = tx.send(BgResult::FileUploadError("Upload cancelled".to_string()));
+                return;
+            }
+            let result = if size <= client::SIMPLE_UPLOAD_MAX {
+                match tokio::fs::read(&path).await {
+                    Ok(bytes) => g.upload_file(&name, bytes).await,
                     Err(e) => {
-                        log_failure("file_share.upload.failed");
-                        let _ = tx.send(BgResult::FileUploadError(e.to_string()));
+                        log_failure("file_share.read_file");
+                        Err(anyhow::anyhow!("Read error: {}", e))
                     }
                 }
-            });
-        }
-        KeyCode::Char(c) => {
-            app.file_picker_insert_char(c);
-            app.file_upload_error = None;
+            } else {
+                log_event("file_share.upload_session.start");
+                let progress_tx = tx.clone();
+                let offset = uploaded;
+                g.upload_large_file(&name, &path, &cancel, move |sent, _| {
+                    let _ = progress_tx.send(BgResult::FileUploadProgress(offset + sent, grand_total));
+                })
+                .await
+            };
+            match result {
+                Ok(item) => items.push(item),
+                Err(e) => {
+                    log_failure("file_share.upload.failed");
+                    let err = if file_count > 1 {
+                        format!("{}: {}", name, e)
+                    } else {
+                        e.to_string()
+                    };
+                    let _ = tx.send(BgResult::FileUploadError(err));
+                    return;
+                }
+            }
+            uploaded += size;
+            let _ = tx.send(BgResult::FileUploadProgress(uploaded, grand_total));
         }
-        KeyCode::Backspace => {
-            app.file_picker_delete_char();
-            app.file_upload_error = None;
+        log_event("file_share.upload.success");
+
+        // Now send one message referencing all uploaded files
+        let send_result = if let Some(cid) = chat_id {
+            g.send_message_with_attachments(&cid, &items).await
+        } else if let Some((tid, chid)) = team_channel {
+            g.send_channel_message_with_attachments(&tid, &chid, &items).await
+        } else {
+            log_failure("file_share.target.missing");
+            Err(anyhow::anyhow!("No chat or channel selected"))
+        };
+        match send_result {
+            Ok(_) => {
+                log_event("file_share.send.success");
+                let msg = match items.as_slice() {
+                    [item] => format!("📎 {} shared", item.name),
+                    _ => format!("📎 {} files shared", items.len()),
+                };
+                let _ = tx.send(BgResult::FileUploaded(msg));
+            }
+            Err(e) => {
+                log_failure("file_share.send.failed");
+                let _ = tx.send(BgResult::FileUploadError(format!(
+                    "Upload succeeded but send failed: {}",
+                    e
+                )));
+            }
         }
-        KeyCode::Left => app.file_picker_cursor_left(),
-        KeyCode::Right => app.file_picker_cursor_right(),
-        _ => {}
-    }
+    });
 }
 
 async fn open_chat_manager(
@@ -1817,11 +3005,9 @@ async fn handle_chat_manager_members_keys(
             app.chat_manager_selected_member =
                 app.chat_manager_selected_member.saturating_sub(1);
         }
-        KeyCode::Down | KeyCode::Char('j') => {
-            if !app.chat_manager_members.is_empty() {
-                app.chat_manager_selected_member = (app.chat_manager_selected_member + 1)
-                    .min(app.chat_manager_members.len().saturating_sub(1));
-            }
+        KeyCode::Down | KeyCode::Char('j') if !app.chat_manager_members.is_empty() => {
+            app.chat_manager_selected_member = (app.chat_manager_selected_member + 1)
+                .min(app.chat_manager_members.len().saturating_sub(1));
         }
         KeyCode::Char('x') => {
             // Remove selected member
@@ -1910,26 +3096,24 @@ async fn handle_chat_manager_rename_keys(
     code: KeyCode,
 ) {
     match code {
-        KeyCode::Enter => {
-            if !app.chat_manager_rename_input.is_empty() {
-                if let Some(chat_id) = app.selected_chat_id().map(String::from) {
-                    let new_topic = app.chat_manager_rename_input.clone();
-                    match graph.rename_chat(&chat_id, &new_topic).await {
-                        Ok(()) => {
-                            // Update local state
-                            if let Some(chat) = app.chats.get_mut(app.selected_chat) {
-                                chat.topic = Some(new_topic.clone());
-                            }
-                            app.status_message = format!("Chat renamed to \"{}\"", new_topic);
-                            app.close_dialog();
-                        }
-                        Err(e) => {
-                            app.show_error(
-                                "Rename Failed",
-                                "Could not rename the chat.",
-                                &e.to_string(),
-                            );
+        KeyCode::Enter if !app.chat_manager_rename_input.is_empty() => {
+            if let Some(chat_id) = app.selected_chat_id().map(String::from) {
+                let new_topic = app.chat_manager_rename_input.clone();
+                match graph.rename_chat(&chat_id, &new_topic).await {
+                    Ok(()) => {
+                        // Update local state
+                        if let Some(chat) = app.chats.get_mut(app.selected_chat) {
+                            chat.topic = Some(new_topic.clone());
                         }
+                        app.status_message = format!("Chat renamed to \"{}\"", new_topic);
+                        app.close_dialog();
+                    }
+                    Err(e) => {
+                        app.show_error(
+                            "Rename Failed",
+                            "Could not rename the chat.",
+                            &e.to_string(),
+                        );
                     }
                 }
             }
@@ -1939,21 +3123,17 @@ async fn handle_chat_manager_rename_keys(
                 .insert(app.chat_manager_rename_cursor, c);
             app.chat_manager_rename_cursor += 1;
         }
-        KeyCode::Backspace => {
-            if app.chat_manager_rename_cursor > 0 {
-                app.chat_manager_rename_cursor -= 1;
-                app.chat_manager_rename_input
-                    .remove(app.chat_manager_rename_cursor);
-            }
+        KeyCode::Backspace if app.chat_manager_rename_cursor > 0 => {
+            app.chat_manager_rename_cursor -= 1;
+            app.chat_manager_rename_input
+                .remove(app.chat_manager_rename_cursor);
         }
         KeyCode::Left => {
             app.chat_manager_rename_cursor =
                 app.chat_manager_rename_cursor.saturating_sub(1);
         }
-        KeyCode::Right => {
-            if app.chat_manager_rename_cursor < app.chat_manager_rename_input.len() {
-                app.chat_manager_rename_cursor += 1;
-            }
+        KeyCode::Right if app.chat_manager_rename_cursor < app.chat_manager_rename_input.len() => {
+            app.chat_manager_rename_cursor += 1;
         }
         _ => {}
     }
@@ -2010,17 +3190,13 @@ async fn handle_chat_manager_add_keys(
                 app.chat_manager_add_selected = 0;
             }
         }
-        KeyCode::Up => {
-            if !app.chat_manager_add_suggestions.is_empty() {
-                app.chat_manager_add_selected =
-                    app.chat_manager_add_selected.saturating_sub(1);
-            }
+        KeyCode::Up if !app.chat_manager_add_suggestions.is_empty() => {
+            app.chat_manager_add_selected =
+                app.chat_manager_add_selected.saturating_sub(1);
         }
-        KeyCode::Down => {
-            if !app.chat_manager_add_suggestions.is_empty() {
-                app.chat_manager_add_selected = (app.chat_manager_add_selected + 1)
-                    .min(app.chat_manager_add_suggestions.len().saturating_sub(1));
-            }
+        KeyCode::Down if !app.chat_manager_add_suggestions.is_empty() => {
+            app.chat_manager_add_selected = (app.chat_manager_add_selected + 1)
+                .min(app.chat_manager_add_suggestions.len().saturating_sub(1));
         }
         KeyCode::Char(c) => {
             app.chat_manager_add_input
@@ -2047,23 +3223,19 @@ async fn handle_chat_manager_add_keys(
                 }
             }
         }
-        KeyCode::Backspace => {
-            if app.chat_manager_add_cursor > 0 {
-                app.chat_manager_add_cursor -= 1;
-                app.chat_manager_add_input
-                    .remove(app.chat_manager_add_cursor);
-                app.chat_manager_add_suggestions.clear();
-                app.chat_manager_add_selected = 0;
-            }
+        KeyCode::Backspace if app.chat_manager_add_cursor > 0 => {
+            app.chat_manager_add_cursor -= 1;
+            app.chat_manager_add_input
+                .remove(app.chat_manager_add_cursor);
+            app.chat_manager_add_suggestions.clear();
+            app.chat_manager_add_selected = 0;
         }
         KeyCode::Left => {
             app.chat_manager_add_cursor =
                 app.chat_manager_add_cursor.saturating_sub(1);
         }
-        KeyCode::Right => {
-            if app.chat_manager_add_cursor < app.chat_manager_add_input.len() {
-                app.chat_manager_add_cursor += 1;
-            }
+        KeyCode::Right if app.chat_manager_add_cursor < app.chat_manager_add_input.len() => {
+            app.chat_manager_add_cursor += 1;
         }
         _ => {}
     }
@@ -2076,17 +3248,31 @@ async fn load_messages(graph: &client::GraphClient, app: &mut app::App) {
     app.selected_message = None;
     app.cancel_reply();
     app.cancel_edit();
+    app.unread_marker = None;
+    app.shown_chat_id = app.selected_chat_id().map(String::from);
     if let Some(chat_id) = app.selected_chat_id().map(String::from) {
         log_event("chat_messages.load.start");
         // Clear delta token so next auto-refresh seeds a fresh one
         app.chat_delta_links.remove(&chat_id);
+        let (last_read, unread) = app
+            .chats
+            .get(app.selected_chat)
+            .map(|c| {
+                let last_read = c.viewpoint.as_ref().and_then(|v| v.last_message_read_date_time.clone());
+                (last_read, c.unread_count())
+            })
+            .unwrap_or_default();
         match graph.get_messages(&chat_id).await {
             Ok((messages, next_link)) => {
                 app.messages = messages;
                 app.messages_next_link = next_link;
                 app.detect_new_messages();
+                if app.set_chat_unread_marker(last_read.as_deref(), unread) {
+                    app.scroll_to_selected = true;
+                }
                 app.status_message.clear();
                 log_event("chat_messages.load.success");
+                app.pins_to_load = Some(chat_id.clone());
             }
             Err(e) => {
                 log_failure("chat_messages.load.failed");
@@ -2097,13 +3283,155 @@ async fn load_messages(graph: &client::GraphClient, app: &mut app::App) {
                 );
             }
         }
-        // Mark chat as read (best-effort)
-        let user_id = app.current_user_id().to_string();
-        if graph.mark_chat_read(&chat_id, &user_id).await.is_err() {
-            log_failure("chat_mark_read.failed");
-        } else {
-            log_event("chat_mark_read.success");
+        if app.schedule_mark_read(&chat_id) {
+            mark_chat_read(graph, app, &chat_id).await;
+        }
+    }
+}
+
+/// Mark a chat read (best-effort); returns whether Graph accepted it
+async fn mark_chat_read(graph: &client::GraphClient, app: &mut app::App, chat_id: &str) -> bool {
+    let user_id = app.current_user_id().to_string();
+    if graph.mark_chat_read(chat_id, &user_id).await.is_err() {
+        log_failure("chat_mark_read.failed");
+        return false;
+    }
+    log_event("chat_mark_read.success");
+    if app.selected_chat_id() == Some(chat_id) {
+        app.mark_selected_chat_read();
+    } else {
+        app.set_chats_read(&[chat_id.to_string()]);
+    }
+    true
+}
+
+/// Mark the open chat read now (`R`, or the palette)
+async fn mark_selected_read(graph: &client::GraphClient, app: &mut app::App) {
+    let Some(chat_id) = app.selected_chat_id().map(String::from) else {
+        return;
+    };
+    app.pending_mark_read = None;
+    app.status_message = if mark_chat_read(graph, app, &chat_id).await {
+        "✓ Marked as read".to_string()
+    } else {
+        "Could not mark the chat read".to_string()
+    };
+}
+
+/// Mark the open chat unread (`U`, or the palette); with a message selected,
+/// from that message on
+async fn mark_selected_unread(graph: &client::GraphClient, app: &mut app::App) {
+    let Some(chat_id) = app.selected_chat_id().map(String::from) else {
+        return;
+    };
+    let user_id = app.current_user_id().to_string();
+    let last_read = app.mark_unread_from();
+    match graph.mark_chat_unread(&chat_id, &user_id, last_read.as_deref()).await {
+        Ok(()) => {
+            log_event("chat_mark_unread.success");
+            app.mark_selected_chat_unread(last_read);
+            app.status_message = "● Marked as unread".to_string();
+        }
+        Err(e) => {
+            log_failure("chat_mark_unread.failed");
+            app.show_error("Mark Unread Failed", "Could not mark the chat unread.", &e.to_string());
+        }
+    }
+}
+
+/// Mark every chat with unread messages read, in the background
+fn mark_all_chats_read(app: &mut app::App, graph: &client::GraphClient, bg_tx: &tokio::sync::mpsc::UnboundedSender<BgResult>) {
+    let chat_ids = app.unread_chat_ids();
+    if chat_ids.is_empty() {
+        app.status_message = "No unread chats".to_string();
+        return;
+    }
+    log_event("chat_mark_all_read.start");
+    app.pending_mark_read = None;
+    app.status_message = format!("Marking {} chats read…", chat_ids.len());
+    let user_id = app.current_user_id().to_string();
+    let g = graph.clone_for_background();
+    let tx = bg_tx.clone();
+    tokio::spawn(async move {
+        let mut done = Vec::new();
+        let mut failed = 0;
+        for chat_id in chat_ids {
+            if g.mark_chat_read(&chat_id, &user_id).await.is_ok() {
+                done.push(chat_id);
+            } else {
+                failed += 1;
+            }
+        }
+        if failed > 0 {
+            log_failure("chat_mark_all_read.partial");
+        }
+        let _ = tx.send(BgResult::ChatsMarkedRead(done, failed));
+    });
+}
+
+/// Refresh the pinned messages of a chat (best-effort; pins are optional)
+async fn load_pinned_messages(graph: &client::GraphClient, app: &mut app::App, chat_id: &str) {
+    match graph.get_pinned_messages(chat_id).await {
+        Ok(pins) => app.set_pinned_messages(chat_id, pins),
+        Err(_) => {
+            log_failure("chat_pins.load.failed");
+            app.set_pinned_messages(chat_id, Vec::new());
+        }
+    }
+}
+
+/// Open the next chat or channel with unread activity (`N`), switching views
+/// as needed; the message view scrolls to the "new" divider
+async fn jump_to_next_unread(graph: &client::GraphClient, app: &mut app::App) {
+    match app.next_unread_target() {
+        Some(app::UnreadTarget::Chat(idx)) => {
+            log_event("unread.jump.chat");
+            app.switch_to_chats();
+            app.selected_chat = idx;
+            if app.selected_chat_collapsed() {
+                app.toggle_selected_section();
+                app.selected_chat = idx;
+            }
+            load_messages(graph, app).await;
+            app.status_message = format!("Unread: {}", app.selected_chat_name());
+        }
+        Some(app::UnreadTarget::Channel(team_idx, channel_id)) => {
+            log_event("unread.jump.channel");
+            app.switch_to_teams();
+            if app.selected_team != team_idx {
+                app.selected_team = team_idx;
+                app.show_cached_channels_for_selected_team();
+            }
+            if let Some(pos) = app.channels.iter().position(|c| c.id == channel_id) {
+                app.selected_channel = pos;
+            }
+            app.show_cached_messages_for_selected_channel();
+            load_channel_messages_cached(graph, app).await;
+            app.teams_panel = TeamsPanel::ChannelMessages;
+            app.status_message = format!("Unread: {}", app.selected_channel_name());
+        }
+        None => app.status_message = "No unread conversations".to_string(),
+    }
+}
+
+/// Pages older chat messages in until `message_id` is loaded
+const MAX_JUMP_PAGES: usize = 20;
+
+/// Select a chat message by ID, loading older pages until it is found
+async fn jump_to_message(graph: &client::GraphClient, app: &mut app::App, message_id: &str) {
+    for _ in 0..MAX_JUMP_PAGES {
+        if app.select_message_by_id(message_id) {
+            app.status_message = "📌 Jumped to pinned message".to_string();
+            return;
+        }
+        if app.messages_next_link.is_none() || app.dialog != DialogMode::None {
+            break;
         }
+        app.status_message = "Loading older messages…".to_string();
+        load_older_messages(graph, app).await;
+    }
+    if !app.select_message_by_id(message_id) {
+        app.status_message = "Pinned message is not in the loaded history".to_string();
     }
 }
 
@@ -2237,8 +3565,8 @@ async fn delete_message(graph: &client::GraphClient, app: &mut app::App) {
         match graph.soft_delete_message(&chat_id, &msg_id).await {
             Ok(_) => {
                 log_event("chat_delete.success");
-                app.status_message = "Message deleted".to_string();
                 app.selected_message = None;
+                app.deleted_message = Some(app::DeletedMessage::new(app::MessageLocation::Chat(chat_id), msg_id));
                 load_messages(graph, app).await;
             }
             Err(e) => {
@@ -2289,30 +3617,221 @@ async fn send_channel_reply(
     }
 }
 
-async fn delete_channel_message(_graph: &client::GraphClient, app: &mut app::App) {
-    // Channel message deletion is not supported via Graph API v1.0 for user-context
-    app.status_message = "Channel message deletion not supported".to_string();
-    app.selected_channel_message = None;
+/// Editing and deleting channel messages needs `ChannelMessage.ReadWrite`,
+/// which is only requested at sign-in when `channel_message_write` is on;
+/// Graph answers 403 without it
+fn channel_write_denied(e: &anyhow::Error) -> bool {
+    e.to_string().contains("403")
+}
+
+const CHANNEL_WRITE_DENIED: &str =
+    "Changing channel messages needs ChannelMessage.ReadWrite: set channel_message_write = true in config.toml and sign in again";
+
+/// Start editing the selected channel message, or explain how to turn channel edits on
+fn start_channel_edit(app: &mut app::App) {
+    if app.channel_message_write {
+        app.start_channel_edit();
+    } else {
+        app.status_message = CHANNEL_WRITE_DENIED.to_string();
+    }
+}
+
+async fn edit_channel_message(
+    graph: &client::GraphClient,
+    app: &mut app::App,
+    message_id: &str,
+    content: &str,
+) {
+    if let (Some(team_id), Some(channel_id)) = (
+        app.selected_team_id().map(String::from),
+        app.selected_channel_id().map(String::from),
+    ) {
+        log_event("channel_edit.start");
+        // Only top-level messages are listed, so there is no parent to pass
+        match graph
+            .update_channel_message(&team_id, &channel_id, message_id, None, content)
+            .await
+        {
+            Ok(()) => {
+                log_event("channel_edit.success");
+                app.cancel_edit();
+                load_channel_messages_cached(graph, app).await;
+                app.status_message = "Message edited".to_string();
+            }
+            Err(e) if channel_write_denied(&e) => {
+                log_failure("channel_edit.permission");
+                app.cancel_edit();
+                app.status_message = CHANNEL_WRITE_DENIED.to_string();
+            }
+            Err(e) => {
+                log_failure("channel_edit.failed");
+                app.show_error(
+                    "Edit Failed",
+                    "Could not edit your message.",
+                    &format!(
+                        "Team: {}\nChannel: {}\nMessage: {}\nError: {}",
+                        team_id, channel_id, message_id, e
+                    ),
+                );
+            }
+        }
+    }
+}
+
+async fn delete_channel_message(graph: &client::GraphClient, app: &mut app::App) {
+    if let (Some(team_id), Some(channel_id), Some(msg_id)) = (
+        app.selected_team_id().map(String::from),
+        app.selected_channel_id().map(String::from),
+        app.selected_channel_message_id().map(String::from),
+    ) {
+        log_event("channel_delete.start");
+        match graph
+            .soft_delete_channel_message(&team_id, &channel_id, &msg_id, None)
+            .await
+        {
+            Ok(()) => {
+                log_event("channel_delete.success");
+                app.selected_channel_message = None;
+                app.deleted_message = Some(app::DeletedMessage::new(
+                    app::MessageLocation::Channel { team_id, channel_id },
+                    msg_id,
+                ));
+                load_channel_messages_cached(graph, app).await;
+            }
+            Err(e) if channel_write_denied(&e) => {
+                log_failure("channel_delete.permission");
+                app.status_message = CHANNEL_WRITE_DENIED.to_string();
+            }
+            Err(e) => {
+                log_failure("channel_delete.failed");
+                app.show_error(
+                    "Delete Failed",
+                    "Could not delete the message.",
+                    &format!("Team: {}\nChannel: {}\nMessage: {}\nError: {}", team_id, channel_id, msg_id, e),
+                );
+            }
+        }
+    }
+}
+
+/// Delete the selected message, after confirming when that is turned on
+async fn request_delete(graph: &client::GraphClient, app: &mut app::App) {
+    if app.view_mode == ViewMode::Teams && !app.channel_message_write {
+        app.status_message = CHANNEL_WRITE_DENIED.to_string();
+    } else if app.confirm_delete {
+        app.open_delete_confirm();
+    } else {
+        delete_selected_message(graph, app).await;
+    }
+}
+
+async fn delete_selected_message(graph: &client::GraphClient, app: &mut app::App) {
+    match app.view_mode {
+        ViewMode::Chats => delete_message(graph, app).await,
+        ViewMode::Teams => delete_channel_message(graph, app).await,
+    }
+}
+
+async fn handle_delete_confirm_keys(
+    app: &mut app::App,
+    graph: &client::GraphClient,
+    code: KeyCode,
+) {
+    match code {
+        KeyCode::Char('y') | KeyCode::Char('d') | KeyCode::Enter => {
+            app.close_dialog();
+            delete_selected_message(graph, app).await;
+        }
+        KeyCode::Char('n') | KeyCode::Esc => app.close_dialog(),
+        _ => {}
+    }
+}
+
+/// Restore the message deleted last with `undoSoftDelete`
+async fn undo_delete(graph: &client::GraphClient, app: &mut app::App) {
+    let Some(deleted) = app.undoable_delete().cloned() else {
+        return;
+    };
+    log_event("undo_delete.start");
+    let result = match &deleted.location {
+        app::MessageLocation::Chat(chat_id) => {
+            graph.undo_soft_delete_message(chat_id, &deleted.message_id).await
+        }
+        app::MessageLocation::Channel { team_id, channel_id } => {
+            graph
+                .undo_soft_delete_channel_message(team_id, channel_id, &deleted.message_id, None)
+                .await
+        }
+    };
+    match result {
+        Ok(()) => {
+            log_event("undo_delete.success");
+            app.deleted_message = None;
+            match deleted.location {
+                app::MessageLocation::Chat(_) => load_messages(graph, app).await,
+                app::MessageLocation::Channel { .. } => load_channel_messages_cached(graph, app).await,
+            }
+            app.status_message = "Message restored".to_string();
+        }
+        Err(e) if matches!(deleted.location, app::MessageLocation::Channel { .. }) && channel_write_denied(&e) => {
+            log_failure("undo_delete.permission");
+            app.status_message = CHANNEL_WRITE_DENIED.to_string();
+        }
+        Err(e) => {
+            log_failure("undo_delete.failed");
+            app.show_error(
+                "Undo Failed",
+                "Could not restore the message.",
+                &format!("Message: {}\nError: {}", deleted.message_id, e),
+            );
+        }
+    }
 }
 
-async fn create_new_chat(graph: &client::GraphClient, app: &mut app::App, email: &str) {
+async fn create_new_chat(
+    graph: &client::GraphClient,
+    app: &mut app::App,
+    people: Vec<app::UserSuggestion>,
+    topic: String,
+) {
+    let names = people.iter().map(|p| p.display_name.as_str()).collect::<Vec<_>>().join(", ");
+
+    // A 1:1 chat with the same person already exists: open it instead
+    if let [person] = people.as_slice() {
+        if let Some(chat_id) = app.find_one_on_one_chat(person) {
+            log_event("chat_create.existing");
+            if app.reveal_chat(&chat_id) {
+                load_messages(graph, app).await;
+                app.active_panel = Panel::Input;
+                app.status_message = format!("Opened existing chat with {}", names);
+                return;
+            }
+        }
+    }
+
     let my_id = app.current_user_id().to_string();
-    app.status_message = format!("Creating chat with {}...", email);
+    app.status_message = format!("Creating chat with {}...", names);
     log_event("chat_create.start");
 
-    match graph.create_chat(email, &my_id).await {
+    let members: Vec<String> = people
+        .iter()
+        .map(|p| if p.id.is_empty() { p.email.clone() } else { p.id.clone() })
+        .collect();
+    let topic = topic.trim();
+    let topic = (!topic.is_empty()).then_some(topic);
+    match graph.create_chat(&members, &my_id, topic).await {
         Ok(new_chat) => {
             log_event("chat_create.success");
             let new_id = new_chat.id.clone();
             match graph.list_chats().await {
                 Ok(chats) => {
-                    let idx = chats.iter().position(|c| c.id == new_id).unwrap_or(0);
-                    app.chats = chats;
-                    app.update_total_unread();
-                    app.selected_chat = idx;
+                    app.set_chats(chats);
+                    if !app.reveal_chat(&new_id) {
+                        app.selected_chat = 0;
+                    }
                     load_messages(graph, app).await;
                     app.active_panel = Panel::Input;
-                    app.status_message = format!("Chat with {} ready", email);
+                    app.status_message = format!("Chat with {} ready", names);
                     log_event("chat_create.list_refresh.success");
                 }
                 Err(_) => {
@@ -2323,10 +3842,12 @@ async fn create_new_chat(graph: &client::GraphClient, app: &mut app::App, email:
         }
         Err(e) => {
             log_failure("chat_create.failed");
+            let recipients =
+                people.iter().map(|p| p.email.as_str()).collect::<Vec<_>>().join(", ");
             app.show_error(
                 "Create Chat Failed",
-                &format!("Could not create a chat with {}.", email),
-                &format!("Recipient: {}\nError: {}", email, e),
+                &format!("Could not create a chat with {}.", names),
+                &format!("Recipients: {}\nError: {}", recipients, e),
             );
         }
     }
@@ -2337,8 +3858,7 @@ async fn refresh_all(graph: &client::GraphClient, app: &mut app::App) {
     log_event("refresh.manual.start");
     match graph.list_chats().await {
         Ok(chats) => {
-            app.chats = chats;
-            app.update_total_unread();
+            app.set_chats(chats);
             load_messages(graph, app).await;
             app.status_message = "Refreshed".to_string();
             log_event("refresh.manual.success");
@@ -2375,6 +3895,15 @@ fn spawn_auto_refresh(
         .as_deref()
         .and_then(|cid| app.chat_delta_links.get(cid))
         .cloned();
+    // Channels sync by delta where permitted; the first query starts from the
+    // newest cached message
+    let channel_delta = channel_id
+        .as_deref()
+        .filter(|cid| !app.channel_delta_unsupported.contains(*cid))
+        .and_then(|cid| match app.channel_delta_links.get(cid) {
+            Some(link) => Some((Some(link.clone()), None)),
+            None => app.channel_latest_time(cid).map(|since| (None, Some(since))),
+        });
 
     tokio::spawn(async move {
         log_event("refresh.auto.spawned");
@@ -2431,9 +3960,27 @@ fn spawn_auto_refresh(
             }
             ViewMode::Teams => {
                 if let (Some(tid), Some(cid)) = (team_id, channel_id) {
-                    if let Ok((msgs, next_link)) = bg_graph.get_channel_messages(&tid, &cid).await {
+                    if let Some((link, since)) = channel_delta {
+                        match bg_graph
+                            .get_channel_messages_delta(&tid, &cid, link.as_deref(), since.as_deref())
+                            .await
+                        {
+                            Ok((msgs, new_delta)) => {
+                                log_event("refresh.auto.channel_delta.success");
+                                let _ = tx.send(BgResult::DeltaChannelMessages(cid, msgs, new_delta));
+                                return;
+                            }
+                            Err(e) => {
+                                log_failure("refresh.auto.channel_delta");
+                                // A stored link that fails may just have expired
+                                let unsupported = link.is_none() && client::delta_unsupported(&e);
+                                let _ = tx.send(BgResult::ChannelDeltaFailed(cid.clone(), unsupported));
+                            }
+                        }
+                    }
+                    if let Ok((msgs, _)) = bg_graph.get_channel_messages(&tid, &cid).await {
                         log_event("refresh.auto.channel.success");
-                        let _ = tx.send(BgResult::RefreshedChannelMessages(cid, msgs, next_link));
+                        let _ = tx.send(BgResult::RefreshedChannelMessages(cid, msgs));
                     } else {
                         log_failure("refresh.auto.channel");
                     }
@@ -2473,7 +4020,7 @@ fn spawn_presence_load(
         let others_fut = async {
             let mut all_presences = Vec::new();
             for chunk in user_ids.chunks(650) {
-                if let Ok(presences) = others_graph.get_presences(&chunk.to_vec()).await {
+                if let Ok(presences) = others_graph.get_presences(chunk).await {
                     all_presences.extend(presences);
                 }
             }
@@ -2534,6 +4081,72 @@ async fn load_teams_with_preload(
     }
 }
 
+/// Load the team list in background
+fn spawn_teams_load(
+    graph: &client::GraphClient,
+    bg_tx: &tokio::sync::mpsc::UnboundedSender<BgResult>,
+) {
+    let bg_graph = graph.clone_for_background();
+    let tx = bg_tx.clone();
+    tokio::spawn(async move {
+        if let Ok(teams) = bg_graph.list_teams().await {
+            log_event("teams.preload.success");
+            let _ = tx.send(BgResult::Teams(teams));
+        } else {
+            log_failure("teams.preload.failed");
+        }
+    });
+}
+
+fn spawn_pinned_messages_load(
+    graph: &client::GraphClient,
+    chat_id: String,
+    bg_tx: &tokio::sync::mpsc::UnboundedSender<BgResult>,
+) {
+    let bg_graph = graph.clone_for_background();
+    let tx = bg_tx.clone();
+    tokio::spawn(async move {
+        let pins = bg_graph.get_pinned_messages(&chat_id).await.unwrap_or_else(|_| {
+            log_failure("chat_pins.load.failed");
+            Vec::new()
+        });
+        let _ = tx.send(BgResult::PinnedMessages(chat_id, pins));
+    });
+}
+
+/// Fetch the newest few messages of each channel, one at a time, to find
+/// unread activity without loading every channel in full
+fn spawn_channel_activity_poll(
+    graph: &client::GraphClient,
+    targets: Vec<(String, String)>,
+    bg_tx: &tokio::sync::mpsc::UnboundedSender<BgResult>,
+) {
+    if targets.is_empty() {
+        return;
+    }
+    let bg_graph = graph.clone_for_background();
+    let tx = bg_tx.clone();
+    tokio::spawn(async move {
+        for (team_id, channel_id) in targets {
+            match bg_graph
+                .get_recent_channel_messages(&team_id, &channel_id, app::CHANNEL_POLL_TOP)
+                .await
+            {
+                Ok(msgs) => {
+                    let _ = tx.send(BgResult::ChannelActivity(channel_id, msgs));
+                }
+                Err(e) if e.to_string().contains("429") => {
+                    // Throttled: skip the rest of this round and poll less often
+                    let _ = tx.send(BgResult::ChannelPollThrottled);
+                    return;
+                }
+                Err(_) => log_failure("channels.poll.failed"),
+            }
+        }
+        log_event("channels.poll.done");
+    });
+}
+
 /// Preload channels for all teams in background
 fn spawn_channels_preload(
     graph: &client::GraphClient,
@@ -2624,6 +4237,7 @@ async fn load_channel_messages_cached(graph: &client::GraphClient, app: &mut app
         app.selected_channel_id().map(String::from),
     ) {
         log_event("channel_messages.load.start");
+        app.note_channel_opened(&team_id, &channel_id);
         match graph.get_channel_messages(&team_id, &channel_id).await {
             Ok((msgs, next_link)) => {
                 log_event("channel_messages.load.success");
@@ -2632,6 +4246,9 @@ async fn load_channel_messages_cached(graph: &client::GraphClient, app: &mut app
                 app.channel_messages = msgs;
                 app.channel_messages_next_link = next_link;
                 app.status_message.clear();
+                if app.mark_selected_channel_read() {
+                    app.scroll_to_selected = true;
+                }
             }
             Err(e) => {
                 log_failure("channel_messages.load.failed");
@@ -2749,17 +4366,32 @@ fn print_help() {
     println!("VIEWS:");
     println!("  1              Switch to Chats view");
     println!("  2              Switch to Teams & Channels view");
+    println!("  N              Jump to next chat or channel with unread messages");
     println!();
     println!("KEYBOARD SHORTCUTS (Chats):");
     println!("  Tab / Shift+Tab  Switch panels (Chats → Messages → Input)");
     println!("  Up/Down or j/k   Navigate chats / scroll messages");
-    println!("  Enter            Send message / select chat");
-    println!("  n                New chat");
+    println!("  Enter            Send message / select chat / view image of selected message");
+    println!("  Space            Collapse / expand chat list section");
+    println!("  i                Filter chats by name (Enter keeps, Esc clears)");
+    println!("  R / U            Mark current chat read / unread");
+    println!("  u / O / G / M    Unread only / 1:1 / group / meeting chats");
+    println!("  n                New chat (add people, Tab for topic)");
     println!("  s                Select message (in Messages panel)");
-    println!("  e                React to selected message");
+    println!("  e                React to selected message (again to remove)");
+    println!("  E                Show who reacted to selected message");
+    println!("  >                Forward selected message");
+    println!("  t                Pin / unpin selected message");
+    println!("  P                Pinned messages (Enter jumps, u unpins)");
+    println!("  y                Copy code block(s) of selected message");
+    println!("  a                Cycle card actions of selected message (Enter to run)");
     println!("  r                Reply to selected / Refresh (no selection)");
     println!("  d                Delete selected message (own only)");
+    println!("  z                Undo the last delete (10 seconds)");
     println!("  w                Edit selected message (own only)");
+    println!("  S                Save attachment of selected message to disk");
+    println!("  F                Shared files of the current chat");
+    println!("  Ctrl+V           Send clipboard image inline");
     println!("  p                Set presence status");
     println!("  Esc              Back to chat list / deselect / cancel reply/edit");
     println!("  q                Quit");
@@ -2770,8 +4402,48 @@ fn print_help() {
     println!("  Up/Down or j/k   Navigate teams / channels / scroll messages");
     println!("  Enter            Expand team / select channel / send message");
     println!("  m                Toggle channel member list");
+    println!("  w / d            Edit / delete selected message (own messages only)");
+    println!("  z                Undo the last delete (10 seconds)");
+    println!("  e / E            React to selected message / show who reacted");
+    println!("  >                Forward selected message");
+    println!("  F                Shared files of the current channel");
+    println!("  Ctrl+V           Send clipboard image inline");
+    println!("  S                Save attachment of selected message to disk");
+    println!("  y                Copy code block(s) of selected message");
+    println!("  a                Cycle card actions of selected message (Enter to run)");
     println!("  Esc              Go back one panel");
     println!();
+    println!("COMMAND PALETTE (Ctrl+P):");
+    println!("  Type             Filter chats, channels and actions");
+    println!("  Chat actions     Pin / Mute / Hide Current Chat, Show Hidden Chats");
+    println!("  Read state       Mark Current Chat Read / Unread, Mark All Chats Read");
+    println!();
+    println!("EMOJI SHORTCODES (composer):");
+    println!("  :ta              Type : and 2+ characters for completions");
+    println!("  Up/Down, Tab     Choose / insert emoji (Esc hides the popup)");
+    println!("  :tada:           Converted to emoji when sent");
+    println!();
+    println!("REACTION PICKER (e):");
+    println!("  Type             Search emoji by name or keyword");
+    println!("  Tab / Shift+Tab  Recently used / categories");
+    println!("  Arrows           Move in the emoji grid");
+    println!("  Ctrl+T           Cycle skin tone");
+    println!("  Enter            React (or remove your reaction)");
+    println!();
+    println!("FILE PICKER (f):");
+    println!("  j/k, h/l         Move / parent folder / open folder");
+    println!("  Space, u         Mark file / upload marked files");
+    println!("  I                Send selected image inline");
+    println!("  Enter            Open folder or upload selected file");
+    println!("  . / ~            Toggle hidden files / go home");
+    println!("  /                Type a path (Tab to complete)");
+    println!();
+    println!("IMAGE VIEWER:");
+    println!("  +/-              Zoom in / out (0 to fit)");
+    println!("  h/j/k/l          Pan");
+    println!("  O                Open image externally");
+    println!("  Esc              Close viewer");
+    println!();
     println!("SECURITY:");
     println!("  Tokens are stored in your OS credential manager:");
     println!("    Windows  - Credential Manager");
@@ -1,6 +1,8 @@
 use serde::Deserialize;
 use std::collections::HashMap;
 
+use crate::cards::{parse_card, Card, CardAction};
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct GraphResponse<T> {
     pub value: Vec<T>,
@@ -45,6 +47,17 @@ pub struct Chat {
     pub last_message_preview: Option<MessagePreview>,
     #[serde(rename = "unreadMessageCount", default)]
     pub unread_message_count: Option<i32>,
+    #[serde(default)]
+    pub viewpoint: Option<ChatViewpoint>,
+}
+
+/// The signed-in user's view of a chat
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ChatViewpoint {
+    #[serde(rename = "isHidden", default)]
+    pub is_hidden: bool,
+    #[serde(rename = "lastMessageReadDateTime", default)]
+    pub last_message_read_date_time: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -54,6 +67,8 @@ pub struct ChatMember {
     pub display_name: Option<String>,
     #[serde(rename = "userId")]
     pub user_id: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -75,6 +90,25 @@ pub struct Message {
     pub reactions: Option<Vec<ChatMessageReaction>>,
     #[serde(default)]
     pub attachments: Vec<ChatMessageAttachment>,
+    #[serde(rename = "eventDetail", default)]
+    pub event_detail: Option<EventDetail>,
+    /// Set when the message was deleted; its body is then empty
+    #[serde(rename = "deletedDateTime", default)]
+    pub deleted_date_time: Option<String>,
+}
+
+/// A pinned chat message (`pinnedChatMessageInfo`), with the message expanded
+#[derive(Debug, Clone, Deserialize)]
+pub struct PinnedMessage {
+    pub id: String,
+    #[serde(default)]
+    pub message: Option<Message>,
+}
+
+impl PinnedMessage {
+    pub fn message_id(&self) -> Option<&str> {
+        self.message.as_ref().map(|m| m.id.as_str())
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -88,6 +122,9 @@ pub struct MessageBody {
 #[derive(Debug, Clone, Deserialize)]
 pub struct MessageFrom {
     pub user: Option<MessageUser>,
+    /// Set instead of `user` for bot, workflow and connector posts
+    #[serde(default)]
+    pub application: Option<MessageUser>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -106,6 +143,9 @@ pub struct ChatMessageAttachment {
     #[serde(rename = "contentUrl")]
     pub content_url: Option<String>,
     pub name: Option<String>,
+    /// Card payload (JSON string) for Adaptive/hero/connector card attachments
+    #[serde(default)]
+    pub content: Option<String>,
 }
 
 impl ChatMessageAttachment {
@@ -158,6 +198,212 @@ pub struct ReactionIdentitySet {
     pub user: Option<MessageUser>,
 }
 
+impl ChatMessageReaction {
+    pub fn reactor_id(&self) -> Option<&str> {
+        self.user.as_ref()?.user.as_ref()?.id.as_deref()
+    }
+}
+
+// System event messages (messageType: systemEventMessage)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EventDetail {
+    #[serde(rename = "@odata.type", default)]
+    pub odata_type: Option<String>,
+    #[serde(default)]
+    pub initiator: Option<MessageFrom>,
+    #[serde(default)]
+    pub members: Vec<MessageUser>,
+    #[serde(rename = "chatDisplayName", default)]
+    pub chat_display_name: Option<String>,
+    #[serde(rename = "channelDisplayName", default)]
+    pub channel_display_name: Option<String>,
+    #[serde(rename = "teamDisplayName", default)]
+    pub team_display_name: Option<String>,
+    #[serde(rename = "teamsAppDisplayName", default)]
+    pub teams_app_display_name: Option<String>,
+    #[serde(rename = "callEventType", default)]
+    pub call_event_type: Option<String>,
+    /// ISO 8601 duration, e.g. `PT1H2M3S`
+    #[serde(rename = "callDuration", default)]
+    pub call_duration: Option<String>,
+    #[serde(rename = "callRecordingDisplayName", default)]
+    pub call_recording_display_name: Option<String>,
+    #[serde(rename = "callRecordingStatus", default)]
+    pub call_recording_status: Option<String>,
+    #[serde(rename = "conversationMemberUser", default)]
+    pub conversation_member_user: Option<MessageUser>,
+    #[serde(rename = "conversationMemberRoles", default)]
+    pub conversation_member_roles: Vec<String>,
+}
+
+impl EventDetail {
+    /// Event name without the Graph namespace and suffix, e.g. `membersAdded`
+    pub fn kind(&self) -> &str {
+        self.odata_type
+            .as_deref()
+            .unwrap_or("")
+            .trim_start_matches("#microsoft.graph.")
+            .trim_end_matches("EventMessageDetail")
+    }
+
+    /// Human-readable sentence describing the event
+    pub fn summary(&self) -> String {
+        let actor = self.initiator_name();
+        let who = actor.as_deref().unwrap_or("Someone");
+        let members = join_names(&self.members);
+        let call = match self.call_event_type.as_deref() {
+            Some("meeting") => "meeting",
+            Some("screenShare") => "screen share",
+            _ => "call",
+        };
+        match self.kind() {
+            "membersAdded" => match actor {
+                Some(actor) => format!("{} added {}", actor, members),
+                None => format!("{} joined", members),
+            },
+            "membersDeleted" => {
+                let left_alone = self.members.len() == 1
+                    && self.members[0].id.is_some()
+                    && self.initiator_id() == self.members[0].id.as_deref();
+                match actor {
+                    Some(_) if left_alone => format!("{} left", members),
+                    Some(actor) => format!("{} removed {}", actor, members),
+                    None => format!("{} left", members),
+                }
+            }
+            "membersJoined" => format!("{} joined", members),
+            "membersLeft" => format!("{} left", members),
+            "chatRenamed" => match self.chat_display_name.as_deref() {
+                Some(name) if !name.is_empty() => format!("{} renamed the chat to \"{}\"", who, name),
+                _ => format!("{} removed the chat name", who),
+            },
+            "callStarted" => format!("{} started a {}", who, call),
+            "callEnded" => {
+                let mut text = format!("{}{} ended", call[..1].to_uppercase(), &call[1..]);
+                if let Some(duration) = self.call_duration.as_deref().and_then(format_iso_duration) {
+                    text.push_str(&format!(" · {}", duration));
+                }
+                text
+            }
+            "callRecording" => {
+                let name = self.call_recording_display_name.as_deref().unwrap_or("Recording");
+                match self.call_recording_status.as_deref() {
+                    Some("success") => format!("{} is ready", name),
+                    Some("failure") => format!("{} failed", name),
+                    _ => format!("{} started recording", who),
+                }
+            }
+            "callTranscript" => "Transcript is available".to_string(),
+            "teamsAppInstalled" => format!("{} added {}", who, self.app_name()),
+            "teamsAppRemoved" => format!("{} removed {}", who, self.app_name()),
+            "teamsAppUpgraded" => format!("{} updated {}", who, self.app_name()),
+            "channelAdded" => format!("{} created channel {}", who, self.channel_name()),
+            "channelDeleted" => format!("{} deleted channel {}", who, self.channel_name()),
+            "channelRenamed" => format!("{} renamed the channel to {}", who, self.channel_name()),
+            "teamRenamed" => format!(
+                "{} renamed the team to {}",
+                who,
+                self.team_display_name.as_deref().unwrap_or("a new name")
+            ),
+            "messagePinned" => format!("{} pinned a message", who),
+            "messageUnpinned" => format!("{} unpinned a message", who),
+            "conversationMemberRoleUpdated" => {
+                let member = self
+                    .conversation_member_user
+                    .as_ref()
+                    .and_then(|u| u.display_name.as_deref())
+                    .unwrap_or("A member");
+                if self.conversation_member_roles.iter().any(|r| r == "owner") {
+                    format!("{} is now an owner", member)
+                } else {
+                    format!("{} is no longer an owner", member)
+                }
+            }
+            "" => "System event".to_string(),
+            other => humanize_event_kind(other),
+        }
+    }
+
+    fn initiator_name(&self) -> Option<String> {
+        self.initiator
+            .as_ref()
+            .and_then(|f| f.user.as_ref().or(f.application.as_ref()))
+            .and_then(|u| u.display_name.clone())
+            .filter(|n| !n.is_empty())
+    }
+
+    fn initiator_id(&self) -> Option<&str> {
+        self.initiator
+            .as_ref()
+            .and_then(|f| f.user.as_ref())
+            .and_then(|u| u.id.as_deref())
+    }
+
+    fn app_name(&self) -> &str {
+        self.teams_app_display_name.as_deref().unwrap_or("an app")
+    }
+
+    fn channel_name(&self) -> &str {
+        self.channel_display_name.as_deref().unwrap_or("a channel")
+    }
+}
+
+/// "A", "A and B", "A, B and C", "A, B and 3 others"
+fn join_names(members: &[MessageUser]) -> String {
+    let names: Vec<&str> = members
+        .iter()
+        .map(|m| m.display_name.as_deref().filter(|n| !n.is_empty()).unwrap_or("someone"))
+        .collect();
+    match names.as_slice() {
+        [] => "Someone".to_string(),
+        [one] => one.to_string(),
+        [a, b] => format!("{} and {}", a, b),
+        [a, b, c] => format!("{}, {} and {}", a, b, c),
+        [a, b, rest @ ..] => format!("{}, {} and {} others", a, b, rest.len()),
+    }
+}
+
+/// `PT1H2M3.5S` → `1h 2m 3s`
+fn format_iso_duration(value: &str) -> Option<String> {
+    let rest = value.strip_prefix("PT")?;
+    let mut parts = Vec::new();
+    let mut number = String::new();
+    for c in rest.chars() {
+        match c {
+            '0'..='9' | '.' => number.push(c),
+            'H' | 'M' | 'S' => {
+                let whole = number.split('.').next().unwrap_or("").parse::<u64>().ok()?;
+                if whole > 0 {
+                    parts.push(format!("{}{}", whole, c.to_ascii_lowercase()));
+                }
+                number.clear();
+            }
+            _ => return None,
+        }
+    }
+    if parts.is_empty() {
+        Some("0s".to_string())
+    } else {
+        Some(parts.join(" "))
+    }
+}
+
+/// `meetingPolicyUpdated` → `Meeting policy updated`
+fn humanize_event_kind(kind: &str) -> String {
+    let mut text = String::new();
+    for (i, c) in kind.chars().enumerate() {
+        if i == 0 {
+            text.extend(c.to_uppercase());
+        } else if c.is_ascii_uppercase() {
+            text.push(' ');
+            text.push(c.to_ascii_lowercase());
+        } else {
+            text.push(c);
+        }
+    }
+    text
+}
+
 // Teams & Channels
 #[derive(Debug, Clone, Deserialize)]
 #[allow(dead_code)]
@@ -256,7 +502,7 @@ impl Message {
     pub fn sender_name(&self) -> String {
         self.from
             .as_ref()
-            .and_then(|f| f.user.as_ref())
+            .and_then(|f| f.user.as_ref().or(f.application.as_ref()))
             .and_then(|u| u.display_name.clone())
             .unwrap_or_else(|| "System".to_string())
     }
@@ -284,10 +530,48 @@ impl Message {
             .unwrap_or_default()
     }
 
+    /// Date and time for quoting, e.g. `2026-01-29 14:05`
+    pub fn formatted_date_time(&self) -> String {
+        self.created_date_time
+            .as_ref()
+            .and_then(|dt| chrono::DateTime::parse_from_rfc3339(dt).ok())
+            .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default()
+    }
+
     pub fn is_user_message(&self) -> bool {
         self.message_type.as_deref() == Some("message")
     }
 
+    pub fn created_at(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        self.created_date_time
+            .as_deref()
+            .and_then(|dt| chrono::DateTime::parse_from_rfc3339(dt).ok())
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_date_time.is_some()
+    }
+
+    pub fn is_system_event(&self) -> bool {
+        self.message_type.as_deref() == Some("systemEventMessage")
+    }
+
+    /// Readable sentence for system event messages (member added, call ended, ...)
+    pub fn event_summary(&self) -> Option<String> {
+        if !self.is_system_event() {
+            return None;
+        }
+        match &self.event_detail {
+            Some(detail) => Some(detail.summary()),
+            None => {
+                let text = self.content_text();
+                let text = text.trim();
+                Some(if text.is_empty() { "System event".to_string() } else { text.to_string() })
+            }
+        }
+    }
+
     /// Returns a summary of reactions as (emoji, count) pairs
     pub fn reactions_summary(&self) -> Vec<(String, usize)> {
         let mut counts: HashMap<String, usize> = HashMap::new();
@@ -300,10 +584,49 @@ impl Message {
             .into_iter()
             .map(|(rtype, count)| (reaction_emoji(&rtype), count))
             .collect();
-        result.sort_by(|a, b| b.1.cmp(&a.1));
+        result.sort_by_key(|r| std::cmp::Reverse(r.1));
         result
     }
 
+    /// Whether `user_id` has reacted with `emoji` (legacy types like "like" match their emoji)
+    pub fn has_reaction_from(&self, user_id: &str, emoji: &str) -> bool {
+        self.reaction_type_from(user_id, emoji).is_some()
+    }
+
+    /// The stored `reactionType` of `user_id`'s reaction matching `emoji`, which
+    /// may be a legacy type or a differently encoded variant of it
+    pub fn reaction_type_from(&self, user_id: &str, emoji: &str) -> Option<&str> {
+        if user_id.is_empty() {
+            return None;
+        }
+        self.reactions
+            .iter()
+            .flatten()
+            .find(|r| same_reaction(&reaction_emoji(&r.reaction_type), emoji) && r.reactor_id() == Some(user_id))
+            .map(|r| r.reaction_type.as_str())
+    }
+
+    /// Who reacted with what: one entry per emoji in first-seen order. Graph often
+    /// omits reactor display names, so `resolve` maps user IDs to names.
+    pub fn reaction_details(&self, resolve: impl Fn(&str) -> Option<String>) -> Vec<(String, Vec<String>)> {
+        let mut details: Vec<(String, Vec<String>)> = Vec::new();
+        for r in self.reactions.iter().flatten() {
+            let emoji = reaction_emoji(&r.reaction_type);
+            let name = r
+                .user
+                .as_ref()
+                .and_then(|u| u.user.as_ref())
+                .and_then(|u| u.display_name.clone())
+                .or_else(|| r.reactor_id().and_then(&resolve))
+                .unwrap_or_else(|| "Unknown user".to_string());
+            match details.iter_mut().find(|(e, _)| same_reaction(e, &emoji)) {
+                Some((_, names)) => names.push(name),
+                None => details.push((emoji, vec![name])),
+            }
+        }
+        details
+    }
+
     pub fn file_attachments(&self) -> Vec<&ChatMessageAttachment> {
         self.attachments
             .iter()
@@ -311,6 +634,48 @@ impl Message {
             .collect()
     }
 
+    /// Contents of all multi-line code blocks in the message body
+    pub fn code_blocks(&self) -> Vec<String> {
+        let html = self
+            .body
+            .as_ref()
+            .and_then(|b| b.content.as_deref())
+            .unwrap_or("");
+        parse_rich_text(html)
+            .into_iter()
+            .filter_map(|seg| match seg {
+                RichSegment::CodeBlock { code, .. } => Some(code),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// hostedContents image URLs referenced by `<img>` tags in the body
+    pub fn hosted_image_urls(&self) -> Vec<String> {
+        let html = self
+            .body
+            .as_ref()
+            .and_then(|b| b.content.as_deref())
+            .unwrap_or("");
+        parse_rich_text(html)
+            .into_iter()
+            .filter_map(|seg| match seg {
+                RichSegment::HostedImage { url, .. } => Some(url),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Adaptive, hero/thumbnail and connector cards attached to the message
+    pub fn cards(&self) -> Vec<Card> {
+        self.attachments.iter().filter_map(parse_card).collect()
+    }
+
+    /// Actions of all cards in the message, in display order
+    pub fn card_actions(&self) -> Vec<CardAction> {
+        self.cards().into_iter().flat_map(|c| c.actions).collect()
+    }
+
     pub fn image_attachments(&self) -> Vec<&ChatMessageAttachment> {
         self.attachments
             .iter()
@@ -319,6 +684,51 @@ impl Message {
     }
 }
 
+/// Escape text for inclusion in an HTML message body
+pub fn escape_html(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Index of the first message someone else sent after `last_read`. Without a
+/// read time the last `unread_count` messages from others are taken as unread.
+pub fn first_unread_index(
+    messages: &[Message],
+    last_read: Option<&str>,
+    unread_count: i32,
+    current_user_id: &str,
+) -> Option<usize> {
+    let from_others = |m: &&Message| m.is_user_message() && m.sender_id() != Some(current_user_id);
+    if let Some(last_read) = last_read.and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok()) {
+        return messages
+            .iter()
+            .position(|m| from_others(&m) && m.created_at().is_some_and(|t| t > last_read));
+    }
+    let unread = usize::try_from(unread_count).ok().filter(|&n| n > 0)?;
+    let others: Vec<usize> = messages
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| from_others(m))
+        .map(|(i, _)| i)
+        .collect();
+    others.get(others.len().saturating_sub(unread)).copied()
+}
+
+/// Number of messages someone else sent after `last_read`
+pub fn count_unread_since(messages: &[Message], last_read: &str, current_user_id: &str) -> usize {
+    let Ok(last_read) = chrono::DateTime::parse_from_rfc3339(last_read) else {
+        return 0;
+    };
+    messages
+        .iter()
+        .filter(|m| m.is_user_message() && m.sender_id() != Some(current_user_id))
+        .filter(|m| m.created_at().is_some_and(|t| t > last_read))
+        .count()
+}
+
 pub fn strip_html(input: &str) -> String {
     let mut result = String::with_capacity(input.len());
     let mut in_tag = false;
@@ -341,16 +751,61 @@ pub fn strip_html(input: &str) -> String {
         .to_string()
 }
 
+/// Display emoji for a reaction type. Graph reports the legacy names
+/// (`like`, `heart`, ...) for older reactions and the unicode emoji itself for
+/// newer ones; shortcodes and hex code points are resolved through the bundled
+/// table, and anything unrecognised shows a placeholder instead of raw text.
 pub fn reaction_emoji(reaction_type: &str) -> String {
     match reaction_type {
-        "like" => "👍".to_string(),
-        "heart" => "❤️".to_string(),
-        "laugh" => "😂".to_string(),
-        "surprised" => "😮".to_string(),
-        "sad" => "😢".to_string(),
-        "angry" => "😡".to_string(),
-        other => other.to_string(),
+        "like" => return "👍".to_string(),
+        "heart" => return "❤️".to_string(),
+        "laugh" => return "😂".to_string(),
+        "surprised" => return "😮".to_string(),
+        "sad" => return "😢".to_string(),
+        "angry" => return "😡".to_string(),
+        _ => {}
+    }
+    let trimmed = reaction_type.trim().trim_matches(':');
+    if crate::emoji::looks_like_emoji(trimmed) {
+        return trimmed.to_string();
+    }
+    if let Some(entry) = crate::emoji::by_shortcode(&trimmed.to_lowercase()) {
+        return entry.emoji.to_string();
     }
+    codepoints_to_emoji(trimmed).unwrap_or_else(|| UNKNOWN_REACTION.to_string())
+}
+
+/// Shown for reaction types that cannot be rendered (e.g. custom org emoji)
+pub const UNKNOWN_REACTION: &str = "❔";
+
+/// Parse code point notation such as `1f44d` or `U+2764-FE0F` into an emoji
+fn codepoints_to_emoji(text: &str) -> Option<String> {
+    let parts: Vec<&str> = text
+        .split(['-', '_', ' '])
+        .filter(|p| !p.is_empty())
+        .collect();
+    if parts.is_empty() {
+        return None;
+    }
+    let mut out = String::new();
+    for part in parts {
+        let hex = part.trim_start_matches("U+").trim_start_matches("u+");
+        if hex.len() < 4 || hex.len() > 6 {
+            return None;
+        }
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        let emoji_range = matches!(value, 0x2000..=0x33FF | 0xFE0F | 0x1F000..=0x1FAFF | 0xE0020..=0xE007F);
+        if !emoji_range {
+            return None;
+        }
+        out.push(char::from_u32(value)?);
+    }
+    Some(out)
+}
+
+/// Compare reaction emoji ignoring the emoji variation selector (❤️ vs ❤)
+pub fn same_reaction(a: &str, b: &str) -> bool {
+    a.trim_end_matches('\u{FE0F}') == b.trim_end_matches('\u{FE0F}')
 }
 
 pub fn presence_indicator(availability: &str) -> (&str, &str) {
@@ -366,18 +821,6 @@ pub fn presence_indicator(availability: &str) -> (&str, &str) {
     }
 }
 
-/// Available reaction types for the reaction picker
-/// Reaction types: (emoji_to_send, display_label)
-/// The Graph API setReaction expects unicode emoji as reactionType
-pub const REACTION_TYPES: &[(&str, &str)] = &[
-    ("👍", "👍 Like"),
-    ("❤️", "❤️ Heart"),
-    ("😂", "😂 Laugh"),
-    ("😮", "😮 Surprised"),
-    ("😢", "😢 Sad"),
-    ("😡", "😡 Angry"),
-];
-
 /// Available presence statuses
 pub const PRESENCE_STATUSES: &[(&str, &str)] = &[
     ("Available", "🟢 Available"),
@@ -401,6 +844,201 @@ pub struct DriveItem {
     pub e_tag: Option<String>,
     #[serde(default)]
     pub size: Option<i64>,
+    #[serde(rename = "lastModifiedDateTime", default)]
+    pub last_modified_date_time: Option<String>,
+    #[serde(rename = "lastModifiedBy", default)]
+    pub last_modified_by: Option<MessageFrom>,
+    #[serde(rename = "parentReference", default)]
+    pub parent_reference: Option<ItemReference>,
+    /// Present (as an empty facet) when the item is a folder
+    #[serde(default)]
+    pub folder: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ItemReference {
+    #[serde(rename = "driveId", default)]
+    pub drive_id: Option<String>,
+}
+
+// ---- Shared files (Files panel) ----
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharedFileSource {
+    /// Attached to a loaded message
+    Message,
+    /// Listed from the channel's SharePoint folder
+    ChannelFolder,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedFile {
+    pub name: String,
+    /// Link opened in the browser and copied to the clipboard
+    pub web_url: String,
+    /// Source for downloads: a sharing URL or a Graph `/content` URL
+    pub download_url: String,
+    pub sender: String,
+    /// RFC 3339 timestamp of the message or last modification
+    pub date: Option<String>,
+    pub size: Option<i64>,
+    pub source: SharedFileSource,
+}
+
+impl SharedFile {
+    pub fn extension(&self) -> String {
+        self.name
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_ascii_lowercase())
+            .unwrap_or_default()
+    }
+
+    pub fn date_label(&self) -> String {
+        self.date_time()
+            .map(|dt| dt.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn date_time(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        self.date
+            .as_deref()
+            .and_then(|dt| chrono::DateTime::parse_from_rfc3339(dt).ok())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharedFileSort {
+    Date,
+    Sender,
+    Type,
+}
+
+impl SharedFileSort {
+    pub fn next(self) -> Self {
+        match self {
+            SharedFileSort::Date => SharedFileSort::Sender,
+            SharedFileSort::Sender => SharedFileSort::Type,
+            SharedFileSort::Type => SharedFileSort::Date,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SharedFileSort::Date => "date",
+            SharedFileSort::Sender => "sender",
+            SharedFileSort::Type => "type",
+        }
+    }
+}
+
+/// Sort shared files: newest first by date; by sender or type with newest first as tie-breaker
+pub fn sort_shared_files(files: &mut [SharedFile], sort: SharedFileSort) {
+    let newest_first = |a: &SharedFile, b: &SharedFile| b.date_time().cmp(&a.date_time());
+    files.sort_by(|a, b| match sort {
+        SharedFileSort::Date => newest_first(a, b),
+        SharedFileSort::Sender => a
+            .sender
+            .to_lowercase()
+            .cmp(&b.sender.to_lowercase())
+            .then_with(|| newest_first(a, b)),
+        SharedFileSort::Type => a
+            .extension()
+            .cmp(&b.extension())
+            .then_with(|| newest_first(a, b)),
+    });
+}
+
+/// Files and images attached to the given messages, one entry per link (latest share wins)
+pub fn shared_files_from_messages(messages: &[Message]) -> Vec<SharedFile> {
+    let mut files: Vec<SharedFile> = Vec::new();
+    for message in messages {
+        for attachment in message.file_attachments().into_iter().chain(message.image_attachments()) {
+            let Some(url) = attachment.content_url.clone() else {
+                continue;
+            };
+            let name = attachment
+                .name
+                .clone()
+                .filter(|n| !n.trim().is_empty())
+                .unwrap_or_else(|| "attachment".to_string());
+            let file = SharedFile {
+                name,
+                web_url: url.clone(),
+                download_url: url,
+                sender: message.sender_name(),
+                date: message.created_date_time.clone(),
+                size: None,
+                source: SharedFileSource::Message,
+            };
+            match files.iter_mut().find(|f| same_file_link(&f.web_url, &file.web_url)) {
+                Some(existing) if existing.date_time() < file.date_time() => *existing = file,
+                Some(_) => {}
+                None => files.push(file),
+            }
+        }
+    }
+    files
+}
+
+/// Files (not folders) from a channel's SharePoint folder listing
+pub fn shared_files_from_drive_items(drive_id: &str, items: &[DriveItem]) -> Vec<SharedFile> {
+    items
+        .iter()
+        .filter(|item| item.folder.is_none())
+        .map(|item| SharedFile {
+            name: item.name.clone(),
+            web_url: item.web_url.clone(),
+            download_url: format!(
+                "https://graph.microsoft.com/v1.0/drives/{}/items/{}/content",
+                drive_id, item.id
+            ),
+            sender: item
+                .last_modified_by
+                .as_ref()
+                .and_then(|f| f.user.as_ref().or(f.application.as_ref()))
+                .and_then(|u| u.display_name.clone())
+                .unwrap_or_default(),
+            date: item.last_modified_date_time.clone(),
+            size: item.size,
+            source: SharedFileSource::ChannelFolder,
+        })
+        .collect()
+}
+
+/// Merge folder files into the list. Folder entries replace message attachments
+/// pointing at the same document since they carry size and download details.
+pub fn merge_shared_files(files: &mut Vec<SharedFile>, folder_files: Vec<SharedFile>) {
+    for file in folder_files {
+        files.retain(|f| !same_file_link(&f.web_url, &file.web_url));
+        files.push(file);
+    }
+}
+
+/// Attachment links and `webUrl`s may differ in case and space encoding
+fn same_file_link(a: &str, b: &str) -> bool {
+    let normalize = |url: &str| url.replace("%20", " ").to_lowercase();
+    normalize(a) == normalize(b)
+}
+
+// ---- Upload session (large file upload) ----
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UploadSession {
+    #[serde(rename = "uploadUrl", default)]
+    pub upload_url: Option<String>,
+    /// Byte ranges the server still needs, e.g. `["26214400-"]`
+    #[serde(rename = "nextExpectedRanges", default)]
+    pub next_expected_ranges: Vec<String>,
+}
+
+impl UploadSession {
+    /// Offset of the first byte the server still expects
+    pub fn next_offset(&self) -> Option<u64> {
+        self.next_expected_ranges
+            .iter()
+            .filter_map(|range| range.split('-').next()?.trim().parse::<u64>().ok())
+            .min()
+    }
 }
 
 // ---- Search results ----
@@ -502,7 +1140,10 @@ pub enum RichSegment {
     Bold(String),
     Italic(String),
     Code(String),
+    CodeBlock { language: Option<String>, code: String },
     Link { text: String, url: String },
+    /// Inline image stored in the message's hostedContents (e.g. a pasted screenshot)
+    HostedImage { url: String, alt: String },
     Newline,
 }
 
@@ -549,15 +1190,32 @@ pub fn parse_rich_text(html: &str) -> Vec<RichSegment> {
                 if !inner.is_empty() {
                     segments.push(RichSegment::Italic(decode_entities(&strip_html(&inner))));
                 }
-            } else if tag_lower == "code" || tag_lower == "pre" {
+            } else if matches!(tag_name(&tag_lower), "code" | "pre" | "codeblock") {
                 if !current.is_empty() {
                     segments.push(RichSegment::Plain(decode_entities(&current)));
                     current.clear();
                 }
-                let inner = collect_until_close(&mut chars, &tag_lower);
-                if !inner.is_empty() {
+                let name = tag_name(&tag_lower).to_string();
+                let inner = collect_until_close(&mut chars, &name);
+                let language = extract_code_language(&tag).or_else(|| extract_code_language(&inner));
+                let code = extract_code_text(&inner);
+                if name != "code" || language.is_some() || code.contains('\n') {
+                    if !code.is_empty() {
+                        segments.push(RichSegment::CodeBlock { language, code });
+                    }
+                } else if !inner.is_empty() {
                     segments.push(RichSegment::Code(decode_entities(&strip_html(&inner))));
                 }
+            } else if tag_name(&tag_lower) == "img" {
+                let src = decode_entities(&extract_attr(&tag, "src"));
+                if is_hosted_content_url(&src) {
+                    if !current.is_empty() {
+                        segments.push(RichSegment::Plain(decode_entities(&current)));
+                        current.clear();
+                    }
+                    let alt = decode_entities(&extract_attr(&tag, "alt"));
+                    segments.push(RichSegment::HostedImage { url: src, alt });
+                }
             } else if tag_lower.starts_with("a ") {
                 if !current.is_empty() {
                     segments.push(RichSegment::Plain(decode_entities(&current)));
@@ -618,6 +1276,64 @@ fn collect_until_close(
     result
 }
 
+fn tag_name(tag_lower: &str) -> &str {
+    tag_lower.split_whitespace().next().unwrap_or("")
+}
+
+/// Find a language hint in the first `class` attribute of a tag or tag soup,
+/// e.g. `pre class="language-rust"` or `codeblock class="Python"`.
+fn extract_code_language(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let pos = lower.find("class=")?;
+    let rest = &html[pos + 6..];
+    let quote = rest.chars().next()?;
+    let value = if quote == '"' || quote == '\'' {
+        rest[1..].split(quote).next().unwrap_or("")
+    } else {
+        rest.split(|c: char| c.is_whitespace() || c == '>').next().unwrap_or("")
+    };
+    value
+        .split_whitespace()
+        .map(|class| {
+            let lower = class.to_lowercase();
+            lower
+                .strip_prefix("language-")
+                .or_else(|| lower.strip_prefix("lang-"))
+                .unwrap_or(&lower)
+                .to_string()
+        })
+        .find(|lang| !lang.is_empty())
+}
+
+/// Convert the inner HTML of a code block to plain text, keeping line structure
+fn extract_code_text(inner: &str) -> String {
+    let mut text = String::with_capacity(inner.len());
+    let mut chars = inner.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == '<' {
+            let mut tag = String::new();
+            for tc in chars.by_ref() {
+                if tc == '>' {
+                    break;
+                }
+                tag.push(tc);
+            }
+            let tag_lower = tag.to_lowercase();
+            let name = tag_name(&tag_lower).trim_end_matches('/');
+            if matches!(name, "br" | "/div" | "/p") {
+                text.push('\n');
+            }
+        } else if ch != '\r' {
+            text.push(ch);
+        }
+    }
+    let decoded = decode_entities(&text);
+    let lines: Vec<&str> = decoded.lines().collect();
+    let start = lines.iter().position(|l| !l.trim().is_empty()).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|l| !l.trim().is_empty()).map(|i| i + 1).unwrap_or(start);
+    lines[start..end].join("\n")
+}
+
 fn extract_href(tag: &str) -> String {
     if let Some(pos) = tag.find("href=\"") {
         let start = pos + 6;
@@ -634,6 +1350,32 @@ fn extract_href(tag: &str) -> String {
     String::new()
 }
 
+/// Value of a quoted attribute, matched only at an attribute boundary
+/// (so `src` does not match `data-src`).
+fn extract_attr(tag: &str, name: &str) -> String {
+    for quote in ['"', '\''] {
+        let needle = format!("{}={}", name, quote);
+        let mut from = 0;
+        while let Some(pos) = tag[from..].find(&needle) {
+            let pos = from + pos;
+            let at_boundary = !matches!(tag[..pos].chars().last(), Some(c) if !c.is_whitespace());
+            let start = pos + needle.len();
+            if at_boundary {
+                if let Some(end) = tag[start..].find(quote) {
+                    return tag[start..start + end].to_string();
+                }
+            }
+            from = start;
+        }
+    }
+    String::new()
+}
+
+/// Graph hostedContents URLs are the only inline images fetched with the Graph token
+pub fn is_hosted_content_url(url: &str) -> bool {
+    url.starts_with("https://graph.microsoft.com/") && url.contains("/hostedContents/")
+}
+
 fn decode_entities(input: &str) -> String {
     input
         .replace("&amp;", "&")
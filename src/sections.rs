@@ -0,0 +1,105 @@
+//! User-defined chat list sections: compiling `[[chat_sections]]` rules and
+//! deciding which section a chat belongs to.
+
+use regex::Regex;
+
+use crate::config::ChatSectionConfig;
+use crate::models::Chat;
+
+/// Header for chats that match no configured section
+pub const OTHER_SECTION: &str = "Other";
+
+#[derive(Debug, Clone)]
+pub struct ChatSection {
+    pub name: String,
+    chat_ids: Vec<String>,
+    chat_types: Vec<String>,
+    members: Vec<String>,
+    topic: Option<Regex>,
+    unread: Option<bool>,
+}
+
+impl ChatSection {
+    pub fn compile(config: &ChatSectionConfig) -> Result<Self, regex::Error> {
+        let topic = match config.topic.as_deref().map(str::trim) {
+            Some(pattern) if !pattern.is_empty() => Some(Regex::new(pattern)?),
+            _ => None,
+        };
+        Ok(Self {
+            name: config.name.trim().to_string(),
+            chat_ids: config.chats.clone(),
+            chat_types: config.chat_types.iter().map(|t| t.to_lowercase()).collect(),
+            members: config.members.iter().map(|m| m.to_lowercase()).collect(),
+            topic,
+            unread: config.unread,
+        })
+    }
+
+    fn has_rules(&self) -> bool {
+        !self.chat_types.is_empty()
+            || !self.members.is_empty()
+            || self.topic.is_some()
+            || self.unread.is_some()
+    }
+
+    /// Listed by ID, or every configured rule matches
+    pub fn matches(&self, chat: &Chat, current_user_id: &str) -> bool {
+        if self.chat_ids.contains(&chat.id) {
+            return true;
+        }
+        if !self.has_rules() {
+            return false;
+        }
+        if !self.chat_types.is_empty() && !self.chat_types.contains(&chat.chat_type.to_lowercase()) {
+            return false;
+        }
+        if !self.members.is_empty() && !self.matches_member(chat, current_user_id) {
+            return false;
+        }
+        if let Some(ref topic) = self.topic {
+            if !topic.is_match(&chat.display_name(current_user_id)) {
+                return false;
+            }
+        }
+        if let Some(unread) = self.unread {
+            if (chat.unread_count() > 0) != unread {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn matches_member(&self, chat: &Chat, current_user_id: &str) -> bool {
+        chat.members
+            .iter()
+            .flatten()
+            .filter(|m| m.user_id.as_deref() != Some(current_user_id))
+            .any(|m| {
+                let name = m.display_name.as_deref().unwrap_or_default().to_lowercase();
+                let user_id = m.user_id.as_deref().unwrap_or_default().to_lowercase();
+                self.members.iter().any(|rule| *rule == user_id || name.contains(rule.as_str()))
+            })
+    }
+}
+
+/// Compile every configured section, collecting the names of sections whose
+/// topic pattern is not a valid regular expression (those are skipped).
+pub fn compile_sections(configs: &[ChatSectionConfig]) -> (Vec<ChatSection>, Vec<String>) {
+    let mut sections = Vec::new();
+    let mut invalid = Vec::new();
+    for config in configs {
+        match ChatSection::compile(config) {
+            Ok(section) => sections.push(section),
+            Err(_) => invalid.push(config.name.clone()),
+        }
+    }
+    (sections, invalid)
+}
+
+/// Index of the first matching section; `sections.len()` means "Other"
+pub fn section_index(sections: &[ChatSection], chat: &Chat, current_user_id: &str) -> usize {
+    sections
+        .iter()
+        .position(|s| s.matches(chat, current_user_id))
+        .unwrap_or(sections.len())
+}
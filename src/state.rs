@@ -0,0 +1,143 @@
+//! Runtime state remembered between sessions: recently used emoji, the picker's
+//! skin tone, chat pin/mute/hide preferences and channel read times. Kept in
+//! `state.json` in the data directory so `config.toml` is only written by the
+//! settings dialog.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+
+/// Channel read times kept per account; the oldest are dropped beyond this
+pub const MAX_CHANNEL_READ_TIMES: usize = 500;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct State {
+    /// Skin tone in the emoji picker: 0 = none, 1-5 = light to dark
+    #[serde(default)]
+    pub skin_tone: usize,
+    /// Most recently used reaction emoji, newest first
+    #[serde(default)]
+    pub recent_emojis: Vec<String>,
+    /// Pinned, muted and hidden chats, keyed by the signed-in user's ID
+    #[serde(default)]
+    pub chat_prefs: HashMap<String, ChatPrefs>,
+    /// Newest message time seen per channel ID, keyed by the signed-in user's ID
+    #[serde(default)]
+    pub channel_last_read: HashMap<String, HashMap<String, String>>,
+}
+
+/// Per-account chat list preferences
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChatPrefs {
+    /// Kept at the top of the chat list
+    #[serde(default)]
+    pub pinned: Vec<String>,
+    /// No bell and not counted in the unread badge
+    #[serde(default)]
+    pub muted: Vec<String>,
+    /// Hidden with `hideForUser`; used when Graph does not report the chat's viewpoint
+    #[serde(default)]
+    pub hidden: Vec<String>,
+}
+
+impl ChatPrefs {
+    /// Add or remove `chat_id` from one of the lists; returns whether it is now present
+    pub fn toggle(list: &mut Vec<String>, chat_id: &str) -> bool {
+        if let Some(pos) = list.iter().position(|id| id == chat_id) {
+            list.remove(pos);
+            false
+        } else {
+            list.push(chat_id.to_string());
+            true
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pinned.is_empty() && self.muted.is_empty() && self.hidden.is_empty()
+    }
+}
+
+impl State {
+    /// State that older versions kept in `config.toml`
+    pub fn from_legacy_config(config: &Config) -> Self {
+        Self {
+            skin_tone: config.skin_tone,
+            recent_emojis: config.recent_emojis.clone(),
+            chat_prefs: config.chat_prefs.clone(),
+            channel_last_read: config.channel_last_read.clone(),
+        }
+    }
+
+    /// Store an account's chat preferences; returns whether anything changed
+    pub fn set_chat_prefs(&mut self, user_id: &str, prefs: &ChatPrefs) -> bool {
+        if prefs.is_empty() {
+            return self.chat_prefs.remove(user_id).is_some();
+        }
+        if self.chat_prefs.get(user_id) == Some(prefs) {
+            return false;
+        }
+        self.chat_prefs.insert(user_id.to_string(), prefs.clone());
+        true
+    }
+}
+
+/// Keep the `max` most recent channel read times; unparseable ones go first
+pub fn prune_read_times(read_times: &mut HashMap<String, String>, max: usize) {
+    if read_times.len() <= max {
+        return;
+    }
+    let mut entries: Vec<_> = read_times
+        .drain()
+        .map(|(id, time)| (chrono::DateTime::parse_from_rfc3339(&time).ok(), id, time))
+        .collect();
+    entries.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    entries.truncate(max);
+    read_times.extend(entries.into_iter().map(|(_, id, time)| (id, time)));
+}
+
+pub fn state_path() -> Result<PathBuf> {
+    let dir = dirs::data_dir()
+        .context("Could not determine data directory")?
+        .join("ttyms");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join("state.json"))
+}
+
+/// Load the saved state, migrating it from `config.toml` on first run
+pub fn load_state(config: &Config) -> Result<State> {
+    let path = state_path()?;
+    if !path.exists() {
+        return Ok(State::from_legacy_config(config));
+    }
+    load_state_from(&path)
+}
+
+/// Read a state file. One that cannot be parsed is copied to `state.json.bak`
+/// first, so starting over with an empty state does not lose it.
+pub fn load_state_from(path: &Path) -> Result<State> {
+    let content = std::fs::read_to_string(path).context("Failed to read state file")?;
+    serde_json::from_str(&content).map_err(|e| {
+        let backup = path.with_extension("json.bak");
+        let note = match std::fs::copy(path, &backup) {
+            Ok(_) => format!("a copy was saved to {}", backup.display()),
+            Err(_) => "it could not be backed up".to_string(),
+        };
+        anyhow::anyhow!(e).context(format!("Invalid state file format ({})", note))
+    })
+}
+
+pub fn save_state(state: &State) -> Result<()> {
+    save_state_to(state, &state_path()?)
+}
+
+/// Write through a temporary file so an interrupted save keeps the old state
+pub fn save_state_to(state: &State, path: &Path) -> Result<()> {
+    let content = serde_json::to_string(state).context("Failed to serialize state")?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, content).context("Failed to write state file")?;
+    std::fs::rename(&tmp, path).context("Failed to write state file")?;
+    Ok(())
+}
@@ -6,7 +6,16 @@ use ratatui::{
     Frame,
 };
 
-use crate::app::{App, AppScreen, ChatManagerTab, DialogMode, LayoutAreas, Panel, TeamsPanel, ViewMode};
+use crate::app::{
+    App, AppScreen, ChatListRow, ChatManagerTab, DialogMode, ForwardStage, LayoutAreas, Panel, TeamsPanel, ViewMode,
+    CHAT_FILTER_TYPES,
+};
+use crate::cards::{Card, CardAction, CardLine, TextEmphasis};
+use crate::downloads;
+use crate::emoji;
+use crate::files;
+use crate::graphics::{self, ImagePreview};
+use crate::highlight::{self, TokenKind};
 use crate::models::{self, RichSegment};
 
 pub fn draw(frame: &mut Frame, app: &mut App) {
@@ -121,9 +130,76 @@ fn draw_main(frame: &mut Frame, app: &mut App) {
         DialogMode::ChatManager => draw_chat_manager_dialog(frame, app),
         DialogMode::CommandPalette => draw_command_palette(frame, app),
         DialogMode::FilePicker => draw_file_picker(frame, app),
+        DialogMode::SharedFiles => draw_shared_files(frame, app),
+        DialogMode::Forward => draw_forward_dialog(frame, app),
+        DialogMode::ReactionDetails => draw_reaction_details(frame, app),
+        DialogMode::PinnedMessages => draw_pinned_messages(frame, app),
+        DialogMode::ConfirmDelete => draw_delete_confirm(frame, app),
+        DialogMode::ImageViewer => {}
         DialogMode::Error(info) => draw_error_dialog(frame, info),
-        DialogMode::None => {}
+        DialogMode::None => draw_shortcode_popup(frame, app),
+    }
+    if app.dialog == DialogMode::ImageViewer {
+        draw_image_viewer(frame, app);
+    }
+}
+
+/// Full-screen image viewer. Pixel protocols (kitty/iTerm2/sixel) are drawn by the
+/// main loop into `layout_areas.image_viewer`; otherwise the image is rendered as cells.
+fn draw_image_viewer(frame: &mut Frame, app: &mut App) {
+    let area = frame.area();
+    frame.render_widget(Clear, area);
+    let protocol = app.image_protocol;
+    let Some(viewer) = app.image_viewer.as_mut() else {
+        return;
+    };
+
+    let (img_w, img_h) = (viewer.image.width(), viewer.image.height());
+    let zoom_label = if viewer.zoom() == 1 {
+        "fit".to_string()
+    } else {
+        format!("{}×", viewer.zoom())
+    };
+    let block = Block::default()
+        .title(format!(" 🖼 {} — {}×{} — {} ", viewer.name, img_w, img_h, zoom_label))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+    let image_area = chunks[0];
+
+    let hint = "+/-: zoom  │  h/j/k/l: pan  │  0: fit  │  O: open externally  │  Esc: close";
+    frame.render_widget(
+        Paragraph::new(Span::styled(hint, Style::default().fg(Color::DarkGray)))
+            .alignment(Alignment::Center),
+        chunks[1],
+    );
+
+    if !protocol.is_pixel_protocol() {
+        let key = viewer.render_key(image_area);
+        let stale = viewer.rendered.as_ref().map(|(k, _)| *k != key).unwrap_or(true);
+        if stale {
+            let rendered = graphics::render_cells(
+                &viewer.visible_image(),
+                image_area.width,
+                image_area.height,
+                protocol.supports_color(),
+            );
+            viewer.rendered = Some((key, rendered));
+        }
+        if let Some((_, rendered)) = viewer.rendered.as_ref() {
+            let top_pad = (image_area.height as usize).saturating_sub(rendered.height()) / 2;
+            let mut lines = vec![Line::from(""); top_pad];
+            lines.extend(preview_lines(rendered, ""));
+            frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), image_area);
+        }
     }
+    app.layout_areas.image_viewer = image_area;
 }
 
 fn draw_header(frame: &mut Frame, app: &App, area: Rect) {
@@ -156,6 +232,12 @@ fn draw_header(frame: &mut Frame, app: &App, area: Rect) {
     } else {
         String::new()
     };
+    let channel_unread = app.total_channel_unread();
+    let channel_unread_text = if channel_unread > 0 {
+        format!(" ({})", channel_unread)
+    } else {
+        String::new()
+    };
 
     let header = Paragraph::new(Line::from(vec![
         Span::styled(
@@ -171,6 +253,7 @@ fn draw_header(frame: &mut Frame, app: &App, area: Rect) {
         Span::styled("  ", Style::default()),
         Span::styled("2:", Style::default().fg(Color::DarkGray)),
         Span::styled("Teams", teams_tab_style),
+        Span::styled(&channel_unread_text, Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
         Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
         Span::styled(
             format!("{} {} ", presence_icon, user_name),
@@ -202,28 +285,47 @@ fn draw_chat_list(frame: &mut Frame, app: &App, area: Rect) {
     let is_active = app.active_panel == Panel::ChatList && app.view_mode == ViewMode::Chats;
     let border_color = if is_active { Color::Cyan } else { Color::DarkGray };
 
+    let title = if app.chat_filter.is_active() {
+        format!(" Chats ({}/{}) ", app.chats.len(), app.all_chats.len())
+    } else {
+        " Chats ".to_string()
+    };
     let block = Block::default()
-        .title(" Chats ")
+        .title(title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(border_color));
+    let mut list_area = block.inner(area);
+    frame.render_widget(block, area);
+    if app.chat_filter_bar_visible() && list_area.height > 0 {
+        let bar = Rect { height: 1, ..list_area };
+        frame.render_widget(Paragraph::new(chat_filter_line(app)), bar);
+        list_area.y += 1;
+        list_area.height -= 1;
+    }
 
-    let inner_height = block.inner(area).height as usize;
-    let item_height = 3usize;
-    let max_visible = if item_height > 0 { inner_height / item_height } else { 0 };
-
-    let scroll_start = if max_visible > 0 && app.selected_chat >= max_visible {
-        app.selected_chat - max_visible + 1
-    } else {
-        0
-    };
+    let inner_height = list_area.height as usize;
+    let rows = app.chat_list_rows();
+    let scroll_start = app.chat_list_scroll(&rows, inner_height);
+    let selected_row = app.selected_chat_row(&rows);
 
-    let items: Vec<ListItem> = app
-        .chats
+    let mut used = 0;
+    let items: Vec<ListItem> = rows
         .iter()
         .enumerate()
         .skip(scroll_start)
-        .take(max_visible.max(1))
-        .map(|(i, chat)| {
+        .take_while(|(_, row)| {
+            let fits = used == 0 || used + row.height() <= inner_height;
+            used += row.height();
+            fits
+        })
+        .map(|(row_idx, row)| {
+            let i = match row {
+                ChatListRow::Chat(i) => *i,
+                ChatListRow::Header { name, chats, unread, collapsed, .. } => {
+                    return section_header_item(name, *chats, *unread, *collapsed, selected_row == Some(row_idx));
+                }
+            };
+            let chat = &app.chats[i];
             let name = chat.display_name(app.current_user_id());
             let preview = chat.preview_text();
             let preview = if preview.chars().count() > 25 {
@@ -235,8 +337,12 @@ fn draw_chat_list(frame: &mut Frame, app: &App, area: Rect) {
             let is_selected = i == app.selected_chat;
             let unread = chat.unread_count();
             let has_unread = unread > 0;
+            let muted = app.is_chat_muted(&chat.id);
+            let hidden = app.is_chat_hidden(chat);
 
-            let name_style = if is_selected {
+            let name_style = if hidden && !is_selected {
+                Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)
+            } else if is_selected {
                 Style::default()
                     .fg(Color::Cyan)
                     .add_modifier(Modifier::BOLD)
@@ -251,15 +357,25 @@ fn draw_chat_list(frame: &mut Frame, app: &App, area: Rect) {
             let indicator = if is_selected { "▸ " } else { "  " };
 
             // Build name line with optional unread badge
-            let mut name_spans = vec![
-                Span::styled(indicator, name_style),
-                Span::styled(name, name_style),
-            ];
+            let mut name_spans = vec![Span::styled(indicator, name_style)];
+            if app.is_chat_pinned(&chat.id) {
+                name_spans.push(Span::raw("📌 "));
+            }
+            name_spans.push(Span::styled(name, name_style));
             if has_unread {
-                name_spans.push(Span::styled(
-                    format!(" ({})", unread),
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-                ));
+                // Muted chats keep a dim count that is left out of the header badge
+                let badge_style = if muted {
+                    Style::default().fg(Color::DarkGray)
+                } else {
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                };
+                name_spans.push(Span::styled(format!(" ({})", unread), badge_style));
+            }
+            if muted {
+                name_spans.push(Span::raw(" 🔕"));
+            }
+            if hidden {
+                name_spans.push(Span::styled(" (hidden)", Style::default().fg(Color::DarkGray)));
             }
 
             // Show presence indicator for 1:1 chats
@@ -292,28 +408,93 @@ fn draw_chat_list(frame: &mut Frame, app: &App, area: Rect) {
         })
         .collect();
 
-    let list = List::new(items).block(block);
-    frame.render_widget(list, area);
+    let list = List::new(items);
+    frame.render_widget(list, list_area);
 }
 
-fn draw_message_area(frame: &mut Frame, app: &mut App, area: Rect) {
-    let reply_or_edit = app.is_replying() || app.is_editing();
-    let constraints = if reply_or_edit {
-        vec![Constraint::Min(5), Constraint::Length(1), Constraint::Length(3)]
+/// Filter bar above the chat list: name query, then the active toggles
+fn chat_filter_line(app: &App) -> Line<'static> {
+    let filter = &app.chat_filter;
+    let query_style = if filter.editing {
+        Style::default().fg(Color::Yellow)
     } else {
-        vec![Constraint::Min(5), Constraint::Length(0), Constraint::Length(3)]
+        Style::default().fg(Color::White)
     };
-    let chunks = Layout::default()
+    let mut spans = vec![
+        Span::styled("🔍 ", Style::default().fg(Color::DarkGray)),
+        Span::styled(filter.query.clone(), query_style),
+    ];
+    if filter.editing {
+        spans.push(Span::styled("▏", Style::default().fg(Color::Yellow)));
+    }
+    let toggle_style = Style::default().fg(Color::Black).bg(Color::Cyan);
+    if filter.unread_only {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(" unread ", toggle_style));
+    }
+    for (chat_type, label) in CHAT_FILTER_TYPES {
+        if filter.chat_types.iter().any(|t| t == chat_type) {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(format!(" {} ", label), toggle_style));
+        }
+    }
+    if app.chats.is_empty() {
+        spans.push(Span::styled("  no matches", Style::default().fg(Color::DarkGray)));
+    }
+    Line::from(spans)
+}
+
+/// One-line chat list section header: fold marker, name, chat count and unread badge
+fn section_header_item(name: &str, chats: usize, unread: i32, collapsed: bool, selected: bool) -> ListItem<'static> {
+    let marker = if collapsed { "▸ " } else { "▾ " };
+    let style = if selected {
+        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+    };
+    let mut spans = vec![
+        Span::styled(format!("{}{}", marker, name), style),
+        Span::styled(format!(" · {}", chats), Style::default().fg(Color::DarkGray)),
+    ];
+    if unread > 0 {
+        spans.push(Span::styled(
+            format!(" ({})", unread),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ));
+    }
+    ListItem::new(Line::from(spans))
+}
+
+fn draw_message_area(frame: &mut Frame, app: &mut App, area: Rect) {
+    let reply_or_edit = app.is_replying() || app.is_editing();
+    let pin_strip = if app.selected_chat_pins().is_empty() { 0 } else { 1 };
+    let constraints = vec![
+        Constraint::Length(pin_strip),
+        Constraint::Min(5),
+        Constraint::Length(if reply_or_edit { 1 } else { 0 }),
+        Constraint::Length(3),
+    ];
+    let outer = Layout::default()
         .direction(Direction::Vertical)
         .constraints(constraints)
         .split(area);
+    let chunks = &outer[1..];
+
+    if pin_strip > 0 {
+        draw_pinned_strip(frame, app, outer[0]);
+    }
 
     app.layout_areas.messages = chunks[0];
     app.layout_areas.input = chunks[2];
 
-    draw_messages(frame, app, &app.messages, app.scroll_offset, app.selected_message,
+    let scrolled = draw_messages(frame, app, &app.messages, app.scroll_offset, app.selected_message,
                   &app.selected_chat_name(), app.active_panel == Panel::Messages,
-                  app.loading_more_messages && app.messages_next_link.is_some(), chunks[0]);
+                  app.loading_more_messages && app.messages_next_link.is_some(),
+                  app.unread_marker.as_deref(), app.scroll_to_selected, chunks[0]);
+    if let Some(offset) = scrolled {
+        app.scroll_offset = offset;
+        app.scroll_to_selected = false;
+    }
 
     if app.is_replying() {
         let reply_line = Paragraph::new(Line::from(vec![
@@ -341,6 +522,7 @@ fn draw_message_area(frame: &mut Frame, app: &mut App, area: Rect) {
                    app.active_panel == Panel::Input, input_title, chunks[2]);
 }
 
+#[allow(clippy::too_many_arguments)]
 fn draw_messages(
     frame: &mut Frame,
     app: &App,
@@ -350,8 +532,10 @@ fn draw_messages(
     title: &str,
     is_active: bool,
     has_more: bool,
+    unread_marker: Option<&str>,
+    scroll_to_selected: bool,
     area: Rect,
-) {
+) -> Option<usize> {
     let border_color = if is_active { Color::Cyan } else { Color::DarkGray };
 
     let block = Block::default()
@@ -367,10 +551,12 @@ fn draw_messages(
             .style(Style::default().fg(Color::DarkGray))
             .alignment(Alignment::Center);
         frame.render_widget(empty, inner);
-        return;
+        return None;
     }
 
     let current_user_id = app.current_user_id();
+    let mut selected_line = None;
+    let mut unread_line = None;
     let mut lines: Vec<Line> = Vec::new();
 
     if has_more {
@@ -382,6 +568,20 @@ fn draw_messages(
     }
 
     for (idx, msg) in messages.iter().enumerate() {
+        if unread_marker == Some(msg.id.as_str()) {
+            unread_line = Some(lines.len());
+            lines.push(
+                Line::from(Span::styled("── new ──", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)))
+                    .alignment(Alignment::Center),
+            );
+        }
+        if let Some(summary) = msg.event_summary() {
+            lines.push(event_line(&summary, &msg.formatted_time()));
+            if !messages.get(idx + 1).is_some_and(|next| next.is_system_event()) {
+                lines.push(Line::from(""));
+            }
+            continue;
+        }
         if !msg.is_user_message() {
             continue;
         }
@@ -414,15 +614,29 @@ fn draw_messages(
             ),
         ];
 
+        if app.is_pinned(&msg.id) {
+            sender_spans.push(Span::raw(" 📌"));
+        }
+
         if is_selected {
             sender_spans.push(Span::styled(
                 " ◀",
                 Style::default().fg(Color::Magenta),
             ));
+            selected_line = Some(lines.len());
         }
 
         lines.push(Line::from(sender_spans));
 
+        if msg.is_deleted() {
+            lines.push(Line::from(Span::styled(
+                "  This message has been deleted.",
+                Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+            )));
+            lines.push(Line::from(""));
+            continue;
+        }
+
         // Message content with rich text
         let content_html = msg
             .body
@@ -472,6 +686,32 @@ fn draw_messages(
                         ));
                     }
                 }
+                RichSegment::CodeBlock { language, code } => {
+                    if content_spans.iter().any(|s| !s.content.trim().is_empty()) {
+                        lines.push(Line::from(content_spans.clone()));
+                    }
+                    content_spans.clear();
+                    let block_width = (inner.width as usize).saturating_sub(2);
+                    for block_line in code_block_lines(language.as_deref(), code, block_width) {
+                        let mut spans = vec![Span::raw("  ")];
+                        spans.extend(block_line.spans);
+                        lines.push(Line::from(spans));
+                    }
+                    content_spans.push(Span::raw("  "));
+                }
+                RichSegment::HostedImage { url, alt } => {
+                    if content_spans.iter().any(|s| !s.content.trim().is_empty()) {
+                        lines.push(Line::from(content_spans.clone()));
+                    }
+                    content_spans.clear();
+                    let name = if alt.trim().is_empty() { "image" } else { alt.as_str() };
+                    lines.push(Line::from(vec![
+                        Span::raw("  "),
+                        Span::styled(format!("🖼 {}", name), Style::default().fg(Color::Magenta)),
+                    ]));
+                    lines.extend(image_preview_block(app, name, Some(url)));
+                    content_spans.push(Span::raw("  "));
+                }
                 RichSegment::Newline => {
                     lines.push(Line::from(content_spans.clone()));
                     content_spans.clear();
@@ -480,7 +720,7 @@ fn draw_messages(
             }
         }
 
-        if !content_spans.is_empty() {
+        if content_spans.iter().any(|s| !s.content.trim().is_empty()) {
             lines.push(Line::from(content_spans));
         }
 
@@ -501,35 +741,7 @@ fn draw_messages(
                 ));
             }
             lines.push(Line::from(att_spans));
-
-            if let Some(url) = attachment.content_url.as_deref() {
-                if let Some(decoded) = app.image_preview_lines(url) {
-                    for preview_line in decoded {
-                        lines.push(Line::from(vec![
-                            Span::raw("  "),
-                            Span::styled(preview_line.clone(), Style::default().fg(Color::DarkGray)),
-                        ]));
-                    }
-                    continue;
-                }
-            }
-
-            let placeholder_lines = if let Some(url) = attachment.content_url.as_deref() {
-                if app.is_image_preview_pending(url) {
-                    image_preview_card_lines(name, "loading preview…")
-                } else {
-                    image_preview_card_lines(name, "preview unavailable")
-                }
-            } else {
-                image_preview_card_lines(name, "preview unavailable")
-            };
-
-            for preview_line in placeholder_lines {
-                lines.push(Line::from(vec![
-                    Span::raw("  "),
-                    Span::styled(preview_line, Style::default().fg(Color::DarkGray)),
-                ]));
-            }
+            lines.extend(image_preview_block(app, name, attachment.content_url.as_deref()));
         }
 
         // File attachments
@@ -549,6 +761,13 @@ fn draw_messages(
             lines.push(Line::from(att_spans));
         }
 
+        // Cards (Adaptive, hero/thumbnail, connector)
+        let mut action_offset = 0;
+        let selected_action = if is_selected { app.selected_card_action } else { None };
+        for card in msg.cards() {
+            lines.extend(card_lines(&card, &mut action_offset, selected_action));
+        }
+
         // Reactions
         let reactions = msg.reactions_summary();
         if !reactions.is_empty() {
@@ -569,10 +788,215 @@ fn draw_messages(
     let visible_height = inner.height as usize;
     let total_lines = lines.len();
     let max_scroll = total_lines.saturating_sub(visible_height);
-    let scroll = max_scroll.saturating_sub(scroll_offset.min(max_scroll));
+    let mut scroll = max_scroll.saturating_sub(scroll_offset.min(max_scroll));
+    // Bring the selected message (or else the "new" divider) near the top,
+    // keeping one line of context
+    let new_offset = match selected_line.or(unread_line).filter(|_| scroll_to_selected) {
+        Some(line) => {
+            scroll = line.saturating_sub(1).min(max_scroll);
+            Some(max_scroll - scroll)
+        }
+        None => None,
+    };
 
     let paragraph = Paragraph::new(Text::from(lines)).scroll((scroll as u16, 0));
     frame.render_widget(paragraph, inner);
+    new_offset
+}
+
+/// Compact centered line for system events (member added, call ended, ...)
+fn event_line(summary: &str, time: &str) -> Line<'static> {
+    let text = if time.is_empty() {
+        format!("── {} ──", summary)
+    } else {
+        format!("── {} · {} ──", summary, time)
+    };
+    Line::from(Span::styled(
+        text,
+        Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+    ))
+    .alignment(Alignment::Center)
+}
+
+/// Render a card as a left-bordered block. Action buttons are numbered across all cards
+/// of a message via `action_offset` so the selected one can be highlighted.
+fn card_lines(card: &Card, action_offset: &mut usize, selected_action: Option<usize>) -> Vec<Line<'static>> {
+    let border_style = Style::default().fg(Color::Blue);
+    let mut lines = vec![Line::from(vec![
+        Span::raw("  "),
+        Span::styled(format!("╭─ {}", card.kind), border_style),
+    ])];
+
+    for card_line in &card.lines {
+        let mut spans = vec![Span::raw("  "), Span::styled("│ ", border_style)];
+        match card_line {
+            CardLine::Text { text, emphasis } => {
+                let style = match emphasis {
+                    TextEmphasis::Heading => Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+                    TextEmphasis::Normal => Style::default().fg(Color::White),
+                    TextEmphasis::Subtle => Style::default().fg(Color::DarkGray),
+                };
+                spans.push(Span::styled(text.clone(), style));
+            }
+            CardLine::Fact { title, value } => {
+                spans.push(Span::styled(
+                    format!("{}: ", title),
+                    Style::default().fg(Color::Gray).add_modifier(Modifier::BOLD),
+                ));
+                spans.push(Span::styled(value.clone(), Style::default().fg(Color::White)));
+            }
+            CardLine::Image { alt } => {
+                spans.push(Span::styled(format!("[🖼 {}]", alt), Style::default().fg(Color::Magenta)));
+            }
+        }
+        lines.push(Line::from(spans));
+    }
+
+    if !card.actions.is_empty() {
+        let mut spans = vec![Span::raw("  "), Span::styled("│ ", border_style)];
+        for action in &card.actions {
+            let (label, mut style) = match action {
+                CardAction::OpenUrl { title, .. } => (
+                    format!("[ {} ↗ ]", title),
+                    Style::default().fg(Color::Cyan),
+                ),
+                CardAction::Unsupported { title, .. } => (
+                    format!("[ {} · unsupported ]", title),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            };
+            if selected_action == Some(*action_offset) {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            spans.push(Span::styled(label, style));
+            spans.push(Span::raw(" "));
+            *action_offset += 1;
+        }
+        lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::from(vec![Span::raw("  "), Span::styled("╰─", border_style)]));
+    lines
+}
+
+/// Render a code block as a bordered, line-preserving box with syntax highlighting.
+/// Lines wider than `max_width` are cut and marked with a trailing `…`.
+fn code_block_lines(language: Option<&str>, code: &str, max_width: usize) -> Vec<Line<'static>> {
+    let border_style = Style::default().fg(Color::DarkGray);
+    let highlighted = highlight::highlight_code(language, code);
+    let label = language
+        .map(|l| highlight::normalize_language(l).unwrap_or(l).to_string())
+        .unwrap_or_else(|| "code".to_string());
+
+    let longest = highlighted
+        .iter()
+        .map(|tokens| tokens.iter().map(|(_, t)| t.chars().count()).sum::<usize>())
+        .max()
+        .unwrap_or(0);
+    let min_width = label.chars().count() + 6;
+    let box_width = (longest + 4).max(min_width).min(max_width.max(min_width));
+    let text_width = box_width.saturating_sub(4);
+
+    let mut lines = Vec::with_capacity(highlighted.len() + 2);
+    lines.push(Line::from(vec![
+        Span::styled("┌─ ", border_style),
+        Span::styled(label.clone(), Style::default().fg(Color::Cyan)),
+        Span::styled(
+            format!(" {}┐", "─".repeat(box_width.saturating_sub(label.chars().count() + 5))),
+            border_style,
+        ),
+    ]));
+
+    for tokens in highlighted {
+        let mut spans = vec![Span::styled("│ ", border_style)];
+        let total: usize = tokens.iter().map(|(_, t)| t.chars().count()).sum();
+        let truncated = total > text_width;
+        let mut budget = if truncated { text_width.saturating_sub(1) } else { text_width };
+        let mut used = 0;
+        for (kind, text) in tokens {
+            if budget == 0 {
+                break;
+            }
+            let piece: String = text.chars().take(budget).collect();
+            let len = piece.chars().count();
+            budget -= len;
+            used += len;
+            spans.push(Span::styled(piece, code_token_style(kind)));
+        }
+        if truncated {
+            spans.push(Span::styled("…", Style::default().fg(Color::Yellow)));
+            used += 1;
+        }
+        spans.push(Span::raw(" ".repeat(text_width.saturating_sub(used))));
+        spans.push(Span::styled(" │", border_style));
+        lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::from(Span::styled(
+        format!("└{}┘", "─".repeat(box_width.saturating_sub(2))),
+        border_style,
+    )));
+    lines
+}
+
+fn code_token_style(kind: TokenKind) -> Style {
+    match kind {
+        TokenKind::Plain => Style::default().fg(Color::White),
+        TokenKind::Keyword => Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+        TokenKind::String => Style::default().fg(Color::Green),
+        TokenKind::Comment => Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+        TokenKind::Number => Style::default().fg(Color::Yellow),
+    }
+}
+
+/// Decoded preview lines for an image URL, or a placeholder card while loading / on failure
+fn image_preview_block(app: &App, name: &str, url: Option<&str>) -> Vec<Line<'static>> {
+    let status = match url.map(|u| (u, app.image_preview(u))) {
+        Some((_, Some(ImagePreview::Unavailable))) | None => "preview unavailable",
+        Some((_, Some(preview))) => return preview_lines(preview, "  "),
+        Some((u, None)) if app.is_image_preview_pending(u) => "loading preview…",
+        Some((_, None)) => "preview unavailable",
+    };
+    image_preview_card_lines(name, status)
+        .into_iter()
+        .map(|preview_line| {
+            Line::from(vec![
+                Span::raw("  "),
+                Span::styled(preview_line, Style::default().fg(Color::DarkGray)),
+            ])
+        })
+        .collect()
+}
+
+/// Convert a cell-based image rendering into lines, each prefixed with `indent`
+fn preview_lines(preview: &ImagePreview, indent: &'static str) -> Vec<Line<'static>> {
+    match preview {
+        ImagePreview::Grayscale(rows) => rows
+            .iter()
+            .map(|row| {
+                Line::from(vec![
+                    Span::raw(indent),
+                    Span::styled(row.clone(), Style::default().fg(Color::DarkGray)),
+                ])
+            })
+            .collect(),
+        ImagePreview::Truecolor(rows) => rows
+            .iter()
+            .map(|row| {
+                let mut spans = vec![Span::raw(indent)];
+                spans.extend(row.iter().map(|cell| {
+                    Span::styled(
+                        "▀",
+                        Style::default()
+                            .fg(Color::Rgb(cell.top[0], cell.top[1], cell.top[2]))
+                            .bg(Color::Rgb(cell.bottom[0], cell.bottom[1], cell.bottom[2])),
+                    )
+                }));
+                Line::from(spans)
+            })
+            .collect(),
+        ImagePreview::Unavailable => Vec::new(),
+    }
 }
 
 fn image_preview_card_lines(name: &str, status: &str) -> Vec<String> {
@@ -694,18 +1118,15 @@ fn draw_team_list(frame: &mut Frame, app: &App, area: Rect) {
         .enumerate()
         .map(|(i, team)| {
             let is_selected = i == app.selected_team;
-            let style = if is_selected {
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::White)
-            };
+            let unread = app.team_unread_count(&team.id);
+            let style = unread_item_style(is_selected, unread);
             let indicator = if is_selected { "▸ " } else { "  " };
-            ListItem::new(Line::from(vec![
+            let mut spans = vec![
                 Span::styled(indicator, style),
                 Span::styled(&team.display_name, style),
-            ]))
+            ];
+            spans.extend(unread_dot(unread));
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
@@ -713,6 +1134,31 @@ fn draw_team_list(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(list, area);
 }
 
+/// Selected items are cyan; unselected ones with unread activity are bold
+fn unread_item_style(is_selected: bool, unread: usize) -> Style {
+    if is_selected {
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD)
+    } else if unread > 0 {
+        Style::default()
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::White)
+    }
+}
+
+/// " ● 3" after a team or channel with unread messages
+fn unread_dot(unread: usize) -> Option<Span<'static>> {
+    (unread > 0).then(|| {
+        Span::styled(
+            format!(" ● {}", unread),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )
+    })
+}
+
 fn draw_channel_list(frame: &mut Frame, app: &App, area: Rect) {
     let is_active = app.teams_panel == TeamsPanel::ChannelList && app.view_mode == ViewMode::Teams;
     let border_color = if is_active { Color::Cyan } else { Color::DarkGray };
@@ -739,23 +1185,20 @@ fn draw_channel_list(frame: &mut Frame, app: &App, area: Rect) {
         .enumerate()
         .map(|(i, channel)| {
             let is_selected = i == app.selected_channel;
-            let style = if is_selected {
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::White)
-            };
+            let unread = app.channel_unread_count(&channel.id);
+            let style = unread_item_style(is_selected, unread);
             let indicator = if is_selected { "▸ " } else { "  " };
             let prefix = match channel.membership_type.as_deref() {
                 Some("private") => "🔒 ",
                 _ => "# ",
             };
-            ListItem::new(Line::from(vec![
+            let mut spans = vec![
                 Span::styled(indicator, style),
                 Span::styled(prefix, Style::default().fg(Color::DarkGray)),
                 Span::styled(&channel.display_name, style),
-            ]))
+            ];
+            spans.extend(unread_dot(unread));
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
@@ -848,12 +1291,17 @@ fn draw_channel_message_area(frame: &mut Frame, app: &mut App, area: Rect) {
         .alignment(Alignment::Center);
         frame.render_widget(hint, inner);
     } else {
-        draw_messages(
+        let scrolled = draw_messages(
             frame, app, &app.channel_messages, app.channel_scroll_offset,
             app.selected_channel_message,
             &title, app.teams_panel == TeamsPanel::ChannelMessages,
-            app.loading_more_messages && app.channel_messages_next_link.is_some(), chunks[0],
+            app.loading_more_messages && app.channel_messages_next_link.is_some(),
+            app.channel_unread_marker.as_deref(), app.scroll_to_selected, chunks[0],
         );
+        if let Some(offset) = scrolled {
+            app.channel_scroll_offset = offset;
+            app.scroll_to_selected = false;
+        }
     }
 
     if app.view_mode == ViewMode::Teams {
@@ -913,8 +1361,17 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
             add_shortcut("1/2", "Switch View", &mut spans);
             add_shortcut("Tab", "Switch Panel", &mut spans);
             match app.active_panel {
+                Panel::ChatList if app.chat_filter.editing => {
+                    add_shortcut("Enter", "Keep Filter", &mut spans);
+                    add_shortcut("Esc", "Clear Filter", &mut spans);
+                }
                 Panel::ChatList => {
                     add_shortcut("n", "New Chat", &mut spans);
+                    add_shortcut("i", "Filter", &mut spans);
+                    add_shortcut("u", "Unread Only", &mut spans);
+                    if !app.chat_sections.is_empty() {
+                        add_shortcut("Space", "Fold Section", &mut spans);
+                    }
                     add_shortcut("g", "Manage Chat", &mut spans);
                     add_shortcut("r", "Refresh", &mut spans);
                 }
@@ -923,9 +1380,26 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
                     if app.selected_message.is_some() {
                         add_shortcut("r", "Reply", &mut spans);
                         add_shortcut("e", "React", &mut spans);
-                        if app.selected_message_attachment_url().is_some() {
+                        add_shortcut(">", "Forward", &mut spans);
+                        if !app.selected_reaction_details().is_empty() {
+                            add_shortcut("E", "Who Reacted", &mut spans);
+                        }
+                        let pin_label = if app.selected_message_pin_id().is_some() { "Unpin" } else { "Pin" };
+                        add_shortcut("t", pin_label, &mut spans);
+                        if app.selected_card_action().is_some() {
+                            add_shortcut("Enter", "Run Action", &mut spans);
+                        } else if app.selected_message_attachment_url().is_some() {
                             add_shortcut("Enter", "Open Preview", &mut spans);
                         }
+                        if app.selected_message_download().is_some() {
+                            add_shortcut("S", "Save File", &mut spans);
+                        }
+                        if !app.selected_message_code_blocks().is_empty() {
+                            add_shortcut("y", "Copy Code", &mut spans);
+                        }
+                        if !app.selected_message_card_actions().is_empty() {
+                            add_shortcut("a", "Card Action", &mut spans);
+                        }
                         if app.is_own_selected_message() {
                             add_shortcut("w", "Edit", &mut spans);
                             add_shortcut("d", "Delete", &mut spans);
@@ -934,10 +1408,15 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
                         add_shortcut("e", "Add Reaction", &mut spans);
                         add_shortcut("r", "Refresh", &mut spans);
                     }
+                    if !app.selected_chat_pins().is_empty() {
+                        add_shortcut("P", "Pinned", &mut spans);
+                    }
                 }
                 Panel::Input => {}
             }
             add_shortcut("f", "Share File", &mut spans);
+            add_shortcut("F", "Files", &mut spans);
+            add_shortcut("C-v", "Paste Image", &mut spans);
             add_shortcut("/", "Search", &mut spans);
             add_shortcut("C-p", "Palette", &mut spans);
             add_shortcut("p", "Set Status", &mut spans);
@@ -961,7 +1440,21 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
                     if app.selected_channel_message.is_some() {
                         add_shortcut("r", "Reply", &mut spans);
                         add_shortcut("e", "React", &mut spans);
+                        add_shortcut(">", "Forward", &mut spans);
+                        if !app.selected_reaction_details().is_empty() {
+                            add_shortcut("E", "Who Reacted", &mut spans);
+                        }
+                        if app.selected_message_download().is_some() {
+                            add_shortcut("S", "Save File", &mut spans);
+                        }
+                        if !app.selected_message_code_blocks().is_empty() {
+                            add_shortcut("y", "Copy Code", &mut spans);
+                        }
+                        if !app.selected_message_card_actions().is_empty() {
+                            add_shortcut("a", "Card Action", &mut spans);
+                        }
                         if app.is_own_selected_channel_message() {
+                            add_shortcut("w", "Edit", &mut spans);
                             add_shortcut("d", "Delete", &mut spans);
                         }
                     } else {
@@ -970,7 +1463,11 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
                     }
                     add_shortcut("m", "Members", &mut spans);
                     add_shortcut("f", "Share File", &mut spans);
-                    if app.selected_channel_message.is_some()
+                    add_shortcut("F", "Files", &mut spans);
+                    add_shortcut("C-v", "Paste Image", &mut spans);
+                    if app.selected_channel_message.is_some() && app.selected_card_action().is_some() {
+                        add_shortcut("Enter", "Run Action", &mut spans);
+                    } else if app.selected_channel_message.is_some()
                         && app.selected_message_attachment_url().is_some()
                     {
                         add_shortcut("Enter", "Open Preview", &mut spans);
@@ -992,6 +1489,21 @@ fn draw_status_bar(frame: &mut Frame, app: &App, area: Rect) {
         }
     }
 
+    // Download progress takes precedence over the status message
+    if let Some(download) = &app.download {
+        spans.push(Span::styled(" │ ", sep_style));
+        spans.push(Span::styled(download.label(), Style::default().fg(Color::Cyan)));
+    }
+
+    // Undo toast for a message that was just deleted
+    if let Some(deleted) = app.undoable_delete() {
+        spans.push(Span::styled(" │ ", sep_style));
+        spans.push(Span::styled(
+            format!(" Message deleted · z to undo ({}s) ", deleted.seconds_left()),
+            Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ));
+    }
+
     // Append status message if any
     let max_status_len = (area.width as usize).saturating_sub(
         spans.iter().map(|s| s.content.len()).sum::<usize>() + 4,
@@ -1013,15 +1525,16 @@ fn draw_new_chat_dialog(frame: &mut Frame, app: &App) {
     let area = frame.area();
     let has_suggestions = !app.suggestions.is_empty();
     let dialog_height = if has_suggestions {
-        7 + app.suggestions.len().min(8) as u16
+        8 + app.suggestions.len().min(8) as u16
     } else {
-        7
+        8
     };
     let popup = centered_rect(60, dialog_height, area);
     frame.render_widget(Clear, popup);
 
+    let title = if app.new_chat_people.len() > 1 { " New Group Chat " } else { " New Chat " };
     let block = Block::default()
-        .title(" New Chat ")
+        .title(title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Cyan));
 
@@ -1039,15 +1552,38 @@ fn draw_new_chat_dialog(frame: &mut Frame, app: &App) {
         Style::default().fg(Color::White)
     };
 
+    let mut to_line = vec![Span::styled("To: ", Style::default().fg(Color::Gray))];
+    for person in &app.new_chat_people {
+        to_line.push(Span::styled(
+            format!("[{} ×]", person.display_name),
+            Style::default().fg(Color::Black).bg(Color::Cyan),
+        ));
+        to_line.push(Span::raw(" "));
+    }
+
+    let (topic_text, topic_style) = if app.new_chat_topic.is_empty() {
+        ("optional, group chats only", Style::default().fg(Color::DarkGray))
+    } else {
+        (app.new_chat_topic.as_str(), Style::default().fg(Color::White))
+    };
+    let label_style = |focused: bool| {
+        if focused {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        }
+    };
+
     let mut lines = vec![
-        Line::from(Span::styled(
-            "To:",
-            Style::default().fg(Color::Gray),
-        )),
+        Line::from(to_line),
         Line::from(vec![
-            Span::styled("> ", Style::default().fg(Color::Cyan)),
+            Span::styled("> ", label_style(!app.new_chat_topic_focus)),
             Span::styled(display_input, input_style),
         ]),
+        Line::from(vec![
+            Span::styled("Topic: ", label_style(app.new_chat_topic_focus)),
+            Span::styled(topic_text, topic_style),
+        ]),
     ];
 
     if has_suggestions {
@@ -1070,10 +1606,16 @@ fn draw_new_chat_dialog(frame: &mut Frame, app: &App) {
     }
 
     lines.push(Line::from(""));
-    let hint = if has_suggestions {
-        "↑↓: select  │  Enter: confirm  │  Esc: cancel"
+    let hint = if app.new_chat_topic_focus {
+        "Enter: create  │  Tab: people  │  Esc: cancel"
+    } else if has_suggestions {
+        "↑↓: select  │  Enter: add  │  Esc: cancel"
+    } else if !app.new_chat_input.is_empty() {
+        "Enter: add by email  │  Esc: cancel"
+    } else if !app.new_chat_people.is_empty() {
+        "Enter: create  │  Backspace: remove  │  Tab: topic  │  Esc: cancel"
     } else {
-        "Enter: create by email  │  Esc: cancel"
+        "Type a name or email  │  Esc: cancel"
     };
     lines.push(Line::from(Span::styled(
         hint,
@@ -1083,59 +1625,293 @@ fn draw_new_chat_dialog(frame: &mut Frame, app: &App) {
     let content = Paragraph::new(lines);
     frame.render_widget(content, inner);
 
-    let cursor_pos = app.new_chat_input[..app.new_chat_cursor].chars().count() as u16;
-    frame.set_cursor_position((inner.x + 2 + cursor_pos, inner.y + 1));
+    if app.new_chat_topic_focus {
+        let cursor_pos = app.new_chat_topic.chars().count() as u16;
+        frame.set_cursor_position((inner.x + 7 + cursor_pos, inner.y + 2));
+    } else {
+        let cursor_pos = app.new_chat_input[..app.new_chat_cursor].chars().count() as u16;
+        frame.set_cursor_position((inner.x + 2 + cursor_pos, inner.y + 1));
+    }
 }
 
-fn draw_reaction_picker(frame: &mut Frame, app: &App) {
-    let area = frame.area();
-    let popup = centered_rect(40, 5, area);
+/// `:shortcode` completions just above the focused composer
+fn draw_shortcode_popup(frame: &mut Frame, app: &App) {
+    let suggestions = app.shortcode_suggestions();
+    if suggestions.is_empty() {
+        return;
+    }
+    let input = match app.view_mode {
+        ViewMode::Chats => app.layout_areas.input,
+        ViewMode::Teams => app.layout_areas.channel_input,
+    };
+    let height = suggestions.len() as u16 + 2;
+    if input.y < height {
+        return;
+    }
+    let popup = Rect {
+        x: input.x + 1,
+        y: input.y - height,
+        width: 40.min(input.width.saturating_sub(2)),
+        height,
+    };
     frame.render_widget(Clear, popup);
 
     let block = Block::default()
-        .title(" React ")
+        .title(" Emoji · Tab to insert ")
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Yellow));
     let inner = block.inner(popup);
     frame.render_widget(block, popup);
 
-    let mut spans: Vec<Span> = vec![Span::raw(" ")];
-    for (i, (_, emoji)) in models::REACTION_TYPES.iter().enumerate() {
-        let style = if i == app.selected_reaction {
-            Style::default()
-                .fg(Color::Yellow)
-                .add_modifier(Modifier::BOLD | Modifier::REVERSED)
-        } else {
-            Style::default().fg(Color::White)
+    let selected = app.shortcode_selected.min(suggestions.len() - 1);
+    let lines: Vec<Line> = suggestions
+        .iter()
+        .enumerate()
+        .map(|(i, e)| {
+            let style = if i == selected {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            Line::from(Span::styled(format!(" {} :{}:", e.emoji, e.shortcode), style))
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+fn draw_reaction_picker(frame: &mut Frame, app: &App) {
+    const GRID_ROWS: usize = 6;
+    let area = frame.area();
+    let popup = centered_rect(60, 16, area);
+    frame.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .title(" React ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+
+    let picker = &app.emoji_picker;
+    let mut lines: Vec<Line> = Vec::new();
+
+    // Show which message is selected
+    let selected = match app.view_mode {
+        ViewMode::Chats => app.selected_message.and_then(|idx| app.messages.get(idx)),
+        ViewMode::Teams => app
+            .selected_channel_message
+            .and_then(|idx| app.channel_messages.get(idx)),
+    };
+    if let Some(msg) = selected {
+        let preview: String = msg.content_text().chars().take(40).collect();
+        lines.push(Line::from(Span::styled(
+            format!("On: {}…", preview),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    lines.push(Line::from(vec![
+        Span::styled("🔍 ", Style::default().fg(Color::Yellow)),
+        if picker.query.is_empty() {
+            Span::styled("type to search emoji", Style::default().fg(Color::DarkGray))
+        } else {
+            Span::styled(picker.query.as_str(), Style::default().fg(Color::White))
+        },
+    ]));
+
+    // Category tabs: recently used first, dimmed while searching
+    let mut tabs: Vec<Span> = Vec::new();
+    let icons = std::iter::once("🕘").chain(emoji::Category::ALL.iter().map(|c| c.icon()));
+    for (i, icon) in icons.enumerate() {
+        let style = if !picker.query.is_empty() {
+            Style::default().fg(Color::DarkGray)
+        } else if i == picker.tab {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default()
         };
-        spans.push(Span::styled(format!(" {} ", emoji), style));
-        spans.push(Span::raw(" "));
+        tabs.push(Span::styled(format!(" {} ", icon), style));
     }
+    tabs.push(Span::styled(
+        format!("  {}", picker.tab_title()),
+        Style::default().fg(Color::Cyan),
+    ));
+    lines.push(Line::from(tabs));
+    lines.push(Line::from(""));
 
-    let mut lines = vec![
-        Line::from(spans),
-        Line::from(""),
-        Line::from(Span::styled(
-            "←→: select  │  Enter: react  │  Esc: cancel",
+    // Emoji grid, scrolled so the selected row stays visible. Reactions the
+    // user already applied are underlined in green and toggle off.
+    let items = app.emoji_picker_items();
+    let columns = emoji::PICKER_COLUMNS;
+    let selected_row = picker.selected / columns;
+    let first_row = selected_row.saturating_sub(GRID_ROWS - 1);
+    if items.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No emoji match your search",
             Style::default().fg(Color::DarkGray),
-        )),
-    ];
-
-    // Show which message is selected
-    if let Some(idx) = app.selected_message {
-        if let Some(msg) = app.messages.get(idx) {
-            let preview: String = msg.content_text().chars().take(30).collect();
-            lines.insert(0, Line::from(Span::styled(
-                format!("On: {}…", preview),
-                Style::default().fg(Color::DarkGray),
-            )));
+        )));
+    }
+    for (row, chunk) in items.chunks(columns).enumerate().skip(first_row).take(GRID_ROWS) {
+        let mut spans: Vec<Span> = vec![Span::raw(" ")];
+        for (col, item) in chunk.iter().enumerate() {
+            let mut style = if app.selected_message_has_my_reaction(item) {
+                Style::default().fg(Color::Green).add_modifier(Modifier::UNDERLINED)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            if row * columns + col == picker.selected {
+                style = style.fg(Color::Yellow).add_modifier(Modifier::BOLD | Modifier::REVERSED);
+            }
+            spans.push(Span::styled(format!(" {} ", item), style));
         }
+        lines.push(Line::from(spans));
     }
+    let shown_rows = items.len().div_ceil(columns).clamp(1, GRID_ROWS);
+    for _ in shown_rows..GRID_ROWS {
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(""));
+
+    let current = picker.selected_emoji(&app.recent_emojis);
+    let name = current
+        .as_deref()
+        .and_then(emoji::lookup)
+        .map(|e| format!(":{}:", e.shortcode))
+        .unwrap_or_default();
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!(" {} {}", current.as_deref().unwrap_or(""), name),
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!("   Tone: {}", emoji::SKIN_TONE_SWATCHES[picker.skin_tone]),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]));
+
+    let selected_is_mine = current
+        .as_deref()
+        .map(|e| app.selected_message_has_my_reaction(e))
+        .unwrap_or(false);
+    let hint = if selected_is_mine {
+        "←↑↓→: select  │  Tab: category  │  ^T: skin tone  │  Enter: remove yours  │  Esc: cancel"
+    } else {
+        "←↑↓→: select  │  Tab: category  │  ^T: skin tone  │  Enter: react  │  Esc: cancel"
+    };
+    lines.push(Line::from(Span::styled(hint, Style::default().fg(Color::DarkGray))));
 
     let content = Paragraph::new(lines);
     frame.render_widget(content, inner);
 }
 
+/// One-line strip above the chat showing the most recent pin
+fn draw_pinned_strip(frame: &mut Frame, app: &App, area: Rect) {
+    let pins = app.selected_chat_pins();
+    let latest = pins
+        .first()
+        .and_then(|p| p.message.as_ref())
+        .map(|m| format!("{}: {}", m.sender_name(), m.content_text()))
+        .unwrap_or_default();
+    let count = if pins.len() == 1 {
+        "1 pinned".to_string()
+    } else {
+        format!("{} pinned", pins.len())
+    };
+    let strip = Paragraph::new(Line::from(vec![
+        Span::styled(format!(" 📌 {} ", count), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        Span::styled(latest, Style::default().fg(Color::Gray)),
+        Span::styled("  (P to view)", Style::default().fg(Color::DarkGray)),
+    ]));
+    frame.render_widget(strip, area);
+}
+
+fn draw_pinned_messages(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+    let pins = app.selected_chat_pins();
+    let height = (pins.len() as u16 * 2 + 4).min(area.height.saturating_sub(4));
+    let popup = centered_rect(60, height, area);
+    frame.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .title(format!(" 📌 Pinned Messages ({}) ", pins.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+
+    let mut lines = Vec::new();
+    for (i, pin) in pins.iter().enumerate() {
+        let selected = i == app.pinned_selected;
+        let (sender, date, text) = match &pin.message {
+            Some(m) => (m.sender_name(), m.formatted_time(), m.content_text()),
+            None => ("Unknown".to_string(), String::new(), "(message unavailable)".to_string()),
+        };
+        let header_style = if selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(if selected { "▸ " } else { "  " }, header_style),
+            Span::styled(sender, header_style),
+            Span::styled(format!("  {}", date), Style::default().fg(Color::DarkGray)),
+        ]));
+        let preview: String = text.chars().take(inner.width.saturating_sub(4) as usize).collect();
+        lines.push(Line::from(Span::styled(
+            format!("    {}", preview),
+            Style::default().fg(Color::White),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "↑↓: select  │  Enter: jump to message  │  u: unpin  │  Esc: close",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    // Keep the selected pin visible when the list is taller than the popup
+    let visible = inner.height as usize;
+    let scroll = (app.pinned_selected * 2 + 2).saturating_sub(visible.saturating_sub(2));
+    frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), inner);
+}
+
+fn draw_reaction_details(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+    let details = app.selected_reaction_details();
+    let rows: usize = details.iter().map(|(_, names)| names.len() + 1).sum();
+    let popup = centered_rect(50, (rows as u16 + 4).min(area.height.saturating_sub(4)), area);
+    frame.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .title(" Reactions ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+
+    let mut lines = Vec::new();
+    for (emoji, names) in &details {
+        lines.push(Line::from(vec![
+            Span::raw(format!("{} ", emoji)),
+            Span::styled(
+                names.len().to_string(),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ),
+        ]));
+        for name in names {
+            lines.push(Line::from(Span::styled(
+                format!("   {}", name),
+                Style::default().fg(Color::White),
+            )));
+        }
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Esc: close",
+        Style::default().fg(Color::DarkGray),
+    )));
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
 fn draw_presence_picker(frame: &mut Frame, app: &App) {
     let area = frame.area();
     let height = models::PRESENCE_STATUSES.len() as u16 + 5;
@@ -1183,8 +1959,37 @@ fn draw_presence_picker(frame: &mut Frame, app: &App) {
 }
 
 fn draw_settings_dialog(frame: &mut Frame, app: &App) {
+    let refresh_secs = app.refresh_interval.as_secs();
+    let image_rendering = if app.image_protocol_setting == app.image_protocol.as_str() {
+        app.image_protocol_setting.clone()
+    } else {
+        format!("{} ({})", app.image_protocol_setting, app.image_protocol.as_str())
+    };
+    let items: Vec<(&str, String)> = vec![
+        ("Refresh interval (seconds)", refresh_secs.to_string()),
+        ("Image rendering (auto/kitty/iterm2/sixel/truecolor/grayscale)", image_rendering),
+        (
+            "Downloads folder",
+            if app.download_dir.is_empty() {
+                "(system Downloads)".to_string()
+            } else {
+                app.download_dir.clone()
+            },
+        ),
+        (
+            "Open files after download (on/off)",
+            if app.open_after_download { "on" } else { "off" }.to_string(),
+        ),
+        ("Mark chats read (view / seconds / manual)", app.mark_read_mode.label()),
+        (
+            "Confirm before deleting messages (on/off)",
+            if app.confirm_delete { "on" } else { "off" }.to_string(),
+        ),
+    ];
+
     let area = frame.area();
-    let popup = centered_rect(50, 10, area);
+    // items + edit line + blank + hint + borders
+    let popup = centered_rect(60, items.len() as u16 + 5, area);
     frame.render_widget(Clear, popup);
 
     let block = Block::default()
@@ -1194,11 +1999,6 @@ fn draw_settings_dialog(frame: &mut Frame, app: &App) {
     let inner = block.inner(popup);
     frame.render_widget(block, popup);
 
-    let refresh_secs = app.refresh_interval.as_secs();
-    let items: Vec<(&str, String)> = vec![
-        ("Refresh interval (seconds)", refresh_secs.to_string()),
-    ];
-
     let mut lines = Vec::new();
     for (i, (label, value)) in items.iter().enumerate() {
         let is_selected = i == app.selected_setting;
@@ -1319,84 +2119,457 @@ fn draw_command_palette(frame: &mut Frame, app: &App) {
     frame.set_cursor_position((inner.x + 2 + cursor_pos, inner.y));
 }
 
-fn draw_file_picker(frame: &mut Frame, app: &App) {
+fn draw_forward_dialog(frame: &mut Frame, app: &App) {
     let area = frame.area();
-    let has_error = app.file_upload_error.is_some();
-    let dialog_height = if app.file_uploading {
-        8
-    } else if has_error {
-        9
-    } else {
-        8
+    let visible_count = app.palette_filtered.len().min(10);
+    let dialog_height = match app.forward_stage {
+        ForwardStage::Target => 8 + visible_count as u16,
+        ForwardStage::Comment => 10,
     };
-    let popup = centered_rect(65, dialog_height.min(area.height.saturating_sub(4)), area);
+    let popup = centered_rect(60, dialog_height.min(area.height.saturating_sub(4)), area);
     frame.render_widget(Clear, popup);
 
     let block = Block::default()
-        .title(" Share File ")
+        .title(" Forward Message ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Green));
-
+        .border_style(Style::default().fg(Color::Magenta));
     let inner = block.inner(popup);
     frame.render_widget(block, popup);
 
-    let display_input = if app.file_path_input.is_empty() {
-        "Enter file path…"
+    let max_len = inner.width.saturating_sub(4) as usize;
+    let truncate = |text: &str| -> String {
+        if text.chars().count() > max_len {
+            text.chars().take(max_len.saturating_sub(1)).collect::<String>() + "…"
+        } else {
+            text.to_string()
+        }
+    };
+
+    // Quoted preview of the message being forwarded
+    let mut lines = Vec::new();
+    if let Some(ref message) = app.forward_message {
+        lines.push(Line::from(vec![
+            Span::styled("│ ", Style::default().fg(Color::DarkGray)),
+            Span::styled(message.sender_name(), Style::default().fg(Color::Cyan)),
+            Span::styled(
+                format!("  {}", message.formatted_date_time()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("│ ", Style::default().fg(Color::DarkGray)),
+            Span::styled(truncate(&message.content_text()), Style::default().fg(Color::Gray)),
+        ]));
+        let files = message.file_attachments().len();
+        if files > 0 {
+            lines.push(Line::from(Span::styled(
+                format!("│ 📎 {} file(s) attached", files),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+    }
+    lines.push(Line::from(""));
+
+    let input_row;
+    let (input, cursor) = match app.forward_stage {
+        ForwardStage::Target => {
+            let display_input = if app.palette_input.is_empty() {
+                "Type to search chats and channels…"
+            } else {
+                &app.palette_input
+            };
+            let input_style = if app.palette_input.is_empty() {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            input_row = lines.len();
+            lines.push(Line::from(vec![
+                Span::styled("To: ", Style::default().fg(Color::Magenta)),
+                Span::styled(display_input, input_style),
+            ]));
+            for (vi, &idx) in app.palette_filtered.iter().take(10).enumerate() {
+                if let Some(item) = app.palette_items.get(idx) {
+                    let is_selected = vi == app.palette_selected;
+                    let indicator = if is_selected { "▸ " } else { "  " };
+                    let name_style = if is_selected {
+                        Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::White)
+                    };
+                    lines.push(Line::from(vec![
+                        Span::styled(indicator, name_style),
+                        Span::raw(format!("{} ", item.icon)),
+                        Span::styled(truncate(&item.label), name_style),
+                    ]));
+                }
+            }
+            if app.palette_filtered.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "No matches",
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            (&app.palette_input, app.palette_cursor)
+        }
+        ForwardStage::Comment => {
+            if let Some(ref target) = app.forward_target {
+                lines.push(Line::from(vec![
+                    Span::styled("To: ", Style::default().fg(Color::Magenta)),
+                    Span::raw(format!("{} ", target.icon)),
+                    Span::styled(truncate(&target.label), Style::default().fg(Color::White)),
+                ]));
+            }
+            input_row = lines.len();
+            let (text, style) = if app.forward_comment.is_empty() {
+                ("Add a comment (optional)…", Style::default().fg(Color::DarkGray))
+            } else {
+                (app.forward_comment.as_str(), Style::default().fg(Color::White))
+            };
+            lines.push(Line::from(vec![
+                Span::styled("> ", Style::default().fg(Color::Magenta)),
+                Span::styled(text, style),
+            ]));
+            if app.forward_sending {
+                lines.push(Line::from(Span::styled(
+                    "⏳ Forwarding…",
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+            (&app.forward_comment, app.forward_comment_cursor)
+        }
+    };
+
+    lines.push(Line::from(""));
+    let hint = match app.forward_stage {
+        ForwardStage::Target => "↑↓: select  │  Enter: choose  │  Esc: cancel",
+        ForwardStage::Comment => "Enter: forward  │  Esc: back",
+    };
+    lines.push(Line::from(Span::styled(hint, Style::default().fg(Color::DarkGray))));
+    frame.render_widget(Paragraph::new(lines), inner);
+
+    let prefix = match app.forward_stage {
+        ForwardStage::Target => 4,
+        ForwardStage::Comment => 2,
+    };
+    let cursor_pos = input[..cursor].chars().count() as u16;
+    frame.set_cursor_position((inner.x + prefix + cursor_pos, inner.y + input_row as u16));
+}
+
+fn draw_file_picker(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+    let popup = centered_rect(70, 24.min(area.height.saturating_sub(4)), area);
+    frame.render_widget(Clear, popup);
+
+    let title = if app.file_picker_marked.is_empty() {
+        " Share File ".to_string()
     } else {
-        &app.file_path_input
+        format!(" Share Files ({} selected) ", app.file_picker_marked.len())
     };
-    let input_style = if app.file_path_input.is_empty() {
-        Style::default().fg(Color::DarkGray)
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Green));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // path / input
+            Constraint::Min(3),    // listing
+            Constraint::Length(4), // details, selection, status, hint
+        ])
+        .split(inner);
+
+    // Path line
+    if app.file_picker_typing {
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled("> ", Style::default().fg(Color::Green)),
+                Span::styled(app.file_path_input.as_str(), Style::default().fg(Color::White)),
+            ])),
+            chunks[0],
+        );
     } else {
-        Style::default().fg(Color::White)
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                format!("📁 {}", app.file_picker_dir.display()),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            )),
+            chunks[0],
+        );
+    }
+
+    // Directory listing, scrolled to keep the selection visible
+    let list_area = chunks[1];
+    let visible = list_area.height as usize;
+    let offset = app
+        .file_picker_selected
+        .saturating_sub(visible.saturating_sub(1));
+    let name_width = (list_area.width as usize).saturating_sub(16);
+    let mut lines = Vec::new();
+    if app.file_picker_entries.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  (empty folder)",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    for (i, entry) in app
+        .file_picker_entries
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(visible)
+    {
+        let is_selected = i == app.file_picker_selected && !app.file_picker_typing;
+        let marked = app.file_picker_is_marked(&entry.path);
+        let indicator = if is_selected { "▸ " } else { "  " };
+        let mark = if marked { "✓ " } else { "  " };
+        let mut name = if entry.is_dir {
+            format!("{}/", entry.name)
+        } else {
+            entry.name.clone()
+        };
+        if name.chars().count() > name_width {
+            name = name.chars().take(name_width.saturating_sub(1)).collect::<String>() + "…";
+        }
+        let style = if is_selected {
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else if entry.is_dir {
+            Style::default().fg(Color::Blue)
+        } else if marked {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let size = if entry.is_dir {
+            String::new()
+        } else {
+            downloads::format_bytes(entry.size)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(indicator, style),
+            Span::styled(mark, Style::default().fg(Color::Green)),
+            Span::styled(format!("{:<width$}", name, width = name_width), style),
+            Span::styled(format!("{:>10}", size), Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines), list_area);
+
+    // Footer: selected entry details, marked summary, status and hint
+    let mut footer = Vec::new();
+    let details = match app.file_picker_selected_entry() {
+        Some(entry) if entry.is_dir => "Folder".to_string(),
+        Some(entry) => {
+            let mut parts = vec![
+                files::describe_file_type(&entry.name).to_string(),
+                downloads::format_bytes(entry.size),
+            ];
+            if let Some(modified) = entry.modified {
+                parts.push(format!("modified {}", modified.format("%Y-%m-%d %H:%M")));
+            }
+            parts.join(" · ")
+        }
+        None => String::new(),
     };
+    footer.push(Line::from(Span::styled(details, Style::default().fg(Color::Gray))));
 
-    let mut lines = vec![
+    let marked_total: u64 = app
+        .file_picker_marked
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum();
+    footer.push(if app.file_picker_marked.is_empty() {
+        Line::from("")
+    } else {
         Line::from(Span::styled(
-            "📎 File path:",
-            Style::default().fg(Color::Gray),
-        )),
-        Line::from(vec![
-            Span::styled("> ", Style::default().fg(Color::Green)),
-            Span::styled(display_input, input_style),
-        ]),
-    ];
+            format!(
+                "✓ {} file(s) selected · {}",
+                app.file_picker_marked.len(),
+                downloads::format_bytes(marked_total)
+            ),
+            Style::default().fg(Color::Green),
+        ))
+    });
 
     if app.file_uploading {
-        lines.push(Line::from(""));
-        lines.push(Line::from(Span::styled(
-            "⏳ Uploading…",
-            Style::default().fg(Color::Yellow),
-        )));
+        let status = match app.file_upload_progress {
+            _ if app.is_file_upload_cancelling() => "⏳ Cancelling upload…".to_string(),
+            Some((sent, total)) => {
+                let pct = (sent * 100).checked_div(total).unwrap_or(100);
+                let bar_width = 20u64;
+                let filled = (pct * bar_width / 100) as usize;
+                format!(
+                    "⏳ Uploading [{}{}] {}% ({} / {})",
+                    "█".repeat(filled),
+                    "░".repeat(bar_width as usize - filled),
+                    pct,
+                    downloads::format_bytes(sent),
+                    downloads::format_bytes(total)
+                )
+            }
+            None => "⏳ Uploading…".to_string(),
+        };
+        footer.push(Line::from(Span::styled(status, Style::default().fg(Color::Yellow))));
     } else if let Some(ref err) = app.file_upload_error {
-        lines.push(Line::from(""));
         let max_len = inner.width.saturating_sub(4) as usize;
-        let truncated = if err.len() > max_len {
-            format!("{}…", &err[..max_len.saturating_sub(1)])
+        let truncated: String = if err.chars().count() > max_len {
+            err.chars().take(max_len.saturating_sub(1)).collect::<String>() + "…"
         } else {
             err.clone()
         };
-        lines.push(Line::from(Span::styled(
+        footer.push(Line::from(Span::styled(
             format!("⚠ {}", truncated),
             Style::default().fg(Color::Red),
         )));
+    } else {
+        footer.push(Line::from(""));
     }
 
-    lines.push(Line::from(""));
-    lines.push(Line::from(Span::styled(
-        "Max 4MB  │  Enter: upload & send  │  Esc: cancel",
-        Style::default().fg(Color::DarkGray),
-    )));
-
-    let content = Paragraph::new(lines);
-    frame.render_widget(content, inner);
+    let hint = if app.file_uploading {
+        "Esc: cancel upload"
+    } else if app.file_picker_typing {
+        "Tab: complete  │  Enter: open folder / upload  │  Esc: back to list"
+    } else {
+        "j/k: move  │  Enter: open/upload  │  Space: mark  │  u: upload  │  I: send inline  │  /: path  │  Esc: close"
+    };
+    footer.push(Line::from(Span::styled(hint, Style::default().fg(Color::DarkGray))));
+    frame.render_widget(Paragraph::new(footer), chunks[2]);
 
-    if !app.file_uploading {
+    if app.file_picker_typing && !app.file_uploading {
         let cursor_pos = app.file_path_input[..app.file_path_cursor]
             .chars()
             .count() as u16;
-        frame.set_cursor_position((inner.x + 2 + cursor_pos, inner.y + 1));
+        frame.set_cursor_position((chunks[0].x + 2 + cursor_pos, chunks[0].y));
+    }
+}
+
+fn draw_shared_files(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+    let popup = centered_rect(80, 24.min(area.height.saturating_sub(4)), area);
+    frame.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .title(format!(" Shared Files ({}) ", app.shared_files.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // sort / loading
+            Constraint::Min(3),    // listing
+            Constraint::Length(3), // details, status, hint
+        ])
+        .split(inner);
+
+    let mut header = vec![Span::styled(
+        format!("Sorted by {}", app.shared_files_sort.label()),
+        Style::default().fg(Color::Gray),
+    )];
+    if app.shared_files_loading.is_some() {
+        header.push(Span::styled(
+            "  ⏳ Loading channel files…",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(header)), chunks[0]);
+
+    // Listing, scrolled to keep the selection visible
+    let list_area = chunks[1];
+    let visible = list_area.height as usize;
+    let offset = app
+        .shared_files_selected
+        .saturating_sub(visible.saturating_sub(1));
+    let sender_width = 18usize;
+    let date_width = 16usize;
+    let name_width = (list_area.width as usize).saturating_sub(sender_width + date_width + 8);
+    let truncate = |text: &str, width: usize| -> String {
+        if text.chars().count() > width {
+            text.chars().take(width.saturating_sub(1)).collect::<String>() + "…"
+        } else {
+            text.to_string()
+        }
+    };
+    let mut lines = Vec::new();
+    if app.shared_files.is_empty() && app.shared_files_loading.is_none() {
+        lines.push(Line::from(Span::styled(
+            "  No files shared in the loaded messages",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    for (i, file) in app
+        .shared_files
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(visible)
+    {
+        let is_selected = i == app.shared_files_selected;
+        let indicator = if is_selected { "▸ " } else { "  " };
+        let icon = match file.source {
+            models::SharedFileSource::Message => "📎 ",
+            models::SharedFileSource::ChannelFolder => "📂 ",
+        };
+        let style = if is_selected {
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(indicator, style),
+            Span::raw(icon),
+            Span::styled(
+                format!("{:<width$}", truncate(&file.name, name_width), width = name_width),
+                style,
+            ),
+            Span::styled(
+                format!(" {:<width$}", truncate(&file.sender, sender_width), width = sender_width),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::styled(
+                format!(" {:>width$}", file.date_label(), width = date_width),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
     }
+    frame.render_widget(Paragraph::new(lines), list_area);
+
+    let mut footer = Vec::new();
+    let details = match app.selected_shared_file() {
+        Some(file) => {
+            let mut parts = vec![files::describe_file_type(&file.name).to_string()];
+            if let Some(size) = file.size.filter(|s| *s >= 0) {
+                parts.push(downloads::format_bytes(size as u64));
+            }
+            parts.push(
+                match file.source {
+                    models::SharedFileSource::Message => "shared in a message",
+                    models::SharedFileSource::ChannelFolder => "channel files",
+                }
+                .to_string(),
+            );
+            parts.join(" · ")
+        }
+        None => String::new(),
+    };
+    footer.push(Line::from(Span::styled(details, Style::default().fg(Color::Gray))));
+    footer.push(match app.shared_files_error {
+        Some(ref err) => Line::from(Span::styled(
+            format!("⚠ Channel files unavailable: {}", truncate(err, inner.width.saturating_sub(32) as usize)),
+            Style::default().fg(Color::Red),
+        )),
+        None => Line::from(""),
+    });
+    footer.push(Line::from(Span::styled(
+        "↑↓: select  │  Enter: open  │  S: download  │  c: copy link  │  s: sort  │  Esc: close",
+        Style::default().fg(Color::DarkGray),
+    )));
+    frame.render_widget(Paragraph::new(footer), chunks[2]);
 }
 
 fn draw_search_dialog(frame: &mut Frame, app: &App) {
@@ -1686,6 +2859,33 @@ fn tab_span(label: &str, active: bool) -> Span<'_> {
     }
 }
 
+fn draw_delete_confirm(frame: &mut Frame, app: &App) {
+    let area = frame.area();
+    let popup = centered_rect(50, 6, area);
+    frame.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .title(" Delete Message ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+
+    let preview = app.delete_confirm_preview();
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("\"{}\"", preview),
+            Style::default().fg(Color::Gray).add_modifier(Modifier::ITALIC),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            "y / Enter: delete  │  n / Esc: cancel",
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
 fn draw_error_dialog(frame: &mut Frame, info: &crate::app::ErrorInfo) {
     let area = frame.area();
 
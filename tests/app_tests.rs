@@ -49,6 +49,7 @@ mod chat_selection {
                 members: None,
                 last_message_preview: None,
                 unread_message_count: None,
+                viewpoint: None,
             })
             .collect()
     }
@@ -312,13 +313,23 @@ mod new_chat_mode_tests {
     }
 
     #[test]
-    fn take_new_chat_input_exits_mode() {
+    fn take_new_chat_exits_mode() {
         let mut app = App::new();
         app.enter_new_chat_mode();
-        app.new_chat_insert_char('x');
-        let input = app.take_new_chat_input();
-        assert_eq!(input, "x");
+        assert!(app.take_new_chat().is_none());
+        assert!(app.new_chat_mode);
+        for c in "bob@test.com".chars() {
+            app.new_chat_insert_char(c);
+        }
+        assert!(app.add_typed_person());
+        app.toggle_new_chat_field();
+        app.new_chat_insert_char('Q');
+        let (people, topic) = app.take_new_chat().unwrap();
+        assert_eq!(people.len(), 1);
+        assert_eq!(people[0].email, "bob@test.com");
+        assert_eq!(topic, "Q");
         assert!(!app.new_chat_mode);
+        assert!(app.new_chat_people.is_empty());
     }
 
     #[test]
@@ -356,24 +367,82 @@ mod new_chat_mode_tests {
         assert_eq!(app.selected_suggestion, 0);
     }
 
-    #[test]
-    fn select_suggestion_returns_email() {
-        let mut app = App::new();
-        app.enter_new_chat_mode();
-        app.suggestions = vec![UserSuggestion {
+    fn alice() -> UserSuggestion {
+        UserSuggestion {
             display_name: "Alice".into(),
             email: "alice@test.com".into(),
             id: "1".into(),
-        }];
-        let email = app.select_suggestion();
-        assert_eq!(email, Some("alice@test.com".to_string()));
-        assert!(!app.new_chat_mode);
+        }
+    }
+
+    #[test]
+    fn add_selected_suggestion_adds_chip() {
+        let mut app = App::new();
+        app.enter_new_chat_mode();
+        app.new_chat_insert_char('a');
+        app.new_chat_insert_char('l');
+        app.suggestions = vec![alice()];
+        assert!(app.add_selected_suggestion());
+        assert_eq!(app.new_chat_people, vec![alice()]);
+        assert!(app.new_chat_mode);
+        assert_eq!(app.new_chat_input, "");
+        assert!(app.suggestions.is_empty());
+    }
+
+    #[test]
+    fn add_selected_suggestion_false_when_empty() {
+        let mut app = App::new();
+        assert!(!app.add_selected_suggestion());
+    }
+
+    #[test]
+    fn duplicate_people_are_skipped() {
+        let mut app = App::new();
+        app.enter_new_chat_mode();
+        app.suggestions = vec![alice()];
+        app.add_selected_suggestion();
+        for c in "ALICE@test.com".chars() {
+            app.new_chat_insert_char(c);
+        }
+        assert!(app.add_typed_person());
+        assert_eq!(app.new_chat_people.len(), 1);
+    }
+
+    #[test]
+    fn typed_person_needs_an_email() {
+        let mut app = App::new();
+        app.enter_new_chat_mode();
+        app.new_chat_insert_char('x');
+        assert!(!app.add_typed_person());
+        assert!(app.new_chat_people.is_empty());
+        assert_eq!(app.new_chat_input, "x");
+    }
+
+    #[test]
+    fn backspace_on_empty_input_removes_last_chip() {
+        let mut app = App::new();
+        app.enter_new_chat_mode();
+        app.suggestions = vec![alice()];
+        app.add_selected_suggestion();
+        app.new_chat_insert_char('b');
+        app.new_chat_delete_char();
+        assert_eq!(app.new_chat_people.len(), 1);
+        app.new_chat_delete_char();
+        assert!(app.new_chat_people.is_empty());
     }
 
     #[test]
-    fn select_suggestion_none_when_empty() {
+    fn topic_field_takes_typing() {
         let mut app = App::new();
-        assert_eq!(app.select_suggestion(), None);
+        app.enter_new_chat_mode();
+        app.toggle_new_chat_field();
+        app.new_chat_insert_char('H');
+        app.new_chat_insert_char('i');
+        app.new_chat_delete_char();
+        assert_eq!(app.new_chat_topic, "H");
+        assert_eq!(app.new_chat_input, "");
+        app.toggle_new_chat_field();
+        assert!(!app.new_chat_topic_focus);
     }
 
     #[test]
@@ -397,6 +466,87 @@ mod new_chat_mode_tests {
     }
 }
 
+#[cfg(test)]
+mod new_chat_reuse_tests {
+    use ttyms::app::{App, ChatFilter, UserSuggestion};
+    use ttyms::models::{Chat, ChatMember, User};
+
+    fn member(user_id: &str, email: Option<&str>) -> ChatMember {
+        ChatMember {
+            id: None,
+            display_name: Some(user_id.to_string()),
+            user_id: Some(user_id.to_string()),
+            email: email.map(String::from),
+        }
+    }
+
+    fn chat(id: &str, chat_type: &str, members: Vec<ChatMember>) -> Chat {
+        Chat {
+            id: id.to_string(),
+            topic: None,
+            chat_type: chat_type.to_string(),
+            members: Some(members),
+            last_message_preview: None,
+            unread_message_count: None,
+            viewpoint: None,
+        }
+    }
+
+    fn person(id: &str, email: &str) -> UserSuggestion {
+        UserSuggestion {
+            display_name: id.to_string(),
+            email: email.to_string(),
+            id: id.to_string(),
+        }
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.current_user = Some(User {
+            id: "me".to_string(),
+            display_name: "Me".to_string(),
+            mail: None,
+            user_principal_name: None,
+        });
+        app.set_chats(vec![
+            chat("g1", "group", vec![member("me", None), member("bob", None)]),
+            chat("c1", "oneOnOne", vec![member("me", None), member("bob", Some("Bob@Test.com"))]),
+        ]);
+        app
+    }
+
+    #[test]
+    fn finds_existing_one_on_one_by_id() {
+        let app = app();
+        assert_eq!(app.find_one_on_one_chat(&person("bob", "x@test.com")), Some("c1".to_string()));
+        assert_eq!(app.find_one_on_one_chat(&person("carol", "carol@test.com")), None);
+    }
+
+    #[test]
+    fn finds_existing_one_on_one_by_email() {
+        let app = app();
+        assert_eq!(app.find_one_on_one_chat(&person("", "bob@test.com")), Some("c1".to_string()));
+    }
+
+    #[test]
+    fn does_not_match_self() {
+        let app = app();
+        assert_eq!(app.find_one_on_one_chat(&person("me", "me@test.com")), None);
+    }
+
+    #[test]
+    fn reveal_chat_clears_filter() {
+        let mut app = app();
+        app.selected_chat = 0;
+        app.chat_filter = ChatFilter { query: "zzz".to_string(), ..Default::default() };
+        app.apply_chat_prefs();
+        assert!(!app.chats.iter().any(|c| c.id == "c1"));
+        assert!(app.reveal_chat("c1"));
+        assert_eq!(app.selected_chat_id(), Some("c1"));
+        assert!(!app.chat_filter.is_active());
+    }
+}
+
 #[cfg(test)]
 mod refresh_tests {
     use ttyms::app::App;
@@ -603,25 +753,25 @@ mod message_selection_tests {
             Message {
                 id: "sys1".to_string(),
                 message_type: Some("systemEventMessage".to_string()),
-                body: None, from: None, created_date_time: None, reactions: None, attachments: vec![],
+                body: None, from: None, created_date_time: None, reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
             },
             Message {
                 id: "msg1".to_string(),
                 message_type: Some("message".to_string()),
                 body: Some(MessageBody { content: Some("First".to_string()), content_type: None }),
-                from: None, created_date_time: None, reactions: None, attachments: vec![],
+                from: None, created_date_time: None, reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
             },
             Message {
                 id: "msg2".to_string(),
                 message_type: Some("message".to_string()),
                 body: Some(MessageBody { content: Some("Second".to_string()), content_type: None }),
-                from: None, created_date_time: None, reactions: None, attachments: vec![],
+                from: None, created_date_time: None, reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
             },
             Message {
                 id: "msg3".to_string(),
                 message_type: Some("message".to_string()),
                 body: Some(MessageBody { content: Some("Third".to_string()), content_type: None }),
-                from: None, created_date_time: None, reactions: None, attachments: vec![],
+                from: None, created_date_time: None, reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
             },
         ]
     }
@@ -705,13 +855,33 @@ mod dialog_tests {
         app.messages = vec![ttyms::models::Message {
             id: "m1".to_string(),
             message_type: Some("message".to_string()),
-            body: None, from: None, created_date_time: None, reactions: None, attachments: vec![],
+            body: None, from: None, created_date_time: None, reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
         }];
         app.selected_message = Some(0);
         app.open_reaction_picker();
         assert_eq!(app.dialog, DialogMode::ReactionPicker);
     }
 
+    #[test]
+    fn reaction_picker_starts_on_recent_tab_with_saved_skin_tone() {
+        let mut app = App::new();
+        app.messages = vec![ttyms::models::Message {
+            id: "m1".to_string(),
+            message_type: Some("message".to_string()),
+            body: None, from: None, created_date_time: None, reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
+        }];
+        app.selected_message = Some(0);
+        app.skin_tone = 4;
+        app.emoji_picker.query = "old".to_string();
+        app.record_recent_emoji("🎉");
+        app.record_recent_emoji("🚀");
+        app.open_reaction_picker();
+        assert!(app.emoji_picker.query.is_empty());
+        assert_eq!(app.emoji_picker.skin_tone, 4);
+        assert_eq!(app.emoji_picker_items(), vec!["🚀", "🎉"]);
+        assert_eq!(app.selected_picker_emoji().as_deref(), Some("🚀"));
+    }
+
     #[test]
     fn presence_picker_opens() {
         let mut app = App::new();
@@ -769,7 +939,24 @@ mod dialog_tests {
 
 #[cfg(test)]
 mod channel_input_tests {
-    use ttyms::app::App;
+    use ttyms::app::{App, ViewMode};
+
+    #[test]
+    fn composer_text_follows_view_and_restores_only_when_empty() {
+        let mut app = App::new();
+        app.view_mode = ViewMode::Teams;
+        app.channel_insert_char('x');
+        app.insert_char('c');
+        assert_eq!(app.take_composer_text(), "x");
+        assert_eq!(app.input, "c");
+
+        app.restore_composer_text("caption".to_string());
+        assert_eq!(app.channel_input, "caption");
+        assert_eq!(app.channel_input_cursor, 7);
+        // Newly typed text is not overwritten
+        app.restore_composer_text("old".to_string());
+        assert_eq!(app.channel_input, "caption");
+    }
 
     #[test]
     fn channel_insert_and_delete() {
@@ -820,18 +1007,18 @@ mod unread_tracking_tests {
     #[test]
     fn update_total_unread() {
         let mut app = App::new();
-        app.chats = vec![
+        app.all_chats = vec![
             Chat {
                 id: "c1".to_string(), topic: None, chat_type: "oneOnOne".to_string(),
-                members: None, last_message_preview: None, unread_message_count: Some(3),
+                members: None, last_message_preview: None, unread_message_count: Some(3), viewpoint: None,
             },
             Chat {
                 id: "c2".to_string(), topic: None, chat_type: "oneOnOne".to_string(),
-                members: None, last_message_preview: None, unread_message_count: Some(2),
+                members: None, last_message_preview: None, unread_message_count: Some(2), viewpoint: None,
             },
             Chat {
                 id: "c3".to_string(), topic: None, chat_type: "oneOnOne".to_string(),
-                members: None, last_message_preview: None, unread_message_count: None,
+                members: None, last_message_preview: None, unread_message_count: None, viewpoint: None,
             },
         ];
         app.update_total_unread();
@@ -843,7 +1030,7 @@ mod unread_tracking_tests {
         let mut app = App::new();
         app.messages = vec![Message {
             id: "m1".to_string(), message_type: None, body: None,
-            from: None, created_date_time: None, reactions: None, attachments: vec![],
+            from: None, created_date_time: None, reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
         }];
         assert!(!app.detect_new_messages()); // First time is init
     }
@@ -853,13 +1040,13 @@ mod unread_tracking_tests {
         let mut app = App::new();
         app.messages = vec![Message {
             id: "m1".to_string(), message_type: None, body: None,
-            from: None, created_date_time: None, reactions: None, attachments: vec![],
+            from: None, created_date_time: None, reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
         }];
         app.detect_new_messages(); // Initialize
 
         app.messages.push(Message {
             id: "m2".to_string(), message_type: None, body: None,
-            from: None, created_date_time: None, reactions: None, attachments: vec![],
+            from: None, created_date_time: None, reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
         });
         assert!(app.detect_new_messages());
     }
@@ -869,7 +1056,7 @@ mod unread_tracking_tests {
         let mut app = App::new();
         app.messages = vec![Message {
             id: "m1".to_string(), message_type: None, body: None,
-            from: None, created_date_time: None, reactions: None, attachments: vec![],
+            from: None, created_date_time: None, reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
         }];
         app.detect_new_messages();
         assert!(!app.detect_new_messages());
@@ -905,7 +1092,7 @@ mod cache_tests {
             body: None,
             from: None,
             created_date_time: None,
-            reactions: None, attachments: vec![],
+            reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
         }
     }
 
@@ -1041,9 +1228,10 @@ mod reply_tests {
                         display_name: Some("Alice".to_string()),
                         id: Some("alice-id".to_string()),
                     }),
+                    application: None,
                 }),
                 created_date_time: None,
-                reactions: None, attachments: vec![],
+                reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
             },
             Message {
                 id: "msg2".to_string(),
@@ -1054,9 +1242,10 @@ mod reply_tests {
                         display_name: Some("Me".to_string()),
                         id: Some("my-id".to_string()),
                     }),
+                    application: None,
                 }),
                 created_date_time: None,
-                reactions: None, attachments: vec![],
+                reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
             },
         ]
     }
@@ -1098,7 +1287,9 @@ mod reply_tests {
 
 #[cfg(test)]
 mod edit_tests {
-    use ttyms::app::{App, Panel};
+    use ttyms::app::{
+        App, DeletedMessage, DialogMode, MessageLocation, Panel, ViewMode, UNDO_DELETE_WINDOW,
+    };
     use ttyms::models::*;
 
     fn setup_app_with_own_message() -> App {
@@ -1119,9 +1310,10 @@ mod edit_tests {
                         display_name: Some("Alice".to_string()),
                         id: Some("alice-id".to_string()),
                     }),
+                    application: None,
                 }),
                 created_date_time: None,
-                reactions: None, attachments: vec![],
+                reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
             },
             Message {
                 id: "msg2".to_string(),
@@ -1132,9 +1324,10 @@ mod edit_tests {
                         display_name: Some("Me".to_string()),
                         id: Some("my-id".to_string()),
                     }),
+                    application: None,
                 }),
                 created_date_time: None,
-                reactions: None, attachments: vec![],
+                reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
             },
         ];
         app
@@ -1189,6 +1382,78 @@ mod edit_tests {
         let app = setup_app_with_own_message();
         assert!(!app.is_own_selected_message());
     }
+
+    fn channel(id: &str) -> Channel {
+        Channel { id: id.to_string(), display_name: id.to_string(), description: None, membership_type: None }
+    }
+
+    #[test]
+    fn start_channel_edit_on_own_message() {
+        let mut app = setup_app_with_own_message();
+        app.channel_messages = std::mem::take(&mut app.messages);
+        app.selected_channel_message = Some(1);
+        assert!(app.is_own_selected_channel_message());
+        app.start_channel_edit();
+        assert_eq!(app.editing_message_id, Some("msg2".to_string()));
+        assert_eq!(app.channel_input, "My message");
+    }
+
+    #[test]
+    fn channel_delete_undo_only_in_its_channel() {
+        let mut app = setup_app_with_own_message();
+        app.view_mode = ViewMode::Teams;
+        app.teams = vec![Team { id: "t1".to_string(), display_name: "T1".to_string(), description: None }];
+        app.channels = vec![channel("ch1"), channel("ch2")];
+        assert!(app.undoable_delete().is_none());
+        let location = MessageLocation::Channel { team_id: "t1".to_string(), channel_id: "ch1".to_string() };
+        app.deleted_message = Some(DeletedMessage::new(location, "msg2".to_string()));
+        assert_eq!(app.undoable_delete().map(|d| d.message_id.as_str()), Some("msg2"));
+        app.selected_channel = 1;
+        assert!(app.undoable_delete().is_none());
+        app.view_mode = ViewMode::Chats;
+        assert!(app.undoable_delete().is_none());
+    }
+
+    #[test]
+    fn chat_delete_undo_expires() {
+        let mut app = setup_app_with_own_message();
+        app.set_chats(vec![Chat {
+            id: "c1".to_string(),
+            topic: None,
+            chat_type: "group".to_string(),
+            members: None,
+            last_message_preview: None,
+            unread_message_count: None,
+            viewpoint: None,
+        }]);
+        let mut deleted = DeletedMessage::new(MessageLocation::Chat("c1".to_string()), "msg2".to_string());
+        assert_eq!(deleted.seconds_left(), UNDO_DELETE_WINDOW.as_secs());
+        app.deleted_message = Some(deleted.clone());
+        assert!(app.undoable_delete().is_some());
+        deleted.deleted_at -= UNDO_DELETE_WINDOW;
+        assert_eq!(deleted.seconds_left(), 0);
+        app.deleted_message = Some(deleted);
+        assert!(app.undoable_delete().is_none());
+    }
+
+    #[test]
+    fn deleted_messages_cannot_be_edited_or_deleted() {
+        let mut app = setup_app_with_own_message();
+        app.selected_message = Some(1);
+        app.messages[1].deleted_date_time = Some("2026-03-01T10:00:00Z".to_string());
+        assert!(!app.is_own_selected_message());
+    }
+
+    #[test]
+    fn delete_confirm_needs_a_selection() {
+        let mut app = setup_app_with_own_message();
+        app.open_delete_confirm();
+        assert_eq!(app.dialog, DialogMode::None);
+        app.selected_message = Some(1);
+        app.open_delete_confirm();
+        assert_eq!(app.dialog, DialogMode::ConfirmDelete);
+        assert_eq!(app.delete_confirm_preview(), "My message");
+    }
 }
 
 #[cfg(test)]
@@ -1203,7 +1468,7 @@ mod pagination_tests {
             body: Some(MessageBody { content: Some(format!("Msg {}", id)), content_type: None }),
             from: None,
             created_date_time: None,
-            reactions: None, attachments: vec![],
+            reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
         }
     }
 
@@ -1324,7 +1589,7 @@ mod channel_members_state {
 
 #[cfg(test)]
 mod delta_sync_tests {
-    use ttyms::app::App;
+    use ttyms::app::{App, ViewMode};
     use ttyms::models::*;
 
     fn make_message_with_time(id: &str, time: &str) -> Message {
@@ -1337,7 +1602,7 @@ mod delta_sync_tests {
             }),
             from: None,
             created_date_time: Some(time.to_string()),
-            reactions: None, attachments: vec![],
+            reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
         }
     }
 
@@ -1426,6 +1691,57 @@ mod delta_sync_tests {
             "https://graph.microsoft.com/delta?token=abc"
         );
     }
+
+    fn open_channel(app: &mut App, messages: Vec<Message>) {
+        app.view_mode = ViewMode::Teams;
+        app.channels = vec![Channel {
+            id: "ch1".to_string(),
+            display_name: "General".to_string(),
+            description: None,
+            membership_type: None,
+        }];
+        app.channel_message_cache.insert("ch1".to_string(), messages.clone());
+        app.channel_messages = messages;
+    }
+
+    #[test]
+    fn channel_merge_keeps_older_pages_and_selection() {
+        let mut app = App::new();
+        open_channel(&mut app, vec![make_message_with_time("m2", "2026-01-01T10:02:00Z")]);
+        // An older page loaded by scrolling up is not in the cache
+        app.channel_messages.insert(0, make_message_with_time("m1", "2026-01-01T10:00:00Z"));
+        app.selected_channel_message = Some(1);
+
+        let delta = vec![
+            make_message_with_time("m0", "2026-01-01T09:00:00Z"),
+            make_message_with_time("m3", "2026-01-01T10:03:00Z"),
+        ];
+        assert!(app.merge_channel_messages("ch1", delta));
+        let ids: Vec<&str> = app.channel_messages.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["m0", "m1", "m2", "m3"]);
+        assert_eq!(app.selected_channel_message, Some(2));
+        assert_eq!(app.channel_message_cache["ch1"].len(), 3);
+    }
+
+    #[test]
+    fn channel_merge_updates_in_place() {
+        let mut app = App::new();
+        open_channel(&mut app, vec![make_message_with_time("m1", "2026-01-01T10:00:00Z")]);
+        let mut edited = make_message_with_time("m1", "2026-01-01T10:00:00Z");
+        edited.body = Some(MessageBody { content: Some("Edited".to_string()), content_type: None });
+        assert!(!app.merge_channel_messages("ch1", vec![edited]));
+        assert_eq!(app.channel_messages[0].content_text(), "Edited");
+        assert_eq!(app.channel_message_cache["ch1"][0].content_text(), "Edited");
+    }
+
+    #[test]
+    fn other_channel_merge_only_touches_cache() {
+        let mut app = App::new();
+        open_channel(&mut app, vec![make_message_with_time("m1", "2026-01-01T10:00:00Z")]);
+        assert!(app.merge_channel_messages("ch2", vec![make_message_with_time("x1", "2026-01-01T10:00:00Z")]));
+        assert_eq!(app.channel_messages.len(), 1);
+        assert_eq!(app.channel_message_cache["ch2"].len(), 1);
+    }
 }
 
 #[cfg(test)]
@@ -1441,6 +1757,7 @@ mod search_tests {
             members: None,
             last_message_preview: None,
             unread_message_count: None,
+            viewpoint: None,
         }
     }
 
@@ -1540,6 +1857,7 @@ mod chat_manager_tests {
             members: None,
             last_message_preview: None,
             unread_message_count: None,
+            viewpoint: None,
         }
     }
 
@@ -1548,6 +1866,7 @@ mod chat_manager_tests {
             id: Some(membership_id.to_string()),
             display_name: Some(name.to_string()),
             user_id: Some(user_id.to_string()),
+            email: None,
         }
     }
 
@@ -1694,6 +2013,7 @@ mod command_palette_tests {
             members: None,
             last_message_preview: None,
             unread_message_count: None,
+            viewpoint: None,
         }
     }
 
@@ -1822,7 +2142,7 @@ mod command_palette_tests {
         app.palette_input = "design".to_string();
         app.palette_filter();
         // Should match "Design Review" only (plus no actions match "design")
-        assert!(app.palette_filtered.len() >= 1);
+        assert!(!app.palette_filtered.is_empty());
         let matched: Vec<_> = app
             .palette_filtered
             .iter()
@@ -1887,95 +2207,406 @@ mod command_palette_tests {
 }
 
 #[cfg(test)]
-mod file_picker_tests {
-    use ttyms::app::{App, DialogMode};
-
-    #[test]
-    fn open_file_picker_sets_dialog() {
-        let mut app = App::new();
-        app.open_file_picker();
-        assert!(matches!(app.dialog, DialogMode::FilePicker));
-        assert!(app.file_path_input.is_empty());
-        assert_eq!(app.file_path_cursor, 0);
-        assert!(!app.file_uploading);
-        assert!(app.file_upload_error.is_none());
-    }
+mod reaction_toggle_tests {
+    use ttyms::app::{App, DialogMode, ViewMode};
+    use ttyms::models::*;
 
-    #[test]
-    fn open_file_picker_clears_previous_state() {
-        let mut app = App::new();
-        app.file_path_input = "/old/path.txt".to_string();
-        app.file_path_cursor = 5;
-        app.file_uploading = true;
-        app.file_upload_error = Some("old error".to_string());
-        app.open_file_picker();
-        assert!(app.file_path_input.is_empty());
-        assert_eq!(app.file_path_cursor, 0);
-        assert!(!app.file_uploading);
-        assert!(app.file_upload_error.is_none());
+    fn make_message(sender: (&str, &str), reactions: Vec<(&str, &str)>) -> Message {
+        Message {
+            id: "m1".to_string(),
+            message_type: Some("message".to_string()),
+            body: None,
+            from: Some(MessageFrom {
+                user: Some(MessageUser {
+                    display_name: Some(sender.1.to_string()),
+                    id: Some(sender.0.to_string()),
+                }),
+                application: None,
+            }),
+            created_date_time: None,
+            reactions: Some(
+                reactions
+                    .into_iter()
+                    .map(|(emoji, uid)| ChatMessageReaction {
+                        reaction_type: emoji.to_string(),
+                        user: Some(ReactionIdentitySet {
+                            user: Some(MessageUser { display_name: None, id: Some(uid.to_string()) }),
+                        }),
+                    })
+                    .collect(),
+            ),
+            attachments: vec![],
+            event_detail: None,
+            deleted_date_time: None,
+        }
     }
 
-    #[test]
-    fn close_file_picker() {
-        let mut app = App::new();
-        app.open_file_picker();
-        app.close_dialog();
-        assert!(matches!(app.dialog, DialogMode::None));
+    fn signed_in(app: &mut App) {
+        app.current_user = Some(User {
+            id: "me".to_string(),
+            display_name: "Me Myself".to_string(),
+            mail: None,
+            user_principal_name: None,
+        });
     }
 
     #[test]
-    fn file_picker_insert_and_delete() {
+    fn detects_own_reaction_on_selected_channel_message() {
         let mut app = App::new();
-        app.open_file_picker();
-        for c in "/tmp/test.txt".chars() {
-            app.file_picker_insert_char(c);
-        }
-        assert_eq!(app.file_path_input, "/tmp/test.txt");
-        assert_eq!(app.file_path_cursor, 13);
-
-        app.file_picker_delete_char();
-        assert_eq!(app.file_path_input, "/tmp/test.tx");
-        assert_eq!(app.file_path_cursor, 12);
+        signed_in(&mut app);
+        app.view_mode = ViewMode::Teams;
+        app.channel_messages = vec![make_message(("u1", "Alice"), vec![("👍", "me"), ("😂", "u1")])];
+        assert!(!app.selected_message_has_my_reaction("👍"));
+        app.selected_channel_message = Some(0);
+        assert!(app.selected_message_has_my_reaction("👍"));
+        assert!(!app.selected_message_has_my_reaction("😂"));
     }
 
     #[test]
-    fn file_picker_cursor_movement() {
+    fn own_reaction_keeps_its_stored_type() {
         let mut app = App::new();
-        app.open_file_picker();
-        for c in "abc".chars() {
-            app.file_picker_insert_char(c);
-        }
-        assert_eq!(app.file_path_cursor, 3);
-
-        app.file_picker_cursor_left();
-        assert_eq!(app.file_path_cursor, 2);
-
-        app.file_picker_cursor_right();
-        assert_eq!(app.file_path_cursor, 3);
+        signed_in(&mut app);
+        app.messages = vec![make_message(("u1", "Alice"), vec![("like", "me"), ("❤", "me")])];
+        app.selected_message = Some(0);
+        assert_eq!(app.selected_message_my_reaction_type("👍").as_deref(), Some("like"));
+        assert_eq!(app.selected_message_my_reaction_type("❤️").as_deref(), Some("❤"));
+        assert_eq!(app.selected_message_my_reaction_type("😂"), None);
     }
 
     #[test]
-    fn file_picker_delete_at_start_is_noop() {
+    fn reaction_details_resolve_names() {
         let mut app = App::new();
-        app.open_file_picker();
-        app.file_picker_delete_char();
-        assert!(app.file_path_input.is_empty());
-        assert_eq!(app.file_path_cursor, 0);
+        signed_in(&mut app);
+        app.messages = vec![make_message(("u1", "Alice"), vec![("👍", "me"), ("👍", "u1"), ("😮", "u9")])];
+        app.selected_message = Some(0);
+        assert!(app.open_reaction_details());
+        assert_eq!(app.dialog, DialogMode::ReactionDetails);
+        assert_eq!(
+            app.selected_reaction_details(),
+            vec![
+                ("👍".to_string(), vec!["Me Myself".to_string(), "Alice".to_string()]),
+                ("😮".to_string(), vec!["Unknown user".to_string()]),
+            ]
+        );
     }
 
     #[test]
-    fn file_upload_error_can_be_set() {
+    fn reaction_details_need_reactions() {
         let mut app = App::new();
-        app.open_file_picker();
-        app.file_upload_error = Some("File not found".to_string());
-        assert_eq!(app.file_upload_error.as_deref(), Some("File not found"));
+        app.messages = vec![make_message(("u1", "Alice"), vec![])];
+        app.selected_message = Some(0);
+        assert!(!app.open_reaction_details());
+        assert_eq!(app.dialog, DialogMode::None);
     }
 }
 
 #[cfg(test)]
-mod attachment_selection_tests {
-    use ttyms::app::{App, ViewMode};
-    use ttyms::models::{ChatMessageAttachment, Message};
+mod forward_tests {
+    use ttyms::app::{App, DialogMode, ForwardStage, PaletteItemKind};
+    use ttyms::models::*;
+
+    fn make_chat(id: &str, topic: &str) -> Chat {
+        Chat {
+            id: id.to_string(),
+            topic: Some(topic.to_string()),
+            chat_type: "group".to_string(),
+            members: None,
+            last_message_preview: None,
+            unread_message_count: None,
+            viewpoint: None,
+        }
+    }
+
+    fn make_message(id: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            message_type: Some("message".to_string()),
+            body: Some(MessageBody {
+                content: Some("hello".to_string()),
+                content_type: Some("text".to_string()),
+            }),
+            from: None,
+            created_date_time: None,
+            reactions: None,
+            attachments: vec![],
+            event_detail: None,
+            deleted_date_time: None,
+        }
+    }
+
+    #[test]
+    fn forward_requires_selected_message() {
+        let mut app = App::new();
+        app.messages = vec![make_message("m1")];
+        assert!(!app.open_forward());
+        assert_eq!(app.dialog, DialogMode::None);
+    }
+
+    #[test]
+    fn forward_targets_exclude_actions() {
+        let mut app = App::new();
+        app.chats = vec![make_chat("c1", "Design"), make_chat("c2", "Standup")];
+        app.messages = vec![make_message("m1")];
+        app.selected_message = Some(0);
+        assert!(app.open_forward());
+        assert_eq!(app.dialog, DialogMode::Forward);
+        assert_eq!(app.forward_message.as_ref().unwrap().id, "m1");
+        assert_eq!(app.palette_items.len(), 2);
+        assert!(app
+            .palette_items
+            .iter()
+            .all(|item| !matches!(item.kind, PaletteItemKind::Action(_))));
+    }
+
+    #[test]
+    fn forward_choose_target_then_comment() {
+        let mut app = App::new();
+        app.chats = vec![make_chat("c1", "Design"), make_chat("c2", "Standup")];
+        app.messages = vec![make_message("m1")];
+        app.selected_message = Some(0);
+        app.open_forward();
+        app.palette_input = "stand".to_string();
+        app.palette_filter();
+        assert!(app.forward_choose_target());
+        assert_eq!(app.forward_stage, ForwardStage::Comment);
+        assert_eq!(
+            app.forward_target.as_ref().unwrap().kind,
+            PaletteItemKind::Chat("c2".to_string())
+        );
+
+        for c in "fyí".chars() {
+            app.forward_insert_char(c);
+        }
+        app.forward_delete_char();
+        assert_eq!(app.forward_comment, "fy");
+        app.forward_move_cursor_left();
+        assert_eq!(app.forward_comment_cursor, 1);
+
+        app.forward_back_to_targets();
+        assert_eq!(app.forward_stage, ForwardStage::Target);
+        assert!(app.forward_target.is_none());
+
+        app.close_forward();
+        assert_eq!(app.dialog, DialogMode::None);
+        assert!(app.forward_message.is_none());
+    }
+
+    #[test]
+    fn forward_choose_target_with_no_matches() {
+        let mut app = App::new();
+        app.chats = vec![make_chat("c1", "Design")];
+        app.messages = vec![make_message("m1")];
+        app.selected_message = Some(0);
+        app.open_forward();
+        app.palette_input = "zzz".to_string();
+        app.palette_filter();
+        assert!(!app.forward_choose_target());
+        assert_eq!(app.forward_stage, ForwardStage::Target);
+    }
+}
+
+#[cfg(test)]
+mod file_picker_tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use ttyms::app::{App, DialogMode};
+
+    #[test]
+    fn cancel_file_upload_sets_flag_for_chunked_upload() {
+        let mut app = App::new();
+        app.open_file_picker();
+        let flag = Arc::new(AtomicBool::new(false));
+        app.file_uploading = true;
+        app.file_upload_cancel = Some(flag.clone());
+        assert!(app.cancel_file_upload());
+        assert!(flag.load(Ordering::Relaxed));
+        assert!(app.is_file_upload_cancelling());
+    }
+
+    #[test]
+    fn cancel_file_upload_ignored_without_session() {
+        let mut app = App::new();
+        app.open_file_picker();
+        app.file_uploading = true;
+        assert!(!app.cancel_file_upload());
+        assert!(!app.is_file_upload_cancelling());
+    }
+
+    #[test]
+    fn open_file_picker_sets_dialog() {
+        let mut app = App::new();
+        app.open_file_picker();
+        assert!(matches!(app.dialog, DialogMode::FilePicker));
+        assert!(app.file_path_input.is_empty());
+        assert_eq!(app.file_path_cursor, 0);
+        assert!(!app.file_uploading);
+        assert!(app.file_upload_error.is_none());
+    }
+
+    #[test]
+    fn open_file_picker_clears_previous_state() {
+        let mut app = App::new();
+        app.file_path_input = "/old/path.txt".to_string();
+        app.file_path_cursor = 5;
+        app.file_uploading = true;
+        app.file_upload_error = Some("old error".to_string());
+        app.open_file_picker();
+        assert!(app.file_path_input.is_empty());
+        assert_eq!(app.file_path_cursor, 0);
+        assert!(!app.file_uploading);
+        assert!(app.file_upload_error.is_none());
+    }
+
+    #[test]
+    fn close_file_picker() {
+        let mut app = App::new();
+        app.open_file_picker();
+        app.close_dialog();
+        assert!(matches!(app.dialog, DialogMode::None));
+    }
+
+    #[test]
+    fn file_picker_insert_and_delete() {
+        let mut app = App::new();
+        app.open_file_picker();
+        for c in "/tmp/test.txt".chars() {
+            app.file_picker_insert_char(c);
+        }
+        assert_eq!(app.file_path_input, "/tmp/test.txt");
+        assert_eq!(app.file_path_cursor, 13);
+
+        app.file_picker_delete_char();
+        assert_eq!(app.file_path_input, "/tmp/test.tx");
+        assert_eq!(app.file_path_cursor, 12);
+    }
+
+    #[test]
+    fn file_picker_cursor_movement() {
+        let mut app = App::new();
+        app.open_file_picker();
+        for c in "abc".chars() {
+            app.file_picker_insert_char(c);
+        }
+        assert_eq!(app.file_path_cursor, 3);
+
+        app.file_picker_cursor_left();
+        assert_eq!(app.file_path_cursor, 2);
+
+        app.file_picker_cursor_right();
+        assert_eq!(app.file_path_cursor, 3);
+    }
+
+    #[test]
+    fn file_picker_delete_at_start_is_noop() {
+        let mut app = App::new();
+        app.open_file_picker();
+        app.file_picker_delete_char();
+        assert!(app.file_path_input.is_empty());
+        assert_eq!(app.file_path_cursor, 0);
+    }
+
+    fn picker_fixture(label: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("ttyms-picker-{}-{}", label, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("a.txt"), b"a").unwrap();
+        std::fs::write(dir.join("b.txt"), b"bb").unwrap();
+        dir
+    }
+
+    #[test]
+    fn file_picker_browses_folders() {
+        let dir = picker_fixture("browse");
+        let mut app = App::new();
+        app.file_picker_dir = dir.clone();
+        app.open_file_picker();
+        let names: Vec<&str> = app.file_picker_entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["sub", "a.txt", "b.txt"]);
+
+        // Entering a folder and going back up reselects it
+        assert!(app.file_picker_open_selected().is_none());
+        assert_eq!(app.file_picker_dir, dir.join("sub"));
+        app.file_picker_parent();
+        assert_eq!(app.file_picker_dir, dir);
+        assert_eq!(app.file_picker_selected_entry().unwrap().name, "sub");
+
+        app.file_picker_move(10);
+        assert_eq!(app.file_picker_selected_entry().unwrap().name, "b.txt");
+        app.file_picker_move(-10);
+        assert_eq!(app.file_picker_selected, 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn file_picker_marks_files_for_upload() {
+        let dir = picker_fixture("mark");
+        let mut app = App::new();
+        app.file_picker_dir = dir.clone();
+        app.open_file_picker();
+        // Folders can't be marked
+        app.file_picker_toggle_mark();
+        assert!(app.file_picker_marked.is_empty());
+
+        app.file_picker_move(1);
+        app.file_picker_toggle_mark(); // a.txt, moves to b.txt
+        app.file_picker_toggle_mark(); // b.txt
+        assert_eq!(app.file_picker_marked, vec![dir.join("a.txt"), dir.join("b.txt")]);
+        assert_eq!(
+            app.file_picker_upload_targets(Some(dir.join("sub"))),
+            vec![dir.join("a.txt"), dir.join("b.txt")]
+        );
+        assert_eq!(
+            app.file_picker_upload_targets(Some(dir.join("b.txt"))),
+            vec![dir.join("a.txt"), dir.join("b.txt")]
+        );
+
+        app.file_picker_move(-1);
+        app.file_picker_toggle_mark();
+        assert_eq!(app.file_picker_marked, vec![dir.join("b.txt")]);
+        // The highlighted file is uploaded along with the marked ones
+        assert_eq!(
+            app.file_picker_upload_targets(Some(dir.join("a.txt"))),
+            vec![dir.join("b.txt"), dir.join("a.txt")]
+        );
+
+        app.file_picker_marked.clear();
+        assert_eq!(app.file_picker_upload_targets(Some(dir.join("a.txt"))), vec![dir.join("a.txt")]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn file_picker_typing_starts_with_current_folder() {
+        let dir = picker_fixture("typing");
+        let mut app = App::new();
+        app.file_picker_dir = dir.clone();
+        app.open_file_picker();
+        app.file_picker_start_typing();
+        assert!(app.file_picker_typing);
+        assert_eq!(app.file_path_input, format!("{}/", dir.display()));
+        for c in "su".chars() {
+            app.file_picker_insert_char(c);
+        }
+        app.file_picker_complete();
+        assert_eq!(app.file_path_input, format!("{}/sub/", dir.display()));
+        assert_eq!(app.file_picker_dir, dir.join("sub"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn file_upload_error_can_be_set() {
+        let mut app = App::new();
+        app.open_file_picker();
+        app.file_upload_error = Some("File not found".to_string());
+        assert_eq!(app.file_upload_error.as_deref(), Some("File not found"));
+    }
+}
+
+#[cfg(test)]
+mod attachment_selection_tests {
+    use ttyms::app::{App, DialogMode, ViewMode};
+    use ttyms::models::{
+        Channel, ChatMessageAttachment, Message, SharedFile, SharedFileSort, SharedFileSource, Team,
+    };
 
     fn make_attachment(name: &str, content_type: &str, url: &str) -> ChatMessageAttachment {
         ChatMessageAttachment {
@@ -1983,6 +2614,7 @@ mod attachment_selection_tests {
             content_type: Some(content_type.to_string()),
             content_url: Some(url.to_string()),
             name: Some(name.to_string()),
+            content: None,
         }
     }
 
@@ -1995,6 +2627,8 @@ mod attachment_selection_tests {
             created_date_time: None,
             reactions: None,
             attachments,
+            event_detail: None,
+            deleted_date_time: None,
         }
     }
 
@@ -2048,32 +2682,1156 @@ mod attachment_selection_tests {
         let app = App::new();
         assert!(app.selected_message_attachment_url().is_none());
     }
+
+    #[test]
+    fn selected_message_download_prefers_files_over_images() {
+        let mut app = App::new();
+        app.messages = vec![make_message(vec![
+            make_attachment("pic.png", "image/png", "https://example.com/pic.png"),
+            make_attachment("file.pdf", "reference", "https://example.com/file.pdf"),
+        ])];
+        app.selected_message = Some(0);
+        assert_eq!(
+            app.selected_message_download(),
+            Some(("https://example.com/file.pdf".to_string(), "file.pdf".to_string()))
+        );
+    }
+
+    #[test]
+    fn shared_files_panel_lists_chat_attachments() {
+        let mut app = App::new();
+        app.messages = vec![
+            make_message(vec![make_attachment("a.pdf", "reference", "https://example.com/a.pdf")]),
+            make_message(vec![make_attachment("b.png", "image/png", "https://example.com/b.png")]),
+        ];
+        app.open_shared_files();
+        assert_eq!(app.dialog, DialogMode::SharedFiles);
+        assert_eq!(app.shared_files.len(), 2);
+        assert!(app.shared_files_loading.is_none());
+        app.shared_files_move(5);
+        assert_eq!(app.shared_files_selected, 1);
+        let selected = app.selected_shared_file().unwrap().name.clone();
+        app.cycle_shared_files_sort();
+        assert_eq!(app.shared_files_sort, SharedFileSort::Sender);
+        assert_eq!(app.selected_shared_file().unwrap().name, selected);
+    }
+
+    #[test]
+    fn shared_files_panel_merges_channel_folder() {
+        let mut app = App::new();
+        app.view_mode = ViewMode::Teams;
+        app.teams = vec![Team { id: "t1".to_string(), display_name: "Team".to_string(), description: None }];
+        app.channels = vec![Channel {
+            id: "ch1".to_string(),
+            display_name: "General".to_string(),
+            description: None,
+            membership_type: None,
+        }];
+        app.open_shared_files();
+        assert_eq!(app.shared_files_loading, Some(("t1".to_string(), "ch1".to_string())));
+
+        let folder_file = SharedFile {
+            name: "plan.pdf".to_string(),
+            web_url: "https://example.com/plan.pdf".to_string(),
+            download_url: "https://graph.microsoft.com/v1.0/drives/d/items/i/content".to_string(),
+            sender: "Dana".to_string(),
+            date: None,
+            size: Some(10),
+            source: SharedFileSource::ChannelFolder,
+        };
+        // Results for another channel are ignored
+        app.merge_channel_files("t1", "other", vec![folder_file.clone()]);
+        assert!(app.shared_files.is_empty());
+        app.merge_channel_files("t1", "ch1", vec![folder_file]);
+        assert_eq!(app.shared_files.len(), 1);
+        assert!(app.shared_files_loading.is_none());
+    }
+
+    #[test]
+    fn shared_files_channel_error_is_shown() {
+        let mut app = App::new();
+        app.dialog = DialogMode::SharedFiles;
+        app.shared_files_loading = Some(("t1".to_string(), "ch1".to_string()));
+        app.channel_files_failed("t1", "ch1", "403 Forbidden".to_string());
+        assert!(app.shared_files_loading.is_none());
+        assert_eq!(app.shared_files_error.as_deref(), Some("403 Forbidden"));
+    }
+
+    #[test]
+    fn selected_message_download_ignores_cards() {
+        let mut app = App::new();
+        app.messages = vec![make_message(vec![make_attachment(
+            "card",
+            "application/vnd.microsoft.card.adaptive",
+            "https://example.com/card",
+        )])];
+        app.selected_message = Some(0);
+        assert!(app.selected_message_download().is_none());
+    }
 }
 
 #[cfg(test)]
-mod image_preview_cache_tests {
-    use ttyms::app::App;
+mod code_block_selection_tests {
+    use ttyms::app::{App, ViewMode};
+    use ttyms::models::{Message, MessageBody};
+
+    fn make_message(html: &str) -> Message {
+        Message {
+            id: "msg-1".to_string(),
+            message_type: Some("message".to_string()),
+            body: Some(MessageBody { content: Some(html.to_string()), content_type: Some("html".to_string()) }),
+            from: None,
+            created_date_time: None,
+            reactions: None,
+            attachments: vec![], event_detail: None, deleted_date_time: None,
+        }
+    }
 
     #[test]
-    fn mark_preview_pending_only_once() {
+    fn selected_message_code_blocks_returns_all_blocks() {
         let mut app = App::new();
-        assert!(app.mark_image_preview_pending("https://example.com/a.png"));
-        assert!(!app.mark_image_preview_pending("https://example.com/a.png"));
-        assert!(app.is_image_preview_pending("https://example.com/a.png"));
+        app.messages = vec![make_message("<pre>one</pre>text<pre>two<br>lines</pre>")];
+        app.selected_message = Some(0);
+        assert_eq!(
+            app.selected_message_code_blocks(),
+            vec!["one".to_string(), "two\nlines".to_string()]
+        );
     }
 
     #[test]
-    fn set_preview_moves_url_from_pending_to_cache() {
+    fn selected_message_code_blocks_ignores_inline_code() {
         let mut app = App::new();
-        app.mark_image_preview_pending("https://example.com/a.png");
-        app.set_image_preview(
-            "https://example.com/a.png".to_string(),
-            vec!["line1".to_string(), "line2".to_string()],
-        );
-        assert!(!app.is_image_preview_pending("https://example.com/a.png"));
-        let lines = app.image_preview_lines("https://example.com/a.png").unwrap();
-        assert_eq!(lines.len(), 2);
-        assert_eq!(lines[0], "line1");
-        assert_eq!(lines[1], "line2");
+        app.messages = vec![make_message("use <code>cargo test</code> here")];
+        app.selected_message = Some(0);
+        assert!(app.selected_message_code_blocks().is_empty());
+    }
+
+    #[test]
+    fn selected_channel_message_code_blocks_in_teams_view() {
+        let mut app = App::new();
+        app.view_mode = ViewMode::Teams;
+        app.channel_messages = vec![make_message("<pre>SELECT 1;</pre>")];
+        app.selected_channel_message = Some(0);
+        assert_eq!(app.selected_message_code_blocks(), vec!["SELECT 1;".to_string()]);
+    }
+
+    #[test]
+    fn selected_message_code_blocks_empty_without_selection() {
+        let mut app = App::new();
+        app.messages = vec![make_message("<pre>one</pre>")];
+        assert!(app.selected_message_code_blocks().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod card_action_selection_tests {
+    use ttyms::app::{App, ViewMode};
+    use ttyms::cards::CardAction;
+    use ttyms::models::{ChatMessageAttachment, Message};
+
+    fn make_card_message(id: &str, actions: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            message_type: Some("message".to_string()),
+            body: None,
+            from: None,
+            created_date_time: None,
+            reactions: None,
+            attachments: vec![ChatMessageAttachment {
+                id: Some("card".to_string()),
+                content_type: Some("application/vnd.microsoft.card.adaptive".to_string()),
+                content_url: None,
+                name: None,
+                content: Some(format!(r#"{{"body":[],"actions":{}}}"#, actions)),
+            }],
+            event_detail: None,
+            deleted_date_time: None,
+        }
+    }
+
+    const TWO_ACTIONS: &str = r#"[{"type":"Action.OpenUrl","title":"Open","url":"https://example.com"},
+                                   {"type":"Action.Submit","title":"Approve"}]"#;
+
+    #[test]
+    fn cycle_card_action_wraps_around() {
+        let mut app = App::new();
+        app.messages = vec![make_card_message("m1", TWO_ACTIONS)];
+        app.selected_message = Some(0);
+        assert!(matches!(app.cycle_card_action(), Some(CardAction::OpenUrl { .. })));
+        assert!(matches!(app.cycle_card_action(), Some(CardAction::Unsupported { .. })));
+        assert!(matches!(app.cycle_card_action(), Some(CardAction::OpenUrl { .. })));
+        assert_eq!(app.selected_card_action, Some(0));
+    }
+
+    #[test]
+    fn cycle_card_action_none_without_actions() {
+        let mut app = App::new();
+        app.messages = vec![make_card_message("m1", "[]")];
+        app.selected_message = Some(0);
+        assert!(app.cycle_card_action().is_none());
+        assert!(app.selected_card_action.is_none());
+    }
+
+    #[test]
+    fn moving_selection_resets_card_action() {
+        let mut app = App::new();
+        app.messages = vec![make_card_message("m1", TWO_ACTIONS), make_card_message("m2", TWO_ACTIONS)];
+        app.selected_message = Some(1);
+        app.cycle_card_action();
+        app.select_message_up();
+        assert_eq!(app.selected_message, Some(0));
+        assert!(app.selected_card_action().is_none());
+    }
+
+    #[test]
+    fn card_actions_follow_teams_view() {
+        let mut app = App::new();
+        app.view_mode = ViewMode::Teams;
+        app.channel_messages = vec![make_card_message("c1", TWO_ACTIONS)];
+        app.selected_channel_message = Some(0);
+        assert_eq!(app.selected_message_card_actions().len(), 2);
+        app.cycle_card_action();
+        assert_eq!(
+            app.selected_card_action(),
+            Some(CardAction::OpenUrl { title: "Open".to_string(), url: "https://example.com".to_string() })
+        );
+    }
+}
+
+#[cfg(test)]
+mod image_preview_cache_tests {
+    use ttyms::app::{App, IMAGE_SOURCE_CACHE_BYTES};
+    use ttyms::graphics::ImagePreview;
+
+    #[test]
+    fn mark_preview_pending_only_once() {
+        let mut app = App::new();
+        assert!(app.mark_image_preview_pending("https://example.com/a.png"));
+        assert!(!app.mark_image_preview_pending("https://example.com/a.png"));
+        assert!(app.is_image_preview_pending("https://example.com/a.png"));
+    }
+
+    #[test]
+    fn set_preview_moves_url_from_pending_to_cache() {
+        let mut app = App::new();
+        app.mark_image_preview_pending("https://example.com/a.png");
+        app.set_image_preview(
+            "https://example.com/a.png".to_string(),
+            ImagePreview::Grayscale(vec!["line1".to_string(), "line2".to_string()]),
+        );
+        assert!(!app.is_image_preview_pending("https://example.com/a.png"));
+        match app.image_preview("https://example.com/a.png").unwrap() {
+            ImagePreview::Grayscale(lines) => {
+                assert_eq!(lines.len(), 2);
+                assert_eq!(lines[0], "line1");
+                assert_eq!(lines[1], "line2");
+            }
+            other => panic!("unexpected preview {:?}", other),
+        }
+    }
+
+    #[test]
+    fn image_sources_evict_least_recently_used() {
+        let mut app = App::new();
+        let half = IMAGE_SOURCE_CACHE_BYTES / 2;
+        for url in ["a", "b"] {
+            app.store_image_source(url.to_string(), vec![0; half]);
+            app.set_image_preview(url.to_string(), ImagePreview::Unavailable);
+        }
+        // Viewing "a" makes "b" the oldest
+        app.open_image_viewer("a", "a.png");
+        app.store_image_source("c".to_string(), vec![0; half]);
+        assert!(app.image_sources.contains_key("a"));
+        assert!(!app.image_sources.contains_key("b"));
+        assert!(app.image_sources.contains_key("c"));
+        // The evicted preview is dropped so it is downloaded again when shown
+        assert!(app.image_preview("b").is_none());
+        assert!(app.image_preview("a").is_some());
+    }
+
+    #[test]
+    fn oversized_image_source_is_kept_alone() {
+        let mut app = App::new();
+        app.store_image_source("a".to_string(), vec![0; 16]);
+        app.store_image_source("big".to_string(), vec![0; IMAGE_SOURCE_CACHE_BYTES + 1]);
+        assert_eq!(app.image_sources.len(), 1);
+        assert!(app.image_sources.contains_key("big"));
+    }
+}
+
+#[cfg(test)]
+mod image_viewer_tests {
+    use ttyms::app::{App, DialogMode};
+    use ttyms::graphics;
+
+    const URL: &str = "https://example.com/photo.png";
+
+    fn app_with_image(width: u32, height: u32) -> App {
+        let mut app = App::new();
+        let png = graphics::encode_png(&image::DynamicImage::new_rgb8(width, height)).unwrap();
+        app.store_image_source(URL.to_string(), png);
+        app
+    }
+
+    #[test]
+    fn open_without_downloaded_source_fails() {
+        let mut app = App::new();
+        assert!(!app.open_image_viewer(URL, "photo.png"));
+        assert!(app.image_viewer.is_none());
+        assert_eq!(app.dialog, DialogMode::None);
+    }
+
+    #[test]
+    fn open_decodes_source_and_shows_dialog() {
+        let mut app = app_with_image(40, 20);
+        assert!(app.open_image_viewer(URL, "photo.png"));
+        assert_eq!(app.dialog, DialogMode::ImageViewer);
+        let viewer = app.image_viewer.as_ref().unwrap();
+        assert_eq!(viewer.zoom(), 1);
+        assert_eq!(viewer.visible_region(), (0, 0, 40, 20));
+
+        app.close_image_viewer();
+        assert!(app.image_viewer.is_none());
+        assert_eq!(app.dialog, DialogMode::None);
+    }
+
+    #[test]
+    fn zoom_is_clamped_to_levels() {
+        let mut app = app_with_image(40, 20);
+        app.open_image_viewer(URL, "photo.png");
+        let viewer = app.image_viewer.as_mut().unwrap();
+        viewer.zoom_out();
+        assert_eq!(viewer.zoom(), 1);
+        for _ in 0..20 {
+            viewer.zoom_in();
+        }
+        assert_eq!(viewer.zoom(), *graphics::ZOOM_LEVELS.last().unwrap());
+        viewer.fit();
+        assert_eq!(viewer.zoom(), 1);
+    }
+
+    #[test]
+    fn pan_stays_inside_image() {
+        let mut app = app_with_image(40, 20);
+        app.open_image_viewer(URL, "photo.png");
+        let viewer = app.image_viewer.as_mut().unwrap();
+        viewer.zoom_in();
+        assert_eq!(viewer.visible_region(), (10, 5, 20, 10));
+        for _ in 0..10 {
+            viewer.pan(-0.25, -0.25);
+        }
+        assert_eq!(viewer.visible_region(), (0, 0, 20, 10));
+        for _ in 0..10 {
+            viewer.pan(0.25, 0.25);
+        }
+        assert_eq!(viewer.visible_region(), (20, 10, 20, 10));
+    }
+}
+
+#[cfg(test)]
+mod shortcode_completion_tests {
+    use ttyms::app::{App, Panel, TeamsPanel, ViewMode};
+
+    fn typed(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.insert_char(c);
+        }
+    }
+
+    #[test]
+    fn suggestions_only_while_composer_is_focused() {
+        let mut app = App::new();
+        typed(&mut app, "hi :tad");
+        assert!(app.shortcode_suggestions().is_empty());
+        app.active_panel = Panel::Input;
+        assert_eq!(app.shortcode_suggestions()[0].shortcode, "tada");
+    }
+
+    #[test]
+    fn accept_replaces_query_with_emoji() {
+        let mut app = App::new();
+        app.active_panel = Panel::Input;
+        typed(&mut app, "hi :tad");
+        assert!(app.accept_shortcode());
+        assert_eq!(app.input, "hi 🎉");
+        assert_eq!(app.input_cursor, app.input.len());
+        assert!(app.shortcode_suggestions().is_empty());
+    }
+
+    #[test]
+    fn selection_wraps_and_picks_other_match() {
+        let mut app = App::new();
+        app.active_panel = Panel::Input;
+        typed(&mut app, ":thumbs");
+        app.shortcode_move(1);
+        assert!(app.accept_shortcode());
+        assert_eq!(app.input, "👎");
+        typed(&mut app, " :thumbs");
+        app.shortcode_move(-1);
+        assert_eq!(app.shortcode_selected, app.shortcode_suggestions().len() - 1);
+    }
+
+    #[test]
+    fn dismiss_hides_popup_until_next_edit() {
+        let mut app = App::new();
+        app.active_panel = Panel::Input;
+        typed(&mut app, ":roc");
+        app.dismiss_shortcodes();
+        assert!(app.shortcode_suggestions().is_empty());
+        assert!(!app.accept_shortcode());
+        app.insert_char('k');
+        assert_eq!(app.shortcode_suggestions()[0].shortcode, "rocket");
+    }
+
+    #[test]
+    fn channel_composer_completes_too() {
+        let mut app = App::new();
+        app.view_mode = ViewMode::Teams;
+        app.teams_panel = TeamsPanel::ChannelInput;
+        for c in "ok :fir".chars() {
+            app.channel_insert_char(c);
+        }
+        assert!(app.accept_shortcode());
+        assert_eq!(app.channel_input, "ok 🔥");
+    }
+}
+
+/// Chats, messages and a signed-in app shared by the chat list test modules below
+#[cfg(test)]
+mod fixtures {
+    use ttyms::app::App;
+    use ttyms::models::*;
+
+    /// A chat whose topic is its upper-cased ID
+    pub fn make_chat(id: &str, chat_type: &str, unread: i32) -> Chat {
+        Chat {
+            id: id.to_string(),
+            topic: Some(id.to_uppercase()),
+            chat_type: chat_type.to_string(),
+            members: None,
+            last_message_preview: None,
+            unread_message_count: Some(unread),
+            viewpoint: None,
+        }
+    }
+
+    /// A user message from the user ID `sender`
+    pub fn make_message(id: &str, sender: &str, time: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            message_type: Some("message".to_string()),
+            body: None,
+            from: Some(MessageFrom {
+                user: Some(MessageUser { display_name: None, id: Some(sender.to_string()) }),
+                application: None,
+            }),
+            created_date_time: Some(time.to_string()),
+            reactions: None,
+            attachments: vec![],
+            event_detail: None,
+            deleted_date_time: None,
+        }
+    }
+
+    /// An app signed in as the user "me"
+    pub fn signed_in_app() -> App {
+        let mut app = App::new();
+        app.current_user = Some(User {
+            id: "me".to_string(),
+            display_name: "Me".to_string(),
+            mail: None,
+            user_principal_name: None,
+        });
+        app
+    }
+}
+
+#[cfg(test)]
+mod pinned_message_tests {
+    use crate::fixtures::{make_chat, make_message};
+    use ttyms::app::{App, DialogMode, Panel};
+    use ttyms::models::*;
+
+    const TIME: &str = "2026-03-01T10:00:00Z";
+
+    fn pin(id: &str, message_id: &str) -> PinnedMessage {
+        PinnedMessage { id: id.to_string(), message: Some(make_message(message_id, "alice", TIME)) }
+    }
+
+    fn app_with_pins() -> App {
+        let mut app = App::new();
+        app.chats = vec![make_chat("c1", "oneOnOne", 0), make_chat("c2", "oneOnOne", 0)];
+        app.messages = vec![
+            make_message("m1", "alice", TIME),
+            make_message("m2", "alice", TIME),
+            make_message("m3", "alice", TIME),
+        ];
+        app.set_pinned_messages("c1", vec![pin("p1", "m3"), pin("p2", "m1")]);
+        app
+    }
+
+    #[test]
+    fn pins_only_apply_to_their_chat() {
+        let mut app = app_with_pins();
+        assert_eq!(app.selected_chat_pins().len(), 2);
+        assert!(app.is_pinned("m1"));
+        app.selected_chat = 1;
+        assert!(app.selected_chat_pins().is_empty());
+        assert!(!app.is_pinned("m1"));
+        assert!(!app.open_pinned_messages());
+    }
+
+    #[test]
+    fn selected_message_pin_id_maps_to_pin() {
+        let mut app = app_with_pins();
+        app.selected_message = Some(2);
+        assert_eq!(app.selected_message_pin_id().as_deref(), Some("p1"));
+        app.selected_message = Some(1);
+        assert_eq!(app.selected_message_pin_id(), None);
+    }
+
+    #[test]
+    fn popup_selection_is_clamped() {
+        let mut app = app_with_pins();
+        assert!(app.open_pinned_messages());
+        assert_eq!(app.dialog, DialogMode::PinnedMessages);
+        app.pinned_move(5);
+        assert_eq!(app.selected_pin().map(|p| p.id.as_str()), Some("p2"));
+        app.pinned_move(-5);
+        assert_eq!(app.pinned_selected, 0);
+    }
+
+    #[test]
+    fn select_message_by_id_focuses_and_scrolls() {
+        let mut app = app_with_pins();
+        assert!(app.select_message_by_id("m3"));
+        assert_eq!(app.selected_message, Some(2));
+        assert_eq!(app.active_panel, Panel::Messages);
+        assert!(app.scroll_to_selected);
+        assert!(!app.select_message_by_id("older"));
+    }
+}
+
+#[cfg(test)]
+mod chat_prefs_tests {
+    use ttyms::app::{App, PaletteAction, PaletteItemKind};
+    use ttyms::state::ChatPrefs;
+    use ttyms::models::*;
+
+    fn make_chat(id: &str, unread: i32, hidden: Option<bool>) -> Chat {
+        Chat {
+            viewpoint: hidden.map(|is_hidden| ChatViewpoint { is_hidden, ..Default::default() }),
+            ..crate::fixtures::make_chat(id, "group", unread)
+        }
+    }
+
+    fn ids(app: &App) -> Vec<&str> {
+        app.chats.iter().map(|c| c.id.as_str()).collect()
+    }
+
+    #[test]
+    fn pinned_chats_move_to_top_in_server_order() {
+        let mut app = App::new();
+        app.chat_prefs.pinned = vec!["c3".to_string(), "c2".to_string()];
+        app.set_chats(vec![make_chat("c1", 0, None), make_chat("c2", 0, None), make_chat("c3", 0, None)]);
+        assert_eq!(ids(&app), vec!["c2", "c3", "c1"]);
+    }
+
+    #[test]
+    fn selection_follows_chat_across_reorder() {
+        let mut app = App::new();
+        app.set_chats(vec![make_chat("c1", 0, None), make_chat("c2", 0, None), make_chat("c3", 0, None)]);
+        app.selected_chat = 2;
+        assert_eq!(app.toggle_selected_chat_pin(), Some(true));
+        assert_eq!(ids(&app), vec!["c3", "c1", "c2"]);
+        assert_eq!(app.selected_chat_id(), Some("c3"));
+        app.set_chats(vec![make_chat("c0", 0, None), make_chat("c1", 0, None), make_chat("c3", 0, None)]);
+        assert_eq!(app.selected_chat_id(), Some("c3"));
+    }
+
+    #[test]
+    fn muted_chats_do_not_count_towards_total_unread() {
+        let mut app = App::new();
+        app.set_chats(vec![make_chat("c1", 3, None), make_chat("c2", 2, None)]);
+        assert_eq!(app.total_unread, 5);
+        app.selected_chat = 1;
+        assert_eq!(app.toggle_selected_chat_mute(), Some(true));
+        assert!(app.selected_chat_muted());
+        assert_eq!(app.total_unread, 3);
+        assert_eq!(app.toggle_selected_chat_mute(), Some(false));
+        assert_eq!(app.total_unread, 5);
+    }
+
+    #[test]
+    fn hidden_chats_follow_graph_viewpoint_then_local_list() {
+        let mut app = App::new();
+        app.chat_prefs.hidden = vec!["c2".to_string(), "c3".to_string()];
+        app.set_chats(vec![
+            make_chat("c1", 0, Some(true)),
+            make_chat("c2", 0, None),
+            make_chat("c3", 0, Some(false)),
+        ]);
+        assert_eq!(ids(&app), vec!["c3"]);
+        app.toggle_show_hidden_chats();
+        assert_eq!(ids(&app), vec!["c1", "c2", "c3"]);
+    }
+
+    #[test]
+    fn set_chat_hidden_updates_list_and_prefs() {
+        let mut app = App::new();
+        app.set_chats(vec![make_chat("c1", 0, None), make_chat("c2", 0, None)]);
+        app.set_chat_hidden("c1", true);
+        assert_eq!(ids(&app), vec!["c2"]);
+        assert_eq!(app.chat_prefs.hidden, vec!["c1"]);
+        app.set_chat_hidden("c1", false);
+        assert_eq!(ids(&app), vec!["c1", "c2"]);
+        assert!(app.chat_prefs.is_empty());
+    }
+
+    #[test]
+    fn toggle_adds_and_removes_ids() {
+        let mut list = Vec::new();
+        assert!(ChatPrefs::toggle(&mut list, "c1"));
+        assert!(!ChatPrefs::toggle(&mut list, "c1"));
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn palette_offers_chat_actions_for_selected_chat() {
+        let mut app = App::new();
+        app.open_command_palette();
+        let has = |app: &App, action: PaletteAction| {
+            app.palette_items.iter().any(|i| i.kind == PaletteItemKind::Action(action.clone()))
+        };
+        assert!(!has(&app, PaletteAction::PinChat));
+        assert!(has(&app, PaletteAction::ShowHiddenChats));
+
+        app.set_chats(vec![make_chat("c1", 0, None)]);
+        app.toggle_selected_chat_pin();
+        app.open_command_palette();
+        assert!(has(&app, PaletteAction::MuteChat));
+        assert!(has(&app, PaletteAction::HideChat));
+        assert!(app.palette_items.iter().any(|i| i.label == "Unpin Current Chat"));
+    }
+}
+
+#[cfg(test)]
+mod chat_section_tests {
+    use crate::fixtures::make_chat;
+    use ttyms::app::{App, ChatListRow};
+    use ttyms::config::ChatSectionConfig;
+
+    fn ids(app: &App) -> Vec<&str> {
+        app.chats.iter().map(|c| c.id.as_str()).collect()
+    }
+
+    fn sectioned_app(collapse_meetings: bool) -> App {
+        let mut app = App::new();
+        app.set_chat_sections(&[
+            ChatSectionConfig { name: "Favorites".to_string(), chats: vec!["g2".to_string()], ..Default::default() },
+            ChatSectionConfig {
+                name: "Meetings".to_string(),
+                chat_types: vec!["meeting".to_string()],
+                collapsed: collapse_meetings,
+                ..Default::default()
+            },
+        ]);
+        app.set_chats(vec![
+            make_chat("m1", "meeting", 2),
+            make_chat("g1", "group", 1),
+            make_chat("g2", "group", 4),
+            make_chat("m2", "meeting", 3),
+        ]);
+        app
+    }
+
+    fn header(name: &str, chats: usize, unread: i32, collapsed: bool, first_chat: usize) -> ChatListRow {
+        ChatListRow::Header { name: name.to_string(), chats, unread, collapsed, first_chat }
+    }
+
+    #[test]
+    fn without_sections_rows_are_the_flat_list() {
+        let mut app = App::new();
+        app.set_chats(vec![make_chat("a", "group", 0), make_chat("b", "group", 0)]);
+        assert_eq!(app.chat_list_rows(), vec![ChatListRow::Chat(0), ChatListRow::Chat(1)]);
+        assert_eq!(app.toggle_selected_section(), None);
+    }
+
+    #[test]
+    fn chats_are_grouped_by_section_with_headers() {
+        let app = sectioned_app(false);
+        assert_eq!(ids(&app), vec!["g2", "m1", "m2", "g1"]);
+        assert_eq!(
+            app.chat_list_rows(),
+            vec![
+                header("Favorites", 1, 4, false, 0),
+                ChatListRow::Chat(0),
+                header("Meetings", 2, 5, false, 1),
+                ChatListRow::Chat(1),
+                ChatListRow::Chat(2),
+                header("Other", 1, 1, false, 3),
+                ChatListRow::Chat(3),
+            ]
+        );
+    }
+
+    #[test]
+    fn header_unread_leaves_out_muted_chats() {
+        let mut app = sectioned_app(false);
+        app.chat_prefs.muted = vec!["m1".to_string()];
+        assert_eq!(app.chat_list_rows()[2], header("Meetings", 2, 3, false, 1));
+    }
+
+    #[test]
+    fn navigation_skips_headers_and_collapsed_chats() {
+        let mut app = sectioned_app(true);
+        let mut visited = vec![app.selected_chat_id().unwrap().to_string()];
+        for _ in 0..4 {
+            app.select_next_chat();
+            visited.push(app.selected_chat_id().unwrap().to_string());
+        }
+        assert_eq!(visited, vec!["g2", "m1", "g1", "g1", "g1"]);
+        app.select_prev_chat();
+        assert_eq!(app.selected_chat_id(), Some("m1"));
+        assert!(app.selected_chat_collapsed());
+        app.select_prev_chat();
+        assert_eq!(app.selected_chat_id(), Some("g2"));
+    }
+
+    #[test]
+    fn collapsing_selects_the_section_header() {
+        let mut app = sectioned_app(false);
+        app.selected_chat = 2;
+        assert_eq!(app.toggle_selected_section(), Some(("Meetings".to_string(), true)));
+        assert_eq!(app.selected_chat_id(), Some("m1"));
+        let rows = app.chat_list_rows();
+        assert_eq!(rows.len(), 5);
+        assert_eq!(app.selected_chat_row(&rows), Some(2));
+        assert_eq!(app.toggle_selected_section(), Some(("Meetings".to_string(), false)));
+        assert_eq!(app.chat_list_rows().len(), 7);
+    }
+
+    #[test]
+    fn scroll_keeps_selected_row_visible() {
+        let mut app = sectioned_app(false);
+        let rows = app.chat_list_rows();
+        assert_eq!(app.chat_list_scroll(&rows, 8), 0);
+        app.selected_chat = 3;
+        // Rows 5..=6 (header + chat) take 4 lines; row 4 would need 3 more
+        assert_eq!(app.chat_list_scroll(&rows, 8), 4);
+        assert_eq!(app.chat_list_scroll(&rows, 4), 5);
+    }
+
+    #[test]
+    fn row_at_maps_lines_to_rows() {
+        let app = sectioned_app(false);
+        assert_eq!(app.chat_list_row_at(0, 30), Some(header("Favorites", 1, 4, false, 0)));
+        assert_eq!(app.chat_list_row_at(1, 30), Some(ChatListRow::Chat(0)));
+        assert_eq!(app.chat_list_row_at(3, 30), Some(ChatListRow::Chat(0)));
+        assert_eq!(app.chat_list_row_at(4, 30), Some(header("Meetings", 2, 5, false, 1)));
+        assert_eq!(app.chat_list_row_at(29, 30), None);
+    }
+}
+
+#[cfg(test)]
+mod chat_filter_tests {
+    use ttyms::app::{fuzzy_match, App};
+    use ttyms::models::*;
+
+    fn make_chat(id: &str, topic: &str, chat_type: &str, unread: i32) -> Chat {
+        Chat { topic: Some(topic.to_string()), ..crate::fixtures::make_chat(id, chat_type, unread) }
+    }
+
+    fn ids(app: &App) -> Vec<&str> {
+        app.chats.iter().map(|c| c.id.as_str()).collect()
+    }
+
+    fn sample_app() -> App {
+        let mut app = App::new();
+        app.set_chats(vec![
+            make_chat("c1", "Jane Doe", "oneOnOne", 0),
+            make_chat("c2", "Design Review", "meeting", 2),
+            make_chat("c3", "Dev Team", "group", 1),
+            make_chat("c4", "John Smith", "oneOnOne", 3),
+        ]);
+        app
+    }
+
+    #[test]
+    fn fuzzy_match_is_a_case_insensitive_subsequence() {
+        assert!(fuzzy_match("", "Anything"));
+        assert!(fuzzy_match("jdoe", "Jane Doe"));
+        assert!(fuzzy_match("jane doe", "Jane Doe"));
+        assert!(fuzzy_match("REV", "Design Review"));
+        assert!(!fuzzy_match("doej", "Jane Doe"));
+    }
+
+    #[test]
+    fn typing_filters_names_incrementally() {
+        let mut app = sample_app();
+        app.start_chat_filter();
+        app.chat_filter_insert('d');
+        assert_eq!(ids(&app), vec!["c1", "c2", "c3"]);
+        app.chat_filter_insert('e');
+        app.chat_filter_insert('v');
+        assert_eq!(ids(&app), vec!["c2", "c3"]);
+        app.chat_filter_delete();
+        app.chat_filter_delete();
+        assert_eq!(ids(&app), vec!["c1", "c2", "c3"]);
+    }
+
+    #[test]
+    fn type_toggles_combine() {
+        let mut app = sample_app();
+        app.toggle_chat_filter_type("oneOnOne");
+        assert_eq!(ids(&app), vec!["c1", "c4"]);
+        app.toggle_chat_filter_type("group");
+        assert_eq!(ids(&app), vec!["c1", "c3", "c4"]);
+        app.toggle_chat_filter_type("oneOnOne");
+        app.toggle_chat_filter_type("group");
+        assert!(!app.chat_filter.is_active());
+        assert_eq!(app.chats.len(), 4);
+    }
+
+    #[test]
+    fn unread_only_keeps_the_open_chat_after_it_is_read() {
+        let mut app = sample_app();
+        app.toggle_chat_filter_unread();
+        // c1 is open, so it stays listed until another chat is selected
+        assert_eq!(ids(&app), vec!["c1", "c2", "c3", "c4"]);
+        app.selected_chat = 2;
+        app.set_chats(vec![
+            make_chat("c1", "Jane Doe", "oneOnOne", 0),
+            make_chat("c2", "Design Review", "meeting", 2),
+            make_chat("c3", "Dev Team", "group", 0),
+            make_chat("c4", "John Smith", "oneOnOne", 3),
+        ]);
+        assert_eq!(ids(&app), vec!["c2", "c3", "c4"]);
+        assert_eq!(app.selected_chat_id(), Some("c3"));
+        app.selected_chat = 0;
+        app.apply_chat_prefs();
+        assert_eq!(ids(&app), vec!["c2", "c4"]);
+    }
+
+    #[test]
+    fn selection_stays_on_the_same_chat_across_refreshes() {
+        let mut app = sample_app();
+        app.toggle_chat_filter_type("oneOnOne");
+        app.select_next_chat();
+        assert_eq!(app.selected_chat_id(), Some("c4"));
+        app.set_chats(vec![
+            make_chat("c5", "Jim Beam", "oneOnOne", 1),
+            make_chat("c1", "Jane Doe", "oneOnOne", 0),
+            make_chat("c4", "John Smith", "oneOnOne", 3),
+        ]);
+        assert_eq!(ids(&app), vec!["c5", "c1", "c4"]);
+        assert_eq!(app.selected_chat_id(), Some("c4"));
+        app.select_prev_chat();
+        assert_eq!(app.selected_chat_id(), Some("c1"));
+    }
+
+    #[test]
+    fn header_badge_ignores_the_filter() {
+        let mut app = sample_app();
+        assert_eq!(app.total_unread, 6);
+        app.toggle_chat_filter_type("group");
+        assert_eq!(ids(&app), vec!["c3"]);
+        assert_eq!(app.total_unread, 6);
+        // Reading the only listed chat keeps the filtered-out chats' counts
+        app.mark_selected_chat_read();
+        assert_eq!(app.total_unread, 5);
+        app.set_chats_read(&["c4".to_string()]);
+        assert_eq!(app.total_unread, 2);
+    }
+
+    #[test]
+    fn clear_restores_the_full_list() {
+        let mut app = sample_app();
+        app.start_chat_filter();
+        app.chat_filter_insert('z');
+        assert!(app.chats.is_empty());
+        assert!(app.chat_filter_bar_visible());
+        app.clear_chat_filter();
+        assert_eq!(app.chats.len(), 4);
+        assert!(!app.chat_filter_bar_visible());
+    }
+}
+
+#[cfg(test)]
+mod next_unread_tests {
+    use crate::fixtures::{make_chat, make_message, signed_in_app};
+    use ttyms::app::{App, UnreadTarget, ViewMode};
+    use ttyms::models::*;
+
+    fn make_app() -> App {
+        let mut app = signed_in_app();
+        app.set_chats(vec![
+            make_chat("c1", "group", 0),
+            make_chat("c2", "group", 2),
+            make_chat("c3", "group", 0),
+            make_chat("c4", "group", 1),
+        ]);
+        app.teams = vec![Team { id: "t1".to_string(), display_name: "T1".to_string(), description: None }];
+        app.channels_cache.insert(
+            "t1".to_string(),
+            vec![Channel {
+                id: "ch1".to_string(),
+                display_name: "General".to_string(),
+                description: None,
+                membership_type: None,
+            }],
+        );
+        app.channel_message_cache
+            .insert("ch1".to_string(), vec![make_message("p1", "alice", "2026-03-01T10:00:00Z")]);
+        app.seed_channel_last_read("ch1");
+        app
+    }
+
+    #[test]
+    fn seeded_channels_start_read() {
+        let mut app = make_app();
+        assert!(!app.channel_has_unread("ch1"));
+        app.channel_message_cache.get_mut("ch1").unwrap().push(make_message("p2", "me", "2026-03-01T11:00:00Z"));
+        assert!(!app.channel_has_unread("ch1"));
+        app.channel_message_cache.get_mut("ch1").unwrap().push(make_message("p3", "bob", "2026-03-01T12:00:00Z"));
+        assert!(app.channel_has_unread("ch1"));
+    }
+
+    #[test]
+    fn walks_chats_then_channels_and_wraps() {
+        let mut app = make_app();
+        app.channel_message_cache.get_mut("ch1").unwrap().push(make_message("p2", "bob", "2026-03-01T12:00:00Z"));
+        assert_eq!(app.next_unread_target(), Some(UnreadTarget::Chat(1)));
+        app.selected_chat = 1;
+        assert_eq!(app.next_unread_target(), Some(UnreadTarget::Chat(3)));
+        app.selected_chat = 3;
+        assert_eq!(app.next_unread_target(), Some(UnreadTarget::Channel(0, "ch1".to_string())));
+        app.view_mode = ViewMode::Teams;
+        app.channels = app.channels_cache["t1"].clone();
+        assert_eq!(app.next_unread_target(), Some(UnreadTarget::Chat(1)));
+    }
+
+    #[test]
+    fn muted_and_current_chats_are_skipped() {
+        let mut app = make_app();
+        app.chat_prefs.muted = vec!["c2".to_string()];
+        app.selected_chat = 3;
+        assert_eq!(app.next_unread_target(), None);
+    }
+
+    #[test]
+    fn opening_a_chat_places_the_divider_and_marks_it_read() {
+        let mut app = make_app();
+        app.selected_chat = 1;
+        app.messages = vec![
+            make_message("m1", "alice", "2026-03-01T09:00:00Z"),
+            make_message("m2", "alice", "2026-03-01T09:30:00Z"),
+            make_message("m3", "bob", "2026-03-01T10:00:00Z"),
+        ];
+        assert!(app.set_chat_unread_marker(None, 2));
+        assert_eq!(app.unread_marker.as_deref(), Some("m2"));
+        app.mark_selected_chat_read();
+        assert_eq!(app.chats[1].unread_count(), 0);
+        assert_eq!(app.total_unread, 1);
+        let last_read = app.chats[1].viewpoint.as_ref().and_then(|v| v.last_message_read_date_time.clone());
+        assert!(!app.set_chat_unread_marker(last_read.as_deref(), 0));
+    }
+
+    #[test]
+    fn opening_a_channel_places_the_divider_once() {
+        let mut app = make_app();
+        app.view_mode = ViewMode::Teams;
+        app.channels = app.channels_cache["t1"].clone();
+        app.channel_message_cache.get_mut("ch1").unwrap().push(make_message("p2", "bob", "2026-03-01T12:00:00Z"));
+        app.channel_messages = app.channel_message_cache["ch1"].clone();
+        assert!(app.mark_selected_channel_read());
+        assert_eq!(app.channel_unread_marker.as_deref(), Some("p2"));
+        assert!(!app.channel_has_unread("ch1"));
+        assert!(!app.mark_selected_channel_read());
+    }
+
+    #[test]
+    fn polled_messages_merge_into_cache_and_roll_up() {
+        let mut app = make_app();
+        let polled = vec![
+            make_message("p1", "alice", "2026-03-01T10:00:00Z"),
+            make_message("p2", "bob", "2026-03-01T12:00:00Z"),
+            make_message("p3", "carol", "2026-03-01T13:00:00Z"),
+        ];
+        assert!(app.merge_channel_activity("ch1", polled.clone()));
+        assert_eq!(app.channel_message_cache["ch1"].len(), 3);
+        assert_eq!(app.channel_unread_count("ch1"), 2);
+        assert_eq!(app.team_unread_count("t1"), 2);
+        assert_eq!(app.total_channel_unread(), 2);
+        assert!(!app.merge_channel_activity("ch1", polled));
+    }
+
+    #[test]
+    fn first_poll_of_unknown_channel_starts_read() {
+        let mut app = make_app();
+        assert!(app.merge_channel_activity("ch2", vec![make_message("q1", "bob", "2026-03-01T12:00:00Z")]));
+        assert_eq!(app.channel_unread_count("ch2"), 0);
+    }
+
+    #[test]
+    fn persisted_read_time_counts_activity_while_away() {
+        let mut app = make_app();
+        app.channel_last_read.insert("ch2".to_string(), "2026-03-01T11:00:00Z".to_string());
+        app.merge_channel_activity("ch2", vec![make_message("q1", "bob", "2026-03-01T12:00:00Z")]);
+        assert_eq!(app.channel_unread_count("ch2"), 1);
+    }
+
+    #[test]
+    fn poll_targets_skip_open_channel() {
+        let mut app = make_app();
+        assert_eq!(app.channel_poll_targets(None), vec![("t1".to_string(), "ch1".to_string())]);
+        assert!(app.channel_poll_targets(Some("t2")).is_empty());
+        app.view_mode = ViewMode::Teams;
+        app.channels = app.channels_cache["t1"].clone();
+        assert!(app.channel_poll_targets(None).is_empty());
+    }
+
+    #[test]
+    fn poll_targets_rotate_other_teams_into_each_round() {
+        let mut app = make_app();
+        let channel = |id: &str| Channel {
+            id: id.to_string(),
+            display_name: id.to_string(),
+            description: None,
+            membership_type: None,
+        };
+        let target = |t: &str, id: &str| (t.to_string(), id.to_string());
+        app.teams.push(Team { id: "t2".to_string(), display_name: "T2".to_string(), description: None });
+        let t2: Vec<Channel> = (0..12).map(|i| channel(&format!("t2-{}", i))).collect();
+        app.channels_cache.insert("t2".to_string(), t2);
+        // The selected team and opened channels every round, other teams in turn
+        app.note_channel_opened("t2", "t2-7");
+        app.note_channel_opened("t2", "gone");
+        let mut rotated = std::collections::HashSet::new();
+        for _ in 0..3 {
+            let targets = app.channel_poll_targets(None);
+            assert_eq!(targets[..2], [target("t1", "ch1"), target("t2", "t2-7")]);
+            assert_eq!(targets.len(), 2 + ttyms::app::CHANNEL_POLL_ROTATION);
+            rotated.extend(targets[2..].iter().cloned());
+            app.mark_channels_polled();
+        }
+        assert_eq!(rotated.len(), 11);
+        assert!(app.channel_poll_targets(Some("t2")).iter().all(|(t, _)| t == "t2"));
+
+        let many: Vec<Channel> = (0..30).map(|i| channel(&format!("many{}", i))).collect();
+        app.channels_cache.insert("t1".to_string(), many);
+        let targets = app.channel_poll_targets(None);
+        assert_eq!(targets.len(), ttyms::app::CHANNEL_POLL_MAX);
+        assert!(targets[..15].iter().all(|(t, _)| t == "t1"));
+    }
+
+    #[test]
+    fn throttled_polls_back_off() {
+        let mut app = make_app();
+        assert_eq!(app.channel_poll_interval(), ttyms::app::CHANNEL_POLL_INTERVAL);
+        app.channel_poll_throttled();
+        assert_eq!(app.channel_poll_interval(), ttyms::app::CHANNEL_POLL_INTERVAL * 2);
+        for _ in 0..10 {
+            app.channel_poll_throttled();
+        }
+        assert_eq!(app.channel_poll_interval(), ttyms::app::CHANNEL_POLL_INTERVAL * 16);
+    }
+}
+
+#[cfg(test)]
+mod mark_read_tests {
+    use crate::fixtures::{make_chat, make_message, signed_in_app};
+    use ttyms::app::{App, PaletteAction, PaletteItemKind};
+    use ttyms::config::MarkReadMode;
+
+    fn make_app() -> App {
+        let mut app = signed_in_app();
+        app.set_chats(vec![make_chat("c1", "group", 0), make_chat("c2", "group", 2), make_chat("c3", "group", 1)]);
+        app
+    }
+
+    #[test]
+    fn schedule_follows_the_mode() {
+        let mut app = make_app();
+        assert!(app.schedule_mark_read("c1"));
+        assert!(app.pending_mark_read.is_none());
+
+        app.mark_read_mode = MarkReadMode::Manual;
+        assert!(!app.schedule_mark_read("c1"));
+        assert!(app.pending_mark_read.is_none());
+
+        app.mark_read_mode = MarkReadMode::After(60);
+        assert!(!app.schedule_mark_read("c1"));
+        assert_eq!(app.take_due_mark_read(), None);
+        assert!(app.pending_mark_read.is_some());
+    }
+
+    #[test]
+    fn due_mark_read_only_applies_to_the_open_chat() {
+        let mut app = make_app();
+        app.pending_mark_read = Some(("c1".to_string(), std::time::Instant::now()));
+        assert_eq!(app.take_due_mark_read(), Some("c1".to_string()));
+        assert!(app.pending_mark_read.is_none());
+
+        app.pending_mark_read = Some(("c2".to_string(), std::time::Instant::now()));
+        assert_eq!(app.take_due_mark_read(), None);
+        assert!(app.pending_mark_read.is_none());
+    }
+
+    #[test]
+    fn mark_unread_from_the_selected_message() {
+        let mut app = make_app();
+        app.mark_read_mode = MarkReadMode::After(60);
+        app.schedule_mark_read("c1");
+        app.messages = vec![
+            make_message("m1", "alice", "2026-03-01T09:00:00Z"),
+            make_message("m2", "bob", "2026-03-01T09:30:00Z"),
+            make_message("m3", "me", "2026-03-01T10:00:00Z"),
+        ];
+        assert_eq!(app.mark_unread_from(), None);
+        app.selected_message = Some(1);
+        let last_read = app.mark_unread_from();
+        assert_eq!(last_read.as_deref(), Some("2026-03-01T09:00:00Z"));
+        app.mark_selected_chat_unread(last_read);
+        assert!(app.pending_mark_read.is_none());
+        assert_eq!(app.chats[0].unread_count(), 1);
+        assert_eq!(app.total_unread, 4);
+    }
+
+    #[test]
+    fn mark_unread_with_stale_selection_does_not_panic() {
+        let mut app = make_app();
+        app.messages = vec![make_message("m1", "alice", "2026-03-01T09:00:00Z")];
+        // A refresh shrank the messages after the selection was made
+        app.selected_message = Some(5);
+        assert_eq!(app.mark_unread_from(), None);
+        app.mark_selected_chat_unread(None);
+        assert_eq!(app.chats[0].unread_count(), 1);
+    }
+
+    #[test]
+    fn mark_all_read_clears_unread_chats() {
+        let mut app = make_app();
+        assert_eq!(app.unread_chat_ids(), vec!["c2".to_string(), "c3".to_string()]);
+        app.set_chats_read(&app.unread_chat_ids());
+        assert!(app.unread_chat_ids().is_empty());
+        assert_eq!(app.total_unread, 0);
+    }
+
+    #[test]
+    fn palette_offers_read_actions() {
+        let mut app = make_app();
+        app.open_command_palette();
+        let actions: Vec<PaletteAction> = app
+            .palette_items
+            .iter()
+            .filter_map(|item| match &item.kind {
+                PaletteItemKind::Action(action) => Some(action.clone()),
+                _ => None,
+            })
+            .collect();
+        assert!(actions.contains(&PaletteAction::MarkRead));
+        assert!(actions.contains(&PaletteAction::MarkUnread));
+        assert!(actions.contains(&PaletteAction::MarkAllRead));
     }
 }
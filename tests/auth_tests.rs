@@ -2,7 +2,8 @@
 
 #[cfg(test)]
 mod token_tests {
-    use ttyms::auth::TokenResponse;
+    use ttyms::auth::{scopes, TokenResponse};
+    use ttyms::config::Config;
 
     fn make_token(expires_in: u64, age_secs: u64) -> TokenResponse {
         let now = std::time::SystemTime::now()
@@ -15,6 +16,7 @@ mod token_tests {
             expires_in,
             token_type: "Bearer".to_string(),
             obtained_at: now.saturating_sub(age_secs),
+            scope: None,
         }
     }
 
@@ -49,6 +51,7 @@ mod token_tests {
             expires_in: 3600,
             token_type: "Bearer".to_string(),
             obtained_at: 0,
+            scope: None,
         };
         let stamped = token.with_timestamp();
         let now = std::time::SystemTime::now()
@@ -57,6 +60,31 @@ mod token_tests {
             .as_secs();
         assert!(stamped.obtained_at >= now - 2 && stamped.obtained_at <= now + 1);
     }
+
+    fn config(channel_message_write: bool) -> Config {
+        toml::from_str(&format!(
+            "client_id = \"\"\ntenant_id = \"common\"\nchannel_message_write = {}",
+            channel_message_write
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn channel_write_scope_is_opt_in() {
+        assert!(!scopes(&config(false)).contains("ChannelMessage.ReadWrite"));
+        assert!(scopes(&config(true)).ends_with(" ChannelMessage.ReadWrite"));
+    }
+
+    #[test]
+    fn token_without_opted_in_scope_needs_new_consent() {
+        let mut token = make_token(3600, 0);
+        token.scope = Some("User.Read ChannelMessage.Send".to_string());
+        assert!(token.grants_scopes_for(&config(false)));
+        assert!(!token.grants_scopes_for(&config(true)));
+
+        token.scope = Some("https://graph.microsoft.com/ChannelMessage.ReadWrite User.Read".to_string());
+        assert!(token.grants_scopes_for(&config(true)));
+    }
 }
 
 #[cfg(test)]
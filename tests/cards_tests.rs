@@ -0,0 +1,226 @@
+//! Tests for the cards module: Adaptive Card, hero card and connector card parsing
+
+#[cfg(test)]
+mod adaptive_card_tests {
+    use ttyms::cards::{is_web_url, parse_card, CardAction, CardLine, TextEmphasis, ADAPTIVE_CARD};
+    use ttyms::models::ChatMessageAttachment;
+
+    fn make_card(content_type: &str, content: &str) -> ChatMessageAttachment {
+        ChatMessageAttachment {
+            id: Some("card-1".to_string()),
+            content_type: Some(content_type.to_string()),
+            content_url: None,
+            name: None,
+            content: Some(content.to_string()),
+        }
+    }
+
+    fn text(text: &str, emphasis: TextEmphasis) -> CardLine {
+        CardLine::Text { text: text.to_string(), emphasis }
+    }
+
+    #[test]
+    fn text_blocks_and_fact_set() {
+        let card = parse_card(&make_card(
+            ADAPTIVE_CARD,
+            r#"{"type":"AdaptiveCard","body":[
+                {"type":"TextBlock","text":"Build **failed**","weight":"Bolder"},
+                {"type":"TextBlock","text":"main #42","isSubtle":true},
+                {"type":"FactSet","facts":[{"title":"Branch","value":"main"},{"title":"Duration","value":"3m"}]}
+            ]}"#,
+        ))
+        .unwrap();
+        assert_eq!(card.kind, "Adaptive Card");
+        assert_eq!(
+            card.lines,
+            vec![
+                text("Build failed", TextEmphasis::Heading),
+                text("main #42", TextEmphasis::Subtle),
+                CardLine::Fact { title: "Branch".to_string(), value: "main".to_string() },
+                CardLine::Fact { title: "Duration".to_string(), value: "3m".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn containers_and_images_are_flattened() {
+        let card = parse_card(&make_card(
+            ADAPTIVE_CARD,
+            r#"{"body":[{"type":"Container","items":[
+                {"type":"Image","url":"https://example.com/a.png","altText":"logo"},
+                {"type":"Image","url":"https://example.com/b.png"},
+                {"type":"TextBlock","text":"hidden","isVisible":false}
+            ]}]}"#,
+        ))
+        .unwrap();
+        assert_eq!(
+            card.lines,
+            vec![
+                CardLine::Image { alt: "logo".to_string() },
+                CardLine::Image { alt: "image".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn single_text_columns_are_joined() {
+        let card = parse_card(&make_card(
+            ADAPTIVE_CARD,
+            r#"{"body":[{"type":"ColumnSet","columns":[
+                {"type":"Column","items":[{"type":"TextBlock","text":"Status"}]},
+                {"type":"Column","items":[{"type":"TextBlock","text":"Passed"}]}
+            ]}]}"#,
+        ))
+        .unwrap();
+        assert_eq!(card.lines, vec![text("Status  │  Passed", TextEmphasis::Normal)]);
+    }
+
+    #[test]
+    fn rich_columns_are_stacked() {
+        let card = parse_card(&make_card(
+            ADAPTIVE_CARD,
+            r#"{"body":[{"type":"ColumnSet","columns":[
+                {"type":"Column","items":[{"type":"TextBlock","text":"a"},{"type":"TextBlock","text":"b"}]},
+                {"type":"Column","items":[{"type":"TextBlock","text":"c"}]}
+            ]}]}"#,
+        ))
+        .unwrap();
+        assert_eq!(card.lines.len(), 3);
+    }
+
+    #[test]
+    fn open_url_and_submit_actions() {
+        let card = parse_card(&make_card(
+            ADAPTIVE_CARD,
+            r#"{"body":[],"actions":[
+                {"type":"Action.OpenUrl","title":"View run","url":"https://ci.example.com/42"},
+                {"type":"Action.Submit","title":"Approve","data":{}}
+            ]}"#,
+        ))
+        .unwrap();
+        assert_eq!(
+            card.actions,
+            vec![
+                CardAction::OpenUrl {
+                    title: "View run".to_string(),
+                    url: "https://ci.example.com/42".to_string(),
+                },
+                CardAction::Unsupported {
+                    title: "Approve".to_string(),
+                    kind: "Action.Submit".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn non_web_open_urls_are_unsupported() {
+        let card = parse_card(&make_card(
+            ADAPTIVE_CARD,
+            r#"{"body":[],"actions":[
+                {"type":"Action.OpenUrl","title":"Local","url":"file:///etc/passwd"},
+                {"type":"Action.OpenUrl","title":"Settings","url":"ms-settings:privacy"},
+                {"type":"Action.OpenUrl","title":"Handler","url":"custom-app://run?cmd=x"},
+                {"type":"Action.OpenUrl","title":"Script","url":"javascript:alert(1)"},
+                {"type":"Action.OpenUrl","title":"Secure","url":"HTTPS://example.com"}
+            ]}"#,
+        ))
+        .unwrap();
+        assert_eq!(card.actions.len(), 5);
+        for action in &card.actions[..4] {
+            assert!(matches!(action, CardAction::Unsupported { kind, .. } if kind == "Non-web link"));
+        }
+        assert!(matches!(card.actions[4], CardAction::OpenUrl { .. }));
+    }
+
+    #[test]
+    fn web_url_check() {
+        assert!(is_web_url("https://example.com"));
+        assert!(is_web_url("http://example.com/path"));
+        assert!(!is_web_url("file:///C:/Windows/system32/calc.exe"));
+        assert!(!is_web_url("ms-word:ofe|u|https://example.com/doc.docx"));
+        assert!(!is_web_url("httpsx://example.com"));
+        assert!(!is_web_url(""));
+    }
+
+    #[test]
+    fn markdown_links_keep_text() {
+        let card = parse_card(&make_card(
+            ADAPTIVE_CARD,
+            r#"{"body":[{"type":"TextBlock","text":"See [the docs](https://example.com) [now"}]}"#,
+        ))
+        .unwrap();
+        assert_eq!(card.lines, vec![text("See the docs [now", TextEmphasis::Normal)]);
+    }
+
+    #[test]
+    fn invalid_json_returns_none() {
+        assert!(parse_card(&make_card(ADAPTIVE_CARD, "not json")).is_none());
+    }
+
+    #[test]
+    fn non_card_attachment_returns_none() {
+        assert!(parse_card(&make_card("reference", "{}")).is_none());
+    }
+}
+
+#[cfg(test)]
+mod connector_card_tests {
+    use ttyms::cards::{parse_card, CardAction, CardLine, TextEmphasis, CONNECTOR_CARD, HERO_CARD};
+    use ttyms::models::ChatMessageAttachment;
+
+    fn make_card(content_type: &str, content: &str) -> ChatMessageAttachment {
+        ChatMessageAttachment {
+            id: None,
+            content_type: Some(content_type.to_string()),
+            content_url: None,
+            name: None,
+            content: Some(content.to_string()),
+        }
+    }
+
+    #[test]
+    fn hero_card_title_text_and_buttons() {
+        let card = parse_card(&make_card(
+            HERO_CARD,
+            r#"{"title":"Incident","subtitle":"sev2","text":"Disk <b>full</b>",
+                "buttons":[{"type":"openUrl","title":"Runbook","value":"https://wiki/runbook"},
+                           {"type":"imBack","title":"Ack","value":"ack"}]}"#,
+        ))
+        .unwrap();
+        assert_eq!(card.kind, "Card");
+        assert_eq!(card.lines.len(), 3);
+        assert_eq!(
+            card.lines[0],
+            CardLine::Text { text: "Incident".to_string(), emphasis: TextEmphasis::Heading }
+        );
+        assert_eq!(
+            card.actions[0],
+            CardAction::OpenUrl { title: "Runbook".to_string(), url: "https://wiki/runbook".to_string() }
+        );
+        assert!(matches!(card.actions[1], CardAction::Unsupported { .. }));
+    }
+
+    #[test]
+    fn connector_sections_facts_and_open_uri() {
+        let card = parse_card(&make_card(
+            CONNECTOR_CARD,
+            r#"{"title":"Deploy","sections":[{"activityTitle":"prod","facts":[{"name":"Version","value":"1.2.3"}]}],
+                "potentialAction":[{"@type":"OpenUri","name":"Open","targets":[{"os":"default","uri":"https://deploy/1"}]},
+                                   {"@type":"HttpPOST","name":"Rollback"}]}"#,
+        ))
+        .unwrap();
+        assert_eq!(card.kind, "Connector Card");
+        assert!(card.lines.contains(&CardLine::Fact {
+            title: "Version".to_string(),
+            value: "1.2.3".to_string(),
+        }));
+        assert_eq!(
+            card.actions,
+            vec![
+                CardAction::OpenUrl { title: "Open".to_string(), url: "https://deploy/1".to_string() },
+                CardAction::Unsupported { title: "Rollback".to_string(), kind: "HttpPOST".to_string() },
+            ]
+        );
+    }
+}
@@ -0,0 +1,39 @@
+//! Tests for the clipboard module: per-platform tool selection and error labels
+
+#[cfg(test)]
+mod clipboard_tests {
+    use ttyms::clipboard::{image_tools, ClipboardError};
+    use ttyms::logging::is_safe_event_label;
+
+    fn programs(os: &str, wayland: bool) -> Vec<&'static str> {
+        image_tools(os, wayland).into_iter().map(|t| t.program).collect()
+    }
+
+    #[test]
+    fn linux_prefers_session_native_tool() {
+        assert_eq!(programs("linux", true), vec!["wl-paste", "xclip"]);
+        assert_eq!(programs("linux", false), vec!["xclip", "wl-paste"]);
+        assert_eq!(programs("freebsd", false), vec!["xclip", "wl-paste"]);
+    }
+
+    #[test]
+    fn macos_and_windows_tools() {
+        assert_eq!(programs("macos", false), vec!["pngpaste"]);
+        assert_eq!(programs("windows", false), vec!["powershell"]);
+    }
+
+    #[test]
+    fn tools_request_png_output() {
+        for tool in image_tools("linux", true) {
+            assert!(tool.args.contains(&"image/png"), "{} should ask for PNG", tool.program);
+        }
+    }
+
+    #[test]
+    fn error_labels_are_safe() {
+        for error in [ClipboardError::NoTool, ClipboardError::NoImage] {
+            assert!(is_safe_event_label(error.as_label()));
+            assert!(!error.describe().is_empty());
+        }
+    }
+}
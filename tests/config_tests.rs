@@ -0,0 +1,40 @@
+//! Tests for config values parsed from `config.toml`
+
+#[cfg(test)]
+mod mark_read_mode_tests {
+    use ttyms::config::MarkReadMode;
+
+    #[test]
+    fn parses_view_manual_and_seconds() {
+        assert_eq!(MarkReadMode::parse("view"), Some(MarkReadMode::OnView));
+        assert_eq!(MarkReadMode::parse(""), Some(MarkReadMode::OnView));
+        assert_eq!(MarkReadMode::parse(" Manual "), Some(MarkReadMode::Manual));
+        assert_eq!(MarkReadMode::parse("5"), Some(MarkReadMode::After(5)));
+        assert_eq!(MarkReadMode::parse("10s"), Some(MarkReadMode::After(10)));
+        assert_eq!(MarkReadMode::parse("0"), Some(MarkReadMode::OnView));
+        assert_eq!(MarkReadMode::parse("soon"), None);
+        assert_eq!(MarkReadMode::parse("-3"), None);
+    }
+
+    #[test]
+    fn setting_round_trips() {
+        for mode in [MarkReadMode::OnView, MarkReadMode::After(7), MarkReadMode::Manual] {
+            assert_eq!(MarkReadMode::parse(&mode.as_setting()), Some(mode));
+        }
+        assert_eq!(MarkReadMode::After(7).label(), "after 7s");
+    }
+}
+
+#[cfg(test)]
+mod config_defaults_tests {
+    use ttyms::config::Config;
+
+    #[test]
+    fn delete_confirmation_defaults_on() {
+        let config: Config = toml::from_str("client_id = \"\"\ntenant_id = \"common\"").unwrap();
+        assert!(config.confirm_delete);
+        let config: Config =
+            toml::from_str("client_id = \"\"\ntenant_id = \"common\"\nconfirm_delete = false").unwrap();
+        assert!(!config.confirm_delete);
+    }
+}
@@ -0,0 +1,79 @@
+//! Tests for the downloads module: file naming, collision handling and progress labels
+
+#[cfg(test)]
+mod file_name_tests {
+    use std::path::{Path, PathBuf};
+    use ttyms::downloads::{sanitize_file_name, unique_path_with};
+
+    #[test]
+    fn sanitize_strips_separators_and_leading_dots() {
+        assert_eq!(sanitize_file_name("../../etc/passwd"), "_.._etc_passwd");
+        assert_eq!(sanitize_file_name("a\\b:c.txt"), "a_b_c.txt");
+        assert_eq!(sanitize_file_name(".hidden"), "hidden");
+        assert_eq!(sanitize_file_name("  "), "download");
+        assert_eq!(sanitize_file_name("Report Q3.xlsx"), "Report Q3.xlsx");
+    }
+
+    #[test]
+    fn unique_path_keeps_free_name() {
+        let dir = Path::new("/tmp/dl");
+        assert_eq!(unique_path_with(dir, "a.pdf", |_| false), dir.join("a.pdf"));
+    }
+
+    #[test]
+    fn unique_path_numbers_collisions_before_extension() {
+        let dir = Path::new("/tmp/dl");
+        let taken: Vec<PathBuf> = vec![dir.join("a.pdf"), dir.join("a (1).pdf")];
+        assert_eq!(
+            unique_path_with(dir, "a.pdf", |p| taken.iter().any(|t| t == p)),
+            dir.join("a (2).pdf")
+        );
+        let taken = [dir.join("README")];
+        assert_eq!(
+            unique_path_with(dir, "README", |p| taken.iter().any(|t| t == p)),
+            dir.join("README (1)")
+        );
+    }
+}
+
+#[cfg(test)]
+mod progress_tests {
+    use ttyms::downloads::{download_dir, format_bytes, DownloadProgress};
+
+    #[test]
+    fn formats_byte_counts() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MB");
+    }
+
+    #[test]
+    fn label_with_known_total_shows_percent() {
+        let progress = DownloadProgress {
+            name: "a.pdf".to_string(),
+            received: 512 * 1024,
+            total: Some(1024 * 1024),
+        };
+        assert_eq!(progress.percent(), Some(50));
+        assert_eq!(progress.label(), "⬇ a.pdf 50% (512.0 KB / 1.0 MB)");
+    }
+
+    #[test]
+    fn label_without_total_shows_bytes() {
+        let mut progress = DownloadProgress::new("a.pdf");
+        progress.received = 100;
+        assert_eq!(progress.percent(), None);
+        assert_eq!(progress.label(), "⬇ a.pdf 100 B");
+    }
+
+    #[test]
+    fn explicit_download_dir_is_used() {
+        assert_eq!(
+            download_dir("/srv/files"),
+            Some(std::path::PathBuf::from("/srv/files"))
+        );
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(download_dir("~/dl"), Some(home.join("dl")));
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Tests for the emoji module: table lookups, search, skin tones and picker state
+
+#[cfg(test)]
+mod emoji_tests {
+    use ttyms::emoji::*;
+
+    #[test]
+    fn every_category_has_emoji() {
+        for category in Category::ALL {
+            assert!(in_category(category).count() > 0, "{:?} is empty", category);
+        }
+    }
+
+    #[test]
+    fn shortcodes_are_unique() {
+        let mut codes: Vec<&str> = EMOJIS.iter().map(|e| e.shortcode).collect();
+        codes.sort_unstable();
+        let before = codes.len();
+        codes.dedup();
+        assert_eq!(codes.len(), before);
+    }
+
+    #[test]
+    fn search_ranks_shortcode_prefix_first() {
+        let results = search("thumbs");
+        assert_eq!(results[0].emoji, "👍");
+        assert!(results.iter().any(|e| e.emoji == "👎"));
+    }
+
+    #[test]
+    fn search_matches_keywords() {
+        let results = search("celebrate");
+        assert!(results.iter().any(|e| e.shortcode == "tada"));
+        assert!(search("").is_empty());
+    }
+
+    #[test]
+    fn skin_tone_is_inserted_after_first_code_point() {
+        assert_eq!(apply_skin_tone("👍", 3), "👍🏽");
+        assert_eq!(apply_skin_tone("✌️", 1), "✌🏻");
+        assert_eq!(apply_skin_tone("👍", 0), "👍");
+    }
+
+    #[test]
+    fn lookup_ignores_skin_tone_and_variation_selector() {
+        assert_eq!(lookup("👍🏿").map(|e| e.shortcode), Some("thumbsup"));
+        assert_eq!(lookup("❤").map(|e| e.shortcode), Some("heart"));
+        assert!(lookup("not an emoji").is_none());
+    }
+
+    #[test]
+    fn push_recent_moves_to_front_and_caps() {
+        let mut recent = vec!["👍".to_string(), "🎉".to_string()];
+        push_recent(&mut recent, "🎉", 2);
+        assert_eq!(recent, vec!["🎉", "👍"]);
+        push_recent(&mut recent, "🚀", 2);
+        assert_eq!(recent, vec!["🚀", "🎉"]);
+    }
+
+    #[test]
+    fn recent_tab_falls_back_to_default_reactions() {
+        let picker = EmojiPicker::new(0);
+        assert_eq!(picker.items(&[]), DEFAULT_REACTIONS.to_vec());
+        let recent = vec!["🚀".to_string()];
+        assert_eq!(picker.items(&recent), recent);
+    }
+
+    #[test]
+    fn picker_applies_skin_tone_to_toned_emoji_only() {
+        let mut picker = EmojiPicker::new(2);
+        picker.insert_char('w');
+        picker.insert_char('a');
+        picker.insert_char('v');
+        picker.insert_char('e');
+        assert_eq!(picker.selected_emoji(&[]).as_deref(), Some("👋🏼"));
+        picker.query = "rocket".to_string();
+        assert_eq!(picker.selected_emoji(&[]).as_deref(), Some("🚀"));
+    }
+
+    #[test]
+    fn picker_tabs_wrap_and_reset_selection() {
+        let mut picker = EmojiPicker::new(0);
+        picker.selected = 3;
+        picker.prev_tab();
+        assert_eq!(picker.category(), Some(Category::Flags));
+        assert_eq!(picker.selected, 0);
+        picker.next_tab();
+        assert_eq!(picker.category(), None);
+    }
+
+    #[test]
+    fn picker_grid_movement_stays_in_bounds() {
+        let mut picker = EmojiPicker::new(0);
+        picker.move_selection(-1, 25);
+        assert_eq!(picker.selected, 0);
+        picker.move_selection(PICKER_COLUMNS as isize, 25);
+        assert_eq!(picker.selected, PICKER_COLUMNS);
+        picker.move_selection(PICKER_COLUMNS as isize * 2, 25);
+        assert_eq!(picker.selected, PICKER_COLUMNS);
+    }
+
+    #[test]
+    fn backspace_edits_query_by_character() {
+        let mut picker = EmojiPicker::new(0);
+        picker.insert_char('é');
+        picker.delete_char();
+        assert!(picker.query.is_empty());
+        assert_eq!(picker.cursor, 0);
+        picker.delete_char();
+    }
+
+    #[test]
+    fn skin_tone_cycles_back_to_default() {
+        let mut picker = EmojiPicker::new(5);
+        picker.cycle_skin_tone();
+        assert_eq!(picker.skin_tone, 0);
+        assert_eq!(EmojiPicker::new(9).skin_tone, SKIN_TONES.len() - 1);
+    }
+
+    #[test]
+    fn shortcode_at_needs_two_characters_after_colon() {
+        assert_eq!(shortcode_at(":t", 2), None);
+        assert_eq!(shortcode_at(":ta", 3), Some((0, "ta")));
+        assert_eq!(shortcode_at("nice :thu", 9), Some((5, "thu")));
+    }
+
+    #[test]
+    fn shortcode_at_ignores_colons_inside_words() {
+        assert_eq!(shortcode_at("at 10:30", 8), None);
+        assert_eq!(shortcode_at("http://example", 14), None);
+        assert_eq!(shortcode_at(":tada: ok", 9), None);
+    }
+
+    #[test]
+    fn complete_lists_prefix_matches_first() {
+        let results = complete("thu");
+        assert_eq!(results[0].shortcode, "thumbsup");
+        assert!(results.len() <= MAX_COMPLETIONS);
+        assert!(complete("zzzzzz").is_empty());
+    }
+
+    #[test]
+    fn replace_shortcodes_converts_known_codes() {
+        assert_eq!(replace_shortcodes("ship it :rocket::tada:"), "ship it 🚀🎉");
+        assert_eq!(replace_shortcodes(":ThumbsUp: thanks"), "👍 thanks");
+    }
+
+    #[test]
+    fn replace_shortcodes_leaves_unknown_and_code_spans() {
+        assert_eq!(replace_shortcodes("at 10:30:45 :nope:"), "at 10:30:45 :nope:");
+        assert_eq!(replace_shortcodes("`:tada:` is :tada:"), "`:tada:` is 🎉");
+    }
+
+    #[test]
+    fn replace_shortcodes_needs_word_boundaries() {
+        assert_eq!(replace_shortcodes("http://host:smile:8080"), "http://host:smile:8080");
+        assert_eq!(replace_shortcodes("a:tada:b"), "a:tada:b");
+        assert_eq!(replace_shortcodes("key:rocket: value"), "key:rocket: value");
+        assert_eq!(replace_shortcodes("(:tada:), :rocket:!"), "(🎉), 🚀!");
+        assert_eq!(replace_shortcodes("done\n:tada:"), "done\n🎉");
+    }
+}
@@ -0,0 +1,88 @@
+//! Tests for the files module: directory listing, tilde expansion and path completion
+
+#[cfg(test)]
+mod files_tests {
+    use std::path::PathBuf;
+    use ttyms::files::{
+        complete_from, complete_path, describe_file_type, expand_tilde_with, list_dir,
+        sort_entries, FileEntry,
+    };
+
+    fn entry(name: &str, is_dir: bool) -> FileEntry {
+        FileEntry {
+            name: name.to_string(),
+            path: PathBuf::from(name),
+            is_dir,
+            size: 0,
+            modified: None,
+        }
+    }
+
+    /// Fresh directory under the system temp dir with a few files and folders
+    fn fixture_dir(label: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ttyms-files-{}-{}", label, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("reports")).unwrap();
+        std::fs::create_dir_all(dir.join("Recordings")).unwrap();
+        std::fs::write(dir.join("readme.txt"), b"hello").unwrap();
+        std::fs::write(dir.join("report-q3.pdf"), b"pdf").unwrap();
+        std::fs::write(dir.join(".secret"), b"x").unwrap();
+        dir
+    }
+
+    #[test]
+    fn expands_tilde_prefix_only() {
+        let home = Some(PathBuf::from("/home/u"));
+        assert_eq!(expand_tilde_with("~", home.clone()), PathBuf::from("/home/u"));
+        assert_eq!(expand_tilde_with("~/a/b", home.clone()), PathBuf::from("/home/u/a/b"));
+        assert_eq!(expand_tilde_with("/tmp/~x", home.clone()), PathBuf::from("/tmp/~x"));
+        assert_eq!(expand_tilde_with("~/a", None), PathBuf::from("~/a"));
+    }
+
+    #[test]
+    fn sorts_folders_first_case_insensitive() {
+        let mut entries = vec![entry("b.txt", false), entry("Zeta", true), entry("A.txt", false), entry("alpha", true)];
+        sort_entries(&mut entries);
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "Zeta", "A.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn completes_to_common_prefix() {
+        let candidates = vec![entry("report-q3.pdf", false), entry("reports", true)];
+        assert_eq!(complete_from("re", &candidates), Some("report".to_string()));
+        assert_eq!(complete_from("rep", &[entry("reports", true)]), Some("reports/".to_string()));
+        assert_eq!(complete_from("x", &[]), None);
+    }
+
+    #[test]
+    fn list_dir_hides_dotfiles_by_default() {
+        let dir = fixture_dir("list");
+        let names: Vec<String> = list_dir(&dir, false).unwrap().into_iter().map(|e| e.name).collect();
+        assert_eq!(names, vec!["Recordings", "reports", "readme.txt", "report-q3.pdf"]);
+        let with_hidden = list_dir(&dir, true).unwrap();
+        assert!(with_hidden.iter().any(|e| e.name == ".secret"));
+        let readme = with_hidden.iter().find(|e| e.name == "readme.txt").unwrap();
+        assert_eq!(readme.size, 5);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn complete_path_against_filesystem() {
+        let dir = fixture_dir("complete");
+        let base = format!("{}/", dir.display());
+        assert_eq!(complete_path(&format!("{}rea", base), false), Some(format!("{}readme.txt", base)));
+        assert_eq!(complete_path(&format!("{}Rec", base), false), Some(format!("{}Recordings/", base)));
+        assert_eq!(complete_path(&format!("{}.se", base), false), Some(format!("{}.secret", base)));
+        assert_eq!(complete_path(&format!("{}nothing", base), false), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn describes_common_file_types() {
+        assert_eq!(describe_file_type("a.PNG"), "Image");
+        assert_eq!(describe_file_type("q3.xlsx"), "Spreadsheet");
+        assert_eq!(describe_file_type("dump.tar.gz"), "Archive");
+        assert_eq!(describe_file_type("Makefile"), "File");
+    }
+}
@@ -0,0 +1,215 @@
+//! Tests for the graphics module: protocol detection, cell rendering and escape-sequence encoding
+
+#[cfg(test)]
+mod protocol_detection_tests {
+    use std::collections::HashMap;
+    use ttyms::graphics::{detect_protocol, GraphicsProtocol};
+
+    fn detect(vars: &[(&str, &str)]) -> GraphicsProtocol {
+        let env: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        detect_protocol(|key| env.get(key).cloned())
+    }
+
+    #[test]
+    fn kitty_detected_from_window_id() {
+        assert_eq!(detect(&[("KITTY_WINDOW_ID", "1")]), GraphicsProtocol::Kitty);
+        assert_eq!(detect(&[("TERM", "xterm-kitty")]), GraphicsProtocol::Kitty);
+    }
+
+    #[test]
+    fn iterm2_and_wezterm_use_inline_images() {
+        assert_eq!(detect(&[("TERM_PROGRAM", "iTerm.app")]), GraphicsProtocol::Iterm2);
+        assert_eq!(detect(&[("TERM_PROGRAM", "WezTerm")]), GraphicsProtocol::Iterm2);
+    }
+
+    #[test]
+    fn sixel_terminals_detected() {
+        assert_eq!(detect(&[("TERM", "foot")]), GraphicsProtocol::Sixel);
+        assert_eq!(detect(&[("TERM", "xterm-sixel")]), GraphicsProtocol::Sixel);
+    }
+
+    #[test]
+    fn multiplexer_falls_back_to_cells() {
+        let vars = [("TMUX", "/tmp/tmux"), ("KITTY_WINDOW_ID", "1"), ("COLORTERM", "truecolor")];
+        assert_eq!(detect(&vars), GraphicsProtocol::Truecolor);
+    }
+
+    #[test]
+    fn plain_terminal_is_grayscale() {
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), GraphicsProtocol::Grayscale);
+        assert_eq!(detect(&[]), GraphicsProtocol::Grayscale);
+    }
+
+    #[test]
+    fn parse_accepts_names_and_rejects_auto() {
+        assert_eq!(GraphicsProtocol::parse("Kitty"), Some(GraphicsProtocol::Kitty));
+        assert_eq!(GraphicsProtocol::parse("iterm2"), Some(GraphicsProtocol::Iterm2));
+        assert_eq!(GraphicsProtocol::parse(" sixel "), Some(GraphicsProtocol::Sixel));
+        assert_eq!(GraphicsProtocol::parse("auto"), None);
+        for protocol in [
+            GraphicsProtocol::Kitty,
+            GraphicsProtocol::Iterm2,
+            GraphicsProtocol::Sixel,
+            GraphicsProtocol::Truecolor,
+            GraphicsProtocol::Grayscale,
+        ] {
+            assert_eq!(GraphicsProtocol::parse(protocol.as_str()), Some(protocol));
+        }
+    }
+}
+
+#[cfg(test)]
+mod rendering_tests {
+    use image::{DynamicImage, Rgb, RgbImage};
+    use ttyms::graphics::{
+        crop_region, fit_cells, luma_pair_to_block, render_cells, render_preview, encode_png,
+        GraphicsProtocol, ImagePreview, PreviewDecodeFailure,
+    };
+
+    #[test]
+    fn fit_cells_preserves_aspect_ratio() {
+        // 200×100 image, square-pixel cells: width-limited
+        assert_eq!(fit_cells(200, 100, 40, 40, 1, 1), (40, 20));
+        // 8×16 cells halve the row count
+        assert_eq!(fit_cells(200, 100, 40, 40, 8, 16), (40, 10));
+        assert_eq!(fit_cells(0, 100, 40, 40, 8, 16), (0, 0));
+    }
+
+    #[test]
+    fn crop_region_zooms_around_center() {
+        assert_eq!(crop_region(100, 50, 1, (0.5, 0.5)), (0, 0, 100, 50));
+        assert_eq!(crop_region(100, 50, 2, (0.5, 0.5)), (25, 13, 50, 25));
+        // Center near an edge is clamped so the region stays inside the image
+        assert_eq!(crop_region(100, 50, 2, (0.0, 1.0)), (0, 25, 50, 25));
+    }
+
+    #[test]
+    fn luma_blocks() {
+        assert_eq!(luma_pair_to_block(255, 0), '▀');
+        assert_eq!(luma_pair_to_block(0, 255), '▄');
+        assert_eq!(luma_pair_to_block(0, 0), ' ');
+        assert_eq!(luma_pair_to_block(255, 255), '█');
+    }
+
+    #[test]
+    fn render_cells_color_uses_half_blocks() {
+        let img = DynamicImage::new_rgb8(20, 20);
+        match render_cells(&img, 10, 10, true) {
+            ImagePreview::Truecolor(rows) => {
+                assert_eq!(rows.len(), 5);
+                assert!(rows.iter().all(|row| row.len() == 10));
+            }
+            other => panic!("unexpected preview {:?}", other),
+        }
+        let rendered = render_cells(&img, 10, 10, false);
+        assert!(matches!(rendered, ImagePreview::Grayscale(_)));
+        assert_eq!(rendered.height(), 5);
+    }
+
+    #[test]
+    fn render_preview_keeps_colors() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, Rgb([200, 10, 10])));
+        let png = encode_png(&img).unwrap();
+        match render_preview(&png, GraphicsProtocol::Truecolor).unwrap() {
+            ImagePreview::Truecolor(rows) => assert_eq!(rows[0][0].top, [200, 10, 10]),
+            other => panic!("unexpected preview {:?}", other),
+        }
+        match render_preview(&png, GraphicsProtocol::Grayscale).unwrap() {
+            ImagePreview::Grayscale(lines) => assert!(lines[0].starts_with('┌')),
+            other => panic!("unexpected preview {:?}", other),
+        }
+    }
+
+    #[test]
+    fn render_preview_rejects_invalid_bytes() {
+        assert_eq!(
+            render_preview(b"not an image", GraphicsProtocol::Truecolor),
+            Err(PreviewDecodeFailure::InvalidBytes)
+        );
+    }
+}
+
+#[cfg(test)]
+mod sequence_tests {
+    use image::{Rgb, RgbImage};
+    use ttyms::graphics::{iterm2_sequence, kitty_sequence, sixel_sequence};
+
+    #[test]
+    fn kitty_sequence_is_chunked() {
+        let png = vec![0u8; 8000];
+        let seq = kitty_sequence(&png, 10, 5);
+        assert!(seq.starts_with("\x1b_Ga=T,f=100,q=2,c=10,r=5,m=1;"));
+        assert!(seq.ends_with("\x1b\\"));
+        assert!(seq.contains("\x1b_Gm=0;"));
+        // 8000 bytes → 10668 base64 chars → 3 chunks of at most 4096
+        assert_eq!(seq.matches("\x1b_G").count(), 3);
+    }
+
+    #[test]
+    fn iterm2_sequence_has_size_and_dimensions() {
+        let seq = iterm2_sequence(b"abc", 12, 6);
+        assert_eq!(
+            seq,
+            "\x1b]1337;File=inline=1;size=3;width=12;height=6;preserveAspectRatio=1:YWJj\x07"
+        );
+    }
+
+    #[test]
+    fn sixel_sequence_framing_and_runs() {
+        let img = RgbImage::from_pixel(10, 6, Rgb([255, 255, 255]));
+        let seq = sixel_sequence(&img);
+        assert!(seq.starts_with("\x1bP0;1;0q\"1;1;10;6"));
+        assert!(seq.ends_with("-\x1b\\"));
+        // White maps to palette index 215; one full band of 10 identical columns
+        assert!(seq.contains("#215;2;100;100;100"));
+        assert!(seq.contains("#215!10~"));
+    }
+}
+
+#[cfg(test)]
+mod inline_image_tests {
+    use image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+    use ttyms::graphics::{
+        encode_png, inline_display_size, prepare_inline_image, InlineImageError,
+    };
+
+    fn encode(img: &DynamicImage, format: ImageFormat) -> Vec<u8> {
+        let mut buf = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut buf, format).unwrap();
+        buf.into_inner()
+    }
+
+    #[test]
+    fn png_is_sent_unchanged() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(30, 10, Rgb([1, 2, 3])));
+        let png = encode_png(&img).unwrap();
+        let inline = prepare_inline_image(png.clone()).unwrap();
+        assert_eq!(inline.content_type, "image/png");
+        assert_eq!(inline.bytes, png);
+        assert_eq!((inline.width, inline.height), (30, 10));
+    }
+
+    #[test]
+    fn other_formats_are_converted_to_png() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(8, 8, Rgb([9, 9, 9])));
+        let inline = prepare_inline_image(encode(&img, ImageFormat::Bmp)).unwrap();
+        assert_eq!(inline.content_type, "image/png");
+        assert!(inline.bytes.starts_with(&[0x89, b'P', b'N', b'G']));
+        let jpeg = prepare_inline_image(encode(&img, ImageFormat::Jpeg)).unwrap();
+        assert_eq!(jpeg.content_type, "image/jpeg");
+    }
+
+    #[test]
+    fn rejects_non_images() {
+        assert_eq!(prepare_inline_image(b"hello".to_vec()), Err(InlineImageError::NotAnImage));
+    }
+
+    #[test]
+    fn wide_images_are_scaled_for_display() {
+        assert_eq!(inline_display_size(300, 200), (300, 200));
+        assert_eq!(inline_display_size(1920, 1080), (480, 270));
+    }
+}
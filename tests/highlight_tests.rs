@@ -0,0 +1,90 @@
+//! Tests for the highlight module: language normalization and code tokenization
+
+#[cfg(test)]
+mod normalize_language_tests {
+    use ttyms::highlight::normalize_language;
+
+    #[test]
+    fn strips_language_prefix() {
+        assert_eq!(normalize_language("language-rust"), Some("rust"));
+        assert_eq!(normalize_language("lang-python"), Some("python"));
+    }
+
+    #[test]
+    fn resolves_aliases_case_insensitively() {
+        assert_eq!(normalize_language("TS"), Some("javascript"));
+        assert_eq!(normalize_language("cpp"), Some("c"));
+        assert_eq!(normalize_language("sh"), Some("bash"));
+    }
+
+    #[test]
+    fn unknown_language_returns_none() {
+        assert_eq!(normalize_language("brainfuck"), None);
+        assert_eq!(normalize_language(""), None);
+    }
+}
+
+#[cfg(test)]
+mod highlight_code_tests {
+    use ttyms::highlight::{highlight_code, TokenKind};
+
+    fn kinds_of(line: &[(TokenKind, String)], kind: TokenKind) -> Vec<String> {
+        line.iter()
+            .filter(|(k, _)| *k == kind)
+            .map(|(_, t)| t.clone())
+            .collect()
+    }
+
+    fn joined(line: &[(TokenKind, String)]) -> String {
+        line.iter().map(|(_, t)| t.as_str()).collect()
+    }
+
+    #[test]
+    fn keeps_one_entry_per_line() {
+        let lines = highlight_code(Some("rust"), "fn a() {}\n\nfn b() {}");
+        assert_eq!(lines.len(), 3);
+        assert_eq!(joined(&lines[0]), "fn a() {}");
+        assert!(lines[1].is_empty() || joined(&lines[1]).is_empty());
+    }
+
+    #[test]
+    fn highlights_keywords_strings_and_numbers() {
+        let lines = highlight_code(Some("rust"), r#"let name = "ttyms"; let n = 42;"#);
+        assert_eq!(kinds_of(&lines[0], TokenKind::Keyword), vec!["let", "let"]);
+        assert_eq!(kinds_of(&lines[0], TokenKind::String), vec!["\"ttyms\""]);
+        assert_eq!(kinds_of(&lines[0], TokenKind::Number), vec!["42"]);
+    }
+
+    #[test]
+    fn keywords_inside_identifiers_are_plain() {
+        let lines = highlight_code(Some("rust"), "letter");
+        assert!(kinds_of(&lines[0], TokenKind::Keyword).is_empty());
+    }
+
+    #[test]
+    fn line_comment_runs_to_end_of_line() {
+        let lines = highlight_code(Some("python"), "x = 1  # note: def");
+        assert_eq!(kinds_of(&lines[0], TokenKind::Comment), vec!["# note: def"]);
+    }
+
+    #[test]
+    fn block_comment_spans_lines() {
+        let lines = highlight_code(Some("c"), "/* start\nmiddle\nend */ int x;");
+        assert_eq!(kinds_of(&lines[1], TokenKind::Comment), vec!["middle"]);
+        assert_eq!(kinds_of(&lines[2], TokenKind::Comment), vec!["end */"]);
+        assert_eq!(kinds_of(&lines[2], TokenKind::Keyword), vec!["int"]);
+    }
+
+    #[test]
+    fn unknown_language_is_plain_text() {
+        let lines = highlight_code(None, "let x = \"y\"; // z");
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0], vec![(TokenKind::Plain, "let x = \"y\"; // z".to_string())]);
+    }
+
+    #[test]
+    fn tabs_are_expanded() {
+        let lines = highlight_code(None, "\tx");
+        assert_eq!(joined(&lines[0]), "    x");
+    }
+}
@@ -5,7 +5,14 @@
 
 #[cfg(test)]
 mod strip_html_tests {
-    use ttyms::models::strip_html;
+    use ttyms::models::{escape_html, strip_html};
+
+    #[test]
+    fn escape_html_round_trips_through_strip() {
+        let text = "a < b && \"c\" > d";
+        assert_eq!(escape_html(text), "a &lt; b &amp;&amp; &quot;c&quot; &gt; d");
+        assert_eq!(strip_html(&escape_html(text)), text);
+    }
 
     #[test]
     fn plain_text_unchanged() {
@@ -90,6 +97,7 @@ mod chat_tests {
                         id: None,
                         display_name: Some(name.to_string()),
                         user_id: Some(id.to_string()),
+                        email: None,
                     })
                     .collect()
             }),
@@ -100,6 +108,7 @@ mod chat_tests {
                 }),
             }),
             unread_message_count: None,
+            viewpoint: None,
         }
     }
 
@@ -171,9 +180,10 @@ mod message_tests {
                     display_name: Some(name.to_string()),
                     id: sender_id.map(String::from),
                 }),
+                application: None,
             }),
             created_date_time: datetime.map(String::from),
-            reactions: None, attachments: vec![],
+            reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
         }
     }
 
@@ -203,7 +213,7 @@ mod message_tests {
             body: None,
             from: None,
             created_date_time: None,
-            reactions: None, attachments: vec![],
+            reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
         };
         assert_eq!(msg.content_text(), "");
     }
@@ -286,6 +296,23 @@ mod deserialization_tests {
         assert!(user.user_principal_name.is_none());
     }
 
+    #[test]
+    fn deserialize_pinned_message_with_expanded_message() {
+        let json = r#"{
+            "id": "pin1",
+            "message": {"id": "msg1", "messageType": "message", "body": {"content": "Agenda"}}
+        }"#;
+        let pin: PinnedMessage = serde_json::from_str(json).unwrap();
+        assert_eq!(pin.id, "pin1");
+        assert_eq!(pin.message_id(), Some("msg1"));
+    }
+
+    #[test]
+    fn deserialize_pinned_message_without_message() {
+        let pin: PinnedMessage = serde_json::from_str(r#"{"id":"pin1"}"#).unwrap();
+        assert_eq!(pin.message_id(), None);
+    }
+
     #[test]
     fn deserialize_message() {
         let json = r#"{
@@ -302,6 +329,26 @@ mod deserialization_tests {
         assert!(msg.is_user_message());
     }
 
+    #[test]
+    fn deserialize_bot_message_with_card() {
+        let json = r#"{
+            "id": "msg2",
+            "messageType": "message",
+            "body": {"content": "<attachment id=\"c1\"></attachment>"},
+            "from": {"user": null, "application": {"displayName": "CI Bot", "id": "app1"}},
+            "attachments": [{
+                "id": "c1",
+                "contentType": "application/vnd.microsoft.card.adaptive",
+                "content": "{\"body\":[{\"type\":\"TextBlock\",\"text\":\"Build passed\"}]}"
+            }]
+        }"#;
+        let msg: Message = serde_json::from_str(json).unwrap();
+        assert_eq!(msg.sender_name(), "CI Bot");
+        assert!(msg.sender_id().is_none());
+        assert_eq!(msg.cards().len(), 1);
+        assert!(msg.card_actions().is_empty());
+    }
+
     #[test]
     fn deserialize_chat() {
         let json = r#"{
@@ -352,10 +399,40 @@ mod reaction_tests {
                     })
                     .collect(),
             ),
-            attachments: vec![],
+            attachments: vec![], event_detail: None, deleted_date_time: None,
         }
     }
 
+    #[test]
+    fn has_reaction_from_matches_user_and_emoji() {
+        let msg = make_message_with_reactions(vec![("like", "u1"), ("❤", "u2")]);
+        // Legacy "like" matches the 👍 sent by the picker
+        assert!(msg.has_reaction_from("u1", "👍"));
+        assert!(!msg.has_reaction_from("u2", "👍"));
+        // Variation selector is ignored
+        assert!(msg.has_reaction_from("u2", "❤️"));
+        assert!(!msg.has_reaction_from("", "👍"));
+    }
+
+    #[test]
+    fn reaction_details_group_names_per_emoji() {
+        let mut msg = make_message_with_reactions(vec![("like", "u1"), ("😂", "u2"), ("👍", "u3")]);
+        // Graph usually leaves reactor names empty; they are resolved by ID
+        if let Some(reactions) = msg.reactions.as_mut() {
+            for r in reactions.iter_mut().skip(1) {
+                r.user.as_mut().unwrap().user.as_mut().unwrap().display_name = None;
+            }
+        }
+        let details = msg.reaction_details(|id| (id == "u3").then(|| "Carol".to_string()));
+        assert_eq!(
+            details,
+            vec![
+                ("👍".to_string(), vec!["User".to_string(), "Carol".to_string()]),
+                ("😂".to_string(), vec!["Unknown user".to_string()]),
+            ]
+        );
+    }
+
     #[test]
     fn reactions_summary_counts_by_type() {
         let msg = make_message_with_reactions(vec![
@@ -380,7 +457,7 @@ mod reaction_tests {
             body: None,
             from: None,
             created_date_time: None,
-            reactions: None, attachments: vec![],
+            reactions: None, attachments: vec![], event_detail: None, deleted_date_time: None,
         };
         assert!(msg.reactions_summary().is_empty());
     }
@@ -409,8 +486,23 @@ mod reaction_tests {
     }
 
     #[test]
-    fn reaction_emoji_returns_unknown_type_as_is() {
-        assert_eq!(reaction_emoji("custom"), "custom");
+    fn reaction_emoji_passes_unicode_emoji_through() {
+        assert_eq!(reaction_emoji("🎉"), "🎉");
+        assert_eq!(reaction_emoji("👍🏽"), "👍🏽");
+    }
+
+    #[test]
+    fn reaction_emoji_resolves_shortcodes_and_code_points() {
+        assert_eq!(reaction_emoji("tada"), "🎉");
+        assert_eq!(reaction_emoji(":rocket:"), "🚀");
+        assert_eq!(reaction_emoji("1f44d"), "👍");
+        assert_eq!(reaction_emoji("U+2764-FE0F"), "❤️");
+    }
+
+    #[test]
+    fn reaction_emoji_shows_placeholder_for_unknown_type() {
+        assert_eq!(reaction_emoji("custom"), UNKNOWN_REACTION);
+        assert_eq!(reaction_emoji("cafe"), UNKNOWN_REACTION);
     }
 }
 
@@ -532,6 +624,81 @@ mod rich_text_tests {
         let segments = parse_rich_text("");
         assert_eq!(segments.len(), 1);
     }
+
+    #[test]
+    fn pre_block_with_language_class_returns_code_block() {
+        let segments = parse_rich_text(
+            r#"<pre><code class="language-rust">fn main() {<br>    let x = 1;<br>}</code></pre>"#,
+        );
+        assert_eq!(
+            segments,
+            vec![RichSegment::CodeBlock {
+                language: Some("rust".to_string()),
+                code: "fn main() {\n    let x = 1;\n}".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn codeblock_tag_preserves_lines_and_decodes_entities() {
+        let segments = parse_rich_text(
+            r#"<codeblock class="Python"><code>if a &lt; b:<br>    print(&quot;hi&quot;)</code></codeblock>"#,
+        );
+        assert_eq!(
+            segments,
+            vec![RichSegment::CodeBlock {
+                language: Some("python".to_string()),
+                code: "if a < b:\n    print(\"hi\")".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn pre_block_without_language_has_no_hint() {
+        let segments = parse_rich_text("<pre>a\nb</pre>");
+        assert_eq!(
+            segments,
+            vec![RichSegment::CodeBlock { language: None, code: "a\nb".to_string() }]
+        );
+    }
+
+    #[test]
+    fn hosted_content_img_returns_hosted_image() {
+        let src = "https://graph.microsoft.com/v1.0/chats/c1/messages/m1/hostedContents/aWQ9/$value";
+        let html = format!(r#"look<img src="{}" alt="screenshot" width="200">"#, src);
+        let segments = parse_rich_text(&html);
+        assert_eq!(
+            segments,
+            vec![
+                RichSegment::Plain("look".to_string()),
+                RichSegment::HostedImage { url: src.to_string(), alt: "screenshot".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn external_img_is_not_a_hosted_image() {
+        let segments = parse_rich_text(
+            r#"<img src="https://evil.example.com/hostedContents/x/$value" alt="x">"#,
+        );
+        assert!(!segments.iter().any(|s| matches!(s, RichSegment::HostedImage { .. })));
+    }
+
+    #[test]
+    fn data_src_attribute_is_ignored() {
+        let segments = parse_rich_text(
+            r#"<img data-src="https://graph.microsoft.com/v1.0/chats/c/messages/m/hostedContents/h/$value">"#,
+        );
+        assert!(!segments.iter().any(|s| matches!(s, RichSegment::HostedImage { .. })));
+    }
+
+    #[test]
+    fn text_around_code_block_is_kept() {
+        let segments = parse_rich_text("before<pre>x = 1</pre>after");
+        assert_eq!(segments[0], RichSegment::Plain("before".to_string()));
+        assert!(matches!(segments[1], RichSegment::CodeBlock { .. }));
+        assert_eq!(segments[2], RichSegment::Plain("after".to_string()));
+    }
 }
 
 #[cfg(test)]
@@ -547,6 +714,7 @@ mod unread_tests {
             members: None,
             last_message_preview: None,
             unread_message_count: Some(5),
+            viewpoint: None,
         };
         assert_eq!(chat.unread_count(), 5);
     }
@@ -560,6 +728,7 @@ mod unread_tests {
             members: None,
             last_message_preview: None,
             unread_message_count: None,
+            viewpoint: None,
         };
         assert_eq!(chat.unread_count(), 0);
     }
@@ -1065,3 +1234,371 @@ mod attachment_tests {
         assert_eq!(files[0].name.as_deref(), Some("spec.pdf"));
     }
 }
+
+#[cfg(test)]
+mod system_event_tests {
+    use ttyms::models::Message;
+
+    fn event_message(detail: &str) -> Message {
+        let json = format!(
+            r#"{{"id":"e1","messageType":"systemEventMessage","body":{{"content":"<systemEventMessage/>"}},"eventDetail":{}}}"#,
+            detail
+        );
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn members_added_by_initiator() {
+        let msg = event_message(
+            r##"{"@odata.type":"#microsoft.graph.membersAddedEventMessageDetail",
+                "initiator":{"user":{"id":"u1","displayName":"Alice"}},
+                "members":[{"id":"u2","displayName":"Bob"},{"id":"u3","displayName":"Carol"}]}"##,
+        );
+        assert!(msg.is_system_event());
+        assert!(!msg.is_user_message());
+        assert_eq!(msg.event_summary().as_deref(), Some("Alice added Bob and Carol"));
+    }
+
+    #[test]
+    fn many_members_are_summarized() {
+        let msg = event_message(
+            r##"{"@odata.type":"#microsoft.graph.membersJoinedEventMessageDetail",
+                "members":[{"displayName":"A"},{"displayName":"B"},{"displayName":"C"},{"displayName":"D"}]}"##,
+        );
+        assert_eq!(msg.event_summary().as_deref(), Some("A, B and 2 others joined"));
+    }
+
+    #[test]
+    fn member_removing_self_left() {
+        let msg = event_message(
+            r##"{"@odata.type":"#microsoft.graph.membersDeletedEventMessageDetail",
+                "initiator":{"user":{"id":"u2","displayName":"Bob"}},
+                "members":[{"id":"u2","displayName":"Bob"}]}"##,
+        );
+        assert_eq!(msg.event_summary().as_deref(), Some("Bob left"));
+    }
+
+    #[test]
+    fn chat_renamed() {
+        let msg = event_message(
+            r##"{"@odata.type":"#microsoft.graph.chatRenamedEventMessageDetail",
+                "initiator":{"user":{"id":"u1","displayName":"Alice"}},
+                "chatDisplayName":"Release crew"}"##,
+        );
+        assert_eq!(
+            msg.event_summary().as_deref(),
+            Some("Alice renamed the chat to \"Release crew\"")
+        );
+    }
+
+    #[test]
+    fn call_ended_with_duration() {
+        let msg = event_message(
+            r##"{"@odata.type":"#microsoft.graph.callEndedEventMessageDetail",
+                "callEventType":"meeting","callDuration":"PT1H5M30.25S"}"##,
+        );
+        assert_eq!(msg.event_summary().as_deref(), Some("Meeting ended · 1h 5m 30s"));
+    }
+
+    #[test]
+    fn app_installed_by_application_initiator() {
+        let msg = event_message(
+            r##"{"@odata.type":"#microsoft.graph.teamsAppInstalledEventMessageDetail",
+                "initiator":{"application":{"id":"a1","displayName":"Admin Bot"}},
+                "teamsAppDisplayName":"Polly"}"##,
+        );
+        assert_eq!(msg.event_summary().as_deref(), Some("Admin Bot added Polly"));
+    }
+
+    #[test]
+    fn unknown_event_type_is_humanized() {
+        let msg = event_message(r##"{"@odata.type":"#microsoft.graph.meetingPolicyUpdatedEventMessageDetail"}"##);
+        assert_eq!(msg.event_summary().as_deref(), Some("Meeting policy updated"));
+    }
+
+    #[test]
+    fn event_without_detail_falls_back() {
+        let msg: Message = serde_json::from_str(
+            r#"{"id":"e2","messageType":"systemEventMessage","body":{"content":""}}"#,
+        )
+        .unwrap();
+        assert_eq!(msg.event_summary().as_deref(), Some("System event"));
+    }
+
+    #[test]
+    fn user_message_has_no_event_summary() {
+        let msg: Message = serde_json::from_str(
+            r#"{"id":"m1","messageType":"message","body":{"content":"hi"}}"#,
+        )
+        .unwrap();
+        assert!(msg.event_summary().is_none());
+    }
+}
+
+#[cfg(test)]
+mod hosted_image_tests {
+    use ttyms::models::{is_hosted_content_url, Message};
+
+    #[test]
+    fn hosted_content_url_requires_graph_host() {
+        assert!(is_hosted_content_url(
+            "https://graph.microsoft.com/v1.0/teams/t/channels/c/messages/m/hostedContents/h/$value"
+        ));
+        assert!(!is_hosted_content_url("https://example.com/v1.0/hostedContents/h/$value"));
+        assert!(!is_hosted_content_url("https://graph.microsoft.com/v1.0/me/photo/$value"));
+    }
+
+    #[test]
+    fn message_collects_hosted_image_urls_with_decoded_entities() {
+        let json = r#"{
+            "id": "m1",
+            "messageType": "message",
+            "body": {"contentType": "html", "content": "<p>a<img src=\"https://graph.microsoft.com/v1.0/chats/c/messages/m/hostedContents/h1/$value?x=1&amp;y=2\"></p><p><img src=\"https://graph.microsoft.com/v1.0/chats/c/messages/m/hostedContents/h2/$value\"></p>"}
+        }"#;
+        let msg: Message = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            msg.hosted_image_urls(),
+            vec![
+                "https://graph.microsoft.com/v1.0/chats/c/messages/m/hostedContents/h1/$value?x=1&y=2".to_string(),
+                "https://graph.microsoft.com/v1.0/chats/c/messages/m/hostedContents/h2/$value".to_string(),
+            ]
+        );
+    }
+}
+
+#[cfg(test)]
+mod upload_session_tests {
+    use ttyms::models::UploadSession;
+
+    #[test]
+    fn next_offset_uses_lowest_expected_range() {
+        let json = r#"{
+            "uploadUrl": "https://contoso.sharepoint.com/upload?token=x",
+            "expirationDateTime": "2026-01-29T09:21:55.523Z",
+            "nextExpectedRanges": ["26214400-", "12345-20000"]
+        }"#;
+        let session: UploadSession = serde_json::from_str(json).unwrap();
+        assert_eq!(session.upload_url.as_deref(), Some("https://contoso.sharepoint.com/upload?token=x"));
+        assert_eq!(session.next_offset(), Some(12345));
+    }
+
+    #[test]
+    fn next_offset_none_without_ranges() {
+        let session: UploadSession = serde_json::from_str("{}").unwrap();
+        assert!(session.upload_url.is_none());
+        assert_eq!(session.next_offset(), None);
+    }
+}
+
+#[cfg(test)]
+mod shared_files_tests {
+    use ttyms::models::{
+        merge_shared_files, shared_files_from_drive_items, shared_files_from_messages,
+        sort_shared_files, ChatMessageAttachment, DriveItem, Message, MessageFrom, MessageUser,
+        SharedFileSort, SharedFileSource,
+    };
+
+    fn make_message(sender: &str, time: &str, attachments: Vec<(&str, &str, &str)>) -> Message {
+        Message {
+            id: format!("m-{time}"),
+            message_type: Some("message".to_string()),
+            body: None,
+            from: Some(MessageFrom {
+                user: Some(MessageUser {
+                    display_name: Some(sender.to_string()),
+                    id: Some(sender.to_lowercase()),
+                }),
+                application: None,
+            }),
+            created_date_time: Some(time.to_string()),
+            reactions: None,
+            attachments: attachments
+                .into_iter()
+                .map(|(name, content_type, url)| ChatMessageAttachment {
+                    id: None,
+                    content_type: Some(content_type.to_string()),
+                    content_url: Some(url.to_string()),
+                    name: Some(name.to_string()),
+                    content: None,
+                })
+                .collect(),
+            event_detail: None,
+            deleted_date_time: None,
+        }
+    }
+
+    fn sample_messages() -> Vec<Message> {
+        vec![
+            make_message("Bob", "2026-01-01T10:00:00Z", vec![("spec.docx", "reference", "https://c.sharepoint.com/spec.docx")]),
+            make_message("Alice", "2026-01-02T10:00:00Z", vec![
+                ("shot.png", "image/png", "https://c.sharepoint.com/shot.png"),
+                ("card", "application/vnd.microsoft.card.adaptive", "https://example.com/card"),
+            ]),
+            // Re-shared later: the newest share wins
+            make_message("Carol", "2026-01-03T10:00:00Z", vec![("spec.docx", "reference", "https://c.sharepoint.com/spec.docx")]),
+        ]
+    }
+
+    #[test]
+    fn aggregates_file_and_image_attachments() {
+        let files = shared_files_from_messages(&sample_messages());
+        assert_eq!(files.len(), 2);
+        let spec = files.iter().find(|f| f.name == "spec.docx").unwrap();
+        assert_eq!(spec.sender, "Carol");
+        assert_eq!(spec.source, SharedFileSource::Message);
+        assert_eq!(spec.download_url, "https://c.sharepoint.com/spec.docx");
+    }
+
+    #[test]
+    fn sorts_by_date_sender_and_type() {
+        let mut files = shared_files_from_messages(&sample_messages());
+        sort_shared_files(&mut files, SharedFileSort::Date);
+        assert_eq!(files[0].name, "spec.docx");
+        sort_shared_files(&mut files, SharedFileSort::Sender);
+        assert_eq!(files[0].sender, "Alice");
+        sort_shared_files(&mut files, SharedFileSort::Type);
+        assert_eq!(files[0].name, "spec.docx");
+        assert_eq!(SharedFileSort::Date.next().next().next(), SharedFileSort::Date);
+    }
+
+    #[test]
+    fn sort_ties_break_newest_first() {
+        let mut files = shared_files_from_messages(&[
+            make_message("Bob", "2026-01-01T10:00:00Z", vec![("a.pdf", "reference", "https://c.sharepoint.com/a.pdf")]),
+            make_message("Bob", "2026-01-03T10:00:00Z", vec![("c.pdf", "reference", "https://c.sharepoint.com/c.pdf")]),
+            make_message("Bob", "2026-01-02T10:00:00Z", vec![("b.pdf", "reference", "https://c.sharepoint.com/b.pdf")]),
+        ]);
+        sort_shared_files(&mut files, SharedFileSort::Sender);
+        let names: Vec<&str> = files.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["c.pdf", "b.pdf", "a.pdf"]);
+        files.reverse();
+        sort_shared_files(&mut files, SharedFileSort::Type);
+        let names: Vec<&str> = files.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["c.pdf", "b.pdf", "a.pdf"]);
+    }
+
+    #[test]
+    fn sort_by_date_parses_fractional_seconds() {
+        let mut files = shared_files_from_messages(&[
+            make_message("Bob", "2026-01-01T10:00:00Z", vec![("a.pdf", "reference", "https://c.sharepoint.com/a.pdf")]),
+            make_message("Bob", "2026-01-01T10:00:00.5Z", vec![("b.pdf", "reference", "https://c.sharepoint.com/b.pdf")]),
+        ]);
+        sort_shared_files(&mut files, SharedFileSort::Date);
+        let names: Vec<&str> = files.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["b.pdf", "a.pdf"]);
+    }
+
+    #[test]
+    fn drive_items_skip_folders_and_build_content_urls() {
+        let json = r#"[
+            {"id": "i1", "name": "Plan.pdf", "webUrl": "https://c.sharepoint.com/Shared%20Documents/Plan.pdf",
+             "size": 2048, "lastModifiedDateTime": "2026-02-01T08:00:00Z",
+             "lastModifiedBy": {"user": {"displayName": "Dana", "id": "d"}}},
+            {"id": "i2", "name": "Archive", "webUrl": "https://c.sharepoint.com/Archive", "folder": {"childCount": 3}}
+        ]"#;
+        let items: Vec<DriveItem> = serde_json::from_str(json).unwrap();
+        let files = shared_files_from_drive_items("drv", &items);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].download_url, "https://graph.microsoft.com/v1.0/drives/drv/items/i1/content");
+        assert_eq!(files[0].sender, "Dana");
+        assert_eq!(files[0].size, Some(2048));
+        assert_eq!(files[0].source, SharedFileSource::ChannelFolder);
+    }
+
+    #[test]
+    fn folder_files_replace_matching_attachments() {
+        let mut files = shared_files_from_messages(&[make_message(
+            "Bob",
+            "2026-01-01T10:00:00Z",
+            vec![("Plan.pdf", "reference", "https://c.sharepoint.com/Shared Documents/Plan.pdf")],
+        )]);
+        let json = r#"[{"id": "i1", "name": "Plan.pdf", "webUrl": "https://c.sharepoint.com/Shared%20Documents/Plan.pdf"}]"#;
+        let items: Vec<DriveItem> = serde_json::from_str(json).unwrap();
+        merge_shared_files(&mut files, shared_files_from_drive_items("drv", &items));
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].source, SharedFileSource::ChannelFolder);
+    }
+}
+
+#[cfg(test)]
+mod unread_marker_tests {
+    use ttyms::models::*;
+
+    fn make_message(id: &str, sender: &str, time: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            message_type: Some("message".to_string()),
+            body: None,
+            from: Some(MessageFrom {
+                user: Some(MessageUser { display_name: None, id: Some(sender.to_string()) }),
+                application: None,
+            }),
+            created_date_time: Some(time.to_string()),
+            reactions: None,
+            attachments: vec![],
+            event_detail: None,
+            deleted_date_time: None,
+        }
+    }
+
+    fn messages() -> Vec<Message> {
+        vec![
+            make_message("m1", "alice", "2026-03-01T10:00:00.1Z"),
+            make_message("m2", "me", "2026-03-01T10:05:00Z"),
+            make_message("m3", "alice", "2026-03-01T10:10:00.25Z"),
+            make_message("m4", "bob", "2026-03-01T10:15:00Z"),
+        ]
+    }
+
+    #[test]
+    fn uses_read_time_when_known() {
+        let msgs = messages();
+        assert_eq!(first_unread_index(&msgs, Some("2026-03-01T10:00:00.123Z"), 0, "me"), Some(2));
+        assert_eq!(first_unread_index(&msgs, Some("2026-03-01T10:20:00Z"), 5, "me"), None);
+    }
+
+    #[test]
+    fn own_messages_are_never_unread() {
+        let msgs = messages();
+        assert_eq!(first_unread_index(&msgs, Some("2026-03-01T10:01:00Z"), 0, "me"), Some(2));
+    }
+
+    #[test]
+    fn falls_back_to_unread_count() {
+        let msgs = messages();
+        assert_eq!(first_unread_index(&msgs, None, 2, "me"), Some(2));
+        assert_eq!(first_unread_index(&msgs, None, 10, "me"), Some(0));
+        assert_eq!(first_unread_index(&msgs, None, 0, "me"), None);
+    }
+
+    #[test]
+    fn viewpoint_read_time_deserializes() {
+        let chat: Chat = serde_json::from_str(
+            r#"{"id":"c1","chatType":"group","viewpoint":{"isHidden":false,"lastMessageReadDateTime":"2026-03-01T10:00:00Z"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            chat.viewpoint.unwrap().last_message_read_date_time.as_deref(),
+            Some("2026-03-01T10:00:00Z")
+        );
+    }
+
+    #[test]
+    fn deleted_message_deserializes() {
+        let msg: Message = serde_json::from_str(
+            r#"{"id":"m1","deletedDateTime":"2026-03-01T10:00:00Z","body":{"content":""}}"#,
+        )
+        .unwrap();
+        assert!(msg.is_deleted());
+        assert!(!messages()[0].is_deleted());
+    }
+
+    #[test]
+    fn counts_messages_from_others_after_read_time() {
+        let msgs = messages();
+        assert_eq!(count_unread_since(&msgs, "2026-03-01T10:00:00Z", "me"), 3);
+        assert_eq!(count_unread_since(&msgs, "2026-03-01T10:05:00Z", "me"), 2);
+        assert_eq!(count_unread_since(&msgs, "2026-03-01T10:15:00Z", "me"), 0);
+        assert_eq!(count_unread_since(&msgs, "not a time", "me"), 0);
+    }
+}
@@ -0,0 +1,124 @@
+//! Tests for chat list sections: rule compilation and chat membership
+
+#[cfg(test)]
+mod sections_tests {
+    use ttyms::config::ChatSectionConfig;
+    use ttyms::models::*;
+    use ttyms::sections::*;
+
+    fn make_chat(id: &str, chat_type: &str, topic: Option<&str>, unread: i32) -> Chat {
+        Chat {
+            id: id.to_string(),
+            topic: topic.map(String::from),
+            chat_type: chat_type.to_string(),
+            members: Some(vec![
+                ChatMember { id: None, display_name: Some("Me".to_string()), user_id: Some("me".to_string()), email: None },
+                ChatMember { id: None, display_name: Some("Build Agent".to_string()), user_id: Some("bot-1".to_string()), email: None },
+            ]),
+            last_message_preview: None,
+            unread_message_count: Some(unread),
+            viewpoint: None,
+        }
+    }
+
+    fn section(config: ChatSectionConfig) -> ChatSection {
+        ChatSection::compile(&config).unwrap()
+    }
+
+    #[test]
+    fn explicit_ids_match_regardless_of_rules() {
+        let s = section(ChatSectionConfig {
+            name: "Favorites".to_string(),
+            chats: vec!["c1".to_string()],
+            chat_types: vec!["meeting".to_string()],
+            ..Default::default()
+        });
+        assert!(s.matches(&make_chat("c1", "group", None, 0), "me"));
+        assert!(!s.matches(&make_chat("c2", "group", None, 0), "me"));
+    }
+
+    #[test]
+    fn section_without_rules_or_ids_matches_nothing() {
+        let s = section(ChatSectionConfig { name: "Empty".to_string(), ..Default::default() });
+        assert!(!s.matches(&make_chat("c1", "group", None, 0), "me"));
+    }
+
+    #[test]
+    fn chat_type_is_case_insensitive() {
+        let s = section(ChatSectionConfig {
+            name: "Meetings".to_string(),
+            chat_types: vec!["Meeting".to_string()],
+            ..Default::default()
+        });
+        assert!(s.matches(&make_chat("c1", "meeting", None, 0), "me"));
+        assert!(!s.matches(&make_chat("c2", "oneOnOne", None, 0), "me"));
+    }
+
+    #[test]
+    fn rules_are_combined() {
+        let s = section(ChatSectionConfig {
+            name: "Bots".to_string(),
+            topic: Some("(?i)alerts".to_string()),
+            unread: Some(true),
+            ..Default::default()
+        });
+        assert!(s.matches(&make_chat("c1", "group", Some("Prod Alerts"), 2), "me"));
+        assert!(!s.matches(&make_chat("c2", "group", Some("Prod Alerts"), 0), "me"));
+        assert!(!s.matches(&make_chat("c3", "group", Some("Lunch"), 2), "me"));
+    }
+
+    #[test]
+    fn topic_falls_back_to_member_names() {
+        let s = section(ChatSectionConfig {
+            name: "Bots".to_string(),
+            topic: Some("Agent$".to_string()),
+            ..Default::default()
+        });
+        assert!(s.matches(&make_chat("c1", "oneOnOne", None, 0), "me"));
+    }
+
+    #[test]
+    fn members_match_name_substring_or_user_id_but_not_self() {
+        let by_name = section(ChatSectionConfig {
+            name: "Bots".to_string(),
+            members: vec!["build".to_string()],
+            ..Default::default()
+        });
+        let by_id = section(ChatSectionConfig {
+            name: "Bots".to_string(),
+            members: vec!["BOT-1".to_string()],
+            ..Default::default()
+        });
+        let self_only = section(ChatSectionConfig {
+            name: "Mine".to_string(),
+            members: vec!["me".to_string()],
+            ..Default::default()
+        });
+        let chat = make_chat("c1", "oneOnOne", None, 0);
+        assert!(by_name.matches(&chat, "me"));
+        assert!(by_id.matches(&chat, "me"));
+        assert!(!self_only.matches(&chat, "me"));
+    }
+
+    #[test]
+    fn invalid_topic_patterns_are_reported_and_skipped() {
+        let (sections, invalid) = compile_sections(&[
+            ChatSectionConfig { name: "Broken".to_string(), topic: Some("(".to_string()), ..Default::default() },
+            ChatSectionConfig { name: "Meetings".to_string(), chat_types: vec!["meeting".to_string()], ..Default::default() },
+        ]);
+        assert_eq!(invalid, vec!["Broken".to_string()]);
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].name, "Meetings");
+    }
+
+    #[test]
+    fn first_matching_section_wins_and_rest_go_to_other() {
+        let (sections, _) = compile_sections(&[
+            ChatSectionConfig { name: "Favorites".to_string(), chats: vec!["c1".to_string()], ..Default::default() },
+            ChatSectionConfig { name: "Groups".to_string(), chat_types: vec!["group".to_string()], ..Default::default() },
+        ]);
+        assert_eq!(section_index(&sections, &make_chat("c1", "group", None, 0), "me"), 0);
+        assert_eq!(section_index(&sections, &make_chat("c2", "group", None, 0), "me"), 1);
+        assert_eq!(section_index(&sections, &make_chat("c3", "meeting", None, 0), "me"), 2);
+    }
+}
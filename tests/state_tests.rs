@@ -0,0 +1,113 @@
+//! Tests for runtime state kept in `state.json`
+
+#[cfg(test)]
+mod state_tests {
+    use std::collections::HashMap;
+    use ttyms::config::Config;
+    use ttyms::state::{load_state_from, prune_read_times, save_state_to, ChatPrefs, State};
+
+    #[test]
+    fn prune_keeps_newest_read_times() {
+        let mut read_times: HashMap<String, String> = [
+            ("c1", "2026-01-01T10:00:00Z"),
+            ("c2", "2026-01-04T10:00:00Z"),
+            ("c3", "2026-01-02T10:00:00Z"),
+            ("c4", "2026-01-03T10:00:00Z"),
+        ]
+        .into_iter()
+        .map(|(id, time)| (id.to_string(), time.to_string()))
+        .collect();
+        prune_read_times(&mut read_times, 4);
+        assert_eq!(read_times.len(), 4);
+        prune_read_times(&mut read_times, 2);
+        let mut kept: Vec<&str> = read_times.keys().map(String::as_str).collect();
+        kept.sort();
+        assert_eq!(kept, vec!["c2", "c4"]);
+    }
+
+    #[test]
+    fn prune_compares_parsed_read_times() {
+        let mut read_times: HashMap<String, String> = [
+            ("whole", "2026-01-01T10:00:00Z"),
+            ("fraction", "2026-01-01T10:00:00.5Z"),
+            ("bad", "yesterday"),
+        ]
+        .into_iter()
+        .map(|(id, time)| (id.to_string(), time.to_string()))
+        .collect();
+        prune_read_times(&mut read_times, 2);
+        assert!(!read_times.contains_key("bad"));
+        prune_read_times(&mut read_times, 1);
+        assert!(read_times.contains_key("fraction"));
+    }
+
+    #[test]
+    fn chat_prefs_change_detection() {
+        let mut state = State::default();
+        let prefs = ChatPrefs { pinned: vec!["c1".to_string()], ..Default::default() };
+        assert!(state.set_chat_prefs("u1", &prefs));
+        assert!(!state.set_chat_prefs("u1", &prefs));
+        // Empty preferences are dropped instead of stored
+        assert!(state.set_chat_prefs("u1", &ChatPrefs::default()));
+        assert!(state.chat_prefs.is_empty());
+        assert!(!state.set_chat_prefs("u1", &ChatPrefs::default()));
+    }
+
+    #[test]
+    fn migrates_state_from_legacy_config() {
+        let config: Config = toml::from_str(
+            r#"client_id = ""
+tenant_id = "common"
+skin_tone = 3
+recent_emojis = ["🎉"]
+
+[chat_prefs.u1]
+muted = ["c1"]
+
+[channel_last_read.u1]
+"19:ch" = "2026-01-01T10:00:00Z"
+"#,
+        )
+        .unwrap();
+        let state = State::from_legacy_config(&config);
+        assert_eq!(state.skin_tone, 3);
+        assert_eq!(state.recent_emojis, vec!["🎉".to_string()]);
+        assert_eq!(state.chat_prefs["u1"].muted, vec!["c1".to_string()]);
+        assert_eq!(state.channel_last_read["u1"]["19:ch"], "2026-01-01T10:00:00Z");
+
+        // Saving the config no longer writes the runtime state back
+        let saved = toml::to_string_pretty(&config).unwrap();
+        assert!(!saved.contains("skin_tone"));
+        assert!(!saved.contains("recent_emojis"));
+        assert!(!saved.contains("chat_prefs"));
+        assert!(!saved.contains("channel_last_read"));
+    }
+
+    #[test]
+    fn state_round_trips_through_file() {
+        let dir = std::env::temp_dir().join(format!("ttyms-state-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("state.json");
+        let mut state = State { skin_tone: 2, recent_emojis: vec!["👍".to_string()], ..Default::default() };
+        state.channel_last_read.insert(
+            "u1".to_string(),
+            HashMap::from([("19:ch".to_string(), "2026-01-01T10:00:00Z".to_string())]),
+        );
+        save_state_to(&state, &path).unwrap();
+        assert_eq!(load_state_from(&path).unwrap(), state);
+        assert!(!path.with_extension("json.tmp").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unreadable_state_file_is_backed_up() {
+        let dir = std::env::temp_dir().join(format!("ttyms-state-bad-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("state.json");
+        std::fs::write(&path, "{\"skin_tone\": ").unwrap();
+        let err = load_state_from(&path).unwrap_err();
+        assert!(format!("{:#}", err).contains("state.json.bak"));
+        assert_eq!(std::fs::read_to_string(path.with_extension("json.bak")).unwrap(), "{\"skin_tone\": ");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}